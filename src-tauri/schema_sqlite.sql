@@ -75,6 +75,7 @@ CREATE TABLE categorias (
   nombre TEXT NOT NULL UNIQUE,
   descripcion TEXT,
   tipo_talla TEXT DEFAULT 'ROPA' CHECK(tipo_talla IN ('ROPA', 'CALZADO', 'NINGUNA')),
+  dias_devolucion INTEGER CHECK (dias_devolucion >= 0), -- 🆕 NULL = política de la tienda, 0 = sin devolución
   activo INTEGER DEFAULT 1,
   fecha_creacion TEXT DEFAULT (datetime('now', 'localtime')),
   fecha_actualizacion TEXT DEFAULT (datetime('now', 'localtime'))
@@ -94,10 +95,12 @@ CREATE TABLE productos (
   descripcion TEXT,
   precio REAL NOT NULL CHECK (precio > 0),
   stock INTEGER NOT NULL DEFAULT 0 CHECK (stock >= 0),
+  stock_cuarentena INTEGER NOT NULL DEFAULT 0 CHECK (stock_cuarentena >= 0), -- 🆕 devuelto defectuoso/vencido, no vendible
   stock_minimo INTEGER DEFAULT 5,
   categoria_id INTEGER NOT NULL,
   descuento_porcentaje REAL DEFAULT 0,
   tiene_variantes INTEGER DEFAULT 0,
+  afectacion_igv TEXT DEFAULT 'GRAVADO' CHECK(afectacion_igv IN ('GRAVADO', 'EXONERADO', 'INAFECTO')),
   imagen_url TEXT,
   activo INTEGER DEFAULT 1,
   fecha_creacion TEXT DEFAULT (datetime('now', 'localtime')),
@@ -119,6 +122,7 @@ CREATE TABLE producto_variantes (
   producto_id INTEGER NOT NULL,
   talla TEXT NOT NULL,
   stock INTEGER NOT NULL DEFAULT 0 CHECK (stock >= 0),
+  stock_cuarentena INTEGER NOT NULL DEFAULT 0 CHECK (stock_cuarentena >= 0), -- 🆕
   stock_minimo INTEGER DEFAULT 2,
   activo INTEGER DEFAULT 1,
   fecha_creacion TEXT DEFAULT (datetime('now', 'localtime')),
@@ -151,6 +155,166 @@ BEGIN
   WHERE id = NEW.producto_id;
 END;
 
+-- 🆕 La cuarentena del producto es la suma de la de sus tallas
+CREATE TRIGGER trg_actualizar_cuarentena_producto
+AFTER UPDATE OF stock_cuarentena ON producto_variantes
+FOR EACH ROW
+BEGIN
+  UPDATE productos
+  SET stock_cuarentena = (SELECT COALESCE(SUM(stock_cuarentena), 0) FROM producto_variantes WHERE producto_id = NEW.producto_id),
+      fecha_actualizacion = datetime('now', 'localtime')
+  WHERE id = NEW.producto_id;
+END;
+
+-- =====================================================
+-- TABLA: listas_precios 🆕 (minorista, mayorista, ...)
+-- La lista predeterminada se usa cuando el cliente no tiene una asignada y
+-- el POS no elige otra; sin precio en la lista rige productos.precio
+-- =====================================================
+DROP TABLE IF EXISTS listas_precios;
+CREATE TABLE listas_precios (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  nombre TEXT NOT NULL UNIQUE COLLATE NOCASE,
+  descripcion TEXT,
+  predeterminada INTEGER DEFAULT 0,             -- solo una lista puede serlo
+  activo INTEGER DEFAULT 1,
+  fecha_creacion TEXT DEFAULT (datetime('now', 'localtime'))
+);
+
+-- Precio por producto (o talla) y escala por cantidad: rige la fila con la mayor
+-- cantidad_minima alcanzada por las unidades del producto en el carrito
+DROP TABLE IF EXISTS precios_lista;
+CREATE TABLE precios_lista (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  lista_id INTEGER NOT NULL,
+  producto_id INTEGER NOT NULL,
+  variante_id INTEGER,                          -- NULL = todas las tallas
+  cantidad_minima INTEGER NOT NULL DEFAULT 1 CHECK (cantidad_minima >= 1),
+  precio REAL NOT NULL CHECK (precio >= 0),
+  fecha_actualizacion TEXT DEFAULT (datetime('now', 'localtime')),
+  FOREIGN KEY (lista_id) REFERENCES listas_precios(id) ON DELETE CASCADE,
+  FOREIGN KEY (producto_id) REFERENCES productos(id) ON DELETE CASCADE,
+  FOREIGN KEY (variante_id) REFERENCES producto_variantes(id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX idx_precios_lista_unico ON precios_lista(lista_id, producto_id, COALESCE(variante_id, 0), cantidad_minima);
+CREATE INDEX idx_precios_lista_producto ON precios_lista(producto_id);
+
+-- =====================================================
+-- TABLA: clientes
+-- =====================================================
+DROP TABLE IF EXISTS clientes;
+CREATE TABLE clientes (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  tipo_documento TEXT NOT NULL CHECK(tipo_documento IN ('DNI', 'RUC', 'CE')),
+  numero_documento TEXT NOT NULL,
+  nombre TEXT NOT NULL,                         -- nombre completo o razón social
+  direccion TEXT,                               -- 🆕 domicilio fiscal (facturas)
+  telefono TEXT,
+  email TEXT,
+  fecha_nacimiento TEXT,                        -- 'YYYY-MM-DD'
+  tallas_preferidas TEXT,                       -- ej. "Polo M, Pantalón 30"
+  notas TEXT,
+  limite_credito REAL DEFAULT 0 CHECK (limite_credito >= 0), -- 🆕 0 = no compra al crédito
+  dias_credito INTEGER DEFAULT 30 CHECK (dias_credito > 0),  -- 🆕 plazo para el vencimiento
+  lista_precio_id INTEGER,                      -- 🆕 NULL = lista predeterminada
+  activo INTEGER DEFAULT 1,
+  fecha_registro TEXT DEFAULT (datetime('now', 'localtime')),
+  UNIQUE(tipo_documento, numero_documento)
+);
+
+CREATE INDEX idx_clientes_documento ON clientes(numero_documento);
+CREATE INDEX idx_clientes_nombre ON clientes(nombre);
+CREATE INDEX idx_clientes_telefono ON clientes(telefono);
+
+-- =====================================================
+-- TABLA: padron_ruc 🆕 (copia local del padrón reducido de SUNAT)
+-- =====================================================
+DROP TABLE IF EXISTS padron_ruc;
+CREATE TABLE padron_ruc (
+  ruc TEXT PRIMARY KEY,
+  razon_social TEXT NOT NULL,
+  estado TEXT,                                  -- ACTIVO, BAJA DE OFICIO, ...
+  condicion TEXT,                               -- HABIDO, NO HALLADO, ...
+  ubigeo TEXT,
+  direccion TEXT,                               -- armada con vía, número, zona, etc.
+  fecha_actualizacion TEXT DEFAULT (datetime('now', 'localtime'))
+) WITHOUT ROWID;
+
+-- =====================================================
+-- TABLA: puntos_movimientos (programa de puntos)
+-- Libro de movimientos: el saldo es la suma, nunca se guarda aparte
+-- =====================================================
+DROP TABLE IF EXISTS puntos_movimientos;
+CREATE TABLE puntos_movimientos (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  cliente_id INTEGER NOT NULL,
+  tipo TEXT NOT NULL CHECK(tipo IN ('ACUMULACION', 'CANJE', 'REVERSION', 'VENCIMIENTO', 'AJUSTE')),
+  puntos INTEGER NOT NULL CHECK (puntos != 0),  -- positivo suma, negativo resta
+  venta_id INTEGER,
+  devolucion_id INTEGER,
+  fecha_vencimiento TEXT,                       -- solo abonos (puntos > 0)
+  descripcion TEXT,
+  usuario_id INTEGER,
+  fecha_hora TEXT DEFAULT (datetime('now', 'localtime')),
+  FOREIGN KEY (cliente_id) REFERENCES clientes(id),
+  FOREIGN KEY (venta_id) REFERENCES ventas(id),
+  FOREIGN KEY (devolucion_id) REFERENCES devoluciones(id),
+  FOREIGN KEY (usuario_id) REFERENCES usuarios(id)
+);
+
+CREATE INDEX idx_puntos_cliente ON puntos_movimientos(cliente_id);
+CREATE INDEX idx_puntos_venta ON puntos_movimientos(venta_id);
+
+-- =====================================================
+-- TABLA: vales (tarjetas de regalo y vales por devolución)
+-- saldo se actualiza junto con cada fila de vales_movimientos
+-- =====================================================
+DROP TABLE IF EXISTS vales;
+CREATE TABLE vales (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  codigo TEXT NOT NULL UNIQUE,
+  origen TEXT NOT NULL CHECK(origen IN ('VENTA', 'DEVOLUCION')),
+  monto_inicial REAL NOT NULL CHECK (monto_inicial > 0),
+  saldo REAL NOT NULL CHECK (saldo >= 0),
+  estado TEXT DEFAULT 'ACTIVO' CHECK(estado IN ('ACTIVO', 'AGOTADO', 'VENCIDO')),
+  fecha_emision TEXT DEFAULT (datetime('now', 'localtime')),
+  fecha_vencimiento TEXT,
+  cliente_id INTEGER,
+  devolucion_id INTEGER,                 -- origen DEVOLUCION
+  caja_id INTEGER,                       -- origen VENTA: caja que cobró la tarjeta de regalo
+  metodo_pago TEXT,                      -- origen VENTA: cómo se pagó
+  usuario_id INTEGER NOT NULL,
+  FOREIGN KEY (cliente_id) REFERENCES clientes(id),
+  FOREIGN KEY (devolucion_id) REFERENCES devoluciones(id),
+  FOREIGN KEY (caja_id) REFERENCES cajas(id),
+  FOREIGN KEY (usuario_id) REFERENCES usuarios(id)
+);
+
+CREATE INDEX idx_vales_estado ON vales(estado);
+CREATE INDEX idx_vales_cliente ON vales(cliente_id);
+
+DROP TABLE IF EXISTS vales_movimientos;
+CREATE TABLE vales_movimientos (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  vale_id INTEGER NOT NULL,
+  tipo TEXT NOT NULL CHECK(tipo IN ('EMISION', 'CANJE', 'REVERSION', 'VENCIMIENTO')),
+  monto REAL NOT NULL CHECK (monto != 0),  -- positivo suma, negativo resta
+  saldo_resultante REAL NOT NULL,
+  venta_id INTEGER,
+  devolucion_id INTEGER,
+  descripcion TEXT,
+  usuario_id INTEGER,
+  fecha_hora TEXT DEFAULT (datetime('now', 'localtime')),
+  FOREIGN KEY (vale_id) REFERENCES vales(id),
+  FOREIGN KEY (venta_id) REFERENCES ventas(id),
+  FOREIGN KEY (devolucion_id) REFERENCES devoluciones(id),
+  FOREIGN KEY (usuario_id) REFERENCES usuarios(id)
+);
+
+CREATE INDEX idx_vales_movimientos_vale ON vales_movimientos(vale_id);
+CREATE INDEX idx_vales_movimientos_venta ON vales_movimientos(venta_id);
+
 -- =====================================================
 -- TABLA: ventas
 -- =====================================================
@@ -162,15 +326,41 @@ CREATE TABLE ventas (
   subtotal REAL NOT NULL CHECK (subtotal >= 0),
   descuento REAL DEFAULT 0 CHECK (descuento >= 0),
   total REAL NOT NULL CHECK (total >= 0),
-  metodo_pago TEXT NOT NULL CHECK(metodo_pago IN ('EFECTIVO', 'TARJETA', 'TRANSFERENCIA', 'MIXTO')),
+  metodo_pago TEXT NOT NULL CHECK(metodo_pago IN ('EFECTIVO', 'TARJETA', 'TRANSFERENCIA', 'PUNTOS', 'VALE', 'CREDITO', 'CAMBIO', 'MIXTO')),
   monto_recibido REAL,
   cambio REAL,
+  redondeo REAL DEFAULT 0,                      -- 🆕 diferencia por redondeo del efectivo (cobrado - exacto)
+  lista_precio_id INTEGER,                      -- 🆕 lista de precios con la que se cobró
   usuario_id INTEGER NOT NULL,
+  vendedor_id INTEGER,                          -- 🆕 quien atendió (usuario_id es el cajero)
+  caja_id INTEGER,
+  cliente_id INTEGER,
+  cupon_id INTEGER,
+  descuento_cupon REAL DEFAULT 0,
+  op_gravada REAL DEFAULT 0,                    -- valor de venta gravado (sin IGV)
+  op_exonerada REAL DEFAULT 0,
+  op_inafecta REAL DEFAULT 0,
+  igv REAL DEFAULT 0,
+  igv_porcentaje REAL DEFAULT 0,                -- tasa vigente al momento de la venta
+  tipo_comprobante TEXT DEFAULT 'NOTA_VENTA' CHECK(tipo_comprobante IN ('BOLETA', 'FACTURA', 'NOTA_VENTA')),
+  serie TEXT,                                   -- B001, F001, NV01... (series_comprobante)
+  correlativo INTEGER,                          -- número dentro de la serie
+  cotizacion_id INTEGER,                        -- 🆕 cotización de la que salió la venta
+  saldo_pendiente REAL DEFAULT 0 CHECK (saldo_pendiente >= 0), -- 🆕 parte al crédito aún no cobrada
+  fecha_vencimiento_pago TEXT,                  -- 🆕 'YYYY-MM-DD' (solo ventas al crédito)
+  estado_pago TEXT CHECK(estado_pago IN ('PENDIENTE', 'PARCIAL', 'PAGADO')), -- 🆕 NULL si no es al crédito
+  cambio_id INTEGER,                            -- 🆕 venta de reemplazo en un cambio de talla
   estado TEXT DEFAULT 'COMPLETADA' CHECK(estado IN ('COMPLETADA', 'CANCELADA', 'PENDIENTE')),
   motivo_cancelacion TEXT,
   notas TEXT,
   licencia_tipo TEXT,
-  FOREIGN KEY (usuario_id) REFERENCES usuarios(id)
+  FOREIGN KEY (usuario_id) REFERENCES usuarios(id),
+  FOREIGN KEY (caja_id) REFERENCES cajas(id),
+  FOREIGN KEY (cliente_id) REFERENCES clientes(id),
+  FOREIGN KEY (cupon_id) REFERENCES cupones(id),
+  FOREIGN KEY (cotizacion_id) REFERENCES cotizaciones(id),
+  FOREIGN KEY (cambio_id) REFERENCES cambios(id),
+  FOREIGN KEY (vendedor_id) REFERENCES usuarios(id)
 );
 
 CREATE INDEX idx_ventas_folio ON ventas(folio);
@@ -178,6 +368,62 @@ CREATE INDEX idx_ventas_fecha ON ventas(fecha_hora);
 CREATE INDEX idx_ventas_usuario ON ventas(usuario_id);
 CREATE INDEX idx_ventas_estado ON ventas(estado);
 CREATE INDEX idx_ventas_metodo ON ventas(metodo_pago);
+CREATE INDEX idx_ventas_caja ON ventas(caja_id);
+CREATE INDEX idx_ventas_cupon ON ventas(cupon_id);
+CREATE INDEX idx_ventas_cliente ON ventas(cliente_id);
+CREATE INDEX idx_ventas_vendedor ON ventas(vendedor_id);
+CREATE UNIQUE INDEX idx_ventas_comprobante ON ventas(serie, correlativo) WHERE serie IS NOT NULL;
+CREATE INDEX idx_ventas_por_cobrar ON ventas(cliente_id, fecha_vencimiento_pago) WHERE saldo_pendiente > 0;
+
+-- =====================================================
+-- TABLA: pagos_venta
+-- Una línea por método de pago (ventas MIXTO incluidas)
+-- 🆕 CREDITO: la parte que el cliente queda debiendo (ver pagos_credito)
+-- =====================================================
+DROP TABLE IF EXISTS pagos_venta;
+CREATE TABLE pagos_venta (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  venta_id INTEGER NOT NULL,
+  metodo_pago TEXT NOT NULL CHECK(metodo_pago IN ('EFECTIVO', 'TARJETA', 'TRANSFERENCIA', 'PUNTOS', 'VALE', 'CREDITO', 'CAMBIO')),
+  monto REAL NOT NULL CHECK (monto > 0),      -- lo aplicado a la venta
+  monto_recibido REAL,                        -- solo EFECTIVO: lo que entregó el cliente
+  referencia TEXT,                            -- voucher de tarjeta / N° de operación / código del vale
+  fecha_hora TEXT DEFAULT (datetime('now', 'localtime')),
+  FOREIGN KEY (venta_id) REFERENCES ventas(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_pagos_venta_venta ON pagos_venta(venta_id);
+CREATE INDEX idx_pagos_venta_metodo ON pagos_venta(metodo_pago);
+
+-- =====================================================
+-- TABLA: pagos_credito 🆕 (cobros de ventas al crédito)
+-- Un cobro que cubre varias ventas deja una fila por venta con el mismo folio.
+-- Las devoluciones de ventas al crédito se descuentan de la deuda (DEVOLUCION)
+-- =====================================================
+DROP TABLE IF EXISTS pagos_credito;
+CREATE TABLE pagos_credito (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  venta_id INTEGER NOT NULL,
+  cliente_id INTEGER NOT NULL,
+  folio TEXT NOT NULL,                          -- recibo de cobro o folio de la devolución
+  monto REAL NOT NULL CHECK (monto > 0),
+  fecha_pago TEXT DEFAULT (datetime('now', 'localtime')),
+  metodo_pago TEXT NOT NULL CHECK(metodo_pago IN ('EFECTIVO', 'TARJETA', 'TRANSFERENCIA', 'DEVOLUCION')),
+  referencia TEXT,
+  notas TEXT,
+  caja_id INTEGER,
+  devolucion_id INTEGER,
+  usuario_id INTEGER NOT NULL,
+  FOREIGN KEY (venta_id) REFERENCES ventas(id),
+  FOREIGN KEY (cliente_id) REFERENCES clientes(id),
+  FOREIGN KEY (caja_id) REFERENCES cajas(id),
+  FOREIGN KEY (devolucion_id) REFERENCES devoluciones(id),
+  FOREIGN KEY (usuario_id) REFERENCES usuarios(id)
+);
+
+CREATE INDEX idx_pagos_credito_venta ON pagos_credito(venta_id);
+CREATE INDEX idx_pagos_credito_cliente ON pagos_credito(cliente_id);
+CREATE INDEX idx_pagos_credito_folio ON pagos_credito(folio);
 
 -- =====================================================
 -- TABLA: detalles_venta
@@ -192,17 +438,221 @@ CREATE TABLE detalles_venta (
   cantidad INTEGER NOT NULL CHECK (cantidad > 0),
   precio_unitario REAL NOT NULL CHECK (precio_unitario >= 0),
   subtotal REAL NOT NULL CHECK (subtotal >= 0),
-  descuento_linea REAL DEFAULT 0,
+  descuento_linea REAL DEFAULT 0,               -- manual + promociones
+  descuento_promocion REAL DEFAULT 0,           -- parte del descuento que viene de promociones
+  descuento_cupon REAL DEFAULT 0,               -- parte del cupón prorrateada a la línea
+  promociones_aplicadas TEXT,                   -- JSON con los id de promociones, ej. [3,7]
   total_linea REAL NOT NULL CHECK (total_linea >= 0),
+  afectacion_igv TEXT DEFAULT 'GRAVADO',        -- GRAVADO | EXONERADO | INAFECTO
+  valor_venta REAL DEFAULT 0,                   -- total_linea sin IGV
+  igv REAL DEFAULT 0,
+  vendedor_id INTEGER,                          -- 🆕 vendedor de la línea (si no, el de la venta)
   FOREIGN KEY (venta_id) REFERENCES ventas(id) ON DELETE CASCADE,
   FOREIGN KEY (producto_id) REFERENCES productos(id),
-  FOREIGN KEY (variante_id) REFERENCES producto_variantes(id)
+  FOREIGN KEY (variante_id) REFERENCES producto_variantes(id),
+  FOREIGN KEY (vendedor_id) REFERENCES usuarios(id)
 );
 
 CREATE INDEX idx_detalles_venta ON detalles_venta(venta_id);
 CREATE INDEX idx_detalles_producto ON detalles_venta(producto_id);
 CREATE INDEX idx_detalles_variante ON detalles_venta(variante_id);
 
+-- =====================================================
+-- TABLA: comprobantes_electronicos (boletas, facturas y notas de crédito SUNAT)
+-- =====================================================
+DROP TABLE IF EXISTS comprobantes_electronicos;
+CREATE TABLE comprobantes_electronicos (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  venta_id INTEGER NOT NULL,
+  nota_credito_id INTEGER,                      -- solo notas de crédito (tipo 07)
+  tipo_comprobante TEXT NOT NULL CHECK(tipo_comprobante IN ('01', '03', '07')), -- 01 factura, 03 boleta, 07 nota de crédito
+  serie TEXT NOT NULL,
+  correlativo INTEGER NOT NULL,
+  nombre_archivo TEXT NOT NULL,                 -- RUC-TIPO-SERIE-CORRELATIVO
+  xml_firmado TEXT NOT NULL,
+  hash TEXT,                                    -- DigestValue de la firma
+  zip BLOB,                                     -- paquete que se envía
+  estado TEXT DEFAULT 'FIRMADO' CHECK(estado IN ('FIRMADO', 'ACEPTADO', 'OBSERVADO', 'RECHAZADO', 'ERROR')),
+  codigo_respuesta TEXT,
+  descripcion_respuesta TEXT,
+  observaciones TEXT,
+  cdr_zip BLOB,                                 -- constancia de recepción
+  intentos_envio INTEGER DEFAULT 0,
+  fecha_emision TEXT DEFAULT (datetime('now', 'localtime')),
+  fecha_envio TEXT,
+  usuario_id INTEGER,
+  UNIQUE(tipo_comprobante, serie, correlativo),
+  FOREIGN KEY (venta_id) REFERENCES ventas(id),
+  FOREIGN KEY (nota_credito_id) REFERENCES notas_credito(id),
+  FOREIGN KEY (usuario_id) REFERENCES usuarios(id)
+);
+
+CREATE UNIQUE INDEX idx_comprobantes_venta ON comprobantes_electronicos(venta_id) WHERE nota_credito_id IS NULL;
+CREATE UNIQUE INDEX idx_comprobantes_nota_credito ON comprobantes_electronicos(nota_credito_id) WHERE nota_credito_id IS NOT NULL;
+CREATE INDEX idx_comprobantes_estado ON comprobantes_electronicos(estado);
+
+-- =====================================================
+-- TABLA: promociones
+-- NXM (2x1, 3x2), SEGUNDA_UNIDAD (2da al X%), DESCUENTO_CATEGORIA (X% a una
+-- categoría/producto) y DESCUENTO_MONTO (por compras desde S/ X, S/ Y o Z% menos)
+-- =====================================================
+DROP TABLE IF EXISTS promociones;
+CREATE TABLE promociones (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  nombre TEXT NOT NULL,
+  tipo TEXT NOT NULL CHECK(tipo IN ('NXM', 'SEGUNDA_UNIDAD', 'DESCUENTO_CATEGORIA', 'DESCUENTO_MONTO')),
+  lleva INTEGER,                                -- NXM: unidades que se llevan (N)
+  paga INTEGER,                                 -- NXM: unidades que se pagan (M)
+  porcentaje REAL CHECK (porcentaje IS NULL OR (porcentaje > 0 AND porcentaje <= 100)),
+  monto_minimo REAL,                            -- DESCUENTO_MONTO: compra mínima
+  monto_descuento REAL,                         -- DESCUENTO_MONTO: soles de descuento
+  categoria_id INTEGER,                         -- alcance (NULL = toda la tienda)
+  producto_id INTEGER,
+  fecha_inicio TEXT,                            -- 'YYYY-MM-DD', NULL = sin límite
+  fecha_fin TEXT,
+  dias_semana TEXT,                             -- '1,2,3' (1 = lunes), NULL = todos
+  hora_inicio TEXT,                             -- 'HH:MM', NULL = todo el día
+  hora_fin TEXT,
+  prioridad INTEGER DEFAULT 0,                  -- mayor prioridad se evalúa primero
+  acumulable INTEGER DEFAULT 0,                 -- 1 = se combina con otras promociones
+  activo INTEGER DEFAULT 1,
+  fecha_creacion TEXT DEFAULT (datetime('now', 'localtime')),
+  FOREIGN KEY (categoria_id) REFERENCES categorias(id),
+  FOREIGN KEY (producto_id) REFERENCES productos(id)
+);
+
+CREATE INDEX idx_promociones_activo ON promociones(activo);
+CREATE INDEX idx_promociones_vigencia ON promociones(fecha_inicio, fecha_fin);
+
+-- =====================================================
+-- TABLA: cupones (códigos de campañas en redes sociales)
+-- =====================================================
+DROP TABLE IF EXISTS cupones;
+CREATE TABLE cupones (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  codigo TEXT NOT NULL UNIQUE COLLATE NOCASE,
+  campana TEXT,                                 -- "Instagram Fiestas Patrias", etc.
+  tipo_descuento TEXT NOT NULL CHECK(tipo_descuento IN ('PORCENTAJE', 'MONTO')),
+  valor REAL NOT NULL CHECK (valor > 0),
+  monto_minimo REAL DEFAULT 0,                  -- compra mínima
+  categoria_id INTEGER,                         -- NULL = toda la tienda
+  limite_usos INTEGER,                          -- NULL = sin límite
+  limite_por_cliente INTEGER,                   -- NULL = sin límite
+  fecha_inicio TEXT,                            -- 'YYYY-MM-DD'
+  fecha_expiracion TEXT,
+  activo INTEGER DEFAULT 1,
+  fecha_creacion TEXT DEFAULT (datetime('now', 'localtime')),
+  FOREIGN KEY (categoria_id) REFERENCES categorias(id)
+);
+
+CREATE INDEX idx_cupones_codigo ON cupones(codigo);
+
+-- Una fila por venta que usó el cupón (las ventas anuladas no cuentan como uso)
+DROP TABLE IF EXISTS cupones_redenciones;
+CREATE TABLE cupones_redenciones (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  cupon_id INTEGER NOT NULL,
+  venta_id INTEGER NOT NULL,
+  cliente_id INTEGER,
+  monto_descuento REAL NOT NULL,
+  fecha_hora TEXT DEFAULT (datetime('now', 'localtime')),
+  FOREIGN KEY (cupon_id) REFERENCES cupones(id),
+  FOREIGN KEY (venta_id) REFERENCES ventas(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_cupones_redenciones_cupon ON cupones_redenciones(cupon_id);
+CREATE INDEX idx_cupones_redenciones_cliente ON cupones_redenciones(cliente_id);
+
+-- =====================================================
+-- TABLA: ventas_suspendidas
+-- Carritos en espera (cliente en el probador). Expiran al cerrar la caja.
+-- =====================================================
+DROP TABLE IF EXISTS ventas_suspendidas;
+CREATE TABLE ventas_suspendidas (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  caja_id INTEGER NOT NULL,
+  usuario_id INTEGER NOT NULL,
+  etiqueta_cliente TEXT,                      -- "señora de casaca roja", nombre, etc.
+  total REAL NOT NULL DEFAULT 0,
+  reservar_stock INTEGER DEFAULT 0,           -- 1 = las prendas no se venden a otro cliente
+  estado TEXT DEFAULT 'ACTIVA' CHECK(estado IN ('ACTIVA', 'RECUPERADA', 'DESCARTADA', 'EXPIRADA')),
+  fecha_suspension TEXT DEFAULT (datetime('now', 'localtime')),
+  fecha_cierre TEXT,
+  FOREIGN KEY (caja_id) REFERENCES cajas(id),
+  FOREIGN KEY (usuario_id) REFERENCES usuarios(id)
+);
+
+CREATE INDEX idx_ventas_suspendidas_caja ON ventas_suspendidas(caja_id);
+CREATE INDEX idx_ventas_suspendidas_estado ON ventas_suspendidas(estado);
+
+DROP TABLE IF EXISTS detalles_venta_suspendida;
+CREATE TABLE detalles_venta_suspendida (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  venta_suspendida_id INTEGER NOT NULL,
+  producto_id INTEGER NOT NULL,
+  variante_id INTEGER,
+  talla TEXT,
+  nombre TEXT NOT NULL,
+  codigo TEXT NOT NULL,
+  cantidad INTEGER NOT NULL CHECK (cantidad > 0),
+  precio_unitario REAL NOT NULL CHECK (precio_unitario >= 0),
+  descuento_porcentaje REAL DEFAULT 0,
+  FOREIGN KEY (venta_suspendida_id) REFERENCES ventas_suspendidas(id) ON DELETE CASCADE,
+  FOREIGN KEY (producto_id) REFERENCES productos(id),
+  FOREIGN KEY (variante_id) REFERENCES producto_variantes(id)
+);
+
+CREATE INDEX idx_detalles_suspendida ON detalles_venta_suspendida(venta_suspendida_id);
+CREATE INDEX idx_detalles_suspendida_variante ON detalles_venta_suspendida(variante_id);
+
+-- =====================================================
+-- TABLA: cotizaciones 🆕 (presupuestos para empresas: uniformes, eventos)
+-- Se convierten en venta con procesar_venta; VENCIDA se calcula con fecha_validez
+-- =====================================================
+DROP TABLE IF EXISTS cotizaciones;
+CREATE TABLE cotizaciones (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  folio TEXT NOT NULL UNIQUE,
+  cliente_id INTEGER,
+  contacto TEXT,                                -- persona o empresa si no es cliente registrado
+  subtotal REAL NOT NULL DEFAULT 0,
+  descuento REAL DEFAULT 0,
+  total REAL NOT NULL DEFAULT 0,
+  fecha_emision TEXT DEFAULT (datetime('now', 'localtime')),
+  fecha_validez TEXT NOT NULL,                  -- 'YYYY-MM-DD', último día en que se respeta
+  estado TEXT DEFAULT 'VIGENTE' CHECK(estado IN ('VIGENTE', 'CONVERTIDA', 'ANULADA')),
+  notas TEXT,
+  usuario_id INTEGER NOT NULL,
+  venta_id INTEGER,
+  fecha_conversion TEXT,
+  FOREIGN KEY (cliente_id) REFERENCES clientes(id),
+  FOREIGN KEY (usuario_id) REFERENCES usuarios(id),
+  FOREIGN KEY (venta_id) REFERENCES ventas(id)
+);
+
+CREATE INDEX idx_cotizaciones_cliente ON cotizaciones(cliente_id);
+CREATE INDEX idx_cotizaciones_estado ON cotizaciones(estado);
+
+DROP TABLE IF EXISTS detalles_cotizacion;
+CREATE TABLE detalles_cotizacion (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  cotizacion_id INTEGER NOT NULL,
+  producto_id INTEGER NOT NULL,
+  variante_id INTEGER,
+  talla TEXT,
+  nombre TEXT NOT NULL,
+  codigo TEXT NOT NULL,
+  cantidad INTEGER NOT NULL CHECK (cantidad > 0),
+  precio_unitario REAL NOT NULL CHECK (precio_unitario >= 0),
+  descuento_porcentaje REAL DEFAULT 0,
+  total_linea REAL NOT NULL,
+  FOREIGN KEY (cotizacion_id) REFERENCES cotizaciones(id) ON DELETE CASCADE,
+  FOREIGN KEY (producto_id) REFERENCES productos(id),
+  FOREIGN KEY (variante_id) REFERENCES producto_variantes(id)
+);
+
+CREATE INDEX idx_detalles_cotizacion ON detalles_cotizacion(cotizacion_id);
+
 -- =====================================================
 -- TABLA: devoluciones (clientes)
 -- =====================================================
@@ -213,16 +663,25 @@ CREATE TABLE devoluciones (
   folio_devolucion TEXT NOT NULL UNIQUE,
   fecha_hora TEXT DEFAULT (datetime('now', 'localtime')),
   usuario_id INTEGER NOT NULL,
+  caja_id INTEGER,                              -- 🆕 caja abierta de quien la procesó
   monto_reembolsado REAL NOT NULL CHECK (monto_reembolsado >= 0),
-  metodo_reembolso TEXT NOT NULL CHECK(metodo_reembolso IN ('EFECTIVO', 'TARJETA', 'VALE', 'CREDITO')),
+  metodo_reembolso TEXT NOT NULL CHECK(metodo_reembolso IN ('EFECTIVO', 'TARJETA', 'VALE', 'CREDITO', 'CAMBIO')),
   motivo TEXT NOT NULL,
+  op_gravada REAL DEFAULT 0,
+  op_exonerada REAL DEFAULT 0,
+  op_inafecta REAL DEFAULT 0,
+  igv REAL DEFAULT 0,
   estado TEXT DEFAULT 'PROCESADA' CHECK(estado IN ('PROCESADA', 'PENDIENTE', 'RECHAZADA')),
   notas TEXT,
+  cambio_id INTEGER,                            -- 🆕 devolución de un cambio de talla (CAMBIO)
   FOREIGN KEY (venta_original_id) REFERENCES ventas(id),
-  FOREIGN KEY (usuario_id) REFERENCES usuarios(id)
+  FOREIGN KEY (usuario_id) REFERENCES usuarios(id),
+  FOREIGN KEY (caja_id) REFERENCES cajas(id),
+  FOREIGN KEY (cambio_id) REFERENCES cambios(id)
 );
 
 CREATE INDEX idx_devoluciones_venta ON devoluciones(venta_original_id);
+CREATE INDEX idx_devoluciones_caja ON devoluciones(caja_id);
 CREATE INDEX idx_devoluciones_folio ON devoluciones(folio_devolucion);
 CREATE INDEX idx_devoluciones_fecha ON devoluciones(fecha_hora);
 
@@ -237,20 +696,170 @@ CREATE TABLE detalles_devolucion (
   variante_id INTEGER,
   talla TEXT,
   venta_id INTEGER NOT NULL,
+  detalle_venta_id INTEGER,                     -- línea exacta de la venta (misma prenda en dos tallas)
   cantidad_devuelta INTEGER NOT NULL CHECK (cantidad_devuelta > 0),
   precio_unitario REAL NOT NULL,
   subtotal REAL NOT NULL CHECK (subtotal >= 0),
+  valor_venta REAL DEFAULT 0,                   -- subtotal sin IGV
+  igv REAL DEFAULT 0,
   condicion TEXT NOT NULL CHECK(condicion IN ('REVENTA', 'DEFECTUOSO', 'VENCIDO')),
   FOREIGN KEY (devolucion_id) REFERENCES devoluciones(id) ON DELETE CASCADE,
   FOREIGN KEY (producto_id) REFERENCES productos(id),
-  FOREIGN KEY (variante_id) REFERENCES producto_variantes(id)
+  FOREIGN KEY (variante_id) REFERENCES producto_variantes(id),
+  FOREIGN KEY (detalle_venta_id) REFERENCES detalles_venta(id)
 );
 
 CREATE INDEX idx_detalles_devolucion ON detalles_devolucion(devolucion_id);
+CREATE INDEX idx_detalles_devolucion_detalle ON detalles_devolucion(detalle_venta_id);
 CREATE INDEX idx_detalles_devolucion_producto ON detalles_devolucion(producto_id);
 
+-- =====================================================
+-- TABLA: cambios 🆕 (cambio de talla / producto)
+-- Une la devolución (metodo_reembolso CAMBIO) y la venta de reemplazo
+-- (pagada con CAMBIO); solo la diferencia mueve dinero
+-- =====================================================
+DROP TABLE IF EXISTS cambios;
+CREATE TABLE cambios (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  folio TEXT NOT NULL UNIQUE,                   -- CAM-YYYYMMDD-####
+  venta_original_id INTEGER NOT NULL,
+  monto_devuelto REAL NOT NULL CHECK (monto_devuelto >= 0), -- lo pagado por las prendas devueltas
+  monto_nuevo REAL NOT NULL CHECK (monto_nuevo >= 0),       -- total de la venta de reemplazo
+  diferencia REAL NOT NULL,                     -- nuevo - devuelto (> 0 paga el cliente, < 0 se le devuelve)
+  metodo_diferencia TEXT CHECK(metodo_diferencia IN ('EFECTIVO', 'TARJETA', 'TRANSFERENCIA', 'VALE')),
+  redondeo REAL DEFAULT 0,                      -- redondeo del efectivo de la diferencia (cobrado o entregado - exacto)
+  motivo TEXT,
+  usuario_id INTEGER NOT NULL,
+  caja_id INTEGER NOT NULL,
+  fecha_hora TEXT DEFAULT (datetime('now', 'localtime')),
+  FOREIGN KEY (venta_original_id) REFERENCES ventas(id),
+  FOREIGN KEY (usuario_id) REFERENCES usuarios(id),
+  FOREIGN KEY (caja_id) REFERENCES cajas(id)
+);
+
+CREATE INDEX idx_cambios_venta ON cambios(venta_original_id);
+CREATE INDEX idx_cambios_fecha ON cambios(fecha_hora);
+
+-- =====================================================
+-- TABLA: reglas_comision 🆕 (comisiones de vendedores)
+-- PORCENTAJE: % sobre lo cobrado por línea; la regla de la categoría gana a
+--             la general y la del vendedor a la de todos
+-- BONO_META:  bono si las ventas netas del periodo del reporte llegan a la meta
+-- =====================================================
+DROP TABLE IF EXISTS reglas_comision;
+CREATE TABLE reglas_comision (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  nombre TEXT NOT NULL,
+  tipo TEXT NOT NULL CHECK(tipo IN ('PORCENTAJE', 'BONO_META')),
+  categoria_id INTEGER,                         -- PORCENTAJE: NULL = todas las categorías
+  vendedor_id INTEGER,                          -- NULL = todos los vendedores
+  porcentaje REAL DEFAULT 0 CHECK (porcentaje >= 0 AND porcentaje <= 100), -- BONO_META: % sobre el exceso de la meta
+  meta REAL,                                    -- BONO_META: ventas netas del periodo
+  bono REAL DEFAULT 0 CHECK (bono >= 0),        -- BONO_META: monto fijo al llegar a la meta
+  activo INTEGER DEFAULT 1,
+  fecha_creacion TEXT DEFAULT (datetime('now', 'localtime')),
+  FOREIGN KEY (categoria_id) REFERENCES categorias(id),
+  FOREIGN KEY (vendedor_id) REFERENCES usuarios(id)
+);
+
+-- =====================================================
+-- TABLA: comisiones 🆕 (libro: una fila por línea vendida; las devoluciones
+-- y anulaciones agregan su REVERSION en negativo)
+-- =====================================================
+DROP TABLE IF EXISTS comisiones;
+CREATE TABLE comisiones (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  vendedor_id INTEGER NOT NULL,
+  venta_id INTEGER NOT NULL,
+  detalle_venta_id INTEGER NOT NULL,
+  devolucion_id INTEGER,                        -- REVERSION por devolución (NULL = anulación)
+  regla_id INTEGER,                             -- NULL = sin regla (0%), igual cuenta para la meta
+  tipo TEXT NOT NULL CHECK(tipo IN ('VENTA', 'REVERSION')),
+  base REAL NOT NULL,                           -- total de la línea cobrado
+  porcentaje REAL DEFAULT 0,
+  monto REAL NOT NULL,
+  fecha_hora TEXT DEFAULT (datetime('now', 'localtime')),
+  FOREIGN KEY (vendedor_id) REFERENCES usuarios(id),
+  FOREIGN KEY (venta_id) REFERENCES ventas(id),
+  FOREIGN KEY (detalle_venta_id) REFERENCES detalles_venta(id),
+  FOREIGN KEY (devolucion_id) REFERENCES devoluciones(id),
+  FOREIGN KEY (regla_id) REFERENCES reglas_comision(id)
+);
+
+CREATE INDEX idx_comisiones_vendedor ON comisiones(vendedor_id, fecha_hora);
+CREATE INDEX idx_comisiones_detalle ON comisiones(detalle_venta_id);
+
+-- =====================================================
+-- TABLA: operaciones_idempotentes 🆕 (reintentos del frontend: la misma
+-- clave devuelve el resultado guardado en vez de repetir la operación)
+-- =====================================================
+DROP TABLE IF EXISTS operaciones_idempotentes;
+CREATE TABLE operaciones_idempotentes (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  clave TEXT NOT NULL,                          -- generada por el frontend (UUID por envío)
+  operacion TEXT NOT NULL CHECK(operacion IN ('VENTA', 'DEVOLUCION', 'COMPRA', 'PAGO_COMPRA')),
+  registro_id INTEGER NOT NULL,                 -- venta, devolución, compra o pago creado
+  resultado TEXT NOT NULL,                      -- JSON devuelto la primera vez
+  fecha_hora TEXT DEFAULT (datetime('now', 'localtime'))
+);
+
+CREATE UNIQUE INDEX idx_operaciones_clave ON operaciones_idempotentes(clave);
+
+-- =====================================================
+-- TABLA: notas_credito 🆕 (devoluciones de ventas con boleta o factura)
+-- Montos proporcionales a las líneas del comprobante original
+-- =====================================================
+DROP TABLE IF EXISTS notas_credito;
+CREATE TABLE notas_credito (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  devolucion_id INTEGER UNIQUE,
+  venta_id INTEGER NOT NULL,
+  serie TEXT NOT NULL,                          -- BC01 (boletas), FC01 (facturas)
+  correlativo INTEGER NOT NULL,
+  tipo_documento_ref TEXT NOT NULL CHECK(tipo_documento_ref IN ('BOLETA', 'FACTURA')),
+  serie_ref TEXT NOT NULL,                      -- comprobante que se modifica
+  correlativo_ref INTEGER NOT NULL,
+  codigo_motivo TEXT NOT NULL,                  -- catálogo 09 de SUNAT (06 devolución total, 07 por ítem...)
+  sustento TEXT NOT NULL,
+  op_gravada REAL DEFAULT 0,
+  op_exonerada REAL DEFAULT 0,
+  op_inafecta REAL DEFAULT 0,
+  igv REAL DEFAULT 0,
+  igv_porcentaje REAL DEFAULT 0,
+  total REAL NOT NULL CHECK (total >= 0),
+  usuario_id INTEGER NOT NULL,
+  fecha_emision TEXT DEFAULT (datetime('now', 'localtime')),
+  UNIQUE(serie, correlativo),
+  FOREIGN KEY (devolucion_id) REFERENCES devoluciones(id),
+  FOREIGN KEY (venta_id) REFERENCES ventas(id),
+  FOREIGN KEY (usuario_id) REFERENCES usuarios(id)
+);
+
+CREATE INDEX idx_notas_credito_venta ON notas_credito(venta_id);
+
+DROP TABLE IF EXISTS detalles_nota_credito;
+CREATE TABLE detalles_nota_credito (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  nota_credito_id INTEGER NOT NULL,
+  detalle_venta_id INTEGER NOT NULL,
+  producto_id INTEGER NOT NULL,
+  cantidad INTEGER NOT NULL CHECK (cantidad > 0),
+  total_linea REAL NOT NULL,                    -- con IGV
+  valor_venta REAL NOT NULL,                    -- sin IGV
+  igv REAL NOT NULL,
+  afectacion_igv TEXT DEFAULT 'GRAVADO',
+  FOREIGN KEY (nota_credito_id) REFERENCES notas_credito(id) ON DELETE CASCADE,
+  FOREIGN KEY (detalle_venta_id) REFERENCES detalles_venta(id),
+  FOREIGN KEY (producto_id) REFERENCES productos(id)
+);
+
+CREATE INDEX idx_detalles_nota_credito ON detalles_nota_credito(nota_credito_id);
+CREATE INDEX idx_detalles_nota_credito_detalle ON detalles_nota_credito(detalle_venta_id);
+
 -- =====================================================
 -- TABLA: movimientos_inventario
+-- 🆕 CUARENTENA entra al stock en cuarentena; MERMA y DEVOLUCION_PROVEEDOR
+-- salen de él (stock_anterior/stock_nuevo son los de la cuarentena)
 -- =====================================================
 DROP TABLE IF EXISTS movimientos_inventario;
 CREATE TABLE movimientos_inventario (
@@ -258,7 +867,7 @@ CREATE TABLE movimientos_inventario (
   producto_id INTEGER NOT NULL,
   variante_id INTEGER,
   talla TEXT,
-  tipo_movimiento TEXT NOT NULL CHECK(tipo_movimiento IN ('VENTA', 'DEVOLUCION', 'ENTRADA', 'SALIDA', 'AJUSTE', 'MERMA')),
+  tipo_movimiento TEXT NOT NULL CHECK(tipo_movimiento IN ('VENTA', 'DEVOLUCION', 'ENTRADA', 'SALIDA', 'AJUSTE', 'MERMA', 'ANULACION', 'CUARENTENA', 'DEVOLUCION_PROVEEDOR')),
   cantidad INTEGER NOT NULL,
   stock_anterior INTEGER NOT NULL,
   stock_nuevo INTEGER NOT NULL,
@@ -447,6 +1056,7 @@ CREATE TABLE detalles_devolucion_proveedor (
   precio_compra REAL NOT NULL,
   subtotal REAL NOT NULL,
   motivo_item TEXT,
+  desde_cuarentena INTEGER DEFAULT 0,           -- 🆕 1 = sale del stock en cuarentena (vuelve si se rechaza)
   FOREIGN KEY (devolucion_proveedor_id) REFERENCES devoluciones_proveedor(id) ON DELETE CASCADE,
   FOREIGN KEY (detalle_compra_id)       REFERENCES detalles_compra(id),
   FOREIGN KEY (producto_id)             REFERENCES productos(id),
@@ -474,15 +1084,71 @@ CREATE TABLE configuracion_tienda (
   mensaje_recibo TEXT,
   moneda TEXT DEFAULT 'PEN',
   formato_folio TEXT DEFAULT 'V-{YYYY}{MM}{DD}-{####}',
-  iva_porcentaje REAL DEFAULT 0,
-  dias_devolucion INTEGER DEFAULT 7,
+  iva_porcentaje REAL DEFAULT 18,               -- IGV; los precios ya lo incluyen
+  dias_devolucion INTEGER DEFAULT 7,           -- plazo para devoluciones y cambios desde la venta
   backup_automatico INTEGER DEFAULT 1,
   hora_backup TEXT DEFAULT '23:00:00',
   impresora_ip TEXT DEFAULT '',
   impresora_tipo TEXT DEFAULT 'TERMICA',
   impresora_puerto INTEGER DEFAULT 9100,
+  puntos_por_sol REAL DEFAULT 1,                -- puntos ganados por cada S/ 1 (0 = programa apagado)
+  valor_punto REAL DEFAULT 0.05,                -- S/ que vale cada punto al canjear
+  puntos_excluir_descuentos INTEGER DEFAULT 0,  -- 1 = las líneas con descuento no suman puntos
+  puntos_meses_vencimiento INTEGER DEFAULT 12,
+  vales_meses_vencimiento INTEGER DEFAULT 12,   -- vigencia de tarjetas de regalo y vales
+  redondeo_efectivo TEXT DEFAULT 'NINGUNO'      -- pagos en efectivo a S/ 0.10 (no circulan monedas de 1 y 5 céntimos)
+    CHECK(redondeo_efectivo IN ('NINGUNO', 'CERCANO', 'ABAJO')),
+  razon_social TEXT,                            -- facturación electrónica (RUC en rfc)
+  nombre_comercial TEXT,
+  ubigeo TEXT DEFAULT '',
+  cpe_certificado_ruta TEXT,                    -- certificado digital .pfx
+  cpe_certificado_clave TEXT,
+  cpe_usuario_sol TEXT,
+  cpe_clave_sol TEXT,
+  cpe_endpoint TEXT DEFAULT 'https://e-beta.sunat.gob.pe/ol-ti-itcpfegem-beta/billService',
   fecha_actualizacion TEXT DEFAULT (datetime('now', 'localtime'))
 );
+
+-- =====================================================
+-- TABLA: numeracion_documentos (formato de folio por tipo de documento)
+-- Tokens: {YYYY} {YY} {MM} {DD} y {####} para el correlativo
+-- =====================================================
+DROP TABLE IF EXISTS numeracion_documentos;
+CREATE TABLE numeracion_documentos (
+  tipo_documento TEXT PRIMARY KEY,       -- VENTA | DEVOLUCION | COMPRA | DEVOLUCION_PROVEEDOR | COTIZACION | COBRO
+  descripcion TEXT,
+  formato TEXT NOT NULL,
+  reinicio TEXT NOT NULL DEFAULT 'DIARIO' CHECK(reinicio IN ('DIARIO', 'MENSUAL', 'NUNCA')),
+  fecha_actualizacion TEXT DEFAULT (datetime('now', 'localtime'))
+);
+
+-- Último número asignado por tipo y periodo (YYYYMMDD, YYYYMM o '' si no reinicia)
+DROP TABLE IF EXISTS contadores_documentos;
+CREATE TABLE contadores_documentos (
+  tipo_documento TEXT NOT NULL,
+  periodo TEXT NOT NULL,
+  ultimo_numero INTEGER NOT NULL DEFAULT 0,
+  fecha_actualizacion TEXT DEFAULT (datetime('now', 'localtime')),
+  PRIMARY KEY (tipo_documento, periodo),
+  FOREIGN KEY (tipo_documento) REFERENCES numeracion_documentos(tipo_documento)
+);
+
+-- =====================================================
+-- TABLA: series_comprobante (series de boleta, factura, nota de venta y nota de crédito por caja)
+-- Cada serie lleva su propio correlativo; una caja usa una serie activa por tipo
+-- =====================================================
+DROP TABLE IF EXISTS series_comprobante;
+CREATE TABLE series_comprobante (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  tipo_comprobante TEXT NOT NULL CHECK(tipo_comprobante IN ('BOLETA', 'FACTURA', 'NOTA_VENTA', 'NOTA_CREDITO_BOLETA', 'NOTA_CREDITO_FACTURA')),
+  serie TEXT NOT NULL UNIQUE,                   -- B001, F001, NV01, BC01, FC01
+  numero_caja INTEGER NOT NULL DEFAULT 1,       -- cajas.numero_caja
+  ultimo_correlativo INTEGER NOT NULL DEFAULT 0,
+  activo INTEGER DEFAULT 1,
+  fecha_creacion TEXT DEFAULT (datetime('now', 'localtime'))
+);
+
+CREATE UNIQUE INDEX idx_series_caja_activa ON series_comprobante(tipo_comprobante, numero_caja) WHERE activo = 1;
 -- =====================================================
 -- TABLA: auditoria
 -- =====================================================
@@ -556,6 +1222,7 @@ CREATE TABLE cajas (
   ingresos_total REAL DEFAULT 0,
   gastos_total REAL DEFAULT 0,
   cambio_total REAL DEFAULT 0,
+  redondeo_total REAL DEFAULT 0,  -- 🆕 suma de ventas.redondeo del turno
   efectivo_esperado REAL,
   diferencia REAL,
   estado_diferencia TEXT CHECK(estado_diferencia IN ('SIN_DIFERENCIA', 'ACEPTABLE', 'SIGNIFICATIVA')),
@@ -682,9 +1349,28 @@ INSERT INTO categorias (nombre, descripcion, tipo_talla, activo) VALUES
 ('Ropa de Niña',              'Ropa para niñas de todas las edades',         'ROPA',    1),
 ('Ofertas y Promociones',     'Productos en oferta y liquidación',           'NINGUNA', 1);
 
+INSERT INTO listas_precios (nombre, descripcion, predeterminada) VALUES
+('Minorista', 'Precio de tienda', 1);
+
 INSERT INTO configuracion_tienda (nombre_tienda, direccion, telefono, email, rfc, mensaje_recibo, moneda) VALUES
 ('Mi Tienda de Ropa', 'Dirección de tu tienda', '(555) 123-4567', 'contacto@mitienda.com', 'XAXX010101000', '¡Gracias por su compra! Vuelva pronto.', 'PEN');
 
+INSERT INTO numeracion_documentos (tipo_documento, descripcion, formato, reinicio) VALUES
+('VENTA',                'Ventas',                      'V-{YYYY}{MM}{DD}-{####}',   'DIARIO'),
+('DEVOLUCION',           'Devoluciones de clientes',    'DEV-{YYYY}{MM}{DD}-{####}', 'DIARIO'),
+('COMPRA',               'Compras a proveedores',       'C-{YYYY}{MM}{DD}-{####}',   'DIARIO'),
+('DEVOLUCION_PROVEEDOR', 'Devoluciones a proveedores',  'DP-{YYYY}{MM}{DD}-{####}',  'DIARIO'),
+('COTIZACION',           'Cotizaciones',                'COT-{YYYY}{MM}-{####}',     'MENSUAL'),
+('COBRO',                'Cobros de ventas al crédito', 'COB-{YYYY}{MM}{DD}-{####}', 'DIARIO'),
+('CAMBIO',               'Cambios de talla',            'CAM-{YYYY}{MM}{DD}-{####}', 'DIARIO');
+
+INSERT INTO series_comprobante (tipo_comprobante, serie, numero_caja) VALUES
+('BOLETA',               'B001', 1),
+('FACTURA',              'F001', 1),
+('NOTA_VENTA',           'NV01', 1),
+('NOTA_CREDITO_BOLETA',  'BC01', 1),
+('NOTA_CREDITO_FACTURA', 'FC01', 1);
+
 INSERT INTO licencias (fecha_instalacion, fecha_expiracion, tipo_licencia, estado, version_app) VALUES (
   datetime('now', 'localtime'),
   datetime('now', 'localtime', '+15 days'),
@@ -780,6 +1466,61 @@ BEGIN
   );
 END;
 
+-- 🆕 Defectuoso o vencido: al stock en cuarentena, no al vendible
+CREATE TRIGGER trg_after_devolucion_cuarentena
+AFTER INSERT ON detalles_devolucion
+FOR EACH ROW
+WHEN NEW.condicion IN ('DEFECTUOSO', 'VENCIDO')
+BEGIN
+  UPDATE producto_variantes
+  SET stock_cuarentena = stock_cuarentena + NEW.cantidad_devuelta,
+      fecha_actualizacion = datetime('now', 'localtime')
+  WHERE id = NEW.variante_id AND NEW.variante_id IS NOT NULL;
+
+  UPDATE productos
+  SET stock_cuarentena = stock_cuarentena + NEW.cantidad_devuelta,
+      fecha_actualizacion = datetime('now', 'localtime')
+  WHERE id = NEW.producto_id AND NEW.variante_id IS NULL;
+
+  INSERT INTO movimientos_inventario (
+    producto_id, variante_id, talla, tipo_movimiento,
+    cantidad, stock_anterior, stock_nuevo, devolucion_id, usuario_id, motivo
+  ) VALUES (
+    NEW.producto_id, NEW.variante_id, NEW.talla, 'CUARENTENA', NEW.cantidad_devuelta,
+    CASE WHEN NEW.variante_id IS NOT NULL
+      THEN (SELECT stock_cuarentena - NEW.cantidad_devuelta FROM producto_variantes WHERE id = NEW.variante_id)
+      ELSE (SELECT stock_cuarentena - NEW.cantidad_devuelta FROM productos WHERE id = NEW.producto_id) END,
+    CASE WHEN NEW.variante_id IS NOT NULL
+      THEN (SELECT stock_cuarentena FROM producto_variantes WHERE id = NEW.variante_id)
+      ELSE (SELECT stock_cuarentena FROM productos WHERE id = NEW.producto_id) END,
+    NEW.devolucion_id,
+    (SELECT usuario_id FROM devoluciones WHERE id = NEW.devolucion_id),
+    'Devolución - Condición: ' || NEW.condicion
+  );
+END;
+
+-- =====================================================
+-- TRIGGERS DE VENTAS AL CRÉDITO 🆕
+-- =====================================================
+
+-- Recalcular saldo de la venta al registrar un cobro o una devolución
+CREATE TRIGGER trg_actualizar_saldo_venta
+AFTER INSERT ON pagos_credito
+FOR EACH ROW
+BEGIN
+  UPDATE ventas
+  SET
+    saldo_pendiente = MAX(0, ROUND(
+      (SELECT COALESCE(SUM(monto), 0) FROM pagos_venta WHERE venta_id = NEW.venta_id AND metodo_pago = 'CREDITO')
+      - (SELECT COALESCE(SUM(monto), 0) FROM pagos_credito WHERE venta_id = NEW.venta_id), 2)),
+    estado_pago = CASE
+      WHEN (SELECT COALESCE(SUM(monto), 0) FROM pagos_venta WHERE venta_id = NEW.venta_id AND metodo_pago = 'CREDITO')
+           <= (SELECT COALESCE(SUM(monto), 0) FROM pagos_credito WHERE venta_id = NEW.venta_id) + 0.005 THEN 'PAGADO'
+      ELSE 'PARCIAL'
+    END
+  WHERE id = NEW.venta_id;
+END;
+
 -- =====================================================
 -- TRIGGERS DE COMPRAS v1.5
 -- =====================================================
@@ -979,39 +1720,86 @@ BEGIN
 END;
 
 -- Triggers de caja
+-- Los totales por método se actualizan desde pagos_venta (trg_actualizar_caja_pago_venta)
 DROP TRIGGER IF EXISTS trg_actualizar_caja_venta;
 CREATE TRIGGER trg_actualizar_caja_venta
 AFTER INSERT ON ventas
 FOR EACH ROW
-WHEN NEW.estado = 'COMPLETADA'
+WHEN NEW.estado = 'COMPLETADA' AND NEW.cambio_id IS NULL
 BEGIN
   UPDATE cajas
   SET
-    ventas_efectivo      = ventas_efectivo      + CASE WHEN NEW.metodo_pago = 'EFECTIVO'      THEN NEW.total ELSE 0 END,
-    ventas_tarjeta       = ventas_tarjeta       + CASE WHEN NEW.metodo_pago = 'TARJETA'       THEN NEW.total ELSE 0 END,
-    ventas_transferencia = ventas_transferencia + CASE WHEN NEW.metodo_pago = 'TRANSFERENCIA' THEN NEW.total ELSE 0 END,
     total_ventas         = total_ventas         + NEW.total,
     numero_transacciones = numero_transacciones + 1,
     cambio_total         = cambio_total         + COALESCE(NEW.cambio, 0),
+    redondeo_total       = redondeo_total       + COALESCE(NEW.redondeo, 0),
     ticket_promedio      = CASE WHEN numero_transacciones + 1 > 0
                            THEN (total_ventas + NEW.total) / (numero_transacciones + 1) ELSE 0 END
-  WHERE usuario_id = NEW.usuario_id AND estado = 'ABIERTA'
-    AND date(fecha_apertura) = date(NEW.fecha_hora);
+  WHERE id = NEW.caja_id;
+END;
+
+-- 🆕 Venta de reemplazo de un cambio de talla: lo pagado con la prenda
+-- devuelta no es venta nueva; a la caja solo entra la diferencia cobrada
+DROP TRIGGER IF EXISTS trg_actualizar_caja_venta_cambio;
+CREATE TRIGGER trg_actualizar_caja_venta_cambio
+AFTER INSERT ON ventas
+FOR EACH ROW
+WHEN NEW.estado = 'COMPLETADA' AND NEW.cambio_id IS NOT NULL
+     AND (SELECT diferencia FROM cambios WHERE id = NEW.cambio_id) > 0
+BEGIN
+  UPDATE cajas
+  SET
+    total_ventas         = total_ventas         + (SELECT diferencia FROM cambios WHERE id = NEW.cambio_id),
+    numero_transacciones = numero_transacciones + 1,
+    cambio_total         = cambio_total         + COALESCE(NEW.cambio, 0),
+    redondeo_total       = redondeo_total       + COALESCE(NEW.redondeo, 0),
+    ticket_promedio      = (total_ventas + (SELECT diferencia FROM cambios WHERE id = NEW.cambio_id))
+                           / (numero_transacciones + 1)
+  WHERE id = NEW.caja_id;
+END;
+
+DROP TRIGGER IF EXISTS trg_actualizar_caja_pago_venta;
+CREATE TRIGGER trg_actualizar_caja_pago_venta
+AFTER INSERT ON pagos_venta
+FOR EACH ROW
+WHEN (SELECT estado FROM ventas WHERE id = NEW.venta_id) = 'COMPLETADA'
+BEGIN
+  UPDATE cajas
+  SET
+    ventas_efectivo      = ventas_efectivo      + CASE WHEN NEW.metodo_pago = 'EFECTIVO'      THEN NEW.monto ELSE 0 END,
+    ventas_tarjeta       = ventas_tarjeta       + CASE WHEN NEW.metodo_pago = 'TARJETA'       THEN NEW.monto ELSE 0 END,
+    ventas_transferencia = ventas_transferencia + CASE WHEN NEW.metodo_pago = 'TRANSFERENCIA' THEN NEW.monto ELSE 0 END
+  WHERE id = (SELECT caja_id FROM ventas WHERE id = NEW.venta_id);
 END;
 
 DROP TRIGGER IF EXISTS trg_actualizar_caja_devolucion;
 CREATE TRIGGER trg_actualizar_caja_devolucion
 AFTER INSERT ON devoluciones
 FOR EACH ROW
-WHEN NEW.estado = 'PROCESADA'
+WHEN NEW.estado = 'PROCESADA' AND NEW.metodo_reembolso <> 'CAMBIO'
 BEGIN
   UPDATE cajas
   SET
     devoluciones_monto    = devoluciones_monto + NEW.monto_reembolsado,
     devoluciones_cantidad = devoluciones_cantidad + 1,
     ventas_efectivo       = ventas_efectivo - CASE WHEN NEW.metodo_reembolso = 'EFECTIVO' THEN NEW.monto_reembolsado ELSE 0 END
-  WHERE usuario_id = NEW.usuario_id AND estado = 'ABIERTA'
-    AND date(fecha_apertura) = date(NEW.fecha_hora);
+  WHERE id = NEW.caja_id;
+END;
+
+-- 🆕 Cambio de talla: la prenda devuelta paga la nueva (no es reembolso);
+-- a la caja solo le afecta la diferencia devuelta en efectivo. La cobrada
+-- entra como pago normal de la venta de reemplazo.
+DROP TRIGGER IF EXISTS trg_actualizar_caja_cambio;
+CREATE TRIGGER trg_actualizar_caja_cambio
+AFTER INSERT ON cambios
+FOR EACH ROW
+WHEN NEW.diferencia < 0 AND NEW.metodo_diferencia = 'EFECTIVO'
+BEGIN
+  UPDATE cajas
+  SET
+    ventas_efectivo = ventas_efectivo + NEW.diferencia,
+    redondeo_total  = redondeo_total  - COALESCE(NEW.redondeo, 0)
+  WHERE id = NEW.caja_id;
 END;
 
 DROP TRIGGER IF EXISTS trg_actualizar_movimientos_caja;
@@ -1035,13 +1823,32 @@ WHEN OLD.estado = 'COMPLETADA' AND NEW.estado = 'CANCELADA'
 BEGIN
   UPDATE cajas
   SET
-    ventas_efectivo      = ventas_efectivo      - CASE WHEN NEW.metodo_pago = 'EFECTIVO'      THEN NEW.total ELSE 0 END,
-    ventas_tarjeta       = ventas_tarjeta       - CASE WHEN NEW.metodo_pago = 'TARJETA'       THEN NEW.total ELSE 0 END,
-    ventas_transferencia = ventas_transferencia - CASE WHEN NEW.metodo_pago = 'TRANSFERENCIA' THEN NEW.total ELSE 0 END,
+    ventas_efectivo      = ventas_efectivo      - COALESCE(
+                             (SELECT SUM(monto) FROM pagos_venta WHERE venta_id = NEW.id AND metodo_pago = 'EFECTIVO'),
+                             CASE WHEN NEW.metodo_pago = 'EFECTIVO' THEN NEW.total ELSE 0 END),
+    ventas_tarjeta       = ventas_tarjeta       - COALESCE(
+                             (SELECT SUM(monto) FROM pagos_venta WHERE venta_id = NEW.id AND metodo_pago = 'TARJETA'),
+                             CASE WHEN NEW.metodo_pago = 'TARJETA' THEN NEW.total ELSE 0 END),
+    ventas_transferencia = ventas_transferencia - COALESCE(
+                             (SELECT SUM(monto) FROM pagos_venta WHERE venta_id = NEW.id AND metodo_pago = 'TRANSFERENCIA'),
+                             CASE WHEN NEW.metodo_pago = 'TRANSFERENCIA' THEN NEW.total ELSE 0 END),
     total_ventas         = total_ventas         - NEW.total,
     numero_transacciones = numero_transacciones - 1,
+    cambio_total         = cambio_total         - COALESCE(NEW.cambio, 0),
+    redondeo_total       = redondeo_total       - COALESCE(NEW.redondeo, 0),
     ticket_promedio      = CASE WHEN numero_transacciones - 1 > 0
                            THEN (total_ventas - NEW.total) / (numero_transacciones - 1) ELSE 0 END
-  WHERE usuario_id = NEW.usuario_id AND estado = 'ABIERTA'
-    AND date(fecha_apertura) = date(NEW.fecha_hora);
-END;
\ No newline at end of file
+  WHERE id = NEW.caja_id;
+END;
+
+-- Al cerrar la caja los carritos en espera expiran (y liberan su reserva)
+DROP TRIGGER IF EXISTS trg_expirar_ventas_suspendidas;
+CREATE TRIGGER trg_expirar_ventas_suspendidas
+AFTER UPDATE OF estado ON cajas
+FOR EACH ROW
+WHEN OLD.estado = 'ABIERTA' AND NEW.estado = 'CERRADA'
+BEGIN
+  UPDATE ventas_suspendidas
+  SET estado = 'EXPIRADA', fecha_cierre = datetime('now', 'localtime')
+  WHERE caja_id = NEW.id AND estado = 'ACTIVA';
+END;
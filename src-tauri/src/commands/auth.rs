@@ -2,7 +2,7 @@
 // Comandos de autenticación - SQLite
 
 use crate::database::DatabasePool;
-use crate::models::{Usuario, UsuarioLogin, UsuarioResponse};
+use crate::models::{Usuario, UsuarioLogin, UsuarioResponse, AutorizacionSupervisor};
 use rusqlite::params;
 use rusqlite::OptionalExtension;
 
@@ -93,4 +93,50 @@ pub fn test_database_connection(db: tauri::State<DatabasePool>) -> Result<String
         Ok(_) => Err("Resultado inesperado en consulta de prueba".to_string()),
         Err(e) => Err(format!("Error al ejecutar consulta: {}", e)),
     }
-}
\ No newline at end of file
+}
+
+// =====================================================
+// 🆕 Autorización de operaciones sensibles (anulaciones, excepciones)
+// =====================================================
+
+// Un administrador (rol_id = 1) se autoriza a sí mismo; cualquier otro usuario
+// necesita las credenciales de un administrador activo.
+// Devuelve el id de quien autoriza.
+pub fn autorizar_operacion(
+    conn: &rusqlite::Connection,
+    usuario_id: i32,
+    supervisor: Option<&AutorizacionSupervisor>,
+) -> Result<i32, String> {
+    let rol_id: i32 = conn
+        .query_row(
+            "SELECT rol_id FROM usuarios WHERE id = ? AND activo = 1",
+            params![usuario_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Error al verificar usuario: {}", e))?
+        .ok_or_else(|| "Usuario no encontrado o inactivo".to_string())?;
+
+    if rol_id == 1 {
+        return Ok(usuario_id);
+    }
+
+    let supervisor = match supervisor {
+        Some(s) => s,
+        None => return Err("🔒 Esta operación requiere autorización de un administrador".to_string()),
+    };
+
+    let admin: Option<(i32, String)> = conn
+        .query_row(
+            "SELECT id, password_hash FROM usuarios WHERE username = ? AND activo = 1 AND rol_id = 1",
+            params![&supervisor.username],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(|e| format!("Error al verificar supervisor: {}", e))?;
+
+    match admin {
+        Some((id, hash)) if bcrypt::verify(&supervisor.password, &hash).unwrap_or(false) => Ok(id),
+        _ => Err("🔒 Credenciales de supervisor incorrectas".to_string()),
+    }
+}
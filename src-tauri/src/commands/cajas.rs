@@ -3,6 +3,7 @@
 
 use crate::database::DatabasePool;
 use crate::models::caja::*;
+use crate::commands::promociones::redondear;
 use rusqlite::{params, OptionalExtension};
 use serde_json;
 
@@ -112,10 +113,11 @@ pub fn cerrar_caja(
     let conn = db.get_conn();
 
     // 1. Verificar que la caja exista y esté abierta
-    let caja_info: Option<(i32, f64, f64, f64, f64, f64, f64, f64, String)> = conn
+    let caja_info: Option<(i32, f64, f64, f64, f64, f64, String, f64)> = conn
         .query_row(
             r"SELECT usuario_id, monto_inicial, ventas_efectivo, retiros_total, 
-                     gastos_total, ingresos_total, devoluciones_monto, cambio_total, fecha_apertura
+                     gastos_total, ingresos_total, fecha_apertura,
+                     COALESCE(redondeo_total, 0)
               FROM cajas 
               WHERE id = ? AND estado = 'ABIERTA'",
             params![request.caja_id],
@@ -129,7 +131,6 @@ pub fn cerrar_caja(
                     row.get(5)?,
                     row.get(6)?,
                     row.get(7)?,
-                    row.get(8)?,
                 ))
             },
         )
@@ -143,9 +144,8 @@ pub fn cerrar_caja(
         retiros_total,
         gastos_total,
         ingresos_total,
-        devoluciones_monto,
-        cambio_total,
         fecha_apertura,
+        redondeo_total,
     ) = match caja_info {
         Some(c) => c,
         None => return Err("❌ Caja no encontrada o ya está cerrada".to_string()),
@@ -156,12 +156,17 @@ pub fn cerrar_caja(
         return Err("🔒 Solo el cajero que abrió la caja o un administrador pueden cerrarla".to_string());
     }
 
-    // 3. Calcular efectivo esperado (incluye cambio_total como salida)
-    let efectivo_esperado = monto_inicial + ventas_efectivo + ingresos_total
-        - cambio_total
+    // 3. Calcular efectivo esperado
+    //    ventas_efectivo ya es lo cobrado neto (recibido - cambio) desde pagos_venta,
+    //    por eso cambio_total no se vuelve a restar
+    //    🆕 pagos_venta guarda el monto exacto: lo cobrado de más/menos por el
+    //    redondeo del efectivo se suma aparte (redondeo_total)
+    //    🆕 Las devoluciones en efectivo ya se descuentan de ventas_efectivo
+    //    (trg_actualizar_caja_devolucion); devoluciones_monto incluye también
+    //    las reembolsadas con tarjeta, vale o crédito y no se vuelve a restar
+    let efectivo_esperado = monto_inicial + ventas_efectivo + redondeo_total + ingresos_total
         - retiros_total
-        - gastos_total
-        - devoluciones_monto;
+        - gastos_total;
 
     // 4. Calcular diferencia (guardado internamente, NO se muestra al cajero)
     let diferencia = request.monto_contado - efectivo_esperado;
@@ -441,12 +446,15 @@ pub fn obtener_reporte_cierre(
         },
     };
 
+    let resumen_impuestos = resumen_impuestos_caja(&conn, &caja)?;
+
     Ok(ReporteCierreCaja {
         caja,
         cajero_nombre,
         movimientos,
         resumen_puntualidad,
         resumen_financiero,
+        resumen_impuestos,
     })
 }
 
@@ -645,12 +653,15 @@ pub fn obtener_detalle_caja(
         },
     };
 
+    let resumen_impuestos = resumen_impuestos_caja(&conn, &caja)?;
+
     Ok(ReporteCierreCaja {
         caja,
         cajero_nombre,
         movimientos,
         resumen_puntualidad,
         resumen_financiero,
+        resumen_impuestos,
     })
 }
 
@@ -658,6 +669,38 @@ pub fn obtener_detalle_caja(
 // FUNCIONES AUXILIARES
 // =====================================================
 
+// 🆕 IGV del turno: ventas de la caja menos devoluciones del cajero en el turno
+fn resumen_impuestos_caja(conn: &rusqlite::Connection, caja: &Caja) -> Result<ResumenImpuestos, String> {
+    let ventas_igv: (f64, f64, f64, f64) = conn
+        .query_row(
+            r"SELECT COALESCE(SUM(op_gravada), 0), COALESCE(SUM(op_exonerada), 0),
+                     COALESCE(SUM(op_inafecta), 0), COALESCE(SUM(igv), 0)
+              FROM ventas WHERE caja_id = ? AND estado = 'COMPLETADA'",
+            params![caja.id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .map_err(|e| format!("Error al obtener IGV de ventas: {}", e))?;
+
+    let devoluciones_igv: (f64, f64, f64, f64) = conn
+        .query_row(
+            r"SELECT COALESCE(SUM(op_gravada), 0), COALESCE(SUM(op_exonerada), 0),
+                     COALESCE(SUM(op_inafecta), 0), COALESCE(SUM(igv), 0)
+              FROM devoluciones WHERE caja_id = ? AND estado = 'PROCESADA'",
+            params![caja.id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .map_err(|e| format!("Error al obtener IGV de devoluciones: {}", e))?;
+
+    Ok(ResumenImpuestos {
+        op_gravada: redondear(ventas_igv.0 - devoluciones_igv.0),
+        op_exonerada: redondear(ventas_igv.1 - devoluciones_igv.1),
+        op_inafecta: redondear(ventas_igv.2 - devoluciones_igv.2),
+        igv_ventas: redondear(ventas_igv.3),
+        igv_devoluciones: redondear(devoluciones_igv.3),
+        igv_neto: redondear(ventas_igv.3 - devoluciones_igv.3),
+    })
+}
+
 fn obtener_caja_por_id(conn: &rusqlite::Connection, caja_id: i32) -> Result<Caja, String> {
     conn.query_row(
         r"SELECT 
@@ -671,7 +714,8 @@ fn obtener_caja_por_id(conn: &rusqlite::Connection, caja_id: i32) -> Result<Caja
             devoluciones_monto, devoluciones_cantidad,
             retiros_total, ingresos_total, gastos_total, cambio_total,
             efectivo_esperado, diferencia, estado_diferencia, 
-            justificacion_diferencia, estado, duracion_turno_minutos
+            justificacion_diferencia, estado, duracion_turno_minutos,
+            COALESCE(redondeo_total, 0)
           FROM cajas WHERE id = ?",
         params![caja_id],
         |row| {
@@ -710,6 +754,7 @@ fn obtener_caja_por_id(conn: &rusqlite::Connection, caja_id: i32) -> Result<Caja
                 justificacion_diferencia: row.get(31)?,
                 estado: row.get(32)?,
                 duracion_turno_minutos: row.get(33)?,
+                redondeo_total: row.get(34)?,
             })
         },
     )
@@ -0,0 +1,682 @@
+// commands/cambios.rs
+// Cambio de talla (o de prenda) en una sola transacción.
+//
+// El documento CAMBIO une dos movimientos: una devolución con
+// metodo_reembolso CAMBIO (la prenda devuelta no se reembolsa, paga la nueva)
+// y una venta de reemplazo cuyo pago principal es una línea CAMBIO. Solo la
+// diferencia mueve dinero: si la nueva cuesta más, el cliente paga la
+// diferencia como en cualquier venta; si cuesta menos, se le devuelve en
+// efectivo (trg_actualizar_caja_cambio) o en vale. El stock lo mueven los
+// triggers de detalles_devolucion y detalles_venta; sus movimientos quedan
+// con la referencia del cambio.
+
+use crate::commands::comisiones::{registrar_comisiones_venta, revertir_comisiones_devolucion, validar_vendedor};
+use crate::commands::devoluciones::{monto_neto_devolucion, registrar_excepcion_politica, validar_politica_devolucion};
+use crate::commands::facturacion::{codigo_tipo_comprobante, receptor_venta};
+use crate::commands::impuestos::{afectacion_producto, desglosar_igv, tasa_igv, TotalesIgv};
+use crate::commands::listas_precios::{aplicar_lista_precios, lista_precios_venta};
+use crate::commands::notas_credito::generar_nota_credito;
+use crate::commands::numeracion::{numero_comprobante, siguiente_correlativo, siguiente_folio};
+use crate::commands::promociones::redondear;
+use crate::commands::puntos::{configuracion_puntos, registrar_movimiento_puntos, revertir_puntos_devolucion};
+use crate::commands::suspendidas::stock_disponible;
+use crate::commands::vales::emitir_vale;
+use crate::commands::ventas::{redondear_efectivo, regla_redondeo_efectivo, validar_pagos, PagoVenta, ProductoVenta};
+use crate::database::DatabasePool;
+use crate::models::cambio::*;
+use crate::models::AutorizacionSupervisor;
+use rusqlite::{params, OptionalExtension};
+use std::collections::HashMap;
+
+// Línea de la venta original que se devuelve, ya valorizada
+struct LineaDevuelta {
+    detalle_id: i32,
+    producto_id: i32,
+    variante_id: Option<i32>,
+    talla: Option<String>,
+    cantidad: i32,
+    monto: f64, // lo pagado por esas unidades (neto de descuentos)
+    afectacion: String,
+    condicion: String, // REVENTA | DEFECTUOSO | VENCIDO
+}
+
+// =====================================================
+// COMANDO: Procesar cambio de talla
+// =====================================================
+#[tauri::command]
+pub fn procesar_cambio(
+    db: tauri::State<'_, DatabasePool>,
+    request: CambioRequest,
+    usuario_id: i32,
+    autorizacion: Option<AutorizacionSupervisor>, // 🆕 prendas fuera de la política de devolución
+) -> Result<CambioResult, String> {
+    let conn = db.get_conn();
+
+    let caja_id: i32 = conn
+        .query_row(
+            "SELECT id FROM cajas WHERE usuario_id = ? AND estado = 'ABIERTA'",
+            params![usuario_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Error al verificar caja: {}", e))?
+        .ok_or("⚠️ Debes abrir una caja antes de procesar cambios")?;
+
+    if request.devueltos.is_empty() || request.nuevos.is_empty() {
+        return Err("❌ Indica las prendas que se devuelven y las que se llevan".to_string());
+    }
+    if request.devueltos.iter().any(|d| d.cantidad <= 0) || request.nuevos.iter().any(|n| n.cantidad <= 0) {
+        return Err("❌ Las cantidades deben ser mayores a 0".to_string());
+    }
+
+    // Venta original
+    let (folio_original, estado, cliente_id, tipo_comprobante, lista_original, saldo_pendiente, vendedor_original):
+        (String, String, Option<i32>, String, Option<i32>, f64, Option<i32>) = conn
+        .query_row(
+            r"SELECT folio, estado, cliente_id, COALESCE(tipo_comprobante, 'NOTA_VENTA'),
+                     lista_precio_id, COALESCE(saldo_pendiente, 0), vendedor_id
+              FROM ventas WHERE id = ?",
+            params![request.venta_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?, row.get(6)?)),
+        )
+        .optional()
+        .map_err(|e| format!("Error al buscar venta: {}", e))?
+        .ok_or("❌ Venta no encontrada")?;
+
+    if estado != "COMPLETADA" {
+        return Err(format!("❌ No se puede cambiar mercadería de una venta en estado {}", estado));
+    }
+    if saldo_pendiente > 0.0 {
+        return Err(format!(
+            "❌ La venta {} tiene S/ {:.2} pendientes de pago: cobra el saldo antes del cambio",
+            folio_original, saldo_pendiente
+        ));
+    }
+
+    // Quien atiende el cambio; si no se indica, el vendedor de la venta original
+    let vendedor_id = request.vendedor_id.or(vendedor_original);
+    if let Some(id) = request.vendedor_id {
+        validar_vendedor(&conn, id)?;
+    }
+
+    // 1. Prendas devueltas: se valorizan a lo que realmente se pagó por ellas
+    let mut devueltas: Vec<LineaDevuelta> = Vec::with_capacity(request.devueltos.len());
+    for d in &request.devueltos {
+        if devueltas.iter().any(|l| l.detalle_id == d.detalle_id) {
+            return Err("❌ Una línea de la venta aparece dos veces en el cambio".to_string());
+        }
+        let condicion = d.condicion.as_deref().map(|c| c.trim().to_uppercase()).unwrap_or_else(|| "REVENTA".to_string());
+        if !["REVENTA", "DEFECTUOSO", "VENCIDO"].contains(&condicion.as_str()) {
+            return Err(format!("❌ Condición de devolución no válida: {}", condicion));
+        }
+
+        let (producto_id, variante_id, talla, cantidad_original, afectacion):
+            (i32, Option<i32>, Option<String>, i32, String) = conn
+            .query_row(
+                r"SELECT producto_id, variante_id, talla, cantidad, COALESCE(afectacion_igv, 'GRAVADO')
+                  FROM detalles_venta WHERE id = ? AND venta_id = ?",
+                params![d.detalle_id, request.venta_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+            )
+            .optional()
+            .map_err(|e| format!("Error al obtener detalle: {}", e))?
+            .ok_or_else(|| format!("❌ La línea {} no pertenece a la venta {}", d.detalle_id, folio_original))?;
+
+        let ya_devuelto: i32 = conn
+            .query_row(
+                r"SELECT COALESCE(SUM(dd.cantidad_devuelta), 0)
+                  FROM detalles_devolucion dd
+                  JOIN devoluciones dv ON dd.devolucion_id = dv.id
+                  WHERE dd.detalle_venta_id = ? AND dv.estado = 'PROCESADA'",
+                params![d.detalle_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("Error al verificar devoluciones: {}", e))?;
+
+        if ya_devuelto + d.cantidad > cantidad_original {
+            return Err(format!(
+                "❌ No puedes cambiar {} unidades. Disponibles para devolución: {}",
+                d.cantidad,
+                cantidad_original - ya_devuelto
+            ));
+        }
+
+        devueltas.push(LineaDevuelta {
+            detalle_id: d.detalle_id,
+            producto_id,
+            variante_id,
+            talla,
+            cantidad: d.cantidad,
+            monto: monto_neto_devolucion(&conn, d.detalle_id, d.cantidad)?,
+            afectacion,
+            condicion,
+        });
+    }
+    let monto_devuelto = redondear(devueltas.iter().map(|l| l.monto).sum());
+
+    // 🆕 El cambio sigue la política de devolución (plazo y categorías)
+    let detalles: Vec<i32> = devueltas.iter().map(|l| l.detalle_id).collect();
+    let excepcion = validar_politica_devolucion(
+        &conn,
+        request.venta_id,
+        &detalles,
+        usuario_id,
+        autorizacion.as_ref(),
+        request.motivo_excepcion.as_deref(),
+    )?;
+
+    // 2. Prendas nuevas: talla activa del producto y stock disponible
+    let mut nuevos: Vec<ProductoVenta> = Vec::with_capacity(request.nuevos.len());
+    for n in &request.nuevos {
+        let producto: Option<(String, String)> = conn
+            .query_row(
+                "SELECT nombre, codigo FROM productos WHERE id = ? AND activo = 1",
+                params![n.producto_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+            .map_err(|e| format!("Error al obtener producto: {}", e))?;
+        let (nombre, codigo) = producto.ok_or_else(|| format!("❌ Producto {} no encontrado", n.producto_id))?;
+
+        let talla = match n.variante_id {
+            Some(vid) => Some(
+                conn.query_row(
+                    "SELECT talla FROM producto_variantes WHERE id = ? AND producto_id = ? AND activo = 1",
+                    params![vid, n.producto_id],
+                    |row| row.get::<_, String>(0),
+                )
+                .optional()
+                .map_err(|e| format!("Error al obtener talla: {}", e))?
+                .ok_or_else(|| format!("❌ Talla no encontrada para '{}'", nombre))?,
+            ),
+            None => None,
+        };
+
+        let pedido: i32 = nuevos
+            .iter()
+            .filter(|p| p.id == n.producto_id && p.variante_id == n.variante_id)
+            .map(|p| p.cantidad)
+            .sum::<i32>()
+            + n.cantidad;
+        let disponible = stock_disponible(&conn, n.producto_id, n.variante_id)?;
+        if disponible < pedido {
+            return Err(match &talla {
+                Some(t) => format!(
+                    "❌ Stock insuficiente para {} talla {} (disponible: {}, solicitado: {})",
+                    nombre, t, disponible, pedido
+                ),
+                None => format!(
+                    "❌ Stock insuficiente para {} (disponible: {}, solicitado: {})",
+                    nombre, disponible, pedido
+                ),
+            });
+        }
+
+        nuevos.push(ProductoVenta {
+            id: n.producto_id,
+            nombre,
+            codigo,
+            precio: 0.0,
+            cantidad: n.cantidad,
+            descuento_porcentaje: None,
+            variante_id: n.variante_id,
+            talla,
+            vendedor_id: None,
+        });
+    }
+
+    // 3. Precio de lo nuevo: la lista de la venta original (o la vigente del
+    //    cliente). El mismo producto en otra talla conserva lo que se pagó por
+    //    unidad, así el cambio de talla puro no cobra ni devuelve nada.
+    let lista = match lista_precios_venta(&conn, lista_original, cliente_id) {
+        Ok(l) => l,
+        Err(_) => lista_precios_venta(&conn, None, cliente_id)?,
+    }
+    .map(|(id, _)| id);
+    aplicar_lista_precios(&conn, lista, &mut nuevos)?;
+
+    let mut pagado_por_producto: HashMap<i32, (i32, f64)> = HashMap::new();
+    for l in &devueltas {
+        let entrada = pagado_por_producto.entry(l.producto_id).or_insert((0, 0.0));
+        entrada.0 += l.cantidad;
+        entrada.1 += l.monto;
+    }
+    for p in nuevos.iter_mut() {
+        if let Some((unidades, monto)) = pagado_por_producto.get_mut(&p.id) {
+            if *unidades >= p.cantidad {
+                let unitario = redondear(*monto / *unidades as f64);
+                p.precio = p.precio.min(unitario);
+                *unidades -= p.cantidad;
+                *monto -= unitario * p.cantidad as f64;
+            }
+        }
+    }
+    let monto_nuevo = redondear(nuevos.iter().map(|p| p.precio * p.cantidad as f64).sum());
+    let diferencia = redondear(monto_nuevo - monto_devuelto);
+
+    // 4. Diferencia: la paga el cliente (como un pago de venta) o se le devuelve
+    let regla_redondeo = regla_redondeo_efectivo(&conn)?;
+    let metodo = request
+        .metodo_diferencia
+        .as_deref()
+        .map(|m| m.trim().to_uppercase())
+        .filter(|m| !m.is_empty())
+        .unwrap_or_else(|| "EFECTIVO".to_string());
+
+    let mut pagos: Vec<PagoVenta> = Vec::new();
+    let monto_cambio = redondear(monto_nuevo.min(monto_devuelto));
+    if monto_cambio > 0.0 {
+        pagos.push(PagoVenta {
+            metodo_pago: "CAMBIO".to_string(),
+            monto: monto_cambio,
+            monto_recibido: None,
+            referencia: None,
+        });
+    }
+
+    let (metodo_diferencia, monto_recibido, vuelto, redondeo) = if diferencia > 0.0 {
+        if !["EFECTIVO", "TARJETA", "TRANSFERENCIA"].contains(&metodo.as_str()) {
+            return Err(format!("❌ La diferencia a pagar no se puede cobrar con {}", metodo));
+        }
+        let pago = PagoVenta {
+            metodo_pago: metodo.clone(),
+            monto: diferencia,
+            monto_recibido: request.monto_recibido,
+            referencia: request.referencia.clone(),
+        };
+        let (_, recibido, vuelto, redondeo) = validar_pagos(std::slice::from_ref(&pago), diferencia, &regla_redondeo)?;
+        pagos.push(pago);
+        (Some(metodo), recibido, vuelto, redondeo)
+    } else if diferencia < 0.0 {
+        if !["EFECTIVO", "VALE"].contains(&metodo.as_str()) {
+            return Err(format!(
+                "❌ La diferencia a favor del cliente se devuelve en EFECTIVO o VALE, no en {}",
+                metodo
+            ));
+        }
+        if request.monto_recibido.is_some() {
+            return Err("❌ El cliente no paga nada en este cambio: la diferencia es a su favor".to_string());
+        }
+        let redondeo = if metodo == "EFECTIVO" {
+            redondear(redondear_efectivo(-diferencia, &regla_redondeo) + diferencia)
+        } else {
+            0.0
+        };
+        (Some(metodo), None, None, redondeo)
+    } else {
+        (None, None, None, 0.0)
+    };
+
+    let metodo_venta = match pagos.as_slice() {
+        [unico] => unico.metodo_pago.clone(),
+        _ => "MIXTO".to_string(),
+    };
+
+    // 5. La venta de reemplazo lleva el mismo comprobante que la original
+    if let Some(codigo) = codigo_tipo_comprobante(&tipo_comprobante) {
+        receptor_venta(&conn, cliente_id, codigo, monto_nuevo)?;
+    }
+
+    let tasa_original: f64 = conn
+        .query_row(
+            "SELECT COALESCE(igv_porcentaje, 0) FROM ventas WHERE id = ?",
+            params![request.venta_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Error al obtener venta: {}", e))?;
+    let mut igv_devolucion = TotalesIgv::default();
+    let mut igv_devueltas: Vec<(f64, f64)> = Vec::with_capacity(devueltas.len());
+    for l in &devueltas {
+        let (valor_venta, igv) = desglosar_igv(l.monto, &l.afectacion, tasa_original);
+        igv_devolucion.agregar(&l.afectacion, valor_venta, igv);
+        igv_devueltas.push((valor_venta, igv));
+    }
+    let igv_devolucion = igv_devolucion.redondeados();
+
+    let tasa = tasa_igv(&conn)?;
+    let mut igv_venta = TotalesIgv::default();
+    let mut igv_nuevos: Vec<(String, f64, f64)> = Vec::with_capacity(nuevos.len());
+    for p in &nuevos {
+        let afectacion = afectacion_producto(&conn, p.id)?;
+        let (valor_venta, igv) = desglosar_igv(p.precio * p.cantidad as f64, &afectacion, tasa);
+        igv_venta.agregar(&afectacion, valor_venta, igv);
+        igv_nuevos.push((afectacion, valor_venta, igv));
+    }
+    let igv_venta = igv_venta.redondeados();
+
+    let motivo = request
+        .motivo
+        .as_deref()
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .map(str::to_string);
+
+    conn.execute("BEGIN TRANSACTION", [])
+        .map_err(|e| format!("Error al iniciar transacción: {}", e))?;
+
+    let rollback = |conn: &rusqlite::Connection, msg: String| -> String {
+        let _ = conn.execute("ROLLBACK", []);
+        msg
+    };
+
+    // 6. Documento del cambio (trg_actualizar_caja_cambio descuenta de la caja
+    //    la diferencia devuelta en efectivo)
+    let folio = match siguiente_folio(&conn, "CAMBIO") {
+        Ok(f) => f,
+        Err(e) => return Err(rollback(&conn, e)),
+    };
+
+    if let Err(e) = conn.execute(
+        r"INSERT INTO cambios
+            (folio, venta_original_id, monto_devuelto, monto_nuevo, diferencia,
+             metodo_diferencia, redondeo, motivo, usuario_id, caja_id)
+          VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        params![&folio, request.venta_id, monto_devuelto, monto_nuevo, diferencia,
+                &metodo_diferencia, redondeo, &motivo, usuario_id, caja_id],
+    ) {
+        return Err(rollback(&conn, format!("Error al registrar cambio: {}", e)));
+    }
+    let cambio_id = conn.last_insert_rowid() as i32;
+
+    // 7. Devolución (CAMBIO): no suma a devoluciones de la caja; el trigger
+    //    de detalles_devolucion repone el stock (o la cuarentena si la
+    //    prenda vuelve defectuosa o vencida)
+    let folio_devolucion = match siguiente_folio(&conn, "DEVOLUCION") {
+        Ok(f) => f,
+        Err(e) => return Err(rollback(&conn, e)),
+    };
+    let sustento = motivo.clone().unwrap_or_else(|| format!("Cambio de talla {}", folio));
+
+    if let Err(e) = conn.execute(
+        r"INSERT INTO devoluciones
+            (venta_original_id, folio_devolucion, monto_reembolsado, metodo_reembolso,
+             motivo, usuario_id, caja_id, op_gravada, op_exonerada, op_inafecta, igv, estado, cambio_id)
+          VALUES (?, ?, ?, 'CAMBIO', ?, ?, ?, ?, ?, ?, ?, 'PROCESADA', ?)",
+        params![request.venta_id, &folio_devolucion, monto_devuelto, &sustento, usuario_id, caja_id,
+                igv_devolucion.op_gravada, igv_devolucion.op_exonerada, igv_devolucion.op_inafecta,
+                igv_devolucion.igv, cambio_id],
+    ) {
+        return Err(rollback(&conn, format!("Error al insertar devolución: {}", e)));
+    }
+    let devolucion_id = conn.last_insert_rowid() as i32;
+
+    if let Some(excepcion) = &excepcion {
+        if let Err(e) = registrar_excepcion_politica(&conn, devolucion_id, &folio_devolucion, usuario_id, excepcion) {
+            return Err(rollback(&conn, e));
+        }
+    }
+
+    for (l, (valor_venta, igv)) in devueltas.iter().zip(&igv_devueltas) {
+        if let Err(e) = conn.execute(
+            r"INSERT INTO detalles_devolucion
+                (devolucion_id, producto_id, variante_id, talla, detalle_venta_id,
+                 venta_id, cantidad_devuelta, precio_unitario, subtotal,
+                 valor_venta, igv, condicion)
+              VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![devolucion_id, l.producto_id, l.variante_id, &l.talla, l.detalle_id,
+                    request.venta_id, l.cantidad, redondear(l.monto / l.cantidad as f64),
+                    l.monto, valor_venta, igv, &l.condicion],
+        ) {
+            return Err(rollback(&conn, format!("Error al insertar detalle de devolución: {}", e)));
+        }
+    }
+
+    // Nota de crédito si la venta original fue con boleta o factura
+    let lineas: Vec<(i32, i32, f64)> = devueltas.iter().map(|l| (l.detalle_id, l.cantidad, l.monto)).collect();
+    if let Err(e) = generar_nota_credito(&conn, devolucion_id, request.venta_id, &lineas, None, &sustento, usuario_id) {
+        return Err(rollback(&conn, e));
+    }
+
+    if let Err(e) = revertir_puntos_devolucion(&conn, request.venta_id, devolucion_id, monto_devuelto, usuario_id) {
+        return Err(rollback(&conn, e));
+    }
+
+    if let Err(e) = revertir_comisiones_devolucion(&conn, devolucion_id) {
+        return Err(rollback(&conn, e));
+    }
+
+    // 8. Venta de reemplazo, pagada con la prenda devuelta (línea CAMBIO)
+    //    y, si corresponde, la diferencia
+    let folio_venta = match siguiente_folio(&conn, "VENTA") {
+        Ok(f) => f,
+        Err(e) => return Err(rollback(&conn, e)),
+    };
+    let (serie, correlativo) = match siguiente_correlativo(&conn, &tipo_comprobante, caja_id) {
+        Ok(s) => s,
+        Err(e) => return Err(rollback(&conn, e)),
+    };
+    // La diferencia devuelta en efectivo lleva su redondeo en el cambio, no en la venta
+    let redondeo_venta = if diferencia > 0.0 { redondeo } else { 0.0 };
+
+    if let Err(e) = conn.execute(
+        r"INSERT INTO ventas (folio, subtotal, descuento, total, metodo_pago,
+                              monto_recibido, cambio, usuario_id, caja_id, cliente_id,
+                              op_gravada, op_exonerada, op_inafecta, igv, igv_porcentaje,
+                              tipo_comprobante, serie, correlativo, redondeo, lista_precio_id,
+                              cambio_id, vendedor_id, estado)
+          VALUES (?, ?, 0, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 'COMPLETADA')",
+        params![&folio_venta, monto_nuevo, monto_nuevo, &metodo_venta,
+                monto_recibido, vuelto, usuario_id, caja_id, cliente_id,
+                igv_venta.op_gravada, igv_venta.op_exonerada, igv_venta.op_inafecta,
+                igv_venta.igv, tasa, &tipo_comprobante, &serie, correlativo,
+                redondeo_venta, lista, cambio_id, vendedor_id],
+    ) {
+        return Err(rollback(&conn, format!("Error al insertar venta: {}", e)));
+    }
+    let venta_id = conn.last_insert_rowid() as i32;
+
+    for (p, (afectacion, valor_venta, igv)) in nuevos.iter().zip(&igv_nuevos) {
+        let total_linea = redondear(p.precio * p.cantidad as f64);
+        if let Err(e) = conn.execute(
+            r"INSERT INTO detalles_venta
+                (venta_id, producto_id, variante_id, talla, cantidad,
+                 precio_unitario, subtotal, total_linea, afectacion_igv, valor_venta, igv)
+              VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![venta_id, p.id, p.variante_id, &p.talla, p.cantidad,
+                    p.precio, total_linea, total_linea, afectacion, valor_venta, igv],
+        ) {
+            return Err(rollback(&conn, format!("Error al insertar detalle: {}", e)));
+        }
+    }
+
+    for pago in pagos.iter_mut() {
+        if pago.metodo_pago == "CAMBIO" {
+            pago.referencia = Some(folio.clone());
+        }
+        if let Err(e) = conn.execute(
+            r"INSERT INTO pagos_venta (venta_id, metodo_pago, monto, monto_recibido, referencia)
+              VALUES (?, ?, ?, ?, ?)",
+            params![venta_id, &pago.metodo_pago, pago.monto, pago.monto_recibido, &pago.referencia],
+        ) {
+            return Err(rollback(&conn, format!("Error al registrar pago: {}", e)));
+        }
+    }
+
+    // Puntos por la venta de reemplazo (la devolución ya quitó los de la original)
+    if let Some(id) = cliente_id {
+        let ganados = match configuracion_puntos(&conn) {
+            Ok(c) => (monto_nuevo * c.puntos_por_sol + 0.000001).floor() as i64,
+            Err(e) => return Err(rollback(&conn, e)),
+        };
+        if let Err(e) = registrar_movimiento_puntos(
+            &conn, id, "ACUMULACION", ganados, Some(venta_id), None,
+            &format!("Compra {} (cambio {})", folio_venta, folio), usuario_id,
+        ) {
+            return Err(rollback(&conn, e));
+        }
+    }
+
+    if let Err(e) = registrar_comisiones_venta(&conn, venta_id) {
+        return Err(rollback(&conn, e));
+    }
+
+    // 9. Diferencia a favor del cliente en vale
+    if diferencia < 0.0 && metodo_diferencia.as_deref() == Some("VALE") {
+        if let Err(e) = emitir_vale(
+            &conn, "DEVOLUCION", -diferencia, cliente_id, Some(devolucion_id), None, None,
+            usuario_id, &format!("Diferencia del cambio {}", folio),
+        ) {
+            return Err(rollback(&conn, e));
+        }
+    }
+
+    // 10. Movimientos de inventario de ambos lados enlazados al cambio
+    if let Err(e) = conn.execute(
+        "UPDATE movimientos_inventario SET referencia = ? WHERE devolucion_id = ? OR venta_id = ?",
+        params![&folio, devolucion_id, venta_id],
+    ) {
+        return Err(rollback(&conn, format!("Error al enlazar movimientos: {}", e)));
+    }
+
+    conn.execute("COMMIT", [])
+        .map_err(|e| format!("Error al confirmar: {}", e))?;
+
+    cambio_por_id(&conn, cambio_id)
+}
+
+// =====================================================
+// COMANDO: Consultar un cambio por folio
+// =====================================================
+#[tauri::command]
+pub fn obtener_cambio(
+    db: tauri::State<'_, DatabasePool>,
+    folio: String,
+) -> Result<CambioResult, String> {
+    let conn = db.get_conn();
+
+    let id: i32 = conn
+        .query_row(
+            "SELECT id FROM cambios WHERE folio = ?",
+            params![folio.trim().to_uppercase()],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Error al buscar cambio: {}", e))?
+        .ok_or("❌ Cambio no encontrado")?;
+
+    cambio_por_id(&conn, id)
+}
+
+// =====================================================
+// FUNCIONES AUXILIARES
+// =====================================================
+
+fn cambio_por_id(conn: &rusqlite::Connection, cambio_id: i32) -> Result<CambioResult, String> {
+    conn.query_row(
+        r"SELECT c.id, c.folio, d.folio_devolucion, v.id, v.folio,
+                 COALESCE(v.serie, ''), COALESCE(v.correlativo, 0),
+                 c.monto_devuelto, c.monto_nuevo, c.diferencia, c.metodo_diferencia,
+                 v.cambio, COALESCE(c.redondeo, 0),
+                 (SELECT codigo FROM vales WHERE devolucion_id = d.id LIMIT 1),
+                 nc.serie, nc.correlativo
+          FROM cambios c
+          JOIN devoluciones d ON d.cambio_id = c.id
+          JOIN ventas v ON v.cambio_id = c.id
+          LEFT JOIN notas_credito nc ON nc.devolucion_id = d.id
+          WHERE c.id = ?",
+        params![cambio_id],
+        |row| {
+            let serie: String = row.get(5)?;
+            let correlativo: i64 = row.get(6)?;
+            let nota: Option<String> = row.get(14)?;
+            let correlativo_nota: Option<i64> = row.get(15)?;
+            Ok(CambioResult {
+                cambio_id: row.get(0)?,
+                folio: row.get(1)?,
+                folio_devolucion: row.get(2)?,
+                venta_id: row.get(3)?,
+                folio_venta: row.get(4)?,
+                numero_comprobante: numero_comprobante(&serie, correlativo),
+                monto_devuelto: row.get(7)?,
+                monto_nuevo: row.get(8)?,
+                diferencia: row.get(9)?,
+                metodo_diferencia: row.get(10)?,
+                cambio: row.get(11)?,
+                redondeo: row.get(12)?,
+                codigo_vale: row.get(13)?,
+                nota_credito: nota.zip(correlativo_nota).map(|(s, c)| numero_comprobante(&s, c)),
+            })
+        },
+    )
+    .map_err(|e| format!("Error al obtener cambio: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Caja abierta con una venta original de 50 cobrada en efectivo
+    fn caja_con_venta() -> rusqlite::Connection {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch(include_str!("../../schema_sqlite.sql")).unwrap();
+        conn.execute_batch(
+            r"INSERT INTO cajas (id, usuario_id, turno, monto_inicial) VALUES (1, 1, 'MAÑANA', 100);
+              INSERT INTO ventas (id, folio, subtotal, total, metodo_pago, usuario_id, caja_id)
+              VALUES (1, 'V-1', 50, 50, 'EFECTIVO', 1, 1);
+              INSERT INTO pagos_venta (venta_id, metodo_pago, monto) VALUES (1, 'EFECTIVO', 50);",
+        )
+        .unwrap();
+        conn
+    }
+
+    // Lo que registra procesar_cambio: documento, devolución CAMBIO y venta de reemplazo
+    fn registrar_cambio(conn: &rusqlite::Connection, monto_devuelto: f64, monto_nuevo: f64) {
+        let diferencia = redondear(monto_nuevo - monto_devuelto);
+        let metodo = if diferencia != 0.0 { Some("EFECTIVO") } else { None };
+        conn.execute(
+            r"INSERT INTO cambios (id, folio, venta_original_id, monto_devuelto, monto_nuevo, diferencia,
+                                   metodo_diferencia, usuario_id, caja_id)
+              VALUES (1, 'CAM-1', 1, ?, ?, ?, ?, 1, 1)",
+            params![monto_devuelto, monto_nuevo, diferencia, metodo],
+        )
+        .unwrap();
+        conn.execute(
+            r"INSERT INTO devoluciones (venta_original_id, folio_devolucion, usuario_id, caja_id,
+                                        monto_reembolsado, metodo_reembolso, motivo, cambio_id)
+              VALUES (1, 'DEV-1', 1, 1, ?, 'CAMBIO', 'Cambio de talla', 1)",
+            params![monto_devuelto],
+        )
+        .unwrap();
+        conn.execute(
+            r"INSERT INTO ventas (id, folio, subtotal, total, metodo_pago, usuario_id, caja_id, cambio_id)
+              VALUES (2, 'V-2', ?, ?, 'CAMBIO', 1, 1, 1)",
+            params![monto_nuevo, monto_nuevo],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO pagos_venta (venta_id, metodo_pago, monto) VALUES (2, 'CAMBIO', ?)",
+            params![redondear(monto_nuevo.min(monto_devuelto))],
+        )
+        .unwrap();
+        if diferencia > 0.0 {
+            conn.execute(
+                "INSERT INTO pagos_venta (venta_id, metodo_pago, monto) VALUES (2, 'EFECTIVO', ?)",
+                params![diferencia],
+            )
+            .unwrap();
+        }
+    }
+
+    fn totales_caja(conn: &rusqlite::Connection) -> (f64, i32, f64, f64) {
+        conn.query_row(
+            "SELECT total_ventas, numero_transacciones, ventas_efectivo, devoluciones_monto FROM cajas WHERE id = 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn cambio_sin_diferencia_no_es_venta_nueva() {
+        let conn = caja_con_venta();
+        registrar_cambio(&conn, 50.0, 50.0);
+        assert_eq!(totales_caja(&conn), (50.0, 1, 50.0, 0.0));
+    }
+
+    #[test]
+    fn cambio_con_diferencia_suma_solo_lo_cobrado() {
+        let conn = caja_con_venta();
+        registrar_cambio(&conn, 50.0, 70.0);
+        assert_eq!(totales_caja(&conn), (70.0, 2, 70.0, 0.0));
+    }
+}
@@ -0,0 +1,460 @@
+// commands/clientes.rs
+// Clientes (DNI / RUC / CE) e historial de compras
+//
+// 🆕 El RUC se valida con su dígito verificador (módulo 11) y, si está en el
+// padrón local, completa la razón social y la dirección del cliente.
+
+use crate::commands::padron::buscar_contribuyente;
+use crate::database::DatabasePool;
+use crate::models::cliente::*;
+use chrono::NaiveDate;
+use rusqlite::{params, OptionalExtension};
+
+// =====================================================
+// COMANDO: Buscar clientes (documento, nombre o teléfono)
+// =====================================================
+#[tauri::command]
+pub fn buscar_clientes(
+    db: tauri::State<'_, DatabasePool>,
+    termino: String,
+    limite: Option<i32>,
+) -> Result<Vec<Cliente>, String> {
+    let conn = db.get_conn();
+
+    let patron = format!("%{}%", termino.trim());
+    let query = format!(
+        r"{}
+          WHERE activo = 1
+            AND (numero_documento LIKE ?1 OR nombre LIKE ?1 OR telefono LIKE ?1)
+          ORDER BY nombre
+          LIMIT ?2",
+        SELECT_CLIENTE
+    );
+
+    let mut stmt = conn
+        .prepare(&query)
+        .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+
+    let clientes = stmt
+        .query_map(params![&patron, limite.unwrap_or(20)], mapear_cliente)
+        .map_err(|e| format!("Error al buscar clientes: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(clientes)
+}
+
+// =====================================================
+// COMANDO: Obtener cliente por id
+// =====================================================
+#[tauri::command]
+pub fn obtener_cliente(
+    db: tauri::State<'_, DatabasePool>,
+    cliente_id: i32,
+) -> Result<Cliente, String> {
+    let conn = db.get_conn();
+    obtener_cliente_por_id(&conn, cliente_id)
+}
+
+// =====================================================
+// COMANDO: Agregar cliente
+// =====================================================
+#[tauri::command]
+pub fn agregar_cliente(
+    db: tauri::State<'_, DatabasePool>,
+    mut cliente: ClienteNuevo,
+) -> Result<Cliente, String> {
+    let conn = db.get_conn();
+
+    let numero = validar_cliente(&conn, &mut cliente)?;
+
+    conn.execute(
+        r"INSERT INTO clientes
+            (tipo_documento, numero_documento, nombre, direccion, telefono, email,
+             fecha_nacimiento, tallas_preferidas, notas, limite_credito, dias_credito,
+             lista_precio_id, activo)
+          VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        params![
+            &cliente.tipo_documento,
+            &numero,
+            cliente.nombre.trim(),
+            &cliente.direccion,
+            &cliente.telefono,
+            &cliente.email,
+            &cliente.fecha_nacimiento,
+            &cliente.tallas_preferidas,
+            &cliente.notas,
+            cliente.limite_credito.unwrap_or(0.0),
+            cliente.dias_credito.unwrap_or(30),
+            cliente.lista_precio_id,
+            cliente.activo.unwrap_or(true) as i32,
+        ],
+    )
+    .map_err(|e| {
+        if e.to_string().contains("UNIQUE") {
+            format!("❌ Ya existe un cliente con {} {}", cliente.tipo_documento, numero)
+        } else {
+            format!("Error al agregar cliente: {}", e)
+        }
+    })?;
+
+    let id = conn.last_insert_rowid() as i32;
+    obtener_cliente_por_id(&conn, id)
+}
+
+// =====================================================
+// COMANDO: Actualizar cliente
+// =====================================================
+#[tauri::command]
+pub fn actualizar_cliente(
+    db: tauri::State<'_, DatabasePool>,
+    cliente_id: i32,
+    mut cliente: ClienteNuevo,
+) -> Result<String, String> {
+    let conn = db.get_conn();
+
+    let numero = validar_cliente(&conn, &mut cliente)?;
+
+    let filas = conn
+        .execute(
+            r"UPDATE clientes
+              SET tipo_documento = ?, numero_documento = ?, nombre = ?, direccion = ?, telefono = ?, email = ?,
+                  fecha_nacimiento = ?, tallas_preferidas = ?, notas = ?,
+                  limite_credito = COALESCE(?, limite_credito), dias_credito = COALESCE(?, dias_credito),
+                  lista_precio_id = ?, activo = ?
+              WHERE id = ?",
+            params![
+                &cliente.tipo_documento,
+                &numero,
+                cliente.nombre.trim(),
+                &cliente.direccion,
+                &cliente.telefono,
+                &cliente.email,
+                &cliente.fecha_nacimiento,
+                &cliente.tallas_preferidas,
+                &cliente.notas,
+                cliente.limite_credito,
+                cliente.dias_credito,
+                cliente.lista_precio_id,
+                cliente.activo.unwrap_or(true) as i32,
+                cliente_id,
+            ],
+        )
+        .map_err(|e| {
+            if e.to_string().contains("UNIQUE") {
+                format!("❌ Ya existe un cliente con {} {}", cliente.tipo_documento, numero)
+            } else {
+                format!("Error al actualizar cliente: {}", e)
+            }
+        })?;
+
+    if filas == 0 {
+        return Err("❌ Cliente no encontrado".to_string());
+    }
+
+    Ok("✅ Cliente actualizado exitosamente".to_string())
+}
+
+// =====================================================
+// 🆕 COMANDO: Validar documento mientras se escribe
+// =====================================================
+// Devuelve el número normalizado o el motivo por el que no es válido
+#[tauri::command]
+pub fn validar_documento_identidad(
+    tipo_documento: String,
+    numero_documento: String,
+) -> Result<String, String> {
+    validar_documento(&tipo_documento, &numero_documento)
+}
+
+// =====================================================
+// COMANDO: Historial del cliente (compras, devoluciones y totales)
+// =====================================================
+#[tauri::command]
+pub fn historial_cliente(
+    db: tauri::State<'_, DatabasePool>,
+    cliente_id: i32,
+) -> Result<HistorialCliente, String> {
+    let conn = db.get_conn();
+
+    let cliente = obtener_cliente_por_id(&conn, cliente_id)?;
+
+    // Compras (las anuladas se muestran, pero no suman)
+    let mut compras: Vec<CompraCliente> = {
+        let mut stmt = conn
+            .prepare(
+                r"SELECT id, folio, strftime('%Y-%m-%d %H:%M', fecha_hora), total, metodo_pago, estado
+                  FROM ventas
+                  WHERE cliente_id = ?
+                  ORDER BY fecha_hora DESC",
+            )
+            .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+
+        let compras = stmt
+            .query_map(params![cliente_id], |row| {
+                Ok(CompraCliente {
+                    venta_id: row.get(0)?,
+                    folio: row.get(1)?,
+                    fecha_hora: row.get(2)?,
+                    total: row.get(3)?,
+                    metodo_pago: row.get(4)?,
+                    estado: row.get(5)?,
+                    articulos: Vec::new(),
+                })
+            })
+            .map_err(|e| format!("Error al obtener compras: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+        compras
+    };
+
+    {
+        let mut stmt = conn
+            .prepare(
+                r"SELECT dv.producto_id, p.nombre, dv.talla, dv.cantidad, dv.total_linea
+                  FROM detalles_venta dv
+                  JOIN productos p ON p.id = dv.producto_id
+                  WHERE dv.venta_id = ?
+                  ORDER BY dv.id",
+            )
+            .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+
+        for compra in compras.iter_mut() {
+            compra.articulos = stmt
+                .query_map(params![compra.venta_id], |row| {
+                    Ok(ArticuloComprado {
+                        producto_id: row.get(0)?,
+                        producto_nombre: row.get(1)?,
+                        talla: row.get(2)?,
+                        cantidad: row.get(3)?,
+                        total_linea: row.get(4)?,
+                    })
+                })
+                .map_err(|e| format!("Error al obtener artículos: {}", e))?
+                .filter_map(|r| r.ok())
+                .collect();
+        }
+    }
+
+    let devoluciones: Vec<DevolucionCliente> = {
+        let mut stmt = conn
+            .prepare(
+                r"SELECT d.id, d.folio_devolucion, v.folio, strftime('%Y-%m-%d %H:%M', d.fecha_hora),
+                         d.monto_reembolsado, d.motivo
+                  FROM devoluciones d
+                  JOIN ventas v ON v.id = d.venta_original_id
+                  WHERE v.cliente_id = ? AND d.estado = 'PROCESADA'
+                  ORDER BY d.fecha_hora DESC",
+            )
+            .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+
+        let devoluciones = stmt
+            .query_map(params![cliente_id], |row| {
+                Ok(DevolucionCliente {
+                    devolucion_id: row.get(0)?,
+                    folio_devolucion: row.get(1)?,
+                    folio_venta: row.get(2)?,
+                    fecha_hora: row.get(3)?,
+                    monto_reembolsado: row.get(4)?,
+                    motivo: row.get(5)?,
+                })
+            })
+            .map_err(|e| format!("Error al obtener devoluciones: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+        devoluciones
+    };
+
+    let completadas = compras.iter().filter(|c| c.estado == "COMPLETADA");
+    let numero_compras = completadas.clone().count() as i32;
+    let total_comprado: f64 = completadas.clone().map(|c| c.total).sum();
+    let ultima_compra = completadas.map(|c| c.fecha_hora.clone()).next();
+    let total_devuelto: f64 = devoluciones.iter().map(|d| d.monto_reembolsado).sum();
+
+    Ok(HistorialCliente {
+        cliente,
+        compras,
+        devoluciones,
+        numero_compras,
+        total_comprado,
+        total_devuelto,
+        ultima_compra,
+    })
+}
+
+// =====================================================
+// FUNCIONES AUXILIARES
+// =====================================================
+
+const SELECT_CLIENTE: &str = r"
+    SELECT id, tipo_documento, numero_documento, nombre, telefono, email,
+           fecha_nacimiento, tallas_preferidas, notas, activo, fecha_registro, direccion,
+           COALESCE(limite_credito, 0), COALESCE(dias_credito, 30), lista_precio_id
+    FROM clientes";
+
+fn mapear_cliente(row: &rusqlite::Row) -> rusqlite::Result<Cliente> {
+    Ok(Cliente {
+        id: row.get(0)?,
+        tipo_documento: row.get(1)?,
+        numero_documento: row.get(2)?,
+        nombre: row.get(3)?,
+        direccion: row.get(11)?,
+        telefono: row.get(4)?,
+        email: row.get(5)?,
+        fecha_nacimiento: row.get(6)?,
+        tallas_preferidas: row.get(7)?,
+        notas: row.get(8)?,
+        limite_credito: row.get(12)?,
+        dias_credito: row.get(13)?,
+        lista_precio_id: row.get(14)?,
+        activo: row.get::<_, i32>(9)? == 1,
+        fecha_registro: row.get(10)?,
+    })
+}
+
+pub fn obtener_cliente_por_id(conn: &rusqlite::Connection, cliente_id: i32) -> Result<Cliente, String> {
+    conn.query_row(
+        &format!("{} WHERE id = ?", SELECT_CLIENTE),
+        params![cliente_id],
+        mapear_cliente,
+    )
+    .optional()
+    .map_err(|e| format!("Error al obtener cliente: {}", e))?
+    .ok_or_else(|| "❌ Cliente no encontrado".to_string())
+}
+
+// Valida el formato del documento y los datos básicos.
+// Devuelve el número de documento normalizado.
+pub fn validar_documento(tipo_documento: &str, numero_documento: &str) -> Result<String, String> {
+    let numero = numero_documento.trim().to_uppercase();
+
+    let valido = match tipo_documento {
+        "DNI" => numero.len() == 8 && numero.chars().all(|c| c.is_ascii_digit()) && numero != "00000000",
+        "RUC" => return validar_ruc(&numero),
+        "CE" => (6..=12).contains(&numero.len()) && numero.chars().all(|c| c.is_ascii_alphanumeric()),
+        _ => return Err(format!("❌ Tipo de documento no válido: {}", tipo_documento)),
+    };
+
+    if !valido {
+        return Err(match tipo_documento {
+            "DNI" => "❌ El DNI debe tener 8 dígitos".to_string(),
+            _ => "❌ El carné de extranjería debe tener entre 6 y 12 caracteres".to_string(),
+        });
+    }
+
+    Ok(numero)
+}
+
+// 🆕 RUC: 11 dígitos, prefijo de contribuyente (10 persona natural, 15/16/17
+// otros, 20 persona jurídica) y dígito verificador módulo 11
+pub fn validar_ruc(ruc: &str) -> Result<String, String> {
+    let ruc = ruc.trim();
+    if ruc.len() != 11 || !ruc.chars().all(|c| c.is_ascii_digit()) {
+        return Err("❌ El RUC debe tener 11 dígitos".to_string());
+    }
+    if !["10", "15", "16", "17", "20"].contains(&&ruc[..2]) {
+        return Err(format!("❌ El RUC no puede empezar con {}", &ruc[..2]));
+    }
+
+    let digitos: Vec<u32> = ruc.chars().filter_map(|c| c.to_digit(10)).collect();
+    let suma: u32 = digitos[..10]
+        .iter()
+        .zip([5, 4, 3, 2, 7, 6, 5, 4, 3, 2])
+        .map(|(d, peso)| d * peso)
+        .sum();
+    let verificador = match 11 - suma % 11 {
+        10 => 0,
+        11 => 1,
+        d => d,
+    };
+    if digitos[10] != verificador {
+        return Err("❌ El RUC no es válido (dígito verificador incorrecto)".to_string());
+    }
+
+    Ok(ruc.to_string())
+}
+
+fn validar_cliente(conn: &rusqlite::Connection, c: &mut ClienteNuevo) -> Result<String, String> {
+    let numero = validar_documento(&c.tipo_documento, &c.numero_documento)?;
+
+    // 🆕 Con RUC se completa lo que falte desde el padrón local
+    if c.tipo_documento == "RUC" {
+        if let Some(contribuyente) = buscar_contribuyente(conn, &numero)? {
+            if c.nombre.trim().is_empty() {
+                c.nombre = contribuyente.razon_social;
+            }
+            if c.direccion.as_deref().is_none_or(|d| d.trim().is_empty()) {
+                c.direccion = contribuyente.direccion;
+            }
+        }
+    }
+
+    if c.nombre.trim().is_empty() {
+        return Err("❌ El nombre del cliente es obligatorio".to_string());
+    }
+    if c.email.as_deref().is_some_and(|e| !e.trim().is_empty() && !e.contains('@')) {
+        return Err("❌ El email no es válido".to_string());
+    }
+    if let Some(fecha) = c.fecha_nacimiento.as_deref().filter(|f| !f.is_empty()) {
+        if NaiveDate::parse_from_str(fecha, "%Y-%m-%d").is_err() {
+            return Err(format!("❌ Fecha de nacimiento no válida: {} (usa AAAA-MM-DD)", fecha));
+        }
+    }
+    if c.limite_credito.is_some_and(|l| l < 0.0) {
+        return Err("❌ El límite de crédito no puede ser negativo".to_string());
+    }
+    if c.dias_credito.is_some_and(|d| d < 1) {
+        return Err("❌ Los días de crédito deben ser al menos 1".to_string());
+    }
+    if let Some(lista_id) = c.lista_precio_id {
+        let activa: bool = conn
+            .query_row(
+                "SELECT activo FROM listas_precios WHERE id = ?",
+                params![lista_id],
+                |row| Ok(row.get::<_, i32>(0)? == 1),
+            )
+            .optional()
+            .map_err(|e| format!("Error al verificar lista de precios: {}", e))?
+            .ok_or("❌ Lista de precios no encontrada")?;
+        if !activa {
+            return Err("❌ La lista de precios está inactiva".to_string());
+        }
+    }
+
+    Ok(numero)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ruc_con_digito_verificador_correcto() {
+        assert_eq!(validar_ruc("20131312955").unwrap(), "20131312955");
+        assert_eq!(validar_ruc(" 10467793549 ").unwrap(), "10467793549");
+    }
+
+    #[test]
+    fn ruc_residuo_10_y_11() {
+        // 11 - suma % 11 = 10 → 0 y = 11 → 1
+        assert!(validar_ruc("20100070970").is_ok());
+        assert!(validar_ruc("20100070911").is_ok());
+    }
+
+    #[test]
+    fn ruc_invalido() {
+        assert!(validar_ruc("20131312954").is_err());
+        assert!(validar_ruc("2013131295").is_err());
+        assert!(validar_ruc("2013131295A").is_err());
+        assert!(validar_ruc("30131312955").is_err());
+    }
+
+    #[test]
+    fn dni_y_carne_de_extranjeria() {
+        assert_eq!(validar_documento("DNI", " 45678912 ").unwrap(), "45678912");
+        assert!(validar_documento("DNI", "4567891").is_err());
+        assert!(validar_documento("DNI", "00000000").is_err());
+        assert_eq!(validar_documento("CE", "ab12345").unwrap(), "AB12345");
+        assert!(validar_documento("PASAPORTE", "123456").is_err());
+    }
+}
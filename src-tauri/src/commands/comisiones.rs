@@ -0,0 +1,403 @@
+// commands/comisiones.rs
+// Vendedores y comisiones.
+//
+// ventas.usuario_id es el cajero; quien atendió va en ventas.vendedor_id o,
+// si una línea la vendió otra persona, en detalles_venta.vendedor_id. Al
+// cobrar se escribe una fila en `comisiones` por línea con vendedor (con la
+// regla vigente en ese momento); devoluciones y anulaciones agregan su
+// REVERSION en negativo. El bono por meta se calcula en el reporte, sobre las
+// ventas netas del periodo consultado.
+
+use crate::commands::promociones::redondear;
+use crate::database::DatabasePool;
+use crate::models::comision::*;
+use chrono::NaiveDate;
+use rusqlite::{params, OptionalExtension};
+
+const TIPOS_REGLA: [&str; 2] = ["PORCENTAJE", "BONO_META"];
+
+// =====================================================
+// COMANDO: Listar reglas de comisión
+// =====================================================
+#[tauri::command]
+pub fn obtener_reglas_comision(db: tauri::State<'_, DatabasePool>) -> Result<Vec<ReglaComision>, String> {
+    let conn = db.get_conn();
+
+    let mut stmt = conn
+        .prepare(
+            r"SELECT r.id, r.nombre, r.tipo, r.categoria_id, c.nombre, r.vendedor_id, u.nombre_completo,
+                     COALESCE(r.porcentaje, 0), r.meta, COALESCE(r.bono, 0), r.activo
+              FROM reglas_comision r
+              LEFT JOIN categorias c ON c.id = r.categoria_id
+              LEFT JOIN usuarios u ON u.id = r.vendedor_id
+              ORDER BY r.tipo, r.activo DESC, r.nombre",
+        )
+        .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+
+    let reglas = stmt
+        .query_map([], |row| {
+            Ok(ReglaComision {
+                id: row.get(0)?,
+                nombre: row.get(1)?,
+                tipo: row.get(2)?,
+                categoria_id: row.get(3)?,
+                categoria_nombre: row.get(4)?,
+                vendedor_id: row.get(5)?,
+                vendedor_nombre: row.get(6)?,
+                porcentaje: row.get(7)?,
+                meta: row.get(8)?,
+                bono: row.get(9)?,
+                activo: row.get::<_, i32>(10)? == 1,
+            })
+        })
+        .map_err(|e| format!("Error al obtener reglas de comisión: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(reglas)
+}
+
+// =====================================================
+// COMANDO: Agregar regla de comisión
+// =====================================================
+#[tauri::command]
+pub fn agregar_regla_comision(
+    db: tauri::State<'_, DatabasePool>,
+    regla: ReglaComisionNueva,
+) -> Result<String, String> {
+    let conn = db.get_conn();
+
+    validar_regla(&conn, &regla)?;
+
+    conn.execute(
+        r"INSERT INTO reglas_comision (nombre, tipo, categoria_id, vendedor_id, porcentaje, meta, bono, activo)
+          VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        params![
+            regla.nombre.trim(),
+            &regla.tipo,
+            regla.categoria_id,
+            regla.vendedor_id,
+            regla.porcentaje.unwrap_or(0.0),
+            regla.meta,
+            regla.bono.unwrap_or(0.0),
+            regla.activo.unwrap_or(true) as i32,
+        ],
+    )
+    .map_err(|e| format!("Error al agregar regla de comisión: {}", e))?;
+
+    Ok("✅ Regla de comisión agregada exitosamente".to_string())
+}
+
+// =====================================================
+// COMANDO: Actualizar regla (también para activar/desactivar)
+// Las comisiones ya registradas conservan la regla con la que se calcularon
+// =====================================================
+#[tauri::command]
+pub fn actualizar_regla_comision(
+    db: tauri::State<'_, DatabasePool>,
+    regla_id: i32,
+    regla: ReglaComisionNueva,
+) -> Result<String, String> {
+    let conn = db.get_conn();
+
+    validar_regla(&conn, &regla)?;
+
+    let filas = conn
+        .execute(
+            r"UPDATE reglas_comision
+              SET nombre = ?, tipo = ?, categoria_id = ?, vendedor_id = ?, porcentaje = ?,
+                  meta = ?, bono = ?, activo = ?
+              WHERE id = ?",
+            params![
+                regla.nombre.trim(),
+                &regla.tipo,
+                regla.categoria_id,
+                regla.vendedor_id,
+                regla.porcentaje.unwrap_or(0.0),
+                regla.meta,
+                regla.bono.unwrap_or(0.0),
+                regla.activo.unwrap_or(true) as i32,
+                regla_id,
+            ],
+        )
+        .map_err(|e| format!("Error al actualizar regla de comisión: {}", e))?;
+
+    if filas == 0 {
+        return Err("❌ Regla de comisión no encontrada".to_string());
+    }
+
+    Ok("✅ Regla de comisión actualizada exitosamente".to_string())
+}
+
+// =====================================================
+// COMANDO: Reporte de comisiones por periodo
+// =====================================================
+#[tauri::command]
+pub fn obtener_reporte_comisiones(
+    db: tauri::State<'_, DatabasePool>,
+    fecha_inicio: String,
+    fecha_fin: String,
+    vendedor_id: Option<i32>,
+) -> Result<ReporteComisiones, String> {
+    let conn = db.get_conn();
+
+    for fecha in [&fecha_inicio, &fecha_fin] {
+        if NaiveDate::parse_from_str(fecha, "%Y-%m-%d").is_err() {
+            return Err(format!("❌ Fecha no válida: {} (usa AAAA-MM-DD)", fecha));
+        }
+    }
+    if fecha_inicio > fecha_fin {
+        return Err("❌ La fecha de inicio es posterior a la fecha de fin".to_string());
+    }
+
+    let mut stmt = conn
+        .prepare(
+            r"SELECT c.vendedor_id, u.nombre_completo,
+                     COUNT(DISTINCT CASE WHEN c.tipo = 'VENTA' THEN c.venta_id END),
+                     COALESCE(SUM(CASE WHEN c.tipo = 'VENTA' THEN c.base END), 0),
+                     COALESCE(-SUM(CASE WHEN c.tipo = 'REVERSION' THEN c.base END), 0),
+                     COALESCE(SUM(c.monto), 0)
+              FROM comisiones c
+              JOIN usuarios u ON u.id = c.vendedor_id
+              WHERE date(c.fecha_hora) BETWEEN ?1 AND ?2
+                AND (?3 IS NULL OR c.vendedor_id = ?3)
+              GROUP BY c.vendedor_id, u.nombre_completo
+              ORDER BY u.nombre_completo",
+        )
+        .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+
+    let filas: Vec<(i32, String, i32, f64, f64, f64)> = stmt
+        .query_map(params![&fecha_inicio, &fecha_fin, vendedor_id], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?))
+        })
+        .map_err(|e| format!("Error al obtener comisiones: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut vendedores = Vec::with_capacity(filas.len());
+    for (vendedor_id, vendedor_nombre, numero_ventas, ventas, devoluciones, comision) in filas {
+        let ventas_netas = redondear(ventas - devoluciones);
+        let (meta, bono) = match bono_meta(&conn, vendedor_id, ventas_netas)? {
+            Some((meta, bono)) => (Some(meta), bono),
+            None => (None, 0.0),
+        };
+        let comision = redondear(comision);
+
+        vendedores.push(ComisionVendedor {
+            vendedor_id,
+            vendedor_nombre,
+            numero_ventas,
+            ventas: redondear(ventas),
+            devoluciones: redondear(devoluciones),
+            ventas_netas,
+            comision,
+            meta,
+            bono,
+            total: redondear(comision + bono),
+        });
+    }
+
+    let total_comisiones = redondear(vendedores.iter().map(|v| v.comision).sum());
+    let total_bonos = redondear(vendedores.iter().map(|v| v.bono).sum());
+
+    Ok(ReporteComisiones {
+        fecha_inicio,
+        fecha_fin,
+        vendedores,
+        total_comisiones,
+        total_bonos,
+        total: redondear(total_comisiones + total_bonos),
+    })
+}
+
+// =====================================================
+// FUNCIONES AUXILIARES
+// =====================================================
+
+// Vendedor: usuario activo del sistema
+pub fn validar_vendedor(conn: &rusqlite::Connection, vendedor_id: i32) -> Result<(), String> {
+    let activo: Option<bool> = conn
+        .query_row(
+            "SELECT activo FROM usuarios WHERE id = ?",
+            params![vendedor_id],
+            |row| Ok(row.get::<_, i32>(0)? == 1),
+        )
+        .optional()
+        .map_err(|e| format!("Error al verificar vendedor: {}", e))?;
+
+    match activo {
+        Some(true) => Ok(()),
+        Some(false) => Err("❌ El vendedor está inactivo".to_string()),
+        None => Err("❌ Vendedor no encontrado".to_string()),
+    }
+}
+
+// Una fila por línea con vendedor (el de la línea o el de la venta). La base
+// es lo cobrado por la línea; sin regla la comisión es 0 pero cuenta para la meta.
+pub fn registrar_comisiones_venta(conn: &rusqlite::Connection, venta_id: i32) -> Result<(), String> {
+    let lineas: Vec<(i32, i32, i32, f64)> = {
+        let mut stmt = conn
+            .prepare(
+                r"SELECT d.id, COALESCE(d.vendedor_id, v.vendedor_id), p.categoria_id, d.total_linea
+                  FROM detalles_venta d
+                  JOIN ventas v ON v.id = d.venta_id
+                  JOIN productos p ON p.id = d.producto_id
+                  WHERE d.venta_id = ? AND COALESCE(d.vendedor_id, v.vendedor_id) IS NOT NULL",
+            )
+            .map_err(|e| format!("Error al preparar comisiones: {}", e))?;
+        let lineas = stmt
+            .query_map(params![venta_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))
+            .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+            .map_err(|e| format!("Error al obtener líneas para comisiones: {}", e))?;
+        lineas
+    };
+
+    for (detalle_id, vendedor_id, categoria_id, base) in lineas {
+        let regla: Option<(i32, f64)> = conn
+            .query_row(
+                r"SELECT id, porcentaje FROM reglas_comision
+                  WHERE tipo = 'PORCENTAJE' AND activo = 1
+                    AND (categoria_id IS NULL OR categoria_id = ?1)
+                    AND (vendedor_id IS NULL OR vendedor_id = ?2)
+                  ORDER BY categoria_id IS NULL, vendedor_id IS NULL, id DESC
+                  LIMIT 1",
+                params![categoria_id, vendedor_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+            .map_err(|e| format!("Error al obtener regla de comisión: {}", e))?;
+
+        let porcentaje = regla.map_or(0.0, |(_, p)| p);
+        conn.execute(
+            r"INSERT INTO comisiones (vendedor_id, venta_id, detalle_venta_id, regla_id, tipo, base, porcentaje, monto)
+              VALUES (?, ?, ?, ?, 'VENTA', ?, ?, ?)",
+            params![
+                vendedor_id,
+                venta_id,
+                detalle_id,
+                regla.map(|(id, _)| id),
+                base,
+                porcentaje,
+                redondear(base * porcentaje / 100.0),
+            ],
+        )
+        .map_err(|e| format!("Error al registrar comisión: {}", e))?;
+    }
+
+    Ok(())
+}
+
+// Devolución (o cambio): se revierte la parte proporcional de cada línea devuelta
+pub fn revertir_comisiones_devolucion(conn: &rusqlite::Connection, devolucion_id: i32) -> Result<(), String> {
+    conn.execute(
+        r"INSERT INTO comisiones
+            (vendedor_id, venta_id, detalle_venta_id, devolucion_id, regla_id, tipo, base, porcentaje, monto)
+          SELECT c.vendedor_id, c.venta_id, c.detalle_venta_id, dd.devolucion_id, c.regla_id, 'REVERSION',
+                 -ROUND(c.base * dd.cantidad_devuelta / dv.cantidad, 2), c.porcentaje,
+                 -ROUND(c.monto * dd.cantidad_devuelta / dv.cantidad, 2)
+          FROM detalles_devolucion dd
+          JOIN detalles_venta dv ON dv.id = dd.detalle_venta_id
+          JOIN comisiones c ON c.detalle_venta_id = dd.detalle_venta_id AND c.tipo = 'VENTA'
+          WHERE dd.devolucion_id = ?",
+        params![devolucion_id],
+    )
+    .map_err(|e| format!("Error al revertir comisiones: {}", e))?;
+
+    Ok(())
+}
+
+// Venta anulada: se revierte todo lo que quedaba de cada línea
+pub fn revertir_comisiones_venta(conn: &rusqlite::Connection, venta_id: i32) -> Result<(), String> {
+    conn.execute(
+        r"INSERT INTO comisiones
+            (vendedor_id, venta_id, detalle_venta_id, regla_id, tipo, base, porcentaje, monto)
+          SELECT vendedor_id, venta_id, detalle_venta_id, MAX(regla_id), 'REVERSION',
+                 -ROUND(SUM(base), 2), MAX(porcentaje), -ROUND(SUM(monto), 2)
+          FROM comisiones
+          WHERE venta_id = ?
+          GROUP BY vendedor_id, venta_id, detalle_venta_id
+          HAVING ABS(SUM(base)) > 0.005 OR ABS(SUM(monto)) > 0.005",
+        params![venta_id],
+    )
+    .map_err(|e| format!("Error al revertir comisiones: {}", e))?;
+
+    Ok(())
+}
+
+// Bono por meta: las reglas del vendedor reemplazan a las generales y se paga
+// solo la meta más alta alcanzada. Devuelve (meta, bono).
+fn bono_meta(conn: &rusqlite::Connection, vendedor_id: i32, ventas_netas: f64) -> Result<Option<(f64, f64)>, String> {
+    let mut stmt = conn
+        .prepare(
+            r"SELECT vendedor_id IS NOT NULL, meta, COALESCE(bono, 0), COALESCE(porcentaje, 0)
+              FROM reglas_comision
+              WHERE tipo = 'BONO_META' AND activo = 1 AND meta IS NOT NULL
+                AND (vendedor_id IS NULL OR vendedor_id = ?)
+              ORDER BY meta DESC",
+        )
+        .map_err(|e| format!("Error al preparar bonos: {}", e))?;
+
+    let reglas: Vec<(bool, f64, f64, f64)> = stmt
+        .query_map(params![vendedor_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))
+        .map_err(|e| format!("Error al obtener bonos: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let propias = reglas.iter().any(|(propia, ..)| *propia);
+    Ok(reglas
+        .into_iter()
+        .filter(|(propia, ..)| *propia == propias)
+        .find(|(_, meta, ..)| ventas_netas + 0.005 >= *meta)
+        .map(|(_, meta, bono, porcentaje)| (meta, redondear(bono + (ventas_netas - meta) * porcentaje / 100.0))))
+}
+
+fn validar_regla(conn: &rusqlite::Connection, r: &ReglaComisionNueva) -> Result<(), String> {
+    if r.nombre.trim().is_empty() {
+        return Err("❌ La regla debe tener un nombre".to_string());
+    }
+    if !TIPOS_REGLA.contains(&r.tipo.as_str()) {
+        return Err(format!("❌ Tipo de regla no válido: {}", r.tipo));
+    }
+    if r.porcentaje.is_some_and(|p| !(0.0..=100.0).contains(&p)) {
+        return Err("❌ El porcentaje debe estar entre 0 y 100".to_string());
+    }
+    if r.bono.is_some_and(|b| b < 0.0) {
+        return Err("❌ El bono no puede ser negativo".to_string());
+    }
+
+    match r.tipo.as_str() {
+        "PORCENTAJE" => {
+            if r.porcentaje.unwrap_or(0.0) <= 0.0 {
+                return Err("❌ Indica el porcentaje de comisión".to_string());
+            }
+            if r.meta.is_some() || r.bono.is_some() {
+                return Err("❌ La meta y el bono son solo para reglas BONO_META".to_string());
+            }
+        }
+        _ => {
+            if r.meta.unwrap_or(0.0) <= 0.0 {
+                return Err("❌ Indica la meta de ventas del periodo".to_string());
+            }
+            if r.bono.unwrap_or(0.0) <= 0.0 && r.porcentaje.unwrap_or(0.0) <= 0.0 {
+                return Err("❌ Indica el bono o el porcentaje sobre lo que pase de la meta".to_string());
+            }
+            if r.categoria_id.is_some() {
+                return Err("❌ La meta se mide sobre todas las ventas del vendedor, no por categoría".to_string());
+            }
+        }
+    }
+
+    if let Some(id) = r.categoria_id {
+        let existe = conn
+            .query_row("SELECT 1 FROM categorias WHERE id = ?", params![id], |_| Ok(()))
+            .optional()
+            .map_err(|e| format!("Error al verificar categoría: {}", e))?;
+        if existe.is_none() {
+            return Err("❌ Categoría no encontrada".to_string());
+        }
+    }
+    if let Some(id) = r.vendedor_id {
+        validar_vendedor(conn, id)?;
+    }
+    Ok(())
+}
@@ -15,6 +15,9 @@ pub struct ConfiguracionTienda {
     pub impresora_ip: String,
     pub impresora_tipo: String,
     pub impresora_puerto: i32,
+    pub iva_porcentaje: f64, // 🆕 IGV incluido en los precios
+    pub redondeo_efectivo: String, // 🆕 NINGUNO | CERCANO | ABAJO (a S/ 0.10)
+    pub dias_devolucion: i32, // 🆕 plazo de devolución desde la venta
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -48,7 +51,7 @@ pub fn obtener_configuracion_tienda(
 ) -> Result<ConfiguracionTienda, String> {
     let conn = db.get_conn();
 
-    let query = "SELECT id, nombre_tienda, direccion, telefono, email, rfc, mensaje_recibo, COALESCE(impresora_ip, ''), COALESCE(impresora_tipo, 'TERMICA'), COALESCE(impresora_puerto, 9100) FROM configuracion_tienda LIMIT 1";
+    let query = "SELECT id, nombre_tienda, direccion, telefono, email, rfc, mensaje_recibo, COALESCE(impresora_ip, ''), COALESCE(impresora_tipo, 'TERMICA'), COALESCE(impresora_puerto, 9100), COALESCE(iva_porcentaje, 0), COALESCE(redondeo_efectivo, 'NINGUNO'), COALESCE(dias_devolucion, 7) FROM configuracion_tienda LIMIT 1";
     
     let result = conn
         .query_row(query, [], |row| {
@@ -63,6 +66,9 @@ pub fn obtener_configuracion_tienda(
                 impresora_ip: row.get(7)?,
                 impresora_tipo: row.get(8)?,
                 impresora_puerto: row.get(9)?,
+                iva_porcentaje: row.get(10)?,
+                redondeo_efectivo: row.get(11)?,
+                dias_devolucion: row.get(12)?,
             })
         })
         .optional()
@@ -83,9 +89,22 @@ pub fn actualizar_configuracion_tienda(
     impresora_ip: String,
     impresora_tipo: String,
     impresora_puerto: i32,
+    iva_porcentaje: Option<f64>, // 🆕 None = se mantiene la actual
+    redondeo_efectivo: Option<String>, // 🆕 None = se mantiene la actual
+    dias_devolucion: Option<i32>, // 🆕 None = se mantiene la actual
 ) -> Result<String, String> {
     let conn = db.get_conn();
 
+    if iva_porcentaje.is_some_and(|t| !(0.0..100.0).contains(&t)) {
+        return Err("El porcentaje de IGV debe estar entre 0 y 100".to_string());
+    }
+    if redondeo_efectivo.as_deref().is_some_and(|r| !["NINGUNO", "CERCANO", "ABAJO"].contains(&r)) {
+        return Err("El redondeo de efectivo debe ser NINGUNO, CERCANO o ABAJO".to_string());
+    }
+    if dias_devolucion.is_some_and(|d| d < 0) {
+        return Err("Los días de devolución no pueden ser negativos".to_string());
+    }
+
     let query = r"
         UPDATE configuracion_tienda 
         SET nombre_tienda = ?,
@@ -96,7 +115,10 @@ pub fn actualizar_configuracion_tienda(
             mensaje_recibo = ?,
             impresora_ip = ?,
             impresora_tipo = ?,
-            impresora_puerto = ?
+            impresora_puerto = ?,
+            iva_porcentaje = COALESCE(?, iva_porcentaje),
+            redondeo_efectivo = COALESCE(?, redondeo_efectivo),
+            dias_devolucion = COALESCE(?, dias_devolucion)
         WHERE id = 1
     ";
 
@@ -112,6 +134,9 @@ pub fn actualizar_configuracion_tienda(
             &impresora_ip,
             &impresora_tipo,
             impresora_puerto,
+            iva_porcentaje,
+            &redondeo_efectivo,
+            dias_devolucion,
         ],
     )
     .map_err(|e| format!("Error al actualizar configuracion: {}", e))?;
@@ -124,11 +149,15 @@ pub fn agregar_categoria(
     db: tauri::State<DatabasePool>,
     nombre: String,
     descripcion: Option<String>,
+    dias_devolucion: Option<i32>, // 🆕 None = política de la tienda, 0 = sin devolución
 ) -> Result<String, String> {
+    if dias_devolucion.is_some_and(|d| d < 0) {
+        return Err("Los días de devolución no pueden ser negativos".to_string());
+    }
     let conn = db.get_conn();
     conn.execute(
-        "INSERT INTO categorias (nombre, descripcion) VALUES (?, ?)",
-        params![&nombre, &descripcion],
+        "INSERT INTO categorias (nombre, descripcion, dias_devolucion) VALUES (?, ?, ?)",
+        params![&nombre, &descripcion, dias_devolucion],
     )
     .map_err(|e| format!("Error al agregar categoria: {}", e))?;
     Ok("Categoria agregada exitosamente".to_string())
@@ -140,11 +169,15 @@ pub fn actualizar_categoria(
     categoria_id: i32,
     nombre: String,
     descripcion: Option<String>,
+    dias_devolucion: Option<i32>, // 🆕 None = política de la tienda, 0 = sin devolución
 ) -> Result<String, String> {
+    if dias_devolucion.is_some_and(|d| d < 0) {
+        return Err("Los días de devolución no pueden ser negativos".to_string());
+    }
     let conn = db.get_conn();
     conn.execute(
-        "UPDATE categorias SET nombre = ?, descripcion = ? WHERE id = ?",
-        params![&nombre, &descripcion, categoria_id],
+        "UPDATE categorias SET nombre = ?, descripcion = ?, dias_devolucion = ? WHERE id = ?",
+        params![&nombre, &descripcion, dias_devolucion, categoria_id],
     )
     .map_err(|e| format!("Error al actualizar categoria: {}", e))?;
     Ok("Categoria actualizada exitosamente".to_string())
@@ -0,0 +1,479 @@
+// commands/cotizaciones.rs
+// Cotizaciones para clientes corporativos (uniformes, eventos): líneas con
+// precio y descuento, cliente y fecha de validez.
+//
+// Para venderla, el POS carga el carrito con cargar_cotizacion y cobra con
+// procesar_venta(..., cotizacion_id): ahí se revalida stock y precio y la venta
+// queda enlazada a la cotización. Mientras está vigente se respeta el precio
+// cotizado, salvo que el de lista haya bajado.
+
+use crate::commands::numeracion::siguiente_folio;
+use crate::commands::promociones::redondear;
+use crate::commands::suspendidas::stock_disponible;
+use crate::commands::ventas::ProductoVenta;
+use crate::database::DatabasePool;
+use chrono::{Duration, Local, NaiveDate};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+const DIAS_VALIDEZ_COTIZACION: i64 = 15;
+
+// =====================================================
+// ESTRUCTURAS
+// =====================================================
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Cotizacion {
+    pub id: i32,
+    pub folio: String,
+    pub cliente_id: Option<i32>,
+    pub cliente_nombre: Option<String>,
+    pub cliente_documento: Option<String>, // RUC 20123456786
+    pub contacto: Option<String>,
+    pub subtotal: f64,
+    pub descuento: f64,
+    pub total: f64,
+    pub fecha_emision: String,
+    pub fecha_validez: String,
+    pub estado: String, // VIGENTE | VENCIDA | CONVERTIDA | ANULADA
+    pub notas: Option<String>,
+    pub usuario_id: i32,
+    pub venta_id: Option<i32>,
+    pub folio_venta: Option<String>,
+    pub productos: Vec<ProductoVenta>,
+}
+
+// =====================================================
+// COMANDO: Crear cotización
+// =====================================================
+#[tauri::command]
+pub fn crear_cotizacion(
+    db: tauri::State<'_, DatabasePool>,
+    productos: Vec<ProductoVenta>,
+    usuario_id: i32,
+    cliente_id: Option<i32>,
+    contacto: Option<String>,
+    fecha_validez: Option<String>, // 'YYYY-MM-DD'; por defecto 15 días
+    notas: Option<String>,
+) -> Result<Cotizacion, String> {
+    let conn = db.get_conn();
+
+    if productos.is_empty() {
+        return Err("❌ La cotización no tiene productos".to_string());
+    }
+
+    let contacto = contacto.map(|c| c.trim().to_string()).filter(|c| !c.is_empty());
+    if cliente_id.is_none() && contacto.is_none() {
+        return Err("❌ Indica el cliente o el contacto de la cotización".to_string());
+    }
+    if let Some(id) = cliente_id {
+        let activo: Option<i32> = conn
+            .query_row("SELECT activo FROM clientes WHERE id = ?", params![id], |row| row.get(0))
+            .optional()
+            .map_err(|e| format!("Error al obtener cliente: {}", e))?;
+        match activo {
+            None => return Err("❌ Cliente no encontrado".to_string()),
+            Some(0) => return Err("❌ El cliente está inactivo".to_string()),
+            _ => {}
+        }
+    }
+
+    let hoy = Local::now().date_naive();
+    let fecha_validez = match fecha_validez.as_deref().map(str::trim).filter(|f| !f.is_empty()) {
+        Some(f) => NaiveDate::parse_from_str(f, "%Y-%m-%d")
+            .map_err(|_| format!("❌ Fecha de validez no válida: {} (usa AAAA-MM-DD)", f))?,
+        None => hoy + Duration::days(DIAS_VALIDEZ_COTIZACION),
+    };
+    if fecha_validez < hoy {
+        return Err("❌ La fecha de validez no puede ser anterior a hoy".to_string());
+    }
+
+    for p in &productos {
+        if p.cantidad <= 0 {
+            return Err(format!("❌ Cantidad no válida para {}", p.nombre));
+        }
+        if p.precio < 0.0 {
+            return Err(format!("❌ Precio no válido para {}", p.nombre));
+        }
+        if !(0.0..=100.0).contains(&p.descuento_porcentaje.unwrap_or(0.0)) {
+            return Err(format!("❌ El descuento de {} debe estar entre 0 y 100%", p.nombre));
+        }
+        let activo: bool = conn
+            .query_row(
+                "SELECT 1 FROM productos WHERE id = ? AND activo = 1",
+                params![p.id],
+                |_| Ok(true),
+            )
+            .optional()
+            .map_err(|e| format!("Error al verificar producto: {}", e))?
+            .unwrap_or(false);
+        if !activo {
+            return Err(format!("❌ El producto {} no existe o está inactivo", p.nombre));
+        }
+    }
+
+    let subtotal: f64 = productos.iter().map(|p| p.precio * p.cantidad as f64).sum();
+    let total: f64 = productos.iter().map(total_linea).sum();
+
+    conn.execute("BEGIN TRANSACTION", [])
+        .map_err(|e| format!("Error al iniciar transacción: {}", e))?;
+
+    let rollback = |conn: &rusqlite::Connection, msg: String| -> String {
+        let _ = conn.execute("ROLLBACK", []);
+        msg
+    };
+
+    let folio = match siguiente_folio(&conn, "COTIZACION") {
+        Ok(f) => f,
+        Err(e) => return Err(rollback(&conn, e)),
+    };
+
+    if let Err(e) = conn.execute(
+        r"INSERT INTO cotizaciones
+            (folio, cliente_id, contacto, subtotal, descuento, total, fecha_validez, notas, usuario_id)
+          VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        params![
+            &folio,
+            cliente_id,
+            &contacto,
+            redondear(subtotal),
+            redondear(subtotal - total),
+            redondear(total),
+            fecha_validez.format("%Y-%m-%d").to_string(),
+            notas.as_deref().map(str::trim).filter(|n| !n.is_empty()),
+            usuario_id,
+        ],
+    ) {
+        return Err(rollback(&conn, format!("Error al crear cotización: {}", e)));
+    }
+
+    let cotizacion_id = conn.last_insert_rowid() as i32;
+
+    for p in &productos {
+        if let Err(e) = conn.execute(
+            r"INSERT INTO detalles_cotizacion
+                (cotizacion_id, producto_id, variante_id, talla, nombre, codigo,
+                 cantidad, precio_unitario, descuento_porcentaje, total_linea)
+              VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                cotizacion_id,
+                p.id,
+                p.variante_id,
+                p.talla,
+                &p.nombre,
+                &p.codigo,
+                p.cantidad,
+                p.precio,
+                p.descuento_porcentaje.unwrap_or(0.0),
+                redondear(total_linea(p)),
+            ],
+        ) {
+            return Err(rollback(&conn, format!("Error al guardar línea: {}", e)));
+        }
+    }
+
+    conn.execute("COMMIT", [])
+        .map_err(|e| format!("Error al confirmar: {}", e))?;
+
+    obtener_cotizacion_por_id(&conn, cotizacion_id)
+}
+
+// =====================================================
+// COMANDO: Listar cotizaciones
+// =====================================================
+#[tauri::command]
+pub fn obtener_cotizaciones(
+    db: tauri::State<'_, DatabasePool>,
+    estado: Option<String>, // VIGENTE | VENCIDA | CONVERTIDA | ANULADA
+    cliente_id: Option<i32>,
+) -> Result<Vec<Cotizacion>, String> {
+    let conn = db.get_conn();
+
+    let ids: Vec<i32> = {
+        let mut stmt = conn
+            .prepare(&format!(
+                r"SELECT c.id FROM cotizaciones c
+                  WHERE (?1 IS NULL OR {} = ?1)
+                    AND (?2 IS NULL OR c.cliente_id = ?2)
+                  ORDER BY c.fecha_emision DESC, c.id DESC",
+                ESTADO_COTIZACION
+            ))
+            .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+        let ids = stmt
+            .query_map(params![estado.map(|e| e.trim().to_uppercase()), cliente_id], |row| row.get(0))
+            .map_err(|e| format!("Error al obtener cotizaciones: {}", e))?
+            .collect::<rusqlite::Result<Vec<i32>>>()
+            .map_err(|e| format!("Error al leer cotizaciones: {}", e))?;
+        ids
+    };
+
+    ids.into_iter()
+        .map(|id| obtener_cotizacion_por_id(&conn, id))
+        .collect()
+}
+
+// =====================================================
+// COMANDO: Obtener cotización
+// =====================================================
+#[tauri::command]
+pub fn obtener_cotizacion(
+    db: tauri::State<'_, DatabasePool>,
+    cotizacion_id: i32,
+) -> Result<Cotizacion, String> {
+    let conn = db.get_conn();
+    obtener_cotizacion_por_id(&conn, cotizacion_id)
+}
+
+// =====================================================
+// COMANDO: Anular cotización
+// =====================================================
+#[tauri::command]
+pub fn anular_cotizacion(
+    db: tauri::State<'_, DatabasePool>,
+    cotizacion_id: i32,
+) -> Result<String, String> {
+    let conn = db.get_conn();
+
+    let estado: Option<String> = conn
+        .query_row(
+            "SELECT estado FROM cotizaciones WHERE id = ?",
+            params![cotizacion_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Error al buscar cotización: {}", e))?;
+
+    match estado.as_deref() {
+        None => return Err("❌ Cotización no encontrada".to_string()),
+        Some("CONVERTIDA") => return Err("❌ La cotización ya se convirtió en venta".to_string()),
+        Some("ANULADA") => return Err("❌ La cotización ya está anulada".to_string()),
+        _ => {}
+    }
+
+    conn.execute(
+        "UPDATE cotizaciones SET estado = 'ANULADA' WHERE id = ? AND estado = 'VIGENTE'",
+        params![cotizacion_id],
+    )
+    .map_err(|e| format!("Error al anular cotización: {}", e))?;
+
+    Ok("✅ Cotización anulada".to_string())
+}
+
+// =====================================================
+// COMANDO: Cargar cotización en el POS
+// =====================================================
+// Devuelve el carrito con los precios revalidados; se cobra con
+// procesar_venta pasando cotizacion_id
+#[tauri::command]
+pub fn cargar_cotizacion(
+    db: tauri::State<'_, DatabasePool>,
+    cotizacion_id: i32,
+) -> Result<Cotizacion, String> {
+    let conn = db.get_conn();
+
+    let mut cotizacion = obtener_cotizacion_por_id(&conn, cotizacion_id)?;
+    cotizacion.productos = lineas_para_venta(&conn, cotizacion_id)?;
+
+    for p in &cotizacion.productos {
+        let disponible = stock_disponible(&conn, p.id, p.variante_id)?;
+        if disponible < p.cantidad {
+            return Err(format!(
+                "❌ Stock insuficiente para {}{} (disponible: {}, cotizado: {})",
+                p.nombre,
+                p.talla.as_deref().map(|t| format!(" talla {}", t)).unwrap_or_default(),
+                disponible,
+                p.cantidad
+            ));
+        }
+    }
+
+    cotizacion.subtotal = redondear(cotizacion.productos.iter().map(|p| p.precio * p.cantidad as f64).sum());
+    cotizacion.total = redondear(cotizacion.productos.iter().map(total_linea).sum());
+    cotizacion.descuento = redondear(cotizacion.subtotal - cotizacion.total);
+    Ok(cotizacion)
+}
+
+// =====================================================
+// FUNCIONES AUXILIARES
+// =====================================================
+
+// Estado con VENCIDA calculado (vigente pasada su fecha de validez); alias c
+const ESTADO_COTIZACION: &str = r"
+    CASE WHEN c.estado = 'VIGENTE' AND c.fecha_validez < date('now', 'localtime') THEN 'VENCIDA' ELSE c.estado END";
+
+fn total_linea(p: &ProductoVenta) -> f64 {
+    p.precio * p.cantidad as f64 * (1.0 - p.descuento_porcentaje.unwrap_or(0.0) / 100.0)
+}
+
+// Líneas de una cotización vigente listas para vender: precio cotizado o el
+// de lista si ahora es menor
+pub fn lineas_para_venta(conn: &rusqlite::Connection, cotizacion_id: i32) -> Result<Vec<ProductoVenta>, String> {
+    let cotizacion = obtener_cotizacion_por_id(conn, cotizacion_id)?;
+    match cotizacion.estado.as_str() {
+        "VIGENTE" => {}
+        "VENCIDA" => {
+            return Err(format!(
+                "❌ La cotización {} venció el {}; genera una nueva",
+                cotizacion.folio, cotizacion.fecha_validez
+            ))
+        }
+        "CONVERTIDA" => {
+            return Err(format!(
+                "❌ La cotización {} ya se vendió ({})",
+                cotizacion.folio,
+                cotizacion.folio_venta.unwrap_or_default()
+            ))
+        }
+        estado => return Err(format!("❌ La cotización {} está {}", cotizacion.folio, estado)),
+    }
+
+    let mut productos = cotizacion.productos;
+    for p in productos.iter_mut() {
+        let precio_lista: Option<f64> = conn
+            .query_row(
+                "SELECT precio FROM productos WHERE id = ? AND activo = 1",
+                params![p.id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| format!("Error al verificar producto: {}", e))?;
+        match precio_lista {
+            Some(precio) => p.precio = p.precio.min(precio),
+            None => return Err(format!("❌ El producto {} ya no está disponible", p.nombre)),
+        }
+    }
+
+    Ok(productos)
+}
+
+// Antes de cobrar: el carrito debe ser el de la cotización (mismas líneas,
+// cantidades, precios y descuentos). Devuelve el cliente de la venta.
+pub fn validar_carrito_cotizacion(
+    conn: &rusqlite::Connection,
+    cotizacion_id: i32,
+    productos: &[ProductoVenta],
+    cliente_id: Option<i32>,
+) -> Result<Option<i32>, String> {
+    let esperado = lineas_para_venta(conn, cotizacion_id)?;
+    let (folio, cliente_cotizacion): (String, Option<i32>) = conn
+        .query_row(
+            "SELECT folio, cliente_id FROM cotizaciones WHERE id = ?",
+            params![cotizacion_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| format!("Error al obtener cotización: {}", e))?;
+
+    let coincide = esperado.len() == productos.len()
+        && esperado.iter().zip(productos).all(|(e, p)| {
+            e.id == p.id
+                && e.variante_id == p.variante_id
+                && e.cantidad == p.cantidad
+                && (e.precio - p.precio).abs() < 0.005
+                && (e.descuento_porcentaje.unwrap_or(0.0) - p.descuento_porcentaje.unwrap_or(0.0)).abs() < 0.005
+        });
+    if !coincide {
+        return Err(format!(
+            "❌ El carrito no coincide con la cotización {}. Vuelve a cargarla",
+            folio
+        ));
+    }
+
+    match (cliente_cotizacion, cliente_id) {
+        (Some(c), Some(v)) if c != v => Err(format!("❌ La cotización {} es de otro cliente", folio)),
+        (Some(c), _) => Ok(Some(c)),
+        (None, v) => Ok(v),
+    }
+}
+
+// Dentro de la transacción de la venta
+pub fn marcar_cotizacion_convertida(
+    conn: &rusqlite::Connection,
+    cotizacion_id: i32,
+    venta_id: i32,
+) -> Result<(), String> {
+    let filas = conn
+        .execute(
+            r"UPDATE cotizaciones
+              SET estado = 'CONVERTIDA', venta_id = ?, fecha_conversion = datetime('now', 'localtime')
+              WHERE id = ? AND estado = 'VIGENTE'",
+            params![venta_id, cotizacion_id],
+        )
+        .map_err(|e| format!("Error al actualizar cotización: {}", e))?;
+
+    if filas == 0 {
+        return Err("❌ La cotización ya no está vigente".to_string());
+    }
+    Ok(())
+}
+
+pub fn obtener_cotizacion_por_id(conn: &rusqlite::Connection, id: i32) -> Result<Cotizacion, String> {
+    let mut cotizacion = conn
+        .query_row(
+            &format!(
+                r"SELECT c.id, c.folio, c.cliente_id, cl.nombre,
+                         cl.tipo_documento || ' ' || cl.numero_documento, c.contacto,
+                         c.subtotal, COALESCE(c.descuento, 0), c.total, c.fecha_emision, c.fecha_validez,
+                         {}, c.notas, c.usuario_id, c.venta_id, v.folio
+                  FROM cotizaciones c
+                  LEFT JOIN clientes cl ON cl.id = c.cliente_id
+                  LEFT JOIN ventas v ON v.id = c.venta_id
+                  WHERE c.id = ?",
+                ESTADO_COTIZACION
+            ),
+            params![id],
+            |row| {
+                Ok(Cotizacion {
+                    id: row.get(0)?,
+                    folio: row.get(1)?,
+                    cliente_id: row.get(2)?,
+                    cliente_nombre: row.get(3)?,
+                    cliente_documento: row.get(4)?,
+                    contacto: row.get(5)?,
+                    subtotal: row.get(6)?,
+                    descuento: row.get(7)?,
+                    total: row.get(8)?,
+                    fecha_emision: row.get(9)?,
+                    fecha_validez: row.get(10)?,
+                    estado: row.get(11)?,
+                    notas: row.get(12)?,
+                    usuario_id: row.get(13)?,
+                    venta_id: row.get(14)?,
+                    folio_venta: row.get(15)?,
+                    productos: Vec::new(),
+                })
+            },
+        )
+        .optional()
+        .map_err(|e| format!("Error al obtener cotización: {}", e))?
+        .ok_or_else(|| "❌ Cotización no encontrada".to_string())?;
+
+    let mut stmt = conn
+        .prepare(
+            r"SELECT producto_id, nombre, codigo, precio_unitario, cantidad,
+                     descuento_porcentaje, variante_id, talla
+              FROM detalles_cotizacion
+              WHERE cotizacion_id = ?
+              ORDER BY id",
+        )
+        .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+
+    cotizacion.productos = stmt
+        .query_map(params![id], |row| {
+            Ok(ProductoVenta {
+                id: row.get(0)?,
+                nombre: row.get(1)?,
+                codigo: row.get(2)?,
+                precio: row.get(3)?,
+                cantidad: row.get(4)?,
+                descuento_porcentaje: row.get(5)?,
+                variante_id: row.get(6)?,
+                talla: row.get(7)?,
+                vendedor_id: None,
+            })
+        })
+        .map_err(|e| format!("Error al obtener líneas: {}", e))?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| format!("Error al leer líneas: {}", e))?;
+
+    Ok(cotizacion)
+}
@@ -0,0 +1,460 @@
+// commands/creditos.rs
+// Ventas al crédito (fiado) y cuentas por cobrar.
+//
+// La parte al crédito de una venta es una línea CREDITO en pagos_venta; la
+// venta guarda saldo_pendiente, fecha_vencimiento_pago y estado_pago, como
+// compras del lado del proveedor. Cada cobro (o devolución descontada de la
+// deuda) es una fila en pagos_credito y trg_actualizar_saldo_venta recalcula
+// el saldo de la venta.
+
+use crate::commands::clientes::obtener_cliente_por_id;
+use crate::commands::numeracion::siguiente_folio;
+use crate::commands::promociones::redondear;
+use crate::database::DatabasePool;
+use crate::models::credito::*;
+use chrono::{Duration, Local, NaiveDate};
+use rusqlite::{params, OptionalExtension};
+
+// =====================================================
+// COMANDO: Registrar cobro al cliente
+// El efectivo entra a la caja como INGRESO (no suma a ventas)
+// =====================================================
+#[tauri::command]
+pub fn registrar_pago_cliente(
+    db: tauri::State<'_, DatabasePool>,
+    request: RegistrarPagoClienteRequest,
+    usuario_id: i32,
+) -> Result<ResultadoPagoCliente, String> {
+    let conn = db.get_conn();
+
+    if !["EFECTIVO", "TARJETA", "TRANSFERENCIA"].contains(&request.metodo_pago.as_str()) {
+        return Err(format!("❌ Método de pago no válido: {}", request.metodo_pago));
+    }
+    let monto = redondear(request.monto);
+    if monto <= 0.0 {
+        return Err("❌ El monto del cobro debe ser mayor a 0".to_string());
+    }
+
+    let caja_id: Option<i32> = conn
+        .query_row(
+            "SELECT id FROM cajas WHERE usuario_id = ? AND estado = 'ABIERTA'",
+            params![usuario_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Error al verificar caja: {}", e))?;
+    if request.metodo_pago == "EFECTIVO" && caja_id.is_none() {
+        return Err("⚠️ Debes abrir una caja antes de cobrar en efectivo".to_string());
+    }
+
+    let cliente = obtener_cliente_por_id(&conn, request.cliente_id)?;
+
+    // Deudas a cubrir: la venta indicada o todas, de la más antigua a la más nueva
+    let deudas: Vec<(i32, String, f64)> = {
+        let mut stmt = conn
+            .prepare(
+                r"SELECT id, folio, saldo_pendiente FROM ventas
+                  WHERE cliente_id = ?1 AND estado = 'COMPLETADA' AND saldo_pendiente > 0
+                    AND (?2 IS NULL OR id = ?2)
+                  ORDER BY fecha_vencimiento_pago, fecha_hora, id",
+            )
+            .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+        let deudas = stmt
+            .query_map(params![request.cliente_id, request.venta_id], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })
+            .map_err(|e| format!("Error al obtener deudas: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+        deudas
+    };
+
+    let saldo: f64 = redondear(deudas.iter().map(|(_, _, s)| s).sum());
+    if deudas.is_empty() {
+        return Err(match request.venta_id {
+            Some(_) => "❌ La venta no tiene saldo pendiente para este cliente".to_string(),
+            None => format!("❌ {} no tiene deudas pendientes", cliente.nombre),
+        });
+    }
+    if monto > saldo + 0.01 {
+        return Err(format!(
+            "❌ El monto S/ {:.2} supera el saldo pendiente S/ {:.2}",
+            monto, saldo
+        ));
+    }
+
+    conn.execute("BEGIN TRANSACTION", [])
+        .map_err(|e| format!("Error al iniciar transacción: {}", e))?;
+
+    let rollback = |conn: &rusqlite::Connection, msg: String| -> String {
+        let _ = conn.execute("ROLLBACK", []);
+        msg
+    };
+
+    let folio = match siguiente_folio(&conn, "COBRO") {
+        Ok(f) => f,
+        Err(e) => return Err(rollback(&conn, e)),
+    };
+
+    // El trigger trg_actualizar_saldo_venta actualiza el saldo de cada venta
+    let mut restante = monto;
+    let mut aplicaciones = Vec::new();
+    for (venta_id, folio_venta, saldo_venta) in deudas {
+        if restante <= 0.005 {
+            break;
+        }
+        let aplicado = redondear(restante.min(saldo_venta));
+        if let Err(e) = conn.execute(
+            r"INSERT INTO pagos_credito
+                (venta_id, cliente_id, folio, monto, metodo_pago, referencia, notas, caja_id, usuario_id)
+              VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                venta_id,
+                request.cliente_id,
+                &folio,
+                aplicado,
+                &request.metodo_pago,
+                &request.referencia,
+                &request.notas,
+                caja_id,
+                usuario_id,
+            ],
+        ) {
+            return Err(rollback(&conn, format!("Error al registrar cobro: {}", e)));
+        }
+        restante = redondear(restante - aplicado);
+        aplicaciones.push(AplicacionPago {
+            venta_id,
+            folio_venta,
+            monto: aplicado,
+            saldo_restante: redondear(saldo_venta - aplicado),
+        });
+    }
+
+    if let (Some(caja_id), "EFECTIVO") = (caja_id, request.metodo_pago.as_str()) {
+        if let Err(e) = conn.execute(
+            r"INSERT INTO movimientos_caja (caja_id, tipo, monto, motivo, usuario_id)
+              VALUES (?, 'INGRESO', ?, ?, ?)",
+            params![caja_id, monto, format!("Cobro {} - {}", folio, cliente.nombre), usuario_id],
+        ) {
+            return Err(rollback(&conn, format!("Error al registrar ingreso en caja: {}", e)));
+        }
+    }
+
+    let saldo_cliente = match saldo_credito_cliente(&conn, request.cliente_id) {
+        Ok((saldo, _)) => saldo,
+        Err(e) => return Err(rollback(&conn, e)),
+    };
+
+    conn.execute("COMMIT", [])
+        .map_err(|e| format!("Error al confirmar: {}", e))?;
+
+    Ok(ResultadoPagoCliente { folio, monto, aplicaciones, saldo_cliente })
+}
+
+// =====================================================
+// COMANDO: Cuentas por cobrar (ventas al crédito con saldo)
+// =====================================================
+#[tauri::command]
+pub fn obtener_cuentas_por_cobrar(
+    db: tauri::State<'_, DatabasePool>,
+    cliente_id: Option<i32>,
+    solo_vencidas: Option<bool>,
+) -> Result<Vec<CuentaPorCobrar>, String> {
+    let conn = db.get_conn();
+    let cuentas = cuentas_por_cobrar(&conn, cliente_id)?;
+
+    Ok(if solo_vencidas.unwrap_or(false) {
+        cuentas.into_iter().filter(|c| c.dias_vencido > 0).collect()
+    } else {
+        cuentas
+    })
+}
+
+// =====================================================
+// COMANDO: Antigüedad de saldos (corriente, 1-30, 31-60, 61-90, +90 días)
+// =====================================================
+#[tauri::command]
+pub fn obtener_antiguedad_saldos(
+    db: tauri::State<'_, DatabasePool>,
+) -> Result<ReporteAntiguedad, String> {
+    let conn = db.get_conn();
+
+    let mut clientes: Vec<AntiguedadCliente> = Vec::new();
+    let mut totales = AntiguedadSaldos::default();
+
+    for cuenta in cuentas_por_cobrar(&conn, None)? {
+        let indice = match clientes.iter().position(|c| c.cliente_id == cuenta.cliente_id) {
+            Some(i) => i,
+            None => {
+                let cliente = obtener_cliente_por_id(&conn, cuenta.cliente_id)?;
+                clientes.push(AntiguedadCliente {
+                    cliente_id: cliente.id,
+                    cliente_nombre: cliente.nombre,
+                    numero_documento: cliente.numero_documento,
+                    limite_credito: cliente.limite_credito,
+                    saldos: AntiguedadSaldos::default(),
+                });
+                clientes.len() - 1
+            }
+        };
+        clientes[indice].saldos.agregar(cuenta.dias_vencido, cuenta.saldo_pendiente);
+        totales.agregar(cuenta.dias_vencido, cuenta.saldo_pendiente);
+    }
+
+    // Primero quien más debe
+    clientes.sort_by(|a, b| b.saldos.total.total_cmp(&a.saldos.total));
+
+    Ok(ReporteAntiguedad {
+        fecha_corte: Local::now().format("%Y-%m-%d").to_string(),
+        clientes,
+        totales,
+    })
+}
+
+// =====================================================
+// COMANDO: Estado de cuenta del cliente
+// =====================================================
+#[tauri::command]
+pub fn obtener_estado_cuenta_cliente(
+    db: tauri::State<'_, DatabasePool>,
+    cliente_id: i32,
+    fecha_desde: Option<String>, // 'YYYY-MM-DD'; sin fecha = desde el inicio
+) -> Result<EstadoCuentaCliente, String> {
+    let conn = db.get_conn();
+
+    let cliente = obtener_cliente_por_id(&conn, cliente_id)?;
+    let fecha_desde = fecha_desde.filter(|f| !f.trim().is_empty());
+    if let Some(fecha) = fecha_desde.as_deref() {
+        if NaiveDate::parse_from_str(fecha, "%Y-%m-%d").is_err() {
+            return Err(format!("❌ Fecha no válida: {} (usa AAAA-MM-DD)", fecha));
+        }
+    }
+
+    // Cargos: la parte al crédito de cada venta. Abonos: cobros y devoluciones
+    let mut stmt = conn
+        .prepare(
+            r"SELECT fecha, tipo, folio, folio_venta, descripcion, cargo, abono FROM (
+                SELECT v.fecha_hora AS fecha, 'VENTA' AS tipo, v.folio AS folio, v.folio AS folio_venta,
+                       'Vence ' || v.fecha_vencimiento_pago AS descripcion,
+                       pv.monto AS cargo, 0.0 AS abono, 0 AS orden, v.id AS ref
+                FROM ventas v
+                JOIN (SELECT venta_id, SUM(monto) AS monto FROM pagos_venta
+                      WHERE metodo_pago = 'CREDITO' GROUP BY venta_id) pv ON pv.venta_id = v.id
+                WHERE v.cliente_id = ?1 AND v.estado = 'COMPLETADA'
+                UNION ALL
+                SELECT pc.fecha_pago,
+                       CASE WHEN pc.metodo_pago = 'DEVOLUCION' THEN 'DEVOLUCION' ELSE 'COBRO' END,
+                       pc.folio, v.folio,
+                       CASE WHEN pc.metodo_pago = 'DEVOLUCION' THEN pc.notas
+                            ELSE pc.metodo_pago || COALESCE(' ' || pc.referencia, '') END,
+                       0.0, pc.monto, 1, pc.id
+                FROM pagos_credito pc
+                JOIN ventas v ON v.id = pc.venta_id
+                WHERE pc.cliente_id = ?1 AND v.estado = 'COMPLETADA'
+              )
+              ORDER BY fecha, orden, ref",
+        )
+        .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+
+    let filas: Vec<MovimientoCuenta> = stmt
+        .query_map(params![cliente_id], |row| {
+            Ok(MovimientoCuenta {
+                fecha: row.get(0)?,
+                tipo: row.get(1)?,
+                folio: row.get(2)?,
+                folio_venta: row.get(3)?,
+                descripcion: row.get(4)?,
+                cargo: row.get(5)?,
+                abono: row.get(6)?,
+                saldo: 0.0,
+            })
+        })
+        .map_err(|e| format!("Error al obtener estado de cuenta: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut saldo_anterior = 0.0f64;
+    let mut saldo = 0.0f64;
+    let mut movimientos = Vec::new();
+    for mut movimiento in filas {
+        saldo = redondear(saldo + movimiento.cargo - movimiento.abono);
+        if fecha_desde.as_deref().is_some_and(|desde| movimiento.fecha.as_str() < desde) {
+            saldo_anterior = saldo;
+            continue;
+        }
+        movimiento.fecha.truncate(16);
+        movimiento.saldo = saldo;
+        movimientos.push(movimiento);
+    }
+
+    let (saldo_actual, saldo_vencido) = saldo_credito_cliente(&conn, cliente_id)?;
+
+    Ok(EstadoCuentaCliente {
+        cliente_id,
+        cliente_nombre: cliente.nombre,
+        limite_credito: cliente.limite_credito,
+        dias_credito: cliente.dias_credito,
+        saldo_anterior,
+        movimientos,
+        saldo_actual,
+        saldo_vencido,
+        credito_disponible: redondear((cliente.limite_credito - saldo_actual).max(0.0)),
+    })
+}
+
+// =====================================================
+// FUNCIONES AUXILIARES (usadas por ventas y devoluciones)
+// =====================================================
+
+impl AntiguedadSaldos {
+    fn agregar(&mut self, dias_vencido: i64, monto: f64) {
+        match dias_vencido {
+            d if d <= 0 => self.corriente = redondear(self.corriente + monto),
+            1..=30 => self.dias_1_30 = redondear(self.dias_1_30 + monto),
+            31..=60 => self.dias_31_60 = redondear(self.dias_31_60 + monto),
+            61..=90 => self.dias_61_90 = redondear(self.dias_61_90 + monto),
+            _ => self.mas_de_90 = redondear(self.mas_de_90 + monto),
+        }
+        self.total = redondear(self.total + monto);
+    }
+}
+
+fn cuentas_por_cobrar(conn: &rusqlite::Connection, cliente_id: Option<i32>) -> Result<Vec<CuentaPorCobrar>, String> {
+    let mut stmt = conn
+        .prepare(
+            r"SELECT v.id, v.folio, v.cliente_id, c.nombre, strftime('%Y-%m-%d %H:%M', v.fecha_hora),
+                     v.fecha_vencimiento_pago, v.total,
+                     (SELECT COALESCE(SUM(monto), 0) FROM pagos_venta WHERE venta_id = v.id AND metodo_pago = 'CREDITO'),
+                     (SELECT COALESCE(SUM(monto), 0) FROM pagos_credito WHERE venta_id = v.id),
+                     v.saldo_pendiente, COALESCE(v.estado_pago, 'PENDIENTE'),
+                     MAX(0, CAST(julianday(date('now', 'localtime')) - julianday(v.fecha_vencimiento_pago) AS INTEGER))
+              FROM ventas v
+              JOIN clientes c ON c.id = v.cliente_id
+              WHERE v.estado = 'COMPLETADA' AND v.saldo_pendiente > 0
+                AND (?1 IS NULL OR v.cliente_id = ?1)
+              ORDER BY v.fecha_vencimiento_pago, v.id",
+        )
+        .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+
+    let cuentas = stmt
+        .query_map(params![cliente_id], |row| {
+            Ok(CuentaPorCobrar {
+                venta_id: row.get(0)?,
+                folio: row.get(1)?,
+                cliente_id: row.get(2)?,
+                cliente_nombre: row.get(3)?,
+                fecha_venta: row.get(4)?,
+                fecha_vencimiento: row.get(5)?,
+                total_venta: row.get(6)?,
+                monto_credito: row.get(7)?,
+                monto_cobrado: row.get(8)?,
+                saldo_pendiente: row.get(9)?,
+                estado_pago: row.get(10)?,
+                dias_vencido: row.get(11)?,
+            })
+        })
+        .map_err(|e| format!("Error al obtener cuentas por cobrar: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(cuentas)
+}
+
+// (saldo pendiente, parte ya vencida)
+pub fn saldo_credito_cliente(conn: &rusqlite::Connection, cliente_id: i32) -> Result<(f64, f64), String> {
+    conn.query_row(
+        r"SELECT COALESCE(SUM(saldo_pendiente), 0),
+                 COALESCE(SUM(CASE WHEN fecha_vencimiento_pago < date('now', 'localtime')
+                                   THEN saldo_pendiente ELSE 0 END), 0)
+          FROM ventas
+          WHERE cliente_id = ? AND estado = 'COMPLETADA' AND saldo_pendiente > 0",
+        params![cliente_id],
+        |row| Ok((redondear(row.get(0)?), redondear(row.get(1)?))),
+    )
+    .map_err(|e| format!("Error al obtener saldo del cliente: {}", e))
+}
+
+// Verifica que el cliente pueda llevar `monto` al crédito: límite asignado,
+// sin deudas vencidas y sin pasarse del límite.
+// Devuelve la fecha de vencimiento ('YYYY-MM-DD') según sus días de crédito.
+pub fn validar_credito(conn: &rusqlite::Connection, cliente_id: Option<i32>, monto: f64) -> Result<String, String> {
+    let id = cliente_id.ok_or("❌ Para vender al crédito debes indicar el cliente")?;
+    let cliente = obtener_cliente_por_id(conn, id)?;
+
+    if cliente.limite_credito <= 0.0 {
+        return Err(format!("❌ {} no tiene crédito autorizado", cliente.nombre));
+    }
+
+    let (saldo, vencido) = saldo_credito_cliente(conn, id)?;
+    if vencido > 0.0 {
+        return Err(format!(
+            "❌ {} tiene S/ {:.2} vencidos. Registra el cobro antes de venderle al crédito",
+            cliente.nombre, vencido
+        ));
+    }
+    if saldo + monto > cliente.limite_credito + 0.01 {
+        return Err(format!(
+            "❌ Límite de crédito excedido: debe S/ {:.2}, límite S/ {:.2} (disponible S/ {:.2})",
+            saldo,
+            cliente.limite_credito,
+            (cliente.limite_credito - saldo).max(0.0)
+        ));
+    }
+
+    Ok((Local::now().date_naive() + Duration::days(cliente.dias_credito as i64))
+        .format("%Y-%m-%d")
+        .to_string())
+}
+
+// La devolución de una venta al crédito se descuenta primero de su deuda.
+// Devuelve lo descontado (el resto se le reembolsa al cliente).
+pub fn descontar_devolucion_credito(
+    conn: &rusqlite::Connection,
+    venta_id: i32,
+    devolucion_id: i32,
+    folio_devolucion: &str,
+    monto: f64,
+    usuario_id: i32,
+) -> Result<f64, String> {
+    let (cliente_id, saldo): (Option<i32>, f64) = conn
+        .query_row(
+            "SELECT cliente_id, COALESCE(saldo_pendiente, 0) FROM ventas WHERE id = ?",
+            params![venta_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| format!("Error al obtener saldo de la venta: {}", e))?;
+
+    let aplicado = redondear(monto.min(saldo));
+    let cliente_id = match cliente_id {
+        Some(id) if aplicado > 0.0 => id,
+        _ => return Ok(0.0),
+    };
+
+    conn.execute(
+        r"INSERT INTO pagos_credito
+            (venta_id, cliente_id, folio, monto, metodo_pago, notas, devolucion_id, usuario_id)
+          VALUES (?, ?, ?, ?, 'DEVOLUCION', ?, ?, ?)",
+        params![
+            venta_id,
+            cliente_id,
+            folio_devolucion,
+            aplicado,
+            format!("Devolución {}", folio_devolucion),
+            devolucion_id,
+            usuario_id,
+        ],
+    )
+    .map_err(|e| format!("Error al descontar la devolución de la deuda: {}", e))?;
+
+    Ok(aplicado)
+}
+
+pub fn tiene_cobros(conn: &rusqlite::Connection, venta_id: i32) -> Result<bool, String> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM pagos_credito WHERE venta_id = ?",
+        params![venta_id],
+        |row| Ok(row.get::<_, i32>(0)? > 0),
+    )
+    .map_err(|e| format!("Error al verificar cobros: {}", e))
+}
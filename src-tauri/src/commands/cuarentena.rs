@@ -0,0 +1,207 @@
+// commands/cuarentena.rs
+// Stock en cuarentena: prendas devueltas DEFECTUOSO o VENCIDO.
+//
+// Entran por el trigger trg_after_devolucion_cuarentena y no se pueden
+// vender. Salen como MERMA (se dan de baja) o como DEVOLUCION_PROVEEDOR al
+// registrar una devolución al proveedor desde cuarentena; si el proveedor la
+// rechaza, vuelven a cuarentena. En estos movimientos stock_anterior y
+// stock_nuevo son los de la cuarentena, no los del stock vendible.
+
+use crate::database::DatabasePool;
+use crate::models::producto::StockCuarentena;
+use rusqlite::{params, OptionalExtension};
+
+// =====================================================
+// COMANDO: Listar stock en cuarentena
+// =====================================================
+#[tauri::command]
+pub fn obtener_stock_cuarentena(db: tauri::State<'_, DatabasePool>) -> Result<Vec<StockCuarentena>, String> {
+    let conn = db.get_conn();
+
+    // Productos con tallas: una fila por talla; sin tallas: una por producto
+    let mut stmt = conn
+        .prepare(
+            r"SELECT p.id, p.codigo, p.nombre, v.id, v.talla, v.stock_cuarentena
+              FROM producto_variantes v
+              JOIN productos p ON p.id = v.producto_id
+              WHERE v.stock_cuarentena > 0
+              UNION ALL
+              SELECT p.id, p.codigo, p.nombre, NULL, NULL, p.stock_cuarentena
+              FROM productos p
+              WHERE p.stock_cuarentena > 0
+                AND NOT EXISTS (SELECT 1 FROM producto_variantes v WHERE v.producto_id = p.id)
+              ORDER BY 3, 5",
+        )
+        .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+
+    let stock = stmt
+        .query_map([], |row| {
+            Ok(StockCuarentena {
+                producto_id: row.get(0)?,
+                codigo: row.get(1)?,
+                nombre: row.get(2)?,
+                variante_id: row.get(3)?,
+                talla: row.get(4)?,
+                cantidad: row.get(5)?,
+            })
+        })
+        .map_err(|e| format!("Error al obtener cuarentena: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(stock)
+}
+
+// =====================================================
+// COMANDO: Dar de baja unidades en cuarentena (merma)
+// =====================================================
+#[tauri::command]
+pub fn registrar_merma_cuarentena(
+    db: tauri::State<'_, DatabasePool>,
+    producto_id: i32,
+    variante_id: Option<i32>,
+    cantidad: i32,
+    motivo: String,
+    usuario_id: i32,
+) -> Result<String, String> {
+    let conn = db.get_conn();
+
+    let motivo = motivo.trim().to_string();
+    if motivo.is_empty() {
+        return Err("❌ Indica el motivo de la merma".to_string());
+    }
+
+    conn.execute("BEGIN TRANSACTION", [])
+        .map_err(|e| format!("Error al iniciar transacción: {}", e))?;
+
+    let rollback = |conn: &rusqlite::Connection, msg: String| -> String {
+        let _ = conn.execute("ROLLBACK", []);
+        msg
+    };
+
+    let restante = match retirar_de_cuarentena(&conn, producto_id, variante_id, cantidad, "MERMA", None, &motivo, usuario_id) {
+        Ok(r) => r,
+        Err(e) => return Err(rollback(&conn, e)),
+    };
+
+    conn.execute("COMMIT", [])
+        .map_err(|e| format!("Error al confirmar: {}", e))?;
+
+    Ok(format!(
+        "✅ {} unidad(es) dadas de baja. Quedan {} en cuarentena",
+        cantidad, restante
+    ))
+}
+
+// =====================================================
+// HELPERS (dentro de la transacción del llamador)
+// =====================================================
+
+// Saca unidades de cuarentena (MERMA | DEVOLUCION_PROVEEDOR); devuelve lo que queda
+#[allow(clippy::too_many_arguments)]
+pub fn retirar_de_cuarentena(
+    conn: &rusqlite::Connection,
+    producto_id: i32,
+    variante_id: Option<i32>,
+    cantidad: i32,
+    tipo: &str,
+    referencia: Option<&str>,
+    motivo: &str,
+    usuario_id: i32,
+) -> Result<i32, String> {
+    if cantidad <= 0 {
+        return Err("❌ La cantidad debe ser mayor a 0".to_string());
+    }
+    mover_cuarentena(conn, producto_id, variante_id, -cantidad, tipo, referencia, motivo, usuario_id)
+}
+
+// Devuelve unidades a cuarentena (devolución al proveedor rechazada)
+pub fn reingresar_a_cuarentena(
+    conn: &rusqlite::Connection,
+    producto_id: i32,
+    variante_id: Option<i32>,
+    cantidad: i32,
+    referencia: Option<&str>,
+    motivo: &str,
+    usuario_id: i32,
+) -> Result<i32, String> {
+    if cantidad <= 0 {
+        return Err("❌ La cantidad debe ser mayor a 0".to_string());
+    }
+    mover_cuarentena(conn, producto_id, variante_id, cantidad, "CUARENTENA", referencia, motivo, usuario_id)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn mover_cuarentena(
+    conn: &rusqlite::Connection,
+    producto_id: i32,
+    variante_id: Option<i32>,
+    delta: i32,
+    tipo: &str,
+    referencia: Option<&str>,
+    motivo: &str,
+    usuario_id: i32,
+) -> Result<i32, String> {
+    // Cuarentena actual de la talla o del producto sin tallas
+    let actual: Option<(i32, Option<String>)> = match variante_id {
+        Some(vid) => conn
+            .query_row(
+                "SELECT stock_cuarentena, talla FROM producto_variantes WHERE id = ? AND producto_id = ?",
+                params![vid, producto_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional(),
+        None => conn
+            .query_row(
+                r"SELECT stock_cuarentena, NULL FROM productos p
+                  WHERE id = ? AND NOT EXISTS (SELECT 1 FROM producto_variantes v WHERE v.producto_id = p.id)",
+                params![producto_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional(),
+    }
+    .map_err(|e| format!("Error al obtener cuarentena: {}", e))?;
+
+    let (anterior, talla) = actual.ok_or_else(|| match variante_id {
+        Some(_) => "❌ La talla no pertenece al producto".to_string(),
+        None => "❌ Producto no encontrado o con tallas: indica la talla".to_string(),
+    })?;
+
+    let nuevo = anterior + delta;
+    if nuevo < 0 {
+        return Err(format!(
+            "❌ Solo hay {} unidad(es) en cuarentena{} (solicitado: {})",
+            anterior,
+            talla.as_deref().map(|t| format!(" de la talla {}", t)).unwrap_or_default(),
+            -delta
+        ));
+    }
+
+    // El trigger trg_actualizar_cuarentena_producto suma las tallas al producto
+    let actualizado = match variante_id {
+        Some(vid) => conn.execute(
+            r"UPDATE producto_variantes
+              SET stock_cuarentena = ?, fecha_actualizacion = datetime('now', 'localtime')
+              WHERE id = ?",
+            params![nuevo, vid],
+        ),
+        None => conn.execute(
+            r"UPDATE productos
+              SET stock_cuarentena = ?, fecha_actualizacion = datetime('now', 'localtime')
+              WHERE id = ?",
+            params![nuevo, producto_id],
+        ),
+    };
+    actualizado.map_err(|e| format!("Error al actualizar cuarentena: {}", e))?;
+
+    conn.execute(
+        r"INSERT INTO movimientos_inventario
+            (producto_id, variante_id, talla, tipo_movimiento, cantidad,
+             stock_anterior, stock_nuevo, usuario_id, referencia, motivo)
+          VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        params![producto_id, variante_id, talla, tipo, delta.abs(), anterior, nuevo, usuario_id, referencia, motivo],
+    )
+    .map_err(|e| format!("Error al registrar movimiento: {}", e))?;
+
+    Ok(nuevo)
+}
@@ -0,0 +1,415 @@
+// commands/cupones.rs
+// Cupones / códigos de descuento de campañas.
+// El cupón se aplica después de las promociones, sobre lo que queda por cobrar,
+// y se registra en la misma transacción que la venta (procesar_venta).
+
+use crate::database::DatabasePool;
+use crate::commands::ventas::ProductoVenta;
+use crate::commands::promociones::{evaluar_carrito, redondear};
+use crate::models::cupon::*;
+use crate::models::promocion::ResultadoPromociones;
+use chrono::NaiveDate;
+use rusqlite::{params, OptionalExtension};
+
+// Fila de `cupones` tal como la lee aplicar_cupon
+type FilaCupon = (
+    i32,            // id
+    Option<String>, // campana
+    String,         // tipo_descuento
+    f64,            // valor
+    f64,            // monto_minimo
+    Option<i32>,    // categoria_id
+    Option<i32>,    // limite_usos
+    Option<i32>,    // limite_por_cliente
+    Option<String>, // fecha_inicio
+    Option<String>, // fecha_expiracion
+    i32,            // activo
+);
+
+// =====================================================
+// COMANDO: Listar cupones con sus usos
+// =====================================================
+#[tauri::command]
+pub fn obtener_cupones(db: tauri::State<'_, DatabasePool>) -> Result<Vec<Cupon>, String> {
+    let conn = db.get_conn();
+
+    let mut stmt = conn
+        .prepare(
+            r"SELECT c.id, c.codigo, c.campana, c.tipo_descuento, c.valor, c.monto_minimo,
+                     c.categoria_id, c.limite_usos, c.limite_por_cliente,
+                     c.fecha_inicio, c.fecha_expiracion, c.activo,
+                     (SELECT COUNT(*) FROM cupones_redenciones r
+                      JOIN ventas v ON v.id = r.venta_id
+                      WHERE r.cupon_id = c.id AND v.estado != 'CANCELADA') as usos
+              FROM cupones c
+              ORDER BY c.fecha_creacion DESC",
+        )
+        .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+
+    let cupones = stmt
+        .query_map([], |row| {
+            Ok(Cupon {
+                id: row.get(0)?,
+                codigo: row.get(1)?,
+                campana: row.get(2)?,
+                tipo_descuento: row.get(3)?,
+                valor: row.get(4)?,
+                monto_minimo: row.get::<_, Option<f64>>(5)?.unwrap_or(0.0),
+                categoria_id: row.get(6)?,
+                limite_usos: row.get(7)?,
+                limite_por_cliente: row.get(8)?,
+                fecha_inicio: row.get(9)?,
+                fecha_expiracion: row.get(10)?,
+                activo: row.get::<_, i32>(11)? == 1,
+                usos: row.get(12)?,
+            })
+        })
+        .map_err(|e| format!("Error al obtener cupones: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(cupones)
+}
+
+// =====================================================
+// COMANDO: Agregar cupón
+// =====================================================
+#[tauri::command]
+pub fn agregar_cupon(
+    db: tauri::State<'_, DatabasePool>,
+    cupon: CuponNuevo,
+) -> Result<String, String> {
+    let conn = db.get_conn();
+
+    validar_datos_cupon(&cupon)?;
+
+    conn.execute(
+        r"INSERT INTO cupones
+            (codigo, campana, tipo_descuento, valor, monto_minimo, categoria_id,
+             limite_usos, limite_por_cliente, fecha_inicio, fecha_expiracion, activo)
+          VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        params![
+            cupon.codigo.trim().to_uppercase(),
+            &cupon.campana,
+            &cupon.tipo_descuento,
+            cupon.valor,
+            cupon.monto_minimo.unwrap_or(0.0),
+            cupon.categoria_id,
+            cupon.limite_usos,
+            cupon.limite_por_cliente,
+            &cupon.fecha_inicio,
+            &cupon.fecha_expiracion,
+            cupon.activo.unwrap_or(true) as i32,
+        ],
+    )
+    .map_err(|e| {
+        if e.to_string().contains("UNIQUE") {
+            format!("❌ Ya existe un cupón con el código {}", cupon.codigo.trim().to_uppercase())
+        } else {
+            format!("Error al agregar cupón: {}", e)
+        }
+    })?;
+
+    Ok("✅ Cupón agregado exitosamente".to_string())
+}
+
+// =====================================================
+// COMANDO: Actualizar cupón (también para desactivarlo)
+// =====================================================
+#[tauri::command]
+pub fn actualizar_cupon(
+    db: tauri::State<'_, DatabasePool>,
+    cupon_id: i32,
+    cupon: CuponNuevo,
+) -> Result<String, String> {
+    let conn = db.get_conn();
+
+    validar_datos_cupon(&cupon)?;
+
+    let filas = conn
+        .execute(
+            r"UPDATE cupones
+              SET codigo = ?, campana = ?, tipo_descuento = ?, valor = ?, monto_minimo = ?,
+                  categoria_id = ?, limite_usos = ?, limite_por_cliente = ?,
+                  fecha_inicio = ?, fecha_expiracion = ?, activo = ?
+              WHERE id = ?",
+            params![
+                cupon.codigo.trim().to_uppercase(),
+                &cupon.campana,
+                &cupon.tipo_descuento,
+                cupon.valor,
+                cupon.monto_minimo.unwrap_or(0.0),
+                cupon.categoria_id,
+                cupon.limite_usos,
+                cupon.limite_por_cliente,
+                &cupon.fecha_inicio,
+                &cupon.fecha_expiracion,
+                cupon.activo.unwrap_or(true) as i32,
+                cupon_id,
+            ],
+        )
+        .map_err(|e| format!("Error al actualizar cupón: {}", e))?;
+
+    if filas == 0 {
+        return Err("❌ Cupón no encontrado".to_string());
+    }
+
+    Ok("✅ Cupón actualizado exitosamente".to_string())
+}
+
+// =====================================================
+// COMANDO: Validar cupón contra el carrito del POS
+// =====================================================
+#[tauri::command]
+pub fn validar_cupon(
+    db: tauri::State<'_, DatabasePool>,
+    codigo: String,
+    productos: Vec<ProductoVenta>,
+    cliente_id: Option<i32>,
+) -> Result<CuponAplicado, String> {
+    let conn = db.get_conn();
+
+    let promociones = evaluar_carrito(&conn, &productos)?;
+    aplicar_cupon(&conn, &codigo, &productos, &promociones, cliente_id)
+}
+
+// =====================================================
+// FUNCIONES AUXILIARES
+// =====================================================
+
+// Valida vigencia, compra mínima y límites de uso, y reparte el descuento
+// entre las líneas del alcance del cupón (en proporción a lo que falta cobrar).
+pub fn aplicar_cupon(
+    conn: &rusqlite::Connection,
+    codigo: &str,
+    productos: &[ProductoVenta],
+    promociones: &ResultadoPromociones,
+    cliente_id: Option<i32>,
+) -> Result<CuponAplicado, String> {
+    let codigo = codigo.trim().to_uppercase();
+
+    let cupon: Option<FilaCupon> = conn
+        .query_row(
+            r"SELECT id, campana, tipo_descuento, valor, COALESCE(monto_minimo, 0), categoria_id,
+                     limite_usos, limite_por_cliente, fecha_inicio, fecha_expiracion, activo
+              FROM cupones WHERE codigo = ?",
+            params![&codigo],
+            |row| Ok((
+                row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?,
+                row.get(6)?, row.get(7)?, row.get(8)?, row.get(9)?, row.get(10)?,
+            )),
+        )
+        .optional()
+        .map_err(|e| format!("Error al buscar cupón: {}", e))?;
+
+    let (cupon_id, campana, tipo_descuento, valor, monto_minimo, categoria_id,
+         limite_usos, limite_por_cliente, fecha_inicio, fecha_expiracion, activo) = match cupon {
+        Some(c) => c,
+        None => return Err(format!("❌ El cupón {} no existe", codigo)),
+    };
+
+    if activo != 1 {
+        return Err(format!("❌ El cupón {} está desactivado", codigo));
+    }
+
+    let hoy = chrono::Local::now().format("%Y-%m-%d").to_string();
+    if fecha_inicio.as_deref().is_some_and(|f| hoy.as_str() < f) {
+        return Err(format!("❌ El cupón {} aún no está vigente", codigo));
+    }
+    if fecha_expiracion.as_deref().is_some_and(|f| hoy.as_str() > f) {
+        return Err(format!("❌ El cupón {} está vencido", codigo));
+    }
+
+    if let Some(limite) = limite_usos {
+        let usos = contar_usos(conn, cupon_id, None)?;
+        if usos >= limite {
+            return Err(format!("❌ El cupón {} ya alcanzó su límite de usos", codigo));
+        }
+    }
+
+    if let Some(limite) = limite_por_cliente {
+        let cliente = match cliente_id {
+            Some(id) => id,
+            None => return Err(format!("❌ El cupón {} requiere identificar al cliente", codigo)),
+        };
+        let usos = contar_usos(conn, cupon_id, Some(cliente))?;
+        if usos >= limite {
+            return Err(format!("❌ El cliente ya usó el cupón {} el máximo de veces permitido", codigo));
+        }
+    }
+
+    if promociones.total + 0.001 < monto_minimo {
+        return Err(format!(
+            "❌ El cupón {} requiere una compra mínima de S/ {:.2}",
+            codigo, monto_minimo
+        ));
+    }
+
+    // Líneas dentro del alcance del cupón
+    let mut elegibles: Vec<(usize, f64)> = Vec::new();
+    for (linea, p) in promociones.lineas.iter().zip(productos) {
+        if linea.total_linea <= 0.0 {
+            continue;
+        }
+        if let Some(categoria) = categoria_id {
+            let categoria_producto: Option<i32> = conn
+                .query_row(
+                    "SELECT categoria_id FROM productos WHERE id = ?",
+                    params![p.id],
+                    |row| row.get(0),
+                )
+                .optional()
+                .map_err(|e| format!("Error al verificar categoría: {}", e))?;
+            if categoria_producto != Some(categoria) {
+                continue;
+            }
+        }
+        elegibles.push((linea.indice, linea.total_linea));
+    }
+
+    let base: f64 = elegibles.iter().map(|(_, t)| t).sum();
+    if base <= 0.0 {
+        return Err(format!("❌ Ningún producto del carrito aplica para el cupón {}", codigo));
+    }
+
+    let (descuento, lineas) = repartir_descuento(&tipo_descuento, valor, &elegibles);
+
+    Ok(CuponAplicado {
+        cupon_id,
+        codigo,
+        campana,
+        descuento,
+        total_con_cupon: redondear(promociones.total - descuento),
+        lineas,
+    })
+}
+
+// Descuento del cupón sobre las líneas elegibles (índice, lo que falta cobrar).
+// La última línea absorbe el redondeo para que la suma cuadre exacta.
+fn repartir_descuento(tipo_descuento: &str, valor: f64, elegibles: &[(usize, f64)]) -> (f64, Vec<DescuentoCuponLinea>) {
+    let base: f64 = elegibles.iter().map(|(_, t)| t).sum();
+    let descuento = match tipo_descuento {
+        "PORCENTAJE" => base * valor / 100.0,
+        _ => valor.min(base),
+    };
+    let descuento = redondear(descuento);
+
+    let mut repartido = 0.0;
+    let mut lineas = Vec::with_capacity(elegibles.len());
+    for (n, (indice, total_linea)) in elegibles.iter().enumerate() {
+        let d = if n + 1 == elegibles.len() {
+            redondear(descuento - repartido)
+        } else {
+            redondear(descuento * total_linea / base)
+        };
+        repartido += d;
+        lineas.push(DescuentoCuponLinea { indice: *indice, descuento: d });
+    }
+    (descuento, lineas)
+}
+
+// Usos del cupón en ventas no anuladas (de un cliente, si se indica)
+fn contar_usos(conn: &rusqlite::Connection, cupon_id: i32, cliente_id: Option<i32>) -> Result<i32, String> {
+    conn.query_row(
+        r"SELECT COUNT(*) FROM cupones_redenciones r
+          JOIN ventas v ON v.id = r.venta_id
+          WHERE r.cupon_id = ?1 AND v.estado != 'CANCELADA'
+            AND (?2 IS NULL OR r.cliente_id = ?2)",
+        params![cupon_id, cliente_id],
+        |row| row.get(0),
+    )
+    .map_err(|e| format!("Error al contar usos del cupón: {}", e))
+}
+
+fn validar_datos_cupon(c: &CuponNuevo) -> Result<(), String> {
+    let codigo = c.codigo.trim();
+    if codigo.is_empty() || codigo.contains(char::is_whitespace) {
+        return Err("❌ El código del cupón no puede estar vacío ni tener espacios".to_string());
+    }
+    if !["PORCENTAJE", "MONTO"].contains(&c.tipo_descuento.as_str()) {
+        return Err(format!("❌ Tipo de descuento no válido: {}", c.tipo_descuento));
+    }
+    if c.valor <= 0.0 || (c.tipo_descuento == "PORCENTAJE" && c.valor > 100.0) {
+        return Err("❌ El valor del descuento no es válido".to_string());
+    }
+    if c.monto_minimo.is_some_and(|m| m < 0.0) {
+        return Err("❌ La compra mínima no puede ser negativa".to_string());
+    }
+    if c.limite_usos.is_some_and(|l| l < 1) || c.limite_por_cliente.is_some_and(|l| l < 1) {
+        return Err("❌ Los límites de uso deben ser al menos 1".to_string());
+    }
+    for fecha in [&c.fecha_inicio, &c.fecha_expiracion].into_iter().flatten() {
+        if NaiveDate::parse_from_str(fecha, "%Y-%m-%d").is_err() {
+            return Err(format!("❌ Fecha no válida: {} (usa AAAA-MM-DD)", fecha));
+        }
+    }
+    if let (Some(inicio), Some(fin)) = (&c.fecha_inicio, &c.fecha_expiracion) {
+        if inicio > fin {
+            return Err("❌ La fecha de inicio es posterior a la de expiración".to_string());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cupon(tipo: &str, valor: f64) -> CuponNuevo {
+        CuponNuevo {
+            codigo: "VERANO10".to_string(),
+            campana: None,
+            tipo_descuento: tipo.to_string(),
+            valor,
+            monto_minimo: None,
+            categoria_id: None,
+            limite_usos: None,
+            limite_por_cliente: None,
+            fecha_inicio: None,
+            fecha_expiracion: None,
+            activo: None,
+        }
+    }
+
+    #[test]
+    fn porcentaje_se_reparte_en_proporcion() {
+        let (descuento, lineas) = repartir_descuento("PORCENTAJE", 10.0, &[(0, 60.0), (2, 40.0)]);
+        assert_eq!(descuento, 10.0);
+        assert_eq!(lineas[0].indice, 0);
+        assert_eq!(lineas[0].descuento, 6.0);
+        assert_eq!(lineas[1].indice, 2);
+        assert_eq!(lineas[1].descuento, 4.0);
+    }
+
+    #[test]
+    fn monto_no_supera_lo_que_falta_cobrar() {
+        let (descuento, lineas) = repartir_descuento("MONTO", 50.0, &[(0, 30.0)]);
+        assert_eq!(descuento, 30.0);
+        assert_eq!(lineas[0].descuento, 30.0);
+    }
+
+    #[test]
+    fn la_ultima_linea_absorbe_el_redondeo() {
+        let (descuento, lineas) = repartir_descuento("MONTO", 10.0, &[(0, 10.0), (1, 10.0), (2, 10.0)]);
+        let suma: f64 = lineas.iter().map(|l| l.descuento).sum();
+        assert_eq!(descuento, 10.0);
+        assert_eq!(lineas[0].descuento, 3.33);
+        assert_eq!(lineas[2].descuento, 3.34);
+        assert_eq!(redondear(suma), 10.0);
+    }
+
+    #[test]
+    fn valida_datos_del_cupon() {
+        assert!(validar_datos_cupon(&cupon("PORCENTAJE", 15.0)).is_ok());
+        assert!(validar_datos_cupon(&cupon("PORCENTAJE", 120.0)).is_err());
+        assert!(validar_datos_cupon(&cupon("MONTO", 0.0)).is_err());
+        assert!(validar_datos_cupon(&cupon("REGALO", 5.0)).is_err());
+        assert!(validar_datos_cupon(&CuponNuevo { codigo: "VER ANO".to_string(), ..cupon("MONTO", 5.0) }).is_err());
+        assert!(validar_datos_cupon(&CuponNuevo {
+            fecha_inicio: Some("2026-03-01".to_string()),
+            fecha_expiracion: Some("2026-02-01".to_string()),
+            ..cupon("MONTO", 5.0)
+        })
+        .is_err());
+    }
+}
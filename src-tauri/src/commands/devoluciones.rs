@@ -3,6 +3,17 @@
 
 use rusqlite::OptionalExtension;
 use crate::database::DatabasePool;
+use crate::commands::puntos::revertir_puntos_devolucion;
+use crate::commands::comisiones::revertir_comisiones_devolucion;
+use crate::commands::idempotencia::{normalizar_clave, registrar_resultado, resultado_previo};
+use crate::commands::vales::emitir_vale;
+use crate::commands::impuestos::{desglosar_igv, TotalesIgv};
+use crate::commands::numeracion::siguiente_folio;
+use crate::commands::notas_credito::generar_nota_credito;
+use crate::commands::creditos::descontar_devolucion_credito;
+use crate::commands::auth::autorizar_operacion;
+use crate::commands::promociones::redondear;
+use crate::models::AutorizacionSupervisor;
 use rusqlite::params;
 use serde::{Deserialize, Serialize};
 
@@ -17,6 +28,7 @@ pub struct VentaParaDevolucion {
     pub fecha_hora: String,
     pub total: f64,
     pub metodo_pago: String,
+    pub dias_transcurridos: i32, // 🆕 desde la fecha de la venta
     pub productos: Vec<ProductoVentaDetalle>,
 }
 
@@ -32,14 +44,19 @@ pub struct ProductoVentaDetalle {
     pub cantidad: i32,
     pub precio_unitario: f64,
     pub subtotal: f64,
+    // 🆕 Elegibilidad según la política de devolución
+    pub cantidad_devuelta: i32,
+    pub devolvible: bool,              // false = requiere autorización de un administrador
+    pub dias_politica: i32,            // plazo de la categoría o de la tienda (0 = sin devolución)
+    pub fecha_limite: String,
+    pub motivo_no_devolvible: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ProductoDevolver {
-    pub detalle_id: i32,       // 🆕 clave única
-    pub producto_id: i32,
-    pub variante_id: Option<i32>, // 🆕
+    pub detalle_id: i32,       // 🆕 clave única; producto y variante se leen de la venta
     pub cantidad: i32,
+    pub condicion: Option<String>, // 🆕 REVENTA (por defecto) | DEFECTUOSO | VENCIDO
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -47,6 +64,26 @@ pub struct DevolucionResponse {
     pub success: bool,
     pub message: String,
     pub folio_devolucion: Option<String>,
+    pub monto_reembolsado: f64, // 🆕 neto de descuentos
+    pub codigo_vale: Option<String>, // 🆕 reembolso con VALE
+    pub nota_credito_id: Option<i32>, // 🆕 ventas con boleta o factura
+    pub nota_credito: Option<String>, // 🆕 BC01-00000001
+}
+
+// 🆕 Política de devolución de una línea de la venta
+pub struct PoliticaLinea {
+    pub detalle_id: i32,
+    pub nombre: String,
+    pub dias_politica: i32,
+    pub fecha_limite: String,
+    pub motivo_no_devolvible: Option<String>,
+}
+
+// 🆕 Devolución fuera de política autorizada por un administrador
+pub struct ExcepcionPolitica {
+    pub autorizado_por: i32,
+    pub motivo: String,
+    pub lineas: Vec<String>,
 }
 
 // =====================================================
@@ -93,7 +130,11 @@ pub fn buscar_venta_para_devolucion(
                 p.nombre,
                 dv.cantidad,
                 dv.precio_unitario,
-                dv.total_linea
+                dv.total_linea,
+                (SELECT COALESCE(SUM(dd.cantidad_devuelta), 0)
+                 FROM detalles_devolucion dd
+                 JOIN devoluciones d ON dd.devolucion_id = d.id
+                 WHERE dd.detalle_venta_id = dv.id AND d.estado = 'PROCESADA')
             FROM detalles_venta dv
             JOIN productos p ON dv.producto_id = p.id
             WHERE dv.venta_id = ?
@@ -101,7 +142,10 @@ pub fn buscar_venta_para_devolucion(
         ")
         .map_err(|e| format!("Error al preparar consulta: {}", e))?;
 
-    let productos: Vec<ProductoVentaDetalle> = stmt
+    // 🆕 Plazo y excepciones de categoría por línea
+    let (dias_transcurridos, politica) = politica_devolucion(&conn, venta_id)?;
+
+    let mut productos: Vec<ProductoVentaDetalle> = stmt
         .query_map([venta_id], |row| {
             Ok(ProductoVentaDetalle {
                 detalle_id:    row.get(0)?,
@@ -112,19 +156,32 @@ pub fn buscar_venta_para_devolucion(
                 cantidad:      row.get(5)?,
                 precio_unitario: row.get(6)?,
                 subtotal:      row.get(7)?,
+                cantidad_devuelta: row.get(8)?,
+                devolvible:    true,
+                dias_politica: 0,
+                fecha_limite:  String::new(),
+                motivo_no_devolvible: None,
             })
         })
         .map_err(|e| format!("Error al obtener productos: {}", e))?
         .filter_map(|r| r.ok())
         .collect();
 
-    Ok(VentaParaDevolucion { venta_id, folio, fecha_hora, total, metodo_pago, productos })
+    for (p, l) in productos.iter_mut().zip(politica) {
+        p.devolvible = l.motivo_no_devolvible.is_none();
+        p.dias_politica = l.dias_politica;
+        p.fecha_limite = l.fecha_limite;
+        p.motivo_no_devolvible = l.motivo_no_devolvible;
+    }
+
+    Ok(VentaParaDevolucion { venta_id, folio, fecha_hora, total, metodo_pago, dias_transcurridos, productos })
 }
 
 // =====================================================
 // COMANDO: Procesar devolución
 // =====================================================
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn procesar_devolucion(
     db: tauri::State<'_, DatabasePool>,
     #[allow(non_snake_case)] ventaId: i32,
@@ -132,9 +189,65 @@ pub fn procesar_devolucion(
     productos: Vec<ProductoDevolver>,
     motivo: String,
     #[allow(non_snake_case)] usuarioId: i32,
+    #[allow(non_snake_case)] metodoReembolso: Option<String>, // 🆕 EFECTIVO (por defecto) | TARJETA | VALE | CREDITO
+    #[allow(non_snake_case)] motivoNotaCredito: Option<String>, // 🆕 catálogo 09 (06/07 automático)
+    #[allow(non_snake_case)] claveIdempotencia: Option<String>, // 🆕 reintento = misma devolución
+    #[allow(non_snake_case)] motivoExcepcion: Option<String>, // 🆕 fuera de política: por qué se acepta
+    autorizacion: Option<AutorizacionSupervisor>, // 🆕 administrador que autoriza la excepción
 ) -> Result<DevolucionResponse, String> {
     let conn = db.get_conn();
 
+    // 🆕 Reintento de una devolución ya procesada: se devuelve el mismo resultado
+    let clave_idempotencia = normalizar_clave(claveIdempotencia)?;
+    if let Some(previa) = resultado_previo(&conn, "DEVOLUCION", clave_idempotencia.as_deref())? {
+        return Ok(previa);
+    }
+
+    // 🆕 Venta al crédito con saldo: la devolución se descuenta de la deuda
+    //    (CREDITO) y lo que exceda se entrega en vale
+    let saldo_credito = saldo_venta_devolvible(&conn, ventaId)?;
+
+    let metodo_reembolso = metodoReembolso.unwrap_or_else(|| {
+        if saldo_credito > 0.0 { "CREDITO" } else { "EFECTIVO" }.to_string()
+    });
+    if !["EFECTIVO", "TARJETA", "VALE", "CREDITO"].contains(&metodo_reembolso.as_str()) {
+        return Err(format!("❌ Método de reembolso no válido: {}", metodo_reembolso));
+    }
+    if saldo_credito > 0.0 && metodo_reembolso != "CREDITO" {
+        return Err(format!(
+            "❌ La venta tiene S/ {:.2} pendientes de pago: la devolución se descuenta de la deuda (CREDITO)",
+            saldo_credito
+        ));
+    }
+    if saldo_credito <= 0.0 && metodo_reembolso == "CREDITO" {
+        return Err("❌ La venta no tiene saldo pendiente para descontar la devolución".to_string());
+    }
+
+    // 🆕 Condición de cada prenda: REVENTA vuelve al stock vendible,
+    //    DEFECTUOSO y VENCIDO quedan en cuarentena
+    let mut condiciones = Vec::with_capacity(productos.len());
+    for p in &productos {
+        if p.cantidad <= 0 {
+            return Err(format!("❌ La cantidad a devolver debe ser mayor a cero (línea {})", p.detalle_id));
+        }
+        let condicion = p.condicion.as_deref().map(|c| c.trim().to_uppercase()).unwrap_or_else(|| "REVENTA".to_string());
+        if !["REVENTA", "DEFECTUOSO", "VENCIDO"].contains(&condicion.as_str()) {
+            return Err(format!("❌ Condición de devolución no válida: {}", condicion));
+        }
+        condiciones.push(condicion);
+    }
+
+    // 🆕 Plazo de devolución y categorías sin devolución
+    let detalles: Vec<i32> = productos.iter().map(|p| p.detalle_id).collect();
+    let excepcion = validar_politica_devolucion(
+        &conn,
+        ventaId,
+        &detalles,
+        usuarioId,
+        autorizacion.as_ref(),
+        motivoExcepcion.as_deref(),
+    )?;
+
     conn.execute("BEGIN TRANSACTION", [])
         .map_err(|e| format!("Error al iniciar transacción: {}", e))?;
 
@@ -144,25 +257,46 @@ pub fn procesar_devolucion(
     };
 
     // Generar folio de devolución
-    let fecha_actual = chrono::Local::now().format("%Y%m%d").to_string();
-    let siguiente: i32 = conn
+    let folio_devolucion = match siguiente_folio(&conn, "DEVOLUCION") {
+        Ok(f) => f,
+        Err(e) => return Err(rollback(&conn, e)),
+    };
+
+    // 🆕 La devolución usa la tasa de IGV con la que se hizo la venta
+    let tasa_igv: f64 = conn
         .query_row(
-            &format!(
-                "SELECT COALESCE(MAX(CAST(substr(folio_devolucion,-4) AS INTEGER)),0)+1
-                 FROM devoluciones WHERE folio_devolucion LIKE 'DEV-{}%'",
-                fecha_actual
-            ),
-            [],
+            "SELECT COALESCE(igv_porcentaje, 0) FROM ventas WHERE id = ?",
+            params![ventaId],
             |row| row.get(0),
         )
-        .unwrap_or(1);
+        .map_err(|e| rollback(&conn, format!("Error al obtener venta: {}", e)))?;
 
-    let folio_devolucion = format!("DEV-{}-{:04}", fecha_actual, siguiente);
-
-    // Validar y calcular monto total
+    // Validar y calcular monto total (lo que realmente se pagó, no el precio de lista)
     let mut monto_total = 0.0f64;
+    let mut montos: Vec<(f64, String)> = Vec::with_capacity(productos.len());
+    let mut lineas_venta: Vec<(i32, Option<i32>, Option<String>)> = Vec::with_capacity(productos.len());
+    let mut totales_igv = TotalesIgv::default();
 
     for p in &productos {
+        // 🆕 Producto y variante salen de la línea vendida, no de lo que envía el cliente
+        let linea: Option<(i32, Option<i32>, Option<String>, i32, String)> = conn
+            .query_row(
+                r"SELECT producto_id, variante_id, talla, cantidad, COALESCE(afectacion_igv, 'GRAVADO')
+                  FROM detalles_venta WHERE id = ? AND venta_id = ?",
+                params![p.detalle_id, ventaId],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+            )
+            .optional()
+            .map_err(|e| rollback(&conn, format!("Error al obtener detalle: {}", e)))?;
+
+        let (producto_id, variante_id, talla, cantidad_original, afectacion) = match linea {
+            Some(l) => l,
+            None => return Err(rollback(&conn, format!(
+                "❌ La línea {} no pertenece a la venta {}",
+                p.detalle_id, folioVenta
+            ))),
+        };
+
         // 🆕 Validar usando detalle_id para precisión con variantes
         let ya_devuelto: i32 = conn
             .query_row(
@@ -175,20 +309,6 @@ pub fn procesar_devolucion(
             )
             .unwrap_or(0);
 
-        let cantidad_original: Option<i32> = conn
-            .query_row(
-                "SELECT cantidad FROM detalles_venta WHERE id = ?",
-                params![p.detalle_id],
-                |row| row.get(0),
-            )
-            .optional()
-            .map_err(|e| rollback(&conn, format!("Error al obtener cantidad: {}", e)))?;
-
-        let cantidad_original = match cantidad_original {
-            Some(c) => c,
-            None => return Err(rollback(&conn, "Detalle de venta no encontrado".to_string())),
-        };
-
         if ya_devuelto + p.cantidad > cantidad_original {
             return Err(rollback(&conn, format!(
                 "No puedes devolver {} unidades. Disponibles para devolución: {}",
@@ -197,115 +317,490 @@ pub fn procesar_devolucion(
             )));
         }
 
-        let precio: f64 = conn
+        lineas_venta.push((producto_id, variante_id, talla));
+
+        let monto = match monto_neto_devolucion(&conn, p.detalle_id, p.cantidad) {
+            Ok(m) => m,
+            Err(e) => return Err(rollback(&conn, e)),
+        };
+        monto_total += monto;
+
+        let (valor_venta, igv) = desglosar_igv(monto, &afectacion, tasa_igv);
+        totales_igv.agregar(&afectacion, valor_venta, igv);
+        montos.push((monto, afectacion));
+    }
+    let totales_igv = totales_igv.redondeados();
+    let monto_total = redondear(monto_total);
+
+    // 🆕 Reversión a tarjeta: hasta lo que se cobró con tarjeta en la venta
+    if metodo_reembolso == "TARJETA" {
+        let disponible: f64 = conn
             .query_row(
-                "SELECT precio_unitario FROM detalles_venta WHERE id = ?",
-                params![p.detalle_id],
+                r"SELECT COALESCE((SELECT SUM(monto) FROM pagos_venta WHERE venta_id = ?1 AND metodo_pago = 'TARJETA'), 0)
+                       - COALESCE((SELECT SUM(monto_reembolsado) FROM devoluciones
+                                   WHERE venta_original_id = ?1 AND metodo_reembolso = 'TARJETA' AND estado = 'PROCESADA'), 0)",
+                params![ventaId],
                 |row| row.get(0),
             )
-            .map_err(|e| rollback(&conn, format!("Error al obtener precio: {}", e)))?;
+            .map_err(|e| rollback(&conn, format!("Error al obtener pagos con tarjeta: {}", e)))?;
 
-        monto_total += precio * p.cantidad as f64;
+        if monto_total > disponible + 0.005 {
+            return Err(rollback(&conn, format!(
+                "❌ Solo se pueden revertir a la tarjeta S/ {:.2} de esta venta (a devolver: S/ {:.2})",
+                disponible.max(0.0),
+                monto_total
+            )));
+        }
     }
 
+    // 🆕 La devolución queda en la caja abierta de quien la procesa
+    let caja_id: Option<i32> = conn
+        .query_row(
+            "SELECT id FROM cajas WHERE usuario_id = ? AND estado = 'ABIERTA'",
+            params![usuarioId],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| rollback(&conn, format!("Error al verificar caja: {}", e)))?;
+
     // Insertar devolución
     if let Err(e) = conn.execute(
         r"INSERT INTO devoluciones
             (venta_original_id, folio_devolucion, monto_reembolsado,
-             metodo_reembolso, motivo, usuario_id, estado)
-          VALUES (?, ?, ?, 'EFECTIVO', ?, ?, 'PROCESADA')",
-        params![ventaId, &folio_devolucion, monto_total, &motivo, usuarioId],
+             metodo_reembolso, motivo, usuario_id, caja_id, op_gravada, op_exonerada,
+             op_inafecta, igv, estado)
+          VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 'PROCESADA')",
+        params![ventaId, &folio_devolucion, monto_total, &metodo_reembolso, &motivo, usuarioId, caja_id,
+                totales_igv.op_gravada, totales_igv.op_exonerada, totales_igv.op_inafecta, totales_igv.igv],
     ) {
         return Err(rollback(&conn, format!("Error al insertar devolución: {}", e)));
     }
 
     let devolucion_id = conn.last_insert_rowid() as i32;
 
-    // Insertar detalles y actualizar stock
-    for p in &productos {
-        let precio: f64 = conn
-            .query_row(
-                "SELECT precio_unitario FROM detalles_venta WHERE id = ?",
-                params![p.detalle_id],
-                |row| row.get(0),
-            )
-            .map_err(|e| rollback(&conn, format!("Error precio: {}", e)))?;
+    if let Some(excepcion) = &excepcion {
+        if let Err(e) = registrar_excepcion_politica(&conn, devolucion_id, &folio_devolucion, usuarioId, excepcion) {
+            return Err(rollback(&conn, e));
+        }
+    }
 
-        let subtotal = precio * p.cantidad as f64;
+    // Insertar detalles; el stock lo mueven los triggers según la condición
+    for (((p, condicion), (subtotal, afectacion)), (producto_id, variante_id, talla)) in
+        productos.iter().zip(&condiciones).zip(&montos).zip(&lineas_venta)
+    {
+        let precio = redondear(subtotal / p.cantidad as f64);
+        let (valor_venta, igv) = desglosar_igv(*subtotal, afectacion, tasa_igv);
 
         // 🆕 Insertar detalle usando detalle_venta_id
         if let Err(e) = conn.execute(
             r"INSERT INTO detalles_devolucion
-                (devolucion_id, producto_id, variante_id, detalle_venta_id,
-                 venta_id, cantidad_devuelta, precio_unitario, subtotal, condicion)
-              VALUES (?, ?, ?, ?, ?, ?, ?, ?, 'REVENTA')",
+                (devolucion_id, producto_id, variante_id, talla, detalle_venta_id,
+                 venta_id, cantidad_devuelta, precio_unitario, subtotal,
+                 valor_venta, igv, condicion)
+              VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
             params![
                 devolucion_id,
-                p.producto_id,
-                p.variante_id,
+                producto_id,
+                variante_id,
+                talla,
                 p.detalle_id,
                 ventaId,
                 p.cantidad,
                 precio,
                 subtotal,
+                valor_venta,
+                igv,
+                condicion,
             ],
         ) {
             return Err(rollback(&conn, format!("Error al insertar detalle: {}", e)));
         }
+    }
 
-        // 🆕 Actualizar stock: variante si tiene, producto si no
-        if let Some(variante_id) = p.variante_id {
-            if let Err(e) = conn.execute(
-                "UPDATE producto_variantes SET stock = stock + ? WHERE id = ?",
-                params![p.cantidad, variante_id],
-            ) {
-                return Err(rollback(&conn, format!("Error al actualizar stock variante: {}", e)));
-            }
-            // El trigger actualiza el stock total del producto automáticamente
-        } else {
-            if let Err(e) = conn.execute(
-                "UPDATE productos SET stock = stock + ? WHERE id = ?",
-                params![p.cantidad, p.producto_id],
-            ) {
-                return Err(rollback(&conn, format!("Error al actualizar stock: {}", e)));
-            }
-        }
+    // 🆕 Los movimientos que registran los triggers llevan el folio de la devolución
+    if let Err(e) = conn.execute(
+        "UPDATE movimientos_inventario SET referencia = ? WHERE devolucion_id = ?",
+        params![&folio_devolucion, devolucion_id],
+    ) {
+        return Err(rollback(&conn, format!("Error al registrar movimiento: {}", e)));
+    }
 
-        // Stock nuevo para el movimiento
-        let stock_nuevo: i32 = conn
-            .query_row(
-                "SELECT stock FROM productos WHERE id = ?",
-                params![p.producto_id],
-                |row| row.get(0),
-            )
-            .unwrap_or(0);
+    // 🆕 Nota de crédito si la venta fue con boleta o factura
+    let sustento = if motivo.trim().is_empty() {
+        format!("Devolución {}", folio_devolucion)
+    } else {
+        motivo.trim().to_string()
+    };
+    let lineas: Vec<(i32, i32, f64)> = productos
+        .iter()
+        .zip(&montos)
+        .map(|(p, (monto, _))| (p.detalle_id, p.cantidad, *monto))
+        .collect();
+    let nota_credito = match generar_nota_credito(
+        &conn,
+        devolucion_id,
+        ventaId,
+        &lineas,
+        motivoNotaCredito.as_deref(),
+        &sustento,
+        usuarioId,
+    ) {
+        Ok(n) => n,
+        Err(e) => return Err(rollback(&conn, e)),
+    };
 
-        // Registrar movimiento de inventario
-        if let Err(e) = conn.execute(
-            r"INSERT INTO movimientos_inventario
-                (producto_id, variante_id, tipo_movimiento, cantidad,
-                 stock_anterior, stock_nuevo, referencia, usuario_id)
-              VALUES (?, ?, 'DEVOLUCION', ?, ?, ?, ?, ?)",
-            params![
-                p.producto_id,
-                p.variante_id,
-                p.cantidad,
-                stock_nuevo - p.cantidad,
-                stock_nuevo,
-                &folio_devolucion,
-                usuarioId,
-            ],
+    // 🆕 Al crédito: primero se descuenta de la deuda de la venta
+    let monto_vale = if metodo_reembolso == "CREDITO" {
+        match descontar_devolucion_credito(&conn, ventaId, devolucion_id, &folio_devolucion, monto_total, usuarioId) {
+            Ok(aplicado) => monto_total - aplicado,
+            Err(e) => return Err(rollback(&conn, e)),
+        }
+    } else if metodo_reembolso == "VALE" {
+        monto_total
+    } else {
+        0.0
+    };
+
+    // 🆕 Reembolso en vale: queda a nombre del cliente de la venta, si lo hay
+    let mut codigo_vale = None;
+    if monto_vale > 0.005 {
+        let cliente_id: Option<i32> = conn
+            .query_row("SELECT cliente_id FROM ventas WHERE id = ?", params![ventaId], |row| row.get(0))
+            .map_err(|e| rollback(&conn, format!("Error al obtener venta: {}", e)))?;
+
+        match emitir_vale(
+            &conn,
+            "DEVOLUCION",
+            monto_vale,
+            cliente_id,
+            Some(devolucion_id),
+            None,
+            None,
+            usuarioId,
+            &format!("Devolución {}", folio_devolucion),
         ) {
-            return Err(rollback(&conn, format!("Error al registrar movimiento: {}", e)));
+            Ok(vale) => codigo_vale = Some(vale.codigo),
+            Err(e) => return Err(rollback(&conn, e)),
         }
     }
 
-    conn.execute("COMMIT", [])
-        .map_err(|e| format!("Error al confirmar: {}", e))?;
+    // 🆕 Quitar los puntos ganados por la parte devuelta
+    if let Err(e) = revertir_puntos_devolucion(&conn, ventaId, devolucion_id, monto_total, usuarioId) {
+        return Err(rollback(&conn, e));
+    }
 
-    Ok(DevolucionResponse {
+    // 🆕 Revertir la comisión del vendedor por lo devuelto
+    if let Err(e) = revertir_comisiones_devolucion(&conn, devolucion_id) {
+        return Err(rollback(&conn, e));
+    }
+
+    let resultado = DevolucionResponse {
         success: true,
-        message: "Devolución procesada exitosamente".to_string(),
+        message: match &nota_credito {
+            Some((_, numero)) => format!("Devolución procesada exitosamente. Nota de crédito {}", numero),
+            None => "Devolución procesada exitosamente".to_string(),
+        },
         folio_devolucion: Some(folio_devolucion),
-    })
-}
\ No newline at end of file
+        monto_reembolsado: monto_total,
+        codigo_vale,
+        nota_credito_id: nota_credito.as_ref().map(|(id, _)| *id),
+        nota_credito: nota_credito.map(|(_, numero)| numero),
+    };
+
+    if let Err(e) = registrar_resultado(&conn, "DEVOLUCION", clave_idempotencia.as_deref(), devolucion_id, &resultado) {
+        return Err(rollback(&conn, e));
+    }
+
+    conn.execute("COMMIT", [])
+        .map_err(|e| format!("Error al confirmar: {}", e))?;
+
+    Ok(resultado)
+}
+// =====================================================
+// 🆕 POLÍTICA DE DEVOLUCIÓN
+// Plazo desde la fecha de la venta: el de la categoría del producto si lo
+// tiene (0 = la categoría no admite devoluciones) o el de la tienda
+// (configuracion_tienda.dias_devolucion). Fuera de política solo se acepta
+// con el motivo y la autorización de un administrador.
+// =====================================================
+
+// Días desde la venta y política de cada línea
+pub fn politica_devolucion(
+    conn: &rusqlite::Connection,
+    venta_id: i32,
+) -> Result<(i32, Vec<PoliticaLinea>), String> {
+    let mut stmt = conn
+        .prepare(
+            r"SELECT dv.id, p.nombre, c.nombre, COALESCE(c.dias_devolucion, t.dias),
+                     CAST(julianday(date('now', 'localtime')) - julianday(date(v.fecha_hora)) AS INTEGER),
+                     date(v.fecha_hora, '+' || COALESCE(c.dias_devolucion, t.dias) || ' days')
+              FROM detalles_venta dv
+              JOIN ventas v ON v.id = dv.venta_id
+              JOIN productos p ON p.id = dv.producto_id
+              LEFT JOIN categorias c ON c.id = p.categoria_id
+              CROSS JOIN (SELECT COALESCE((SELECT dias_devolucion FROM configuracion_tienda LIMIT 1), 7) AS dias) t
+              WHERE dv.venta_id = ?
+              ORDER BY dv.id",
+        )
+        .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+
+    let filas = stmt
+        .query_map(params![venta_id], |row| {
+            Ok((
+                row.get::<_, i32>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, i32>(3)?,
+                row.get::<_, i32>(4)?,
+                row.get::<_, String>(5)?,
+            ))
+        })
+        .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+        .map_err(|e| format!("Error al obtener política de devolución: {}", e))?;
+
+    let dias_transcurridos = filas.first().map(|f| f.4).unwrap_or(0);
+
+    let lineas = filas
+        .into_iter()
+        .map(|(detalle_id, nombre, categoria, dias, transcurridos, fecha_limite)| {
+            let motivo_no_devolvible = if dias == 0 {
+                Some(format!(
+                    "La categoría {} no admite devoluciones",
+                    categoria.unwrap_or_default()
+                ))
+            } else if transcurridos > dias {
+                Some(format!(
+                    "Fuera de plazo: {} días desde la venta (máximo {})",
+                    transcurridos, dias
+                ))
+            } else {
+                None
+            };
+            PoliticaLinea { detalle_id, nombre, dias_politica: dias, fecha_limite, motivo_no_devolvible }
+        })
+        .collect();
+
+    Ok((dias_transcurridos, lineas))
+}
+
+// 🆕 Solo se devuelve mercadería de ventas COMPLETADA: una venta anulada ya
+// repuso su stock y su caja. Devuelve el saldo pendiente de la venta
+pub fn saldo_venta_devolvible(conn: &rusqlite::Connection, venta_id: i32) -> Result<f64, String> {
+    let (estado, saldo_pendiente): (String, f64) = conn
+        .query_row(
+            "SELECT estado, COALESCE(saldo_pendiente, 0) FROM ventas WHERE id = ?",
+            params![venta_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(|e| format!("Error al obtener venta: {}", e))?
+        .ok_or("❌ Venta no encontrada")?;
+
+    if estado != "COMPLETADA" {
+        return Err(format!("❌ No se puede devolver mercadería de una venta en estado {}", estado));
+    }
+    Ok(saldo_pendiente)
+}
+
+// Verifica las líneas a devolver; fuera de política exige motivo y autorización
+pub fn validar_politica_devolucion(
+    conn: &rusqlite::Connection,
+    venta_id: i32,
+    detalles: &[i32],
+    usuario_id: i32,
+    autorizacion: Option<&AutorizacionSupervisor>,
+    motivo_excepcion: Option<&str>,
+) -> Result<Option<ExcepcionPolitica>, String> {
+    let (_, politica) = politica_devolucion(conn, venta_id)?;
+
+    let mut fuera_de_politica = Vec::new();
+    for detalle_id in detalles {
+        let linea = politica
+            .iter()
+            .find(|l| l.detalle_id == *detalle_id)
+            .ok_or_else(|| format!("❌ La línea {} no pertenece a la venta", detalle_id))?;
+
+        if let Some(motivo) = &linea.motivo_no_devolvible {
+            fuera_de_politica.push(format!("{}: {}", linea.nombre, motivo));
+        }
+    }
+
+    if fuera_de_politica.is_empty() {
+        return Ok(None);
+    }
+
+    let motivo = motivo_excepcion.map(str::trim).unwrap_or_default();
+    if motivo.is_empty() {
+        return Err(format!(
+            "❌ Fuera de la política de devolución ({}). Indica el motivo de la excepción y la autorización de un administrador",
+            fuera_de_politica.join("; ")
+        ));
+    }
+
+    let autorizado_por = autorizar_operacion(conn, usuario_id, autorizacion)?;
+
+    Ok(Some(ExcepcionPolitica {
+        autorizado_por,
+        motivo: motivo.to_string(),
+        lineas: fuera_de_politica,
+    }))
+}
+
+// Deja constancia de la excepción en auditoría (dentro de la transacción)
+pub fn registrar_excepcion_politica(
+    conn: &rusqlite::Connection,
+    devolucion_id: i32,
+    folio_devolucion: &str,
+    usuario_id: i32,
+    excepcion: &ExcepcionPolitica,
+) -> Result<(), String> {
+    let valores = serde_json::json!({
+        "folio": folio_devolucion,
+        "motivo": &excepcion.motivo,
+        "autorizado_por": excepcion.autorizado_por,
+        "lineas": &excepcion.lineas,
+    });
+
+    conn.execute(
+        r"INSERT INTO auditoria (usuario_id, accion, tabla_afectada, registro_id, valores_nuevos)
+          VALUES (?, 'EXCEPCION_DEVOLUCION', 'devoluciones', ?, ?)",
+        params![usuario_id, devolucion_id, valores.to_string()],
+    )
+    .map_err(|e| format!("Error al registrar auditoría: {}", e))?;
+
+    Ok(())
+}
+
+// =====================================================
+// 🆕 MONTO A DEVOLVER
+// Lo que el cliente pagó por esas unidades: total_linea ya viene neto de
+// descuento manual, promociones y cupón; si la venta tiene un descuento
+// que no quedó repartido en las líneas (total < suma de líneas), se prorratea.
+// Las últimas unidades de la línea se llevan el saldo para no perder céntimos.
+// =====================================================
+pub fn monto_neto_devolucion(
+    conn: &rusqlite::Connection,
+    detalle_id: i32,
+    cantidad: i32,
+) -> Result<f64, String> {
+    let (cantidad_original, total_linea, factor): (i32, f64, f64) = conn
+        .query_row(
+            r"SELECT dv.cantidad, dv.total_linea,
+                     CASE WHEN s.suma > v.total AND s.suma > 0 THEN v.total / s.suma ELSE 1 END
+              FROM detalles_venta dv
+              JOIN ventas v ON v.id = dv.venta_id
+              JOIN (SELECT venta_id, SUM(total_linea) AS suma FROM detalles_venta GROUP BY venta_id) s
+                ON s.venta_id = dv.venta_id
+              WHERE dv.id = ?",
+            params![detalle_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|e| format!("Error al obtener detalle de venta: {}", e))?;
+
+    let (cantidad_previa, monto_previo): (i32, f64) = conn
+        .query_row(
+            r"SELECT COALESCE(SUM(dd.cantidad_devuelta), 0), COALESCE(SUM(dd.subtotal), 0)
+              FROM detalles_devolucion dd
+              JOIN devoluciones d ON dd.devolucion_id = d.id
+              WHERE dd.detalle_venta_id = ? AND d.estado = 'PROCESADA'",
+            params![detalle_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| format!("Error al verificar devoluciones: {}", e))?;
+
+    let neto_linea = total_linea * factor;
+    if cantidad_previa + cantidad >= cantidad_original {
+        Ok(redondear(neto_linea - monto_previo).max(0.0))
+    } else {
+        Ok(redondear(neto_linea * cantidad as f64 / cantidad_original as f64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Venta mínima: `total` es lo cobrado, las líneas (id, cantidad, total_linea) antes del cupón
+    fn venta(total: f64, lineas: &[(i32, i32, f64)]) -> rusqlite::Connection {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            r"CREATE TABLE ventas (
+                id INTEGER PRIMARY KEY, total REAL NOT NULL,
+                estado TEXT NOT NULL DEFAULT 'COMPLETADA', saldo_pendiente REAL
+              );
+              CREATE TABLE detalles_venta (
+                id INTEGER PRIMARY KEY, venta_id INTEGER NOT NULL,
+                cantidad INTEGER NOT NULL, total_linea REAL NOT NULL
+              );
+              CREATE TABLE devoluciones (id INTEGER PRIMARY KEY, estado TEXT NOT NULL);
+              CREATE TABLE detalles_devolucion (
+                devolucion_id INTEGER NOT NULL, detalle_venta_id INTEGER NOT NULL,
+                cantidad_devuelta INTEGER NOT NULL, subtotal REAL NOT NULL
+              );",
+        )
+        .unwrap();
+        conn.execute("INSERT INTO ventas (id, total) VALUES (1, ?)", params![total]).unwrap();
+        for (id, cantidad, total_linea) in lineas {
+            conn.execute(
+                "INSERT INTO detalles_venta (id, venta_id, cantidad, total_linea) VALUES (?, 1, ?, ?)",
+                params![id, cantidad, total_linea],
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    fn devolver(conn: &rusqlite::Connection, detalle_id: i32, cantidad: i32) -> f64 {
+        let monto = monto_neto_devolucion(conn, detalle_id, cantidad).unwrap();
+        conn.execute("INSERT INTO devoluciones (estado) VALUES ('PROCESADA')", []).unwrap();
+        conn.execute(
+            "INSERT INTO detalles_devolucion VALUES (?, ?, ?, ?)",
+            params![conn.last_insert_rowid(), detalle_id, cantidad, monto],
+        )
+        .unwrap();
+        monto
+    }
+
+    #[test]
+    fn devolucion_descuenta_el_cupon_de_la_venta() {
+        // Las líneas suman 120 y se cobraron 96: cada sol vendido vale 0.80
+        let conn = venta(96.0, &[(1, 3, 90.0), (2, 1, 30.0)]);
+        assert_eq!(monto_neto_devolucion(&conn, 1, 1).unwrap(), 24.0);
+        assert_eq!(monto_neto_devolucion(&conn, 2, 1).unwrap(), 24.0);
+    }
+
+    #[test]
+    fn devoluciones_parciales_no_pasan_de_lo_cobrado() {
+        let conn = venta(96.0, &[(1, 3, 90.0), (2, 1, 30.0)]);
+        assert_eq!(devolver(&conn, 1, 1), 24.0);
+        assert_eq!(devolver(&conn, 1, 2), 48.0);
+        assert_eq!(devolver(&conn, 2, 1), 24.0);
+    }
+
+    #[test]
+    fn la_ultima_unidad_toma_el_saldo_del_redondeo() {
+        let conn = venta(10.0, &[(1, 3, 10.0)]);
+        assert_eq!(devolver(&conn, 1, 1), 3.33);
+        assert_eq!(devolver(&conn, 1, 1), 3.33);
+        assert_eq!(devolver(&conn, 1, 1), 3.34);
+    }
+
+    #[test]
+    fn venta_anulada_no_admite_devolucion() {
+        let conn = venta(96.0, &[(1, 3, 90.0)]);
+        assert_eq!(saldo_venta_devolvible(&conn, 1).unwrap(), 0.0);
+
+        // Lo mismo que hace anular_venta
+        conn.execute("UPDATE ventas SET estado = 'CANCELADA' WHERE id = 1 AND estado = 'COMPLETADA'", [])
+            .unwrap();
+        let error = saldo_venta_devolvible(&conn, 1).unwrap_err();
+        assert!(error.contains("CANCELADA"), "{}", error);
+        assert!(saldo_venta_devolvible(&conn, 2).is_err());
+    }
+
+    #[test]
+    fn sin_descuento_se_devuelve_el_total_de_la_linea() {
+        let conn = venta(120.0, &[(1, 3, 90.0), (2, 1, 30.0)]);
+        assert_eq!(monto_neto_devolucion(&conn, 1, 2).unwrap(), 60.0);
+    }
+}
@@ -0,0 +1,191 @@
+// commands/envio_sunat.rs
+// Paquete ZIP y envío de comprobantes. El envío pasa por el trait
+// ClienteSunat; la implementación por defecto es el servicio SOAP sendBill
+// de SUNAT/OSE, que puede apuntar a un OSE de prueba local cambiando el
+// endpoint en la configuración.
+
+use crate::models::comprobante::ConfiguracionFacturacion;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use std::io::{Cursor, Read, Write};
+use std::time::Duration;
+
+// Respuesta de SUNAT/OSE. codigo "0" = aceptado; 2000-3999 = rechazado;
+// 4000 en adelante = aceptado con observaciones; el resto son excepciones.
+pub struct RespuestaSunat {
+    pub codigo: String,
+    pub descripcion: String,
+    pub observaciones: Vec<String>,
+    pub cdr_zip: Option<Vec<u8>>,
+}
+
+pub trait ClienteSunat {
+    // Err solo cuando no hubo respuesta (sin conexión, timeout): se puede reintentar
+    fn enviar(&self, nombre_zip: &str, zip: &[u8]) -> Result<RespuestaSunat, String>;
+}
+
+// =====================================================
+// CLIENTE SOAP (sendBill)
+// =====================================================
+pub struct ClienteSoap {
+    pub endpoint: String,
+    pub usuario: String, // RUC + usuario SOL
+    pub clave: String,
+}
+
+impl ClienteSunat for ClienteSoap {
+    fn enviar(&self, nombre_zip: &str, zip: &[u8]) -> Result<RespuestaSunat, String> {
+        let sobre = format!(
+            concat!(
+                r#"<soapenv:Envelope xmlns:soapenv="http://schemas.xmlsoap.org/soap/envelope/" xmlns:ser="http://service.sunat.gob.pe" "#,
+                r#"xmlns:wsse="http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-wssecurity-secext-1.0.xsd">"#,
+                r#"<soapenv:Header><wsse:Security><wsse:UsernameToken><wsse:Username>{}</wsse:Username><wsse:Password>{}</wsse:Password></wsse:UsernameToken></wsse:Security></soapenv:Header>"#,
+                r#"<soapenv:Body><ser:sendBill><fileName>{}</fileName><contentFile>{}</contentFile></ser:sendBill></soapenv:Body></soapenv:Envelope>"#
+            ),
+            escapar(&self.usuario),
+            escapar(&self.clave),
+            escapar(nombre_zip),
+            BASE64.encode(zip)
+        );
+
+        let respuesta = ureq::post(&self.endpoint)
+            .set("Content-Type", "text/xml; charset=utf-8")
+            .set("SOAPAction", "urn:sendBill")
+            .timeout(Duration::from_secs(30))
+            .send_string(&sobre);
+
+        // Los SOAP Fault llegan con HTTP 500 pero traen respuesta
+        let cuerpo = match respuesta {
+            Ok(r) => r.into_string(),
+            Err(ureq::Error::Status(_, r)) => r.into_string(),
+            Err(e) => return Err(format!("No se pudo conectar con SUNAT/OSE: {}", e)),
+        }
+        .map_err(|e| format!("Error al leer la respuesta: {}", e))?;
+
+        interpretar_respuesta(&cuerpo)
+    }
+}
+
+// Cliente según la configuración de la tienda
+pub fn cliente_configurado(config: &ConfiguracionFacturacion) -> Result<Box<dyn ClienteSunat>, String> {
+    let usuario = config
+        .usuario_sol
+        .clone()
+        .filter(|u| !u.is_empty())
+        .ok_or_else(|| "Configura el usuario SOL para enviar comprobantes".to_string())?;
+
+    Ok(Box::new(ClienteSoap {
+        endpoint: config.endpoint.clone(),
+        usuario: format!("{}{}", config.ruc, usuario),
+        clave: config.clave_sol.clone().unwrap_or_default(),
+    }))
+}
+
+fn interpretar_respuesta(cuerpo: &str) -> Result<RespuestaSunat, String> {
+    if let Some(codigo) = valor_etiqueta(cuerpo, "faultcode") {
+        // ej. soap-env:Client.2033 -> 2033
+        let codigo: String = codigo.chars().filter(|c| c.is_ascii_digit()).collect();
+        return Ok(RespuestaSunat {
+            codigo: if codigo.is_empty() { "0100".to_string() } else { codigo },
+            descripcion: valor_etiqueta(cuerpo, "faultstring").unwrap_or_default(),
+            observaciones: Vec::new(),
+            cdr_zip: None,
+        });
+    }
+
+    let contenido = valor_etiqueta(cuerpo, "applicationResponse")
+        .ok_or_else(|| "Respuesta sin constancia de recepción (CDR)".to_string())?;
+    let cdr_zip = BASE64
+        .decode(contenido.trim())
+        .map_err(|e| format!("CDR inválido: {}", e))?;
+    let cdr = leer_xml_zip(&cdr_zip)?;
+
+    Ok(RespuestaSunat {
+        codigo: valor_etiqueta(&cdr, "ResponseCode").unwrap_or_else(|| "0".to_string()),
+        descripcion: valor_etiqueta(&cdr, "Description").unwrap_or_default(),
+        observaciones: valores_etiqueta(&cdr, "Note"),
+        cdr_zip: Some(cdr_zip),
+    })
+}
+
+// =====================================================
+// ZIP
+// =====================================================
+pub fn empaquetar_zip(nombre_xml: &str, xml: &str) -> Result<Vec<u8>, String> {
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let opciones = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    zip.start_file(nombre_xml, opciones)
+        .map_err(|e| format!("Error al crear ZIP: {}", e))?;
+    zip.write_all(xml.as_bytes())
+        .map_err(|e| format!("Error al escribir ZIP: {}", e))?;
+
+    zip.finish()
+        .map(|c| c.into_inner())
+        .map_err(|e| format!("Error al cerrar ZIP: {}", e))
+}
+
+// Primer .xml del ZIP (el CDR trae R-<nombre>.xml)
+pub fn leer_xml_zip(datos: &[u8]) -> Result<String, String> {
+    let mut zip = zip::ZipArchive::new(Cursor::new(datos))
+        .map_err(|e| format!("ZIP inválido: {}", e))?;
+
+    for i in 0..zip.len() {
+        let mut archivo = zip.by_index(i).map_err(|e| format!("ZIP inválido: {}", e))?;
+        if archivo.name().to_lowercase().ends_with(".xml") {
+            let mut xml = String::new();
+            archivo
+                .read_to_string(&mut xml)
+                .map_err(|e| format!("Error al leer XML del ZIP: {}", e))?;
+            return Ok(xml);
+        }
+    }
+
+    Err("El ZIP no contiene un XML".to_string())
+}
+
+// =====================================================
+// LECTURA SIMPLE DE ETIQUETAS (con o sin prefijo)
+// =====================================================
+fn valor_etiqueta(xml: &str, nombre: &str) -> Option<String> {
+    valores_etiqueta(xml, nombre).into_iter().next()
+}
+
+fn valores_etiqueta(xml: &str, nombre: &str) -> Vec<String> {
+    let mut valores = Vec::new();
+    let mut resto = xml;
+
+    while let Some(inicio) = resto.find('<') {
+        resto = &resto[inicio + 1..];
+        let fin_etiqueta = match resto.find('>') {
+            Some(f) => f,
+            None => break,
+        };
+        let etiqueta = resto[..fin_etiqueta].split_whitespace().next().unwrap_or("");
+        let local = etiqueta.rsplit(':').next().unwrap_or(etiqueta);
+
+        // Sin cierres (</x>), declaraciones ni etiquetas vacías (<x/>)
+        if local == nombre && !etiqueta.starts_with(['/', '?', '!']) && !resto[..fin_etiqueta].ends_with('/') {
+            let contenido = &resto[fin_etiqueta + 1..];
+            let fin = contenido.find(&format!("</{}>", etiqueta)).unwrap_or(contenido.len());
+            valores.push(desescapar(&contenido[..fin]));
+        }
+        resto = &resto[fin_etiqueta..];
+    }
+
+    valores
+}
+
+fn escapar(texto: &str) -> String {
+    texto.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn desescapar(texto: &str) -> String {
+    texto
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
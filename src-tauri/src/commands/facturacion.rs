@@ -0,0 +1,712 @@
+// commands/facturacion.rs
+// Comprobantes electrónicos SUNAT: genera el XML UBL 2.1 de la venta, lo
+// firma con el certificado de la tienda, arma el ZIP y guarda todo junto con
+// la respuesta (CDR) de SUNAT/OSE.
+//
+// Flujo: emitir_comprobante (FIRMADO) -> enviar_comprobante
+//        (ACEPTADO | OBSERVADO | RECHAZADO | ERROR, este último se reintenta)
+//
+// 🆕 El tipo, la serie y el número salen de la venta (se asignan al cobrar);
+// las notas de venta no generan comprobante electrónico.
+//
+// 🆕 Las notas de crédito (tipo 07) se emiten igual con emitir_nota_credito y
+// quedan en la misma tabla con nota_credito_id.
+
+use crate::commands::clientes::validar_ruc;
+use crate::commands::envio_sunat::{cliente_configurado, empaquetar_zip};
+use crate::commands::firma_digital::{firmar_xml, CertificadoDigital};
+use crate::commands::impuestos::TotalesIgv;
+use crate::commands::notas_credito::obtener_nota_credito_por_id;
+use crate::commands::numeracion::numero_comprobante;
+use crate::commands::ubl::*;
+use crate::database::DatabasePool;
+use crate::models::comprobante::*;
+use rusqlite::{params, OptionalExtension};
+
+const ENDPOINT_SUNAT_BETA: &str = "https://e-beta.sunat.gob.pe/ol-ti-itcpfegem-beta/billService";
+
+const SELECT_COMPROBANTE: &str = r"
+    SELECT id, venta_id, nota_credito_id, tipo_comprobante, serie, correlativo, nombre_archivo, hash, estado,
+           codigo_respuesta, descripcion_respuesta, observaciones, COALESCE(intentos_envio, 0),
+           fecha_emision, fecha_envio
+    FROM comprobantes_electronicos";
+
+// =====================================================
+// COMANDO: Configuración de facturación electrónica
+// =====================================================
+#[tauri::command]
+pub fn obtener_configuracion_facturacion(
+    db: tauri::State<'_, DatabasePool>,
+) -> Result<ConfiguracionFacturacion, String> {
+    let conn = db.get_conn();
+    let mut config = configuracion_facturacion(&conn)?;
+
+    // Las contraseñas no salen del backend
+    config.certificado_clave = None;
+    config.clave_sol = None;
+    Ok(config)
+}
+
+#[tauri::command]
+pub fn actualizar_configuracion_facturacion(
+    db: tauri::State<'_, DatabasePool>,
+    configuracion: ConfiguracionFacturacion,
+) -> Result<String, String> {
+    let conn = db.get_conn();
+
+    let ruc = validar_ruc(&configuracion.ruc)?;
+    if configuracion.razon_social.trim().is_empty() {
+        return Err("❌ Indica la razón social".to_string());
+    }
+    if !configuracion.ubigeo.is_empty()
+        && (configuracion.ubigeo.len() != 6 || !configuracion.ubigeo.chars().all(|c| c.is_ascii_digit()))
+    {
+        return Err("❌ El ubigeo debe tener 6 dígitos".to_string());
+    }
+    let endpoint = configuracion.endpoint.trim();
+    if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+        return Err("❌ El endpoint debe ser una URL http(s)".to_string());
+    }
+
+    // Contraseñas en None = se mantienen las guardadas
+    conn.execute(
+        r"UPDATE configuracion_tienda
+          SET rfc = ?, razon_social = ?, nombre_comercial = ?, direccion = ?, ubigeo = ?,
+              cpe_certificado_ruta = ?, cpe_certificado_clave = COALESCE(?, cpe_certificado_clave),
+              cpe_usuario_sol = ?, cpe_clave_sol = COALESCE(?, cpe_clave_sol), cpe_endpoint = ?,
+              fecha_actualizacion = datetime('now', 'localtime')
+          WHERE id = 1",
+        params![
+            &ruc,
+            configuracion.razon_social.trim(),
+            configuracion.nombre_comercial,
+            configuracion.direccion,
+            configuracion.ubigeo,
+            configuracion.certificado_ruta,
+            configuracion.certificado_clave,
+            configuracion.usuario_sol,
+            configuracion.clave_sol,
+            endpoint,
+        ],
+    )
+    .map_err(|e| format!("Error al actualizar configuración de facturación: {}", e))?;
+
+    Ok("✅ Configuración de facturación actualizada".to_string())
+}
+
+// =====================================================
+// COMANDO: Emitir (generar y firmar) el comprobante de una venta
+// =====================================================
+#[tauri::command]
+pub fn emitir_comprobante(
+    db: tauri::State<'_, DatabasePool>,
+    venta_id: i32,
+    usuario_id: i32,
+) -> Result<ComprobanteElectronico, String> {
+    let conn = db.get_conn();
+    let comprobante_id = generar_comprobante(&conn, venta_id, usuario_id)?;
+    obtener_comprobante_por_id(&conn, comprobante_id)
+}
+
+// =====================================================
+// 🆕 COMANDO: Emitir (generar y firmar) una nota de crédito
+// =====================================================
+#[tauri::command]
+pub fn emitir_nota_credito(
+    db: tauri::State<'_, DatabasePool>,
+    nota_credito_id: i32,
+    usuario_id: i32,
+) -> Result<ComprobanteElectronico, String> {
+    let conn = db.get_conn();
+    let comprobante_id = generar_comprobante_nota_credito(&conn, nota_credito_id, usuario_id)?;
+    obtener_comprobante_por_id(&conn, comprobante_id)
+}
+
+// =====================================================
+// COMANDO: Enviar a SUNAT/OSE
+// =====================================================
+#[tauri::command]
+pub fn enviar_comprobante(
+    db: tauri::State<'_, DatabasePool>,
+    comprobante_id: i32,
+) -> Result<ComprobanteElectronico, String> {
+    // El envío puede tardar hasta el timeout: la conexión no queda tomada
+    // mientras tanto, así el POS sigue vendiendo aunque SUNAT esté lento
+    let conn = db.get_conn();
+
+    let (nombre_archivo, zip, estado): (String, Vec<u8>, String) = conn
+        .query_row(
+            "SELECT nombre_archivo, zip, estado FROM comprobantes_electronicos WHERE id = ?",
+            params![comprobante_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()
+        .map_err(|e| format!("Error al obtener comprobante: {}", e))?
+        .ok_or_else(|| "❌ Comprobante no encontrado".to_string())?;
+
+    match estado.as_str() {
+        "ACEPTADO" | "OBSERVADO" => return Err("❌ El comprobante ya fue aceptado".to_string()),
+        "RECHAZADO" => return Err("❌ El comprobante fue rechazado; corrige y emite uno nuevo".to_string()),
+        _ => {}
+    }
+
+    let config = configuracion_facturacion(&conn)?;
+    let cliente = cliente_configurado(&config)?;
+    drop(conn);
+
+    let envio = cliente.enviar(&format!("{}.zip", nombre_archivo), &zip);
+
+    let conn = db.get_conn();
+    match envio {
+        Ok(respuesta) => {
+            let estado = estado_por_codigo(&respuesta.codigo, !respuesta.observaciones.is_empty());
+            conn.execute(
+                r"UPDATE comprobantes_electronicos
+                  SET estado = ?, codigo_respuesta = ?, descripcion_respuesta = ?, observaciones = ?,
+                      cdr_zip = COALESCE(?, cdr_zip), intentos_envio = COALESCE(intentos_envio, 0) + 1,
+                      fecha_envio = datetime('now', 'localtime')
+                  WHERE id = ?",
+                params![
+                    estado,
+                    respuesta.codigo,
+                    respuesta.descripcion,
+                    if respuesta.observaciones.is_empty() { None } else { Some(respuesta.observaciones.join("\n")) },
+                    respuesta.cdr_zip,
+                    comprobante_id,
+                ],
+            )
+            .map_err(|e| format!("Error al guardar respuesta de SUNAT: {}", e))?;
+
+            obtener_comprobante_por_id(&conn, comprobante_id)
+        }
+        Err(e) => {
+            conn.execute(
+                r"UPDATE comprobantes_electronicos
+                  SET estado = 'ERROR', descripcion_respuesta = ?,
+                      intentos_envio = COALESCE(intentos_envio, 0) + 1,
+                      fecha_envio = datetime('now', 'localtime')
+                  WHERE id = ?",
+                params![e, comprobante_id],
+            )
+            .map_err(|e| format!("Error al registrar envío fallido: {}", e))?;
+
+            Err(format!("❌ {}", e))
+        }
+    }
+}
+
+// =====================================================
+// COMANDO: Consultas
+// =====================================================
+#[tauri::command]
+pub fn obtener_comprobante_venta(
+    db: tauri::State<'_, DatabasePool>,
+    venta_id: i32,
+) -> Result<Option<ComprobanteElectronico>, String> {
+    let conn = db.get_conn();
+
+    conn.query_row(
+        &format!("{} WHERE venta_id = ? AND nota_credito_id IS NULL", SELECT_COMPROBANTE),
+        params![venta_id],
+        fila_comprobante,
+    )
+    .optional()
+    .map_err(|e| format!("Error al obtener comprobante: {}", e))
+}
+
+// Pendientes de envío o con error (para reintentar)
+#[tauri::command]
+pub fn obtener_comprobantes_pendientes(
+    db: tauri::State<'_, DatabasePool>,
+) -> Result<Vec<ComprobanteElectronico>, String> {
+    let conn = db.get_conn();
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "{} WHERE estado IN ('FIRMADO', 'ERROR') ORDER BY id",
+            SELECT_COMPROBANTE
+        ))
+        .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+
+    let comprobantes = stmt
+        .query_map([], fila_comprobante)
+        .map_err(|e| format!("Error al obtener comprobantes: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(comprobantes)
+}
+
+#[tauri::command]
+pub fn obtener_xml_comprobante(
+    db: tauri::State<'_, DatabasePool>,
+    comprobante_id: i32,
+) -> Result<String, String> {
+    let conn = db.get_conn();
+
+    conn.query_row(
+        "SELECT xml_firmado FROM comprobantes_electronicos WHERE id = ?",
+        params![comprobante_id],
+        |row| row.get(0),
+    )
+    .map_err(|e| format!("Error al obtener XML: {}", e))
+}
+
+// =====================================================
+// FUNCIONES AUXILIARES
+// =====================================================
+
+pub fn configuracion_facturacion(conn: &rusqlite::Connection) -> Result<ConfiguracionFacturacion, String> {
+    conn.query_row(
+        r"SELECT COALESCE(rfc, ''), COALESCE(NULLIF(razon_social, ''), nombre_tienda), nombre_comercial,
+                 COALESCE(direccion, ''), COALESCE(ubigeo, ''), cpe_certificado_ruta, cpe_certificado_clave,
+                 cpe_usuario_sol, cpe_clave_sol, COALESCE(NULLIF(cpe_endpoint, ''), ?)
+          FROM configuracion_tienda LIMIT 1",
+        params![ENDPOINT_SUNAT_BETA],
+        |row| {
+            Ok(ConfiguracionFacturacion {
+                ruc: row.get(0)?,
+                razon_social: row.get(1)?,
+                nombre_comercial: row.get(2)?,
+                direccion: row.get(3)?,
+                ubigeo: row.get(4)?,
+                certificado_ruta: row.get(5)?,
+                certificado_clave: row.get(6)?,
+                usuario_sol: row.get(7)?,
+                clave_sol: row.get(8)?,
+                endpoint: row.get(9)?,
+            })
+        },
+    )
+    .map_err(|e| format!("Error al obtener configuración de facturación: {}", e))
+}
+
+// Genera, firma y guarda el comprobante de la venta con el número que se le
+// asignó al cobrar
+pub fn generar_comprobante(
+    conn: &rusqlite::Connection,
+    venta_id: i32,
+    usuario_id: i32,
+) -> Result<i32, String> {
+    let ya_emitido: Option<String> = conn
+        .query_row(
+            r"SELECT serie || '-' || printf('%08d', correlativo) FROM comprobantes_electronicos
+              WHERE venta_id = ? AND nota_credito_id IS NULL",
+            params![venta_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Error al verificar comprobante: {}", e))?;
+    if let Some(numero) = ya_emitido {
+        return Err(format!("❌ La venta ya tiene el comprobante {}", numero));
+    }
+
+    let (fecha_hora, total, estado, cliente_id, tasa_igv, totales, tipo, serie, correlativo) = conn
+        .query_row(
+            r"SELECT fecha_hora, total, estado, cliente_id, COALESCE(igv_porcentaje, 0),
+                     COALESCE(op_gravada, 0), COALESCE(op_exonerada, 0), COALESCE(op_inafecta, 0), COALESCE(igv, 0),
+                     COALESCE(tipo_comprobante, 'NOTA_VENTA'), serie, correlativo
+              FROM ventas WHERE id = ?",
+            params![venta_id],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, f64>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, Option<i32>>(3)?,
+                    row.get::<_, f64>(4)?,
+                    TotalesIgv {
+                        op_gravada: row.get(5)?,
+                        op_exonerada: row.get(6)?,
+                        op_inafecta: row.get(7)?,
+                        igv: row.get(8)?,
+                    },
+                    row.get::<_, String>(9)?,
+                    row.get::<_, Option<String>>(10)?,
+                    row.get::<_, Option<i64>>(11)?,
+                ))
+            },
+        )
+        .optional()
+        .map_err(|e| format!("Error al obtener venta: {}", e))?
+        .ok_or_else(|| "❌ Venta no encontrada".to_string())?;
+
+    if estado != "COMPLETADA" {
+        return Err("❌ Solo se emiten comprobantes de ventas completadas".to_string());
+    }
+
+    let tipo_comprobante = codigo_tipo_comprobante(&tipo)
+        .ok_or_else(|| "❌ Las notas de venta no generan comprobante electrónico".to_string())?;
+    let (serie, correlativo) = serie
+        .zip(correlativo)
+        .ok_or_else(|| "❌ La venta no tiene serie ni número asignados".to_string())?;
+
+    let receptor = receptor_venta(conn, cliente_id, tipo_comprobante, total)?;
+
+    let (config, certificado) = configuracion_emisor(conn)?;
+
+    let lineas = lineas_venta(conn, venta_id)?;
+    if lineas.is_empty() {
+        return Err("❌ La venta no tiene productos".to_string());
+    }
+
+    let cuotas = cuotas_credito(conn, venta_id)?;
+
+    let numero = numero_comprobante(&serie, correlativo);
+    let nombre_archivo = format!("{}-{}-{}", config.ruc, tipo_comprobante, numero);
+
+    let (fecha_emision, hora_emision) = fecha_hora.split_once(' ').unwrap_or((&fecha_hora, "00:00:00"));
+    let documento = DocumentoUbl {
+        tipo_comprobante: tipo_comprobante.to_string(),
+        numero,
+        fecha_emision: fecha_emision.to_string(),
+        hora_emision: hora_emision.to_string(),
+        moneda: "PEN".to_string(),
+        tasa_igv,
+        emisor: emisor_ubl(&config),
+        receptor,
+        lineas,
+        totales,
+        total,
+        referencia: None,
+        cuotas,
+    };
+
+    guardar_comprobante(conn, &documento, &certificado, &nombre_archivo, venta_id, None, &serie, correlativo, usuario_id)
+}
+
+// 🆕 Genera, firma y guarda la nota de crédito; el comprobante que modifica
+// debe estar emitido y no rechazado
+pub fn generar_comprobante_nota_credito(
+    conn: &rusqlite::Connection,
+    nota_credito_id: i32,
+    usuario_id: i32,
+) -> Result<i32, String> {
+    let ya_emitido: Option<String> = conn
+        .query_row(
+            "SELECT estado FROM comprobantes_electronicos WHERE nota_credito_id = ?",
+            params![nota_credito_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Error al verificar comprobante: {}", e))?;
+    if ya_emitido.is_some() {
+        return Err("❌ La nota de crédito ya fue emitida".to_string());
+    }
+
+    let nota = obtener_nota_credito_por_id(conn, nota_credito_id)?;
+    let (serie, correlativo, fecha_emision_nota): (String, i64, String) = conn
+        .query_row(
+            "SELECT serie, correlativo, fecha_emision FROM notas_credito WHERE id = ?",
+            params![nota_credito_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|e| format!("Error al obtener nota de crédito: {}", e))?;
+
+    let estado_original: Option<String> = conn
+        .query_row(
+            "SELECT estado FROM comprobantes_electronicos WHERE venta_id = ? AND nota_credito_id IS NULL",
+            params![nota.venta_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Error al obtener comprobante original: {}", e))?;
+    match estado_original.as_deref() {
+        None => return Err(format!("❌ Emite primero el comprobante {}", nota.numero_ref)),
+        Some("RECHAZADO") => {
+            return Err(format!("❌ El comprobante {} fue rechazado; no admite notas de crédito", nota.numero_ref))
+        }
+        _ => {}
+    }
+
+    let tipo_ref = codigo_tipo_comprobante(&nota.tipo_documento_ref)
+        .ok_or_else(|| "❌ Tipo de comprobante de referencia no válido".to_string())?;
+
+    let (cliente_id, total_venta): (Option<i32>, f64) = conn
+        .query_row(
+            "SELECT cliente_id, total FROM ventas WHERE id = ?",
+            params![nota.venta_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| format!("Error al obtener venta: {}", e))?;
+    let receptor = receptor_venta(conn, cliente_id, tipo_ref, total_venta)?;
+
+    let (config, certificado) = configuracion_emisor(conn)?;
+
+    let numero = numero_comprobante(&serie, correlativo);
+    let nombre_archivo = format!("{}-07-{}", config.ruc, numero);
+
+    let (fecha_emision, hora_emision) = fecha_emision_nota
+        .split_once(' ')
+        .unwrap_or((&fecha_emision_nota, "00:00:00"));
+    let documento = DocumentoUbl {
+        tipo_comprobante: "07".to_string(),
+        numero,
+        fecha_emision: fecha_emision.to_string(),
+        hora_emision: hora_emision.to_string(),
+        moneda: "PEN".to_string(),
+        tasa_igv: nota.igv_porcentaje,
+        emisor: emisor_ubl(&config),
+        receptor,
+        lineas: nota
+            .detalles
+            .iter()
+            .map(|d| LineaUbl {
+                codigo: d.codigo.clone(),
+                descripcion: d.descripcion.clone(),
+                cantidad: d.cantidad,
+                precio_unitario: d.total_linea / d.cantidad.max(1) as f64,
+                valor_venta: d.valor_venta,
+                igv: d.igv,
+                afectacion: d.afectacion_igv.clone(),
+            })
+            .collect(),
+        totales: TotalesIgv {
+            op_gravada: nota.op_gravada,
+            op_exonerada: nota.op_exonerada,
+            op_inafecta: nota.op_inafecta,
+            igv: nota.igv,
+        },
+        total: nota.total,
+        referencia: Some(ReferenciaUbl {
+            tipo_comprobante: tipo_ref.to_string(),
+            numero: nota.numero_ref.clone(),
+            codigo_motivo: nota.codigo_motivo.clone(),
+            sustento: nota.sustento.clone(),
+        }),
+        cuotas: Vec::new(),
+    };
+
+    guardar_comprobante(
+        conn,
+        &documento,
+        &certificado,
+        &nombre_archivo,
+        nota.venta_id,
+        Some(nota_credito_id),
+        &serie,
+        correlativo,
+        usuario_id,
+    )
+}
+
+// RUC y certificado listos para firmar
+fn configuracion_emisor(
+    conn: &rusqlite::Connection,
+) -> Result<(ConfiguracionFacturacion, CertificadoDigital), String> {
+    let config = configuracion_facturacion(conn)?;
+    if config.ruc.len() != 11 {
+        return Err("❌ Configura el RUC de la tienda antes de emitir comprobantes".to_string());
+    }
+    let certificado = CertificadoDigital::desde_pfx(
+        config
+            .certificado_ruta
+            .as_deref()
+            .filter(|r| !r.is_empty())
+            .ok_or_else(|| "❌ Configura el certificado digital (.pfx)".to_string())?,
+        config.certificado_clave.as_deref().unwrap_or(""),
+    )?;
+    Ok((config, certificado))
+}
+
+fn emisor_ubl(config: &ConfiguracionFacturacion) -> EmisorUbl {
+    EmisorUbl {
+        ruc: config.ruc.clone(),
+        razon_social: config.razon_social.clone(),
+        nombre_comercial: config.nombre_comercial.clone(),
+        direccion: config.direccion.clone(),
+        ubigeo: config.ubigeo.clone(),
+    }
+}
+
+// Firma, empaqueta y guarda el comprobante en estado FIRMADO
+#[allow(clippy::too_many_arguments)]
+fn guardar_comprobante(
+    conn: &rusqlite::Connection,
+    documento: &DocumentoUbl,
+    certificado: &CertificadoDigital,
+    nombre_archivo: &str,
+    venta_id: i32,
+    nota_credito_id: Option<i32>,
+    serie: &str,
+    correlativo: i64,
+    usuario_id: i32,
+) -> Result<i32, String> {
+    let (xml_firmado, hash) = firmar_xml(&generar_xml(documento), certificado)?;
+    let zip = empaquetar_zip(&format!("{}.xml", nombre_archivo), &xml_firmado)?;
+
+    conn.execute(
+        r"INSERT INTO comprobantes_electronicos
+            (venta_id, nota_credito_id, tipo_comprobante, serie, correlativo, nombre_archivo, xml_firmado, hash, zip,
+             estado, usuario_id)
+          VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, 'FIRMADO', ?)",
+        params![
+            venta_id,
+            nota_credito_id,
+            documento.tipo_comprobante,
+            serie,
+            correlativo,
+            nombre_archivo,
+            xml_firmado,
+            hash,
+            zip,
+            usuario_id,
+        ],
+    )
+    .map_err(|e| format!("Error al guardar comprobante: {}", e))?;
+
+    Ok(conn.last_insert_rowid() as i32)
+}
+
+// 🆕 Código SUNAT (catálogo 01); None para la nota de venta
+pub fn codigo_tipo_comprobante(tipo: &str) -> Option<&'static str> {
+    match tipo {
+        "FACTURA" => Some("01"),
+        "BOLETA" => Some("03"),
+        _ => None,
+    }
+}
+
+// Factura: cliente con RUC. Boleta: cliente opcional salvo desde S/ 700.
+// También se usa al cobrar para no emitir un número que luego no se pueda declarar.
+pub fn receptor_venta(
+    conn: &rusqlite::Connection,
+    cliente_id: Option<i32>,
+    tipo_comprobante: &str,
+    total: f64,
+) -> Result<ReceptorUbl, String> {
+    let cliente: Option<(String, String, String)> = match cliente_id {
+        Some(id) => conn
+            .query_row(
+                "SELECT tipo_documento, numero_documento, nombre FROM clientes WHERE id = ?",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()
+            .map_err(|e| format!("Error al obtener cliente: {}", e))?,
+        None => None,
+    };
+
+    match cliente {
+        Some((tipo_documento, numero_documento, nombre)) => {
+            if tipo_comprobante == "01" && tipo_documento != "RUC" {
+                return Err("❌ La factura requiere un cliente con RUC".to_string());
+            }
+            let codigo = match tipo_documento.as_str() {
+                "RUC" => "6",
+                "CE" => "4",
+                _ => "1",
+            };
+            Ok(ReceptorUbl { tipo_documento: codigo.to_string(), numero_documento, nombre })
+        }
+        None if tipo_comprobante == "01" => Err("❌ La factura requiere un cliente con RUC".to_string()),
+        None if total >= 700.0 => {
+            Err("❌ Las boletas desde S/ 700 deben identificar al cliente".to_string())
+        }
+        None => Ok(ReceptorUbl {
+            tipo_documento: "0".to_string(),
+            numero_documento: "-".to_string(),
+            nombre: "CLIENTES VARIOS".to_string(),
+        }),
+    }
+}
+
+fn lineas_venta(conn: &rusqlite::Connection, venta_id: i32) -> Result<Vec<LineaUbl>, String> {
+    let mut stmt = conn
+        .prepare(
+            r"SELECT p.codigo, p.nombre, dv.talla, dv.cantidad, dv.total_linea,
+                     COALESCE(dv.valor_venta, dv.total_linea), COALESCE(dv.igv, 0),
+                     COALESCE(dv.afectacion_igv, 'GRAVADO')
+              FROM detalles_venta dv
+              JOIN productos p ON p.id = dv.producto_id
+              WHERE dv.venta_id = ?
+              ORDER BY dv.id",
+        )
+        .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+
+    let lineas = stmt
+        .query_map(params![venta_id], |row| {
+            let nombre: String = row.get(1)?;
+            let talla: Option<String> = row.get(2)?;
+            let cantidad: i32 = row.get(3)?;
+            let total_linea: f64 = row.get(4)?;
+            Ok(LineaUbl {
+                codigo: row.get(0)?,
+                descripcion: match talla {
+                    Some(t) if !t.is_empty() => format!("{} - Talla {}", nombre, t),
+                    _ => nombre,
+                },
+                cantidad,
+                precio_unitario: total_linea / cantidad.max(1) as f64,
+                valor_venta: row.get(5)?,
+                igv: row.get(6)?,
+                afectacion: row.get(7)?,
+            })
+        })
+        .map_err(|e| format!("Error al obtener detalle de la venta: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(lineas)
+}
+
+// 🆕 Parte al crédito de la venta: una cuota con el vencimiento pactado
+fn cuotas_credito(conn: &rusqlite::Connection, venta_id: i32) -> Result<Vec<CuotaUbl>, String> {
+    let (monto, fecha_vencimiento): (f64, Option<String>) = conn
+        .query_row(
+            r"SELECT COALESCE((SELECT SUM(monto) FROM pagos_venta
+                               WHERE venta_id = ?1 AND metodo_pago = 'CREDITO'), 0),
+                     fecha_vencimiento_pago
+              FROM ventas WHERE id = ?1",
+            params![venta_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| format!("Error al obtener crédito de la venta: {}", e))?;
+
+    match fecha_vencimiento {
+        Some(fecha) if monto > 0.0 => Ok(vec![CuotaUbl {
+            monto: (monto * 100.0).round() / 100.0,
+            fecha_vencimiento: fecha,
+        }]),
+        _ => Ok(Vec::new()),
+    }
+}
+
+// Catálogo de códigos de respuesta de SUNAT
+fn estado_por_codigo(codigo: &str, con_observaciones: bool) -> &'static str {
+    match codigo.parse::<u32>() {
+        Ok(0) if con_observaciones => "OBSERVADO",
+        Ok(0) => "ACEPTADO",
+        Ok(2000..=3999) => "RECHAZADO",
+        Ok(n) if n >= 4000 => "OBSERVADO",
+        _ => "ERROR",
+    }
+}
+
+fn obtener_comprobante_por_id(conn: &rusqlite::Connection, id: i32) -> Result<ComprobanteElectronico, String> {
+    conn.query_row(&format!("{} WHERE id = ?", SELECT_COMPROBANTE), params![id], fila_comprobante)
+        .map_err(|e| format!("Error al obtener comprobante: {}", e))
+}
+
+fn fila_comprobante(row: &rusqlite::Row) -> rusqlite::Result<ComprobanteElectronico> {
+    let serie: String = row.get(4)?;
+    let correlativo: i64 = row.get(5)?;
+    Ok(ComprobanteElectronico {
+        id: row.get(0)?,
+        venta_id: row.get(1)?,
+        nota_credito_id: row.get(2)?,
+        tipo_comprobante: row.get(3)?,
+        numero: numero_comprobante(&serie, correlativo),
+        serie,
+        correlativo,
+        nombre_archivo: row.get(6)?,
+        hash: row.get(7)?,
+        estado: row.get(8)?,
+        codigo_respuesta: row.get(9)?,
+        descripcion_respuesta: row.get(10)?,
+        observaciones: row.get(11)?,
+        intentos_envio: row.get(12)?,
+        fecha_emision: row.get(13)?,
+        fecha_envio: row.get(14)?,
+    })
+}
@@ -0,0 +1,117 @@
+// commands/firma_digital.rs
+// Firma XMLDSig (enveloped) de comprobantes con el certificado .pfx de la
+// tienda: RSA-SHA256, digest SHA-256 y canonicalización C14N inclusiva.
+//
+// El XML generado en ubl.rs ya está en forma canónica, así que el digest se
+// calcula sobre el documento tal cual (sin la declaración XML y sin la firma).
+// SignedInfo, en cambio, se canonicaliza con los namespaces heredados de la
+// raíz, que es como lo verifica SUNAT.
+
+use crate::commands::ubl::MARCADOR_FIRMA;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use rsa::pkcs1v15::SigningKey;
+use rsa::pkcs8::DecodePrivateKey;
+use rsa::signature::{SignatureEncoding, Signer};
+use rsa::RsaPrivateKey;
+use sha2::{Digest, Sha256};
+
+pub struct CertificadoDigital {
+    clave_privada: RsaPrivateKey,
+    certificado_der: Vec<u8>,
+}
+
+impl CertificadoDigital {
+    // Lee el .pfx / .p12 guardado en el equipo
+    pub fn desde_pfx(ruta: &str, clave: &str) -> Result<Self, String> {
+        let datos = std::fs::read(ruta)
+            .map_err(|e| format!("No se pudo leer el certificado {}: {}", ruta, e))?;
+
+        let almacen = p12_keystore::KeyStore::from_pkcs12(&datos, clave)
+            .map_err(|e| format!("Certificado o contraseña inválidos: {}", e))?;
+
+        let (_, cadena) = almacen
+            .private_key_chain()
+            .ok_or_else(|| "El certificado no contiene una clave privada".to_string())?;
+
+        let certificado = cadena
+            .chain()
+            .first()
+            .ok_or_else(|| "El certificado no contiene el certificado público".to_string())?;
+
+        let clave_privada = RsaPrivateKey::from_pkcs8_der(cadena.key())
+            .map_err(|e| format!("La clave del certificado no es RSA: {}", e))?;
+
+        Ok(CertificadoDigital {
+            clave_privada,
+            certificado_der: certificado.as_der().to_vec(),
+        })
+    }
+}
+
+// Firma el XML reemplazando MARCADOR_FIRMA por ds:Signature.
+// Devuelve (xml firmado, DigestValue) — el digest es el "hash" del comprobante.
+pub fn firmar_xml(xml: &str, certificado: &CertificadoDigital) -> Result<(String, String), String> {
+    if xml.matches(MARCADOR_FIRMA).count() != 1 {
+        return Err("El XML no tiene el lugar de la firma".to_string());
+    }
+
+    // C14N no incluye la declaración XML
+    let documento = match xml.strip_prefix("<?xml") {
+        Some(resto) => {
+            let fin = resto.find("?>").ok_or_else(|| "Declaración XML inválida".to_string())?;
+            resto[fin + 2..].trim_start()
+        }
+        None => xml,
+    };
+
+    // Transformación enveloped-signature: el documento sin la firma
+    let sin_firma = documento.replace(MARCADOR_FIRMA, "");
+    let digest = BASE64.encode(Sha256::digest(sin_firma.as_bytes()));
+
+    let namespaces = namespaces_raiz(documento)?;
+    let signed_info_canonico = signed_info(&namespaces, &digest);
+
+    let firma = SigningKey::<Sha256>::new(certificado.clave_privada.clone())
+        .try_sign(signed_info_canonico.as_bytes())
+        .map_err(|e| format!("Error al firmar: {}", e))?;
+
+    let signature = format!(
+        r#"<ds:Signature Id="SignatureSP">{}<ds:SignatureValue>{}</ds:SignatureValue><ds:KeyInfo><ds:X509Data><ds:X509Certificate>{}</ds:X509Certificate></ds:X509Data></ds:KeyInfo></ds:Signature>"#,
+        signed_info("", &digest),
+        BASE64.encode(firma.to_bytes()),
+        BASE64.encode(&certificado.certificado_der)
+    );
+
+    Ok((xml.replace(MARCADOR_FIRMA, &signature), digest))
+}
+
+// ds:SignedInfo; `namespaces` solo se usa para la versión canónica que se firma
+fn signed_info(namespaces: &str, digest: &str) -> String {
+    format!(
+        concat!(
+            r#"<ds:SignedInfo{}>"#,
+            r#"<ds:CanonicalizationMethod Algorithm="http://www.w3.org/TR/2001/REC-xml-c14n-20010315"></ds:CanonicalizationMethod>"#,
+            r#"<ds:SignatureMethod Algorithm="http://www.w3.org/2001/04/xmldsig-more#rsa-sha256"></ds:SignatureMethod>"#,
+            r#"<ds:Reference URI=""><ds:Transforms><ds:Transform Algorithm="http://www.w3.org/2000/09/xmldsig#enveloped-signature"></ds:Transform></ds:Transforms>"#,
+            r#"<ds:DigestMethod Algorithm="http://www.w3.org/2001/04/xmlenc#sha256"></ds:DigestMethod>"#,
+            r#"<ds:DigestValue>{}</ds:DigestValue></ds:Reference></ds:SignedInfo>"#
+        ),
+        namespaces, digest
+    )
+}
+
+// Declaraciones xmlns de la raíz, en el orden canónico en que se escribieron
+fn namespaces_raiz(documento: &str) -> Result<String, String> {
+    let fin = documento.find('>').ok_or_else(|| "XML sin elemento raíz".to_string())?;
+    let declaraciones: Vec<&str> = documento[..fin]
+        .split_whitespace()
+        .filter(|a| a.starts_with("xmlns"))
+        .collect();
+
+    if !declaraciones.iter().any(|d| d.starts_with("xmlns:ds=")) {
+        return Err("La raíz del XML no declara el namespace ds".to_string());
+    }
+
+    Ok(declaraciones.iter().map(|d| format!(" {}", d)).collect())
+}
@@ -0,0 +1,76 @@
+// commands/idempotencia.rs
+// Claves de idempotencia para operaciones que mueven dinero o stock.
+//
+// El frontend genera una clave por envío (un UUID al abrir el cobro, la
+// devolución o el pago) y la repite si reintenta. La primera vez la clave se
+// guarda en operaciones_idempotentes dentro de la misma transacción que la
+// operación, junto con el resultado que se devolvió; los reintentos
+// encuentran la clave y reciben ese mismo resultado sin volver a procesar.
+// Si la operación falla no queda clave y el reintento se procesa normalmente.
+
+use rusqlite::{params, OptionalExtension};
+use serde::{de::DeserializeOwned, Serialize};
+
+// Clave limpia (sin espacios); vacía = sin idempotencia
+pub fn normalizar_clave(clave: Option<String>) -> Result<Option<String>, String> {
+    match clave.map(|c| c.trim().to_string()).filter(|c| !c.is_empty()) {
+        Some(c) if c.len() > 100 => Err("❌ La clave de idempotencia no puede superar 100 caracteres".to_string()),
+        clave => Ok(clave),
+    }
+}
+
+// Resultado guardado si la clave ya se usó para esta operación
+pub fn resultado_previo<T: DeserializeOwned>(
+    conn: &rusqlite::Connection,
+    operacion: &str,
+    clave: Option<&str>,
+) -> Result<Option<T>, String> {
+    let Some(clave) = clave else {
+        return Ok(None);
+    };
+
+    let previo: Option<(String, String)> = conn
+        .query_row(
+            "SELECT operacion, resultado FROM operaciones_idempotentes WHERE clave = ?",
+            params![clave],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(|e| format!("Error al verificar clave de idempotencia: {}", e))?;
+
+    match previo {
+        None => Ok(None),
+        Some((op, _)) if op != operacion => Err(format!(
+            "❌ La clave {} ya se usó para otra operación ({})",
+            clave, op
+        )),
+        Some((_, resultado)) => serde_json::from_str(&resultado)
+            .map(Some)
+            .map_err(|e| format!("Error al leer el resultado guardado: {}", e)),
+    }
+}
+
+// Guarda la clave con el resultado; llamar dentro de la transacción, antes del COMMIT
+pub fn registrar_resultado<T: Serialize>(
+    conn: &rusqlite::Connection,
+    operacion: &str,
+    clave: Option<&str>,
+    registro_id: i32,
+    resultado: &T,
+) -> Result<(), String> {
+    let Some(clave) = clave else {
+        return Ok(());
+    };
+
+    let json = serde_json::to_string(resultado)
+        .map_err(|e| format!("Error al guardar el resultado: {}", e))?;
+
+    conn.execute(
+        r"INSERT INTO operaciones_idempotentes (clave, operacion, registro_id, resultado)
+          VALUES (?, ?, ?, ?)",
+        params![clave, operacion, registro_id, json],
+    )
+    .map_err(|e| format!("Error al registrar clave de idempotencia: {}", e))?;
+
+    Ok(())
+}
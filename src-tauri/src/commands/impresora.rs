@@ -4,6 +4,9 @@ use std::thread;
 use std::time::Duration;
 use serde::Deserialize;
 use crate::database::DatabasePool;
+use crate::commands::notas_credito::obtener_nota_credito_por_id;
+use crate::commands::cotizaciones::obtener_cotizacion_por_id;
+use crate::commands::ventas::venta_detalle_por_id;
 
 #[derive(Deserialize)]
 pub struct DatosImpresion {
@@ -16,6 +19,7 @@ pub struct DatosImpresion {
     pub cambio: Option<f64>,
     pub numero_boleta: Option<String>,
     pub cajero: Option<String>,
+    pub venta_id: Option<i32>, // 🆕 para imprimir el desglose de IGV guardado en la venta
 }
 
 #[derive(Deserialize)]
@@ -36,6 +40,71 @@ fn obtener_config_impresora(db: &DatabasePool) -> (String, String, i32) {
     result.unwrap_or_else(|_| ("".to_string(), "TERMICA".to_string(), 9100))
 }
 
+// 🆕 (op. gravada, op. exonerada, op. inafecta, IGV, tasa) de la venta
+fn obtener_igv_venta(db: &DatabasePool, venta_id: i32) -> Option<(f64, f64, f64, f64, f64)> {
+    let conn = db.get_conn();
+    conn.query_row(
+        "SELECT COALESCE(op_gravada, 0), COALESCE(op_exonerada, 0), COALESCE(op_inafecta, 0), COALESCE(igv, 0), COALESCE(igv_porcentaje, 0) FROM ventas WHERE id = ?",
+        [venta_id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+    )
+    .ok()
+}
+
+// 🆕 Parte al crédito de la venta y su vencimiento (None si se pagó completa)
+fn obtener_credito_venta(db: &DatabasePool, venta_id: i32) -> Option<(f64, String)> {
+    let conn = db.get_conn();
+    conn.query_row(
+        r"SELECT (SELECT SUM(monto) FROM pagos_venta WHERE venta_id = v.id AND metodo_pago = 'CREDITO'),
+                 v.fecha_vencimiento_pago
+          FROM ventas v WHERE v.id = ? AND v.fecha_vencimiento_pago IS NOT NULL",
+        [venta_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .ok()
+}
+
+// 🆕 Redondeo del efectivo a S/ 0.10 (0 si no hubo)
+fn obtener_redondeo_venta(db: &DatabasePool, venta_id: i32) -> f64 {
+    let conn = db.get_conn();
+    conn.query_row(
+        "SELECT COALESCE(redondeo, 0) FROM ventas WHERE id = ?",
+        [venta_id],
+        |row| row.get(0),
+    )
+    .unwrap_or(0.0)
+}
+
+// 🆕 Nombre, dirección y teléfono de la tienda para documentos que no vienen
+// del POS (nota de crédito, cotización)
+fn encabezado_tienda(db: &DatabasePool, ancho: usize) -> Result<String, String> {
+    let conn = db.get_conn();
+    let (nombre, direccion, telefono): (String, Option<String>, Option<String>) = conn
+        .query_row(
+            "SELECT nombre_tienda, direccion, telefono FROM configuracion_tienda LIMIT 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|e| format!("Error al obtener configuración: {}", e))?;
+
+    let mut texto = String::new();
+    texto.push_str(&"=".repeat(ancho));
+    texto.push('\n');
+    texto.push_str(&centrar(&nombre, ancho));
+    texto.push('\n');
+    if let Some(dir) = direccion.filter(|d| !d.is_empty()) {
+        texto.push_str(&centrar(&dir, ancho));
+        texto.push('\n');
+    }
+    if let Some(tel) = telefono.filter(|t| !t.is_empty()) {
+        texto.push_str(&centrar(&format!("Tel: {}", tel), ancho));
+        texto.push('\n');
+    }
+    texto.push_str(&"=".repeat(ancho));
+    texto.push('\n');
+    Ok(texto)
+}
+
 fn centrar(texto: &str, ancho: usize) -> String {
     if texto.len() >= ancho {
         return texto.to_string();
@@ -80,6 +149,42 @@ fn enviar_lento(stream: &mut TcpStream, texto: &str, es_matricial: bool) -> Resu
 
     Ok(())
 }
+// 🆕 Comprobante de la venta para el encabezado del ticket
+struct ComprobanteTicket {
+    tipo_comprobante: String,
+    numero: Option<String>,
+    cliente: Option<(String, String, String)>, // (tipo doc, número, nombre)
+    hash: Option<String>,
+}
+
+fn obtener_comprobante_ticket(db: &DatabasePool, venta_id: i32) -> Option<ComprobanteTicket> {
+    let conn = db.get_conn();
+    conn.query_row(
+        r"SELECT COALESCE(v.tipo_comprobante, 'NOTA_VENTA'),
+                 CASE WHEN v.serie IS NOT NULL THEN v.serie || '-' || printf('%08d', v.correlativo) END,
+                 c.tipo_documento, c.numero_documento, c.nombre,
+                 (SELECT hash FROM comprobantes_electronicos WHERE venta_id = v.id AND nota_credito_id IS NULL)
+          FROM ventas v
+          LEFT JOIN clientes c ON c.id = v.cliente_id
+          WHERE v.id = ?",
+        [venta_id],
+        |row| {
+            let tipo_doc: Option<String> = row.get(2)?;
+            let numero_doc: Option<String> = row.get(3)?;
+            let nombre: Option<String> = row.get(4)?;
+            Ok(ComprobanteTicket {
+                tipo_comprobante: row.get(0)?,
+                numero: row.get(1)?,
+                cliente: match (tipo_doc, numero_doc, nombre) {
+                    (Some(t), Some(n), Some(c)) => Some((t, n, c)),
+                    _ => None,
+                },
+                hash: row.get(5)?,
+            })
+        },
+    )
+    .ok()
+}
 
 #[tauri::command]
 pub fn imprimir_boleta(
@@ -125,8 +230,29 @@ pub fn imprimir_boleta(
     texto.push_str(&"=".repeat(ancho));
     texto.push('\n');
 
-    if let Some(num) = &datos.numero_boleta {
-        texto.push_str(&format!("Boleta N: {}\n", num));
+    // 🆕 Tipo y número del comprobante guardados en la venta
+    let comprobante = datos.venta_id.and_then(|id| obtener_comprobante_ticket(&db, id));
+    match comprobante.as_ref().and_then(|c| c.numero.as_ref().map(|n| (c, n))) {
+        Some((c, numero)) => {
+            let titulo = match c.tipo_comprobante.as_str() {
+                "BOLETA" => "BOLETA DE VENTA ELECTRONICA",
+                "FACTURA" => "FACTURA ELECTRONICA",
+                _ => "NOTA DE VENTA",
+            };
+            texto.push_str(&centrar(titulo, ancho));
+            texto.push('\n');
+            texto.push_str(&centrar(numero, ancho));
+            texto.push('\n');
+            if let Some((tipo_doc, numero_doc, nombre)) = &c.cliente {
+                texto.push_str(&format!("Cliente: {}\n", nombre));
+                texto.push_str(&format!("{}: {}\n", tipo_doc, numero_doc));
+            }
+        }
+        None => {
+            if let Some(num) = &datos.numero_boleta {
+                texto.push_str(&format!("Boleta N: {}\n", num));
+            }
+        }
     }
     if let Some(cajero) = &datos.cajero {
         texto.push_str(&format!("Cajero: {}\n", cajero));
@@ -140,11 +266,7 @@ pub fn imprimir_boleta(
     // Items con alineacion
     for item in &datos.items {
         // Nombre del producto (truncar si es muy largo)
-        let nombre = if item.nombre.len() > ancho {
-            item.nombre[..ancho].to_string()
-        } else {
-            item.nombre.clone()
-        };
+        let nombre: String = item.nombre.chars().take(ancho).collect();
         texto.push_str(&format!("{}\n", nombre));
 
         let izq = format!("  {:.0} x S/.{:.2}", item.cantidad, item.precio_unitario);
@@ -156,9 +278,35 @@ pub fn imprimir_boleta(
     // Totales con alineacion
     texto.push_str(&"=".repeat(ancho));
     texto.push('\n');
+
+    // 🆕 Desglose de IGV (los precios lo incluyen)
+    if let Some((gravada, exonerada, inafecta, igv, tasa)) = datos.venta_id.and_then(|id| obtener_igv_venta(&db, id)) {
+        texto.push_str(&alinear_derecha("Op. Gravada:", &format!("S/.{:.2}", gravada), ancho));
+        texto.push('\n');
+        if exonerada > 0.0 {
+            texto.push_str(&alinear_derecha("Op. Exonerada:", &format!("S/.{:.2}", exonerada), ancho));
+            texto.push('\n');
+        }
+        if inafecta > 0.0 {
+            texto.push_str(&alinear_derecha("Op. Inafecta:", &format!("S/.{:.2}", inafecta), ancho));
+            texto.push('\n');
+        }
+        texto.push_str(&alinear_derecha(&format!("IGV ({:.0}%):", tasa), &format!("S/.{:.2}", igv), ancho));
+        texto.push('\n');
+    }
+
     texto.push_str(&alinear_derecha("TOTAL:", &format!("S/.{:.2}", datos.total), ancho));
     texto.push('\n');
 
+    // 🆕 Redondeo del efectivo (no circulan monedas de 1 y 5 céntimos)
+    let redondeo = datos.venta_id.map_or(0.0, |id| obtener_redondeo_venta(&db, id));
+    if redondeo.abs() >= 0.005 {
+        texto.push_str(&alinear_derecha("Redondeo:", &format!("S/.{:.2}", redondeo), ancho));
+        texto.push('\n');
+        texto.push_str(&alinear_derecha("A pagar:", &format!("S/.{:.2}", datos.total + redondeo), ancho));
+        texto.push('\n');
+    }
+
     if let Some(efectivo) = datos.efectivo {
         texto.push_str(&alinear_derecha("Efectivo:", &format!("S/.{:.2}", efectivo), ancho));
         texto.push('\n');
@@ -167,9 +315,20 @@ pub fn imprimir_boleta(
         texto.push_str(&alinear_derecha("Cambio:", &format!("S/.{:.2}", cambio), ancho));
         texto.push('\n');
     }
+    // 🆕 Venta al crédito
+    if let Some((credito, vencimiento)) = datos.venta_id.and_then(|id| obtener_credito_venta(&db, id)) {
+        texto.push_str(&alinear_derecha("Al credito:", &format!("S/.{:.2}", credito), ancho));
+        texto.push('\n');
+        texto.push_str(&alinear_derecha("Vence:", &vencimiento, ancho));
+        texto.push('\n');
+    }
 
     texto.push_str(&"=".repeat(ancho));
     texto.push('\n');
+    // 🆕 Resumen (hash) de la firma del comprobante electrónico
+    if let Some(hash) = comprobante.as_ref().and_then(|c| c.hash.as_ref()) {
+        texto.push_str(&format!("Resumen: {}\n", hash));
+    }
     texto.push_str(&centrar("Gracias por su compra!", ancho));
     texto.push('\n');
     texto.push_str("\n\n\n");
@@ -179,6 +338,335 @@ pub fn imprimir_boleta(
     Ok("Boleta impresa correctamente".to_string())
 }
 
+// 🆕 Nota de crédito: todo sale de la base de datos
+#[tauri::command]
+pub fn imprimir_nota_credito(
+    db: tauri::State<DatabasePool>,
+    nota_credito_id: i32,
+) -> Result<String, String> {
+    let (ip, tipo, puerto) = obtener_config_impresora(&db);
+
+    if ip.is_empty() {
+        return Err("No hay IP de impresora configurada. Ve a Configuracion para agregarla.".to_string());
+    }
+
+    let (nota, hash) = {
+        let conn = db.get_conn();
+        let nota = obtener_nota_credito_por_id(&conn, nota_credito_id)?;
+        let hash: Option<String> = conn
+            .query_row(
+                "SELECT hash FROM comprobantes_electronicos WHERE nota_credito_id = ?",
+                [nota_credito_id],
+                |row| row.get(0),
+            )
+            .unwrap_or(None);
+        (nota, hash)
+    };
+    let comprobante = obtener_comprobante_ticket(&db, nota.venta_id);
+
+    let direccion = format!("{}:{}", ip, puerto);
+    let es_matricial = tipo.to_uppercase() == "MATRICIAL";
+    let ancho = if es_matricial { 32 } else { 42 };
+
+    let mut texto = encabezado_tienda(&db, ancho)?;
+
+    let mut stream = TcpStream::connect(&direccion)
+        .map_err(|e| format!("No se pudo conectar a la impresora {}: {}", direccion, e))?;
+
+    stream.set_write_timeout(Some(Duration::from_secs(10))).ok();
+
+    texto.push_str(&centrar("NOTA DE CREDITO ELECTRONICA", ancho));
+    texto.push('\n');
+    texto.push_str(&centrar(&nota.numero, ancho));
+    texto.push('\n');
+    texto.push_str(&format!("Fecha: {}\n", nota.fecha_emision));
+    if let Some((tipo_doc, numero_doc, nombre)) = comprobante.as_ref().and_then(|c| c.cliente.as_ref()) {
+        texto.push_str(&format!("Cliente: {}\n", nombre));
+        texto.push_str(&format!("{}: {}\n", tipo_doc, numero_doc));
+    }
+    let tipo_ref = if nota.tipo_documento_ref == "FACTURA" { "Factura" } else { "Boleta" };
+    texto.push_str(&format!("Doc. afectado: {} {}\n", tipo_ref, nota.numero_ref));
+    texto.push_str(&format!("Motivo: {} - {}\n", nota.codigo_motivo, nota.descripcion_motivo));
+    if !nota.sustento.is_empty() {
+        texto.push_str(&format!("Sustento: {}\n", nota.sustento));
+    }
+    texto.push_str(&"-".repeat(ancho));
+    texto.push('\n');
+
+    for item in &nota.detalles {
+        // Por caracteres: cortar bytes parte la Ñ o las tildes
+        let nombre: String = item.descripcion.chars().take(ancho).collect();
+        texto.push_str(&format!("{}\n", nombre));
+
+        let izq = format!("  {} x S/.{:.2}", item.cantidad, item.total_linea / item.cantidad.max(1) as f64);
+        let der = format!("S/.{:.2}", item.total_linea);
+        texto.push_str(&alinear_derecha(&izq, &der, ancho));
+        texto.push('\n');
+    }
+
+    texto.push_str(&"=".repeat(ancho));
+    texto.push('\n');
+    texto.push_str(&alinear_derecha("Op. Gravada:", &format!("S/.{:.2}", nota.op_gravada), ancho));
+    texto.push('\n');
+    if nota.op_exonerada > 0.0 {
+        texto.push_str(&alinear_derecha("Op. Exonerada:", &format!("S/.{:.2}", nota.op_exonerada), ancho));
+        texto.push('\n');
+    }
+    if nota.op_inafecta > 0.0 {
+        texto.push_str(&alinear_derecha("Op. Inafecta:", &format!("S/.{:.2}", nota.op_inafecta), ancho));
+        texto.push('\n');
+    }
+    texto.push_str(&alinear_derecha(&format!("IGV ({:.0}%):", nota.igv_porcentaje), &format!("S/.{:.2}", nota.igv), ancho));
+    texto.push('\n');
+    texto.push_str(&alinear_derecha("TOTAL:", &format!("S/.{:.2}", nota.total), ancho));
+    texto.push('\n');
+    texto.push_str(&"=".repeat(ancho));
+    texto.push('\n');
+    if let Some(hash) = hash {
+        texto.push_str(&format!("Resumen: {}\n", hash));
+    }
+    texto.push_str("\n\n\n");
+
+    enviar_lento(&mut stream, &texto, es_matricial)?;
+
+    Ok("Nota de crédito impresa correctamente".to_string())
+}
+
+// 🆕 Reimpresión de un comprobante: se arma desde la venta guardada (no desde
+// el POS) y sale marcado COPIA. Cada reimpresión queda en auditoría.
+#[tauri::command]
+pub fn reimprimir_boleta(
+    db: tauri::State<DatabasePool>,
+    venta_id: i32,
+    usuario_id: i32,
+) -> Result<String, String> {
+    let (ip, tipo, puerto) = obtener_config_impresora(&db);
+
+    if ip.is_empty() {
+        return Err("No hay IP de impresora configurada. Ve a Configuracion para agregarla.".to_string());
+    }
+
+    let venta = {
+        let conn = db.get_conn();
+        venta_detalle_por_id(&conn, venta_id)?
+    };
+    let comprobante = obtener_comprobante_ticket(&db, venta_id);
+    let copia = venta.reimpresiones + 1;
+
+    let direccion = format!("{}:{}", ip, puerto);
+    let es_matricial = tipo.to_uppercase() == "MATRICIAL";
+    let ancho = if es_matricial { 32 } else { 42 };
+
+    let mut texto = encabezado_tienda(&db, ancho)?;
+
+    let mut stream = TcpStream::connect(&direccion)
+        .map_err(|e| format!("No se pudo conectar a la impresora {}: {}", direccion, e))?;
+
+    stream.set_write_timeout(Some(Duration::from_secs(10))).ok();
+
+    texto.push_str(&centrar(&format!("*** COPIA {} ***", copia), ancho));
+    texto.push('\n');
+    let titulo = match venta.tipo_comprobante.as_str() {
+        "BOLETA" => "BOLETA DE VENTA ELECTRONICA",
+        "FACTURA" => "FACTURA ELECTRONICA",
+        _ => "NOTA DE VENTA",
+    };
+    texto.push_str(&centrar(titulo, ancho));
+    texto.push('\n');
+    texto.push_str(&centrar(venta.numero_comprobante.as_deref().unwrap_or(&venta.folio), ancho));
+    texto.push('\n');
+    if venta.estado == "CANCELADA" {
+        texto.push_str(&centrar("*** ANULADA ***", ancho));
+        texto.push('\n');
+    }
+    if let (Some(nombre), Some(documento)) = (&venta.cliente_nombre, &venta.cliente_documento) {
+        texto.push_str(&format!("Cliente: {}\n", nombre));
+        texto.push_str(&format!("{}\n", documento));
+    }
+    texto.push_str(&format!("Cajero: {}\n", venta.cajero));
+    if let Some(vendedor) = &venta.vendedor {
+        texto.push_str(&format!("Vendedor: {}\n", vendedor));
+    }
+    texto.push_str(&format!("Fecha: {}\n", venta.fecha_hora));
+    texto.push_str(&"-".repeat(ancho));
+    texto.push('\n');
+
+    for item in &venta.lineas {
+        let descripcion = match &item.talla {
+            Some(t) if !t.is_empty() => format!("{} - Talla {}", item.nombre, t),
+            _ => item.nombre.clone(),
+        };
+        let nombre: String = descripcion.chars().take(ancho).collect();
+        texto.push_str(&format!("{}\n", nombre));
+
+        let izq = format!("  {} x S/.{:.2}", item.cantidad, item.precio_unitario);
+        texto.push_str(&alinear_derecha(&izq, &format!("S/.{:.2}", item.subtotal), ancho));
+        texto.push('\n');
+        if item.descuento_linea > 0.0 {
+            texto.push_str(&alinear_derecha("  Descuento", &format!("-S/.{:.2}", item.descuento_linea), ancho));
+            texto.push('\n');
+        }
+    }
+
+    texto.push_str(&"=".repeat(ancho));
+    texto.push('\n');
+    texto.push_str(&alinear_derecha("Op. Gravada:", &format!("S/.{:.2}", venta.op_gravada), ancho));
+    texto.push('\n');
+    if venta.op_exonerada > 0.0 {
+        texto.push_str(&alinear_derecha("Op. Exonerada:", &format!("S/.{:.2}", venta.op_exonerada), ancho));
+        texto.push('\n');
+    }
+    if venta.op_inafecta > 0.0 {
+        texto.push_str(&alinear_derecha("Op. Inafecta:", &format!("S/.{:.2}", venta.op_inafecta), ancho));
+        texto.push('\n');
+    }
+    texto.push_str(&alinear_derecha(&format!("IGV ({:.0}%):", venta.igv_porcentaje), &format!("S/.{:.2}", venta.igv), ancho));
+    texto.push('\n');
+    texto.push_str(&alinear_derecha("TOTAL:", &format!("S/.{:.2}", venta.total), ancho));
+    texto.push('\n');
+    if venta.redondeo.abs() >= 0.005 {
+        texto.push_str(&alinear_derecha("Redondeo:", &format!("S/.{:.2}", venta.redondeo), ancho));
+        texto.push('\n');
+        texto.push_str(&alinear_derecha("A pagar:", &format!("S/.{:.2}", venta.total + venta.redondeo), ancho));
+        texto.push('\n');
+    }
+
+    for pago in &venta.pagos {
+        let metodo = match pago.metodo_pago.as_str() {
+            "EFECTIVO" => "Efectivo:",
+            "TARJETA" => "Tarjeta:",
+            "TRANSFERENCIA" => "Transferencia:",
+            "PUNTOS" => "Puntos:",
+            "VALE" => "Vale:",
+            "CREDITO" => "Al credito:",
+            "CAMBIO" => "Cambio de prenda:",
+            otro => otro,
+        };
+        let monto = pago.monto_recibido.unwrap_or(pago.monto);
+        texto.push_str(&alinear_derecha(metodo, &format!("S/.{:.2}", monto), ancho));
+        texto.push('\n');
+    }
+    if let Some(cambio) = venta.cambio.filter(|c| *c > 0.0) {
+        texto.push_str(&alinear_derecha("Cambio:", &format!("S/.{:.2}", cambio), ancho));
+        texto.push('\n');
+    }
+
+    texto.push_str(&"=".repeat(ancho));
+    texto.push('\n');
+    if let Some(hash) = comprobante.as_ref().and_then(|c| c.hash.as_ref()) {
+        texto.push_str(&format!("Resumen: {}\n", hash));
+    }
+    texto.push_str(&centrar(&format!("Reimpreso: {}", chrono::Local::now().format("%d/%m/%Y %H:%M")), ancho));
+    texto.push('\n');
+    texto.push_str("\n\n\n");
+
+    enviar_lento(&mut stream, &texto, es_matricial)?;
+
+    // Solo cuenta si llegó a la impresora
+    {
+        let conn = db.get_conn();
+        conn.execute(
+            r"INSERT INTO auditoria (usuario_id, accion, tabla_afectada, registro_id, valores_nuevos)
+              VALUES (?, 'REIMPRIMIR_BOLETA', 'ventas', ?, ?)",
+            rusqlite::params![usuario_id, venta_id, serde_json::json!({ "copia": copia }).to_string()],
+        )
+        .map_err(|e| format!("Comprobante reimpreso, pero no se pudo registrar en auditoría: {}", e))?;
+    }
+
+    Ok(format!("Copia {} de {} impresa correctamente", copia, venta.folio))
+}
+
+// 🆕 Cotización para entregar al cliente
+#[tauri::command]
+pub fn imprimir_cotizacion(
+    db: tauri::State<DatabasePool>,
+    cotizacion_id: i32,
+) -> Result<String, String> {
+    let (ip, tipo, puerto) = obtener_config_impresora(&db);
+
+    if ip.is_empty() {
+        return Err("No hay IP de impresora configurada. Ve a Configuracion para agregarla.".to_string());
+    }
+
+    let cotizacion = {
+        let conn = db.get_conn();
+        obtener_cotizacion_por_id(&conn, cotizacion_id)?
+    };
+
+    let direccion = format!("{}:{}", ip, puerto);
+    let es_matricial = tipo.to_uppercase() == "MATRICIAL";
+    let ancho = if es_matricial { 32 } else { 42 };
+
+    let mut texto = encabezado_tienda(&db, ancho)?;
+
+    let mut stream = TcpStream::connect(&direccion)
+        .map_err(|e| format!("No se pudo conectar a la impresora {}: {}", direccion, e))?;
+
+    stream.set_write_timeout(Some(Duration::from_secs(10))).ok();
+
+    texto.push_str(&centrar("COTIZACION", ancho));
+    texto.push('\n');
+    texto.push_str(&centrar(&cotizacion.folio, ancho));
+    texto.push('\n');
+    texto.push_str(&format!("Fecha: {}\n", cotizacion.fecha_emision));
+    texto.push_str(&format!("Valida hasta: {}\n", cotizacion.fecha_validez));
+    if let Some(nombre) = &cotizacion.cliente_nombre {
+        texto.push_str(&format!("Cliente: {}\n", nombre));
+    }
+    if let Some(documento) = &cotizacion.cliente_documento {
+        texto.push_str(&format!("{}\n", documento));
+    }
+    if let Some(contacto) = &cotizacion.contacto {
+        texto.push_str(&format!("Atencion: {}\n", contacto));
+    }
+    texto.push_str(&"-".repeat(ancho));
+    texto.push('\n');
+
+    for item in &cotizacion.productos {
+        let descripcion = match &item.talla {
+            Some(t) if !t.is_empty() => format!("{} - Talla {}", item.nombre, t),
+            _ => item.nombre.clone(),
+        };
+        let nombre: String = descripcion.chars().take(ancho).collect();
+        texto.push_str(&format!("{}\n", nombre));
+
+        let descuento = item.descuento_porcentaje.unwrap_or(0.0);
+        let total_linea = item.precio * item.cantidad as f64 * (1.0 - descuento / 100.0);
+        let izq = if descuento > 0.0 {
+            format!("  {} x S/.{:.2} -{:.0}%", item.cantidad, item.precio, descuento)
+        } else {
+            format!("  {} x S/.{:.2}", item.cantidad, item.precio)
+        };
+        texto.push_str(&alinear_derecha(&izq, &format!("S/.{:.2}", total_linea), ancho));
+        texto.push('\n');
+    }
+
+    texto.push_str(&"=".repeat(ancho));
+    texto.push('\n');
+    if cotizacion.descuento > 0.0 {
+        texto.push_str(&alinear_derecha("Subtotal:", &format!("S/.{:.2}", cotizacion.subtotal), ancho));
+        texto.push('\n');
+        texto.push_str(&alinear_derecha("Descuento:", &format!("-S/.{:.2}", cotizacion.descuento), ancho));
+        texto.push('\n');
+    }
+    texto.push_str(&alinear_derecha("TOTAL:", &format!("S/.{:.2}", cotizacion.total), ancho));
+    texto.push('\n');
+    texto.push_str(&"=".repeat(ancho));
+    texto.push('\n');
+    if let Some(notas) = &cotizacion.notas {
+        texto.push_str(&format!("{}\n", notas));
+    }
+    texto.push_str(&centrar("Precios incluyen IGV", ancho));
+    texto.push('\n');
+    texto.push_str(&centrar("Sujeto a disponibilidad de stock", ancho));
+    texto.push('\n');
+    texto.push_str("\n\n\n");
+
+    enviar_lento(&mut stream, &texto, es_matricial)?;
+
+    Ok("Cotización impresa correctamente".to_string())
+}
+
 #[tauri::command]
 pub fn probar_impresora(
     db: tauri::State<DatabasePool>,
@@ -0,0 +1,65 @@
+// commands/impuestos.rs
+// IGV: los precios de venta ya incluyen el impuesto, así que de cada monto
+// se separa el valor de venta (base) y el IGV según la afectación del producto.
+
+use crate::commands::promociones::redondear;
+
+// Totales por tipo de operación, como van en la boleta
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TotalesIgv {
+    pub op_gravada: f64,
+    pub op_exonerada: f64,
+    pub op_inafecta: f64,
+    pub igv: f64,
+}
+
+impl TotalesIgv {
+    // Suma una línea ya desglosada
+    pub fn agregar(&mut self, afectacion: &str, valor_venta: f64, igv: f64) {
+        match afectacion {
+            "EXONERADO" => self.op_exonerada += valor_venta,
+            "INAFECTO" => self.op_inafecta += valor_venta,
+            _ => self.op_gravada += valor_venta,
+        }
+        self.igv += igv;
+    }
+
+    pub fn redondeados(self) -> Self {
+        TotalesIgv {
+            op_gravada: redondear(self.op_gravada),
+            op_exonerada: redondear(self.op_exonerada),
+            op_inafecta: redondear(self.op_inafecta),
+            igv: redondear(self.igv),
+        }
+    }
+}
+
+// Tasa configurada en la tienda (porcentaje, ej. 18)
+pub fn tasa_igv(conn: &rusqlite::Connection) -> Result<f64, String> {
+    conn.query_row(
+        "SELECT COALESCE(iva_porcentaje, 0) FROM configuracion_tienda LIMIT 1",
+        [],
+        |row| row.get(0),
+    )
+    .map_err(|e| format!("Error al obtener tasa de IGV: {}", e))
+}
+
+// Afectación del producto (GRAVADO si no está definida)
+pub fn afectacion_producto(conn: &rusqlite::Connection, producto_id: i32) -> Result<String, String> {
+    conn.query_row(
+        "SELECT COALESCE(afectacion_igv, 'GRAVADO') FROM productos WHERE id = ?",
+        [producto_id],
+        |row| row.get(0),
+    )
+    .map_err(|e| format!("Error al obtener afectación de IGV: {}", e))
+}
+
+// Separa un monto con IGV incluido en (valor de venta, IGV).
+// Exonerado e inafecto no llevan IGV.
+pub fn desglosar_igv(monto: f64, afectacion: &str, tasa: f64) -> (f64, f64) {
+    if afectacion != "GRAVADO" || tasa <= 0.0 {
+        return (redondear(monto), 0.0);
+    }
+    let valor_venta = redondear(monto / (1.0 + tasa / 100.0));
+    (valor_venta, redondear(monto - valor_venta))
+}
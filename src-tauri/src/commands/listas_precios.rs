@@ -0,0 +1,453 @@
+// commands/listas_precios.rs
+// Listas de precios (minorista, mayorista, ...) y escalas por cantidad.
+//
+// Orden para resolver el precio de una línea (procesar_venta lo hace en el servidor):
+//  - Lista elegida en el POS; si no, la del cliente; si no, la predeterminada.
+//  - Dentro de la lista rige la fila con la mayor cantidad_minima alcanzada por
+//    las unidades del producto en el carrito (todas las tallas suman), y a igual
+//    escala la de la talla antes que la del producto.
+//  - Si la lista no tiene precio para el producto se busca en la predeterminada,
+//    y al final se usa productos.precio.
+
+use crate::database::DatabasePool;
+use crate::commands::ventas::ProductoVenta;
+use crate::models::lista_precio::*;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// Carrito con los precios de la lista aplicados (para mostrar en el POS)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CarritoConPrecios {
+    pub lista_precio_id: Option<i32>,
+    pub lista_nombre: Option<String>,
+    pub productos: Vec<ProductoVenta>,
+}
+
+// =====================================================
+// COMANDO: Listar listas de precios
+// =====================================================
+#[tauri::command]
+pub fn obtener_listas_precios(db: tauri::State<'_, DatabasePool>) -> Result<Vec<ListaPrecios>, String> {
+    let conn = db.get_conn();
+
+    let mut stmt = conn
+        .prepare(
+            r"SELECT l.id, l.nombre, l.descripcion, l.predeterminada, l.activo,
+                     (SELECT COUNT(*) FROM precios_lista p WHERE p.lista_id = l.id),
+                     (SELECT COUNT(*) FROM clientes c WHERE c.lista_precio_id = l.id)
+              FROM listas_precios l
+              ORDER BY l.predeterminada DESC, l.nombre",
+        )
+        .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+
+    let listas = stmt
+        .query_map([], |row| {
+            Ok(ListaPrecios {
+                id: row.get(0)?,
+                nombre: row.get(1)?,
+                descripcion: row.get(2)?,
+                predeterminada: row.get::<_, i32>(3)? == 1,
+                activo: row.get::<_, i32>(4)? == 1,
+                precios: row.get(5)?,
+                clientes: row.get(6)?,
+            })
+        })
+        .map_err(|e| format!("Error al obtener listas de precios: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(listas)
+}
+
+// =====================================================
+// COMANDO: Agregar lista de precios
+// =====================================================
+#[tauri::command]
+pub fn agregar_lista_precios(
+    db: tauri::State<'_, DatabasePool>,
+    lista: ListaPreciosNueva,
+) -> Result<String, String> {
+    let conn = db.get_conn();
+
+    validar_lista(&lista)?;
+    let predeterminada = lista.predeterminada.unwrap_or(false);
+
+    conn.execute("BEGIN TRANSACTION", [])
+        .map_err(|e| format!("Error al iniciar transacción: {}", e))?;
+
+    let rollback = |conn: &rusqlite::Connection, msg: String| -> String {
+        let _ = conn.execute("ROLLBACK", []);
+        msg
+    };
+
+    // Solo una lista predeterminada
+    if predeterminada {
+        if let Err(e) = conn.execute("UPDATE listas_precios SET predeterminada = 0", []) {
+            return Err(rollback(&conn, format!("Error al actualizar listas: {}", e)));
+        }
+    }
+
+    if let Err(e) = conn.execute(
+        "INSERT INTO listas_precios (nombre, descripcion, predeterminada, activo) VALUES (?, ?, ?, ?)",
+        params![
+            lista.nombre.trim(),
+            &lista.descripcion,
+            predeterminada as i32,
+            lista.activo.unwrap_or(true) as i32,
+        ],
+    ) {
+        return Err(rollback(
+            &conn,
+            if e.to_string().contains("UNIQUE") {
+                format!("❌ Ya existe una lista de precios llamada {}", lista.nombre.trim())
+            } else {
+                format!("Error al agregar lista de precios: {}", e)
+            },
+        ));
+    }
+
+    conn.execute("COMMIT", [])
+        .map_err(|e| format!("Error al confirmar: {}", e))?;
+
+    Ok("✅ Lista de precios agregada exitosamente".to_string())
+}
+
+// =====================================================
+// COMANDO: Actualizar lista de precios (también para desactivarla)
+// =====================================================
+#[tauri::command]
+pub fn actualizar_lista_precios(
+    db: tauri::State<'_, DatabasePool>,
+    lista_id: i32,
+    lista: ListaPreciosNueva,
+) -> Result<String, String> {
+    let conn = db.get_conn();
+
+    validar_lista(&lista)?;
+    let activo = lista.activo.unwrap_or(true);
+
+    let era_predeterminada: bool = conn
+        .query_row(
+            "SELECT predeterminada FROM listas_precios WHERE id = ?",
+            params![lista_id],
+            |row| Ok(row.get::<_, i32>(0)? == 1),
+        )
+        .optional()
+        .map_err(|e| format!("Error al obtener lista de precios: {}", e))?
+        .ok_or("❌ Lista de precios no encontrada")?;
+
+    // Sin valor se mantiene; la predeterminada no se puede desactivar
+    let predeterminada = lista.predeterminada.unwrap_or(era_predeterminada);
+    if predeterminada && !activo {
+        return Err("❌ La lista predeterminada no se puede desactivar".to_string());
+    }
+
+    conn.execute("BEGIN TRANSACTION", [])
+        .map_err(|e| format!("Error al iniciar transacción: {}", e))?;
+
+    let rollback = |conn: &rusqlite::Connection, msg: String| -> String {
+        let _ = conn.execute("ROLLBACK", []);
+        msg
+    };
+
+    if predeterminada && !era_predeterminada {
+        if let Err(e) = conn.execute("UPDATE listas_precios SET predeterminada = 0", []) {
+            return Err(rollback(&conn, format!("Error al actualizar listas: {}", e)));
+        }
+    }
+
+    if let Err(e) = conn.execute(
+        "UPDATE listas_precios SET nombre = ?, descripcion = ?, predeterminada = ?, activo = ? WHERE id = ?",
+        params![
+            lista.nombre.trim(),
+            &lista.descripcion,
+            predeterminada as i32,
+            activo as i32,
+            lista_id,
+        ],
+    ) {
+        return Err(rollback(
+            &conn,
+            if e.to_string().contains("UNIQUE") {
+                format!("❌ Ya existe una lista de precios llamada {}", lista.nombre.trim())
+            } else {
+                format!("Error al actualizar lista de precios: {}", e)
+            },
+        ));
+    }
+
+    conn.execute("COMMIT", [])
+        .map_err(|e| format!("Error al confirmar: {}", e))?;
+
+    Ok("✅ Lista de precios actualizada exitosamente".to_string())
+}
+
+// =====================================================
+// COMANDO: Precios de una lista (con escalas)
+// =====================================================
+#[tauri::command]
+pub fn obtener_precios_lista(
+    db: tauri::State<'_, DatabasePool>,
+    lista_id: i32,
+) -> Result<Vec<PrecioLista>, String> {
+    let conn = db.get_conn();
+
+    let mut stmt = conn
+        .prepare(
+            r"SELECT pl.id, pl.lista_id, pl.producto_id, p.nombre, pl.variante_id, v.talla,
+                     pl.cantidad_minima, pl.precio, p.precio
+              FROM precios_lista pl
+              JOIN productos p ON p.id = pl.producto_id
+              LEFT JOIN producto_variantes v ON v.id = pl.variante_id
+              WHERE pl.lista_id = ?
+              ORDER BY p.nombre, v.talla, pl.cantidad_minima",
+        )
+        .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+
+    let precios = stmt
+        .query_map(params![lista_id], |row| {
+            Ok(PrecioLista {
+                id: row.get(0)?,
+                lista_id: row.get(1)?,
+                producto_id: row.get(2)?,
+                producto_nombre: row.get(3)?,
+                variante_id: row.get(4)?,
+                talla: row.get(5)?,
+                cantidad_minima: row.get(6)?,
+                precio: row.get(7)?,
+                precio_base: row.get(8)?,
+            })
+        })
+        .map_err(|e| format!("Error al obtener precios de la lista: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(precios)
+}
+
+// =====================================================
+// COMANDO: Guardar precio en la lista (crea o reemplaza la escala)
+// =====================================================
+#[tauri::command]
+pub fn guardar_precio_lista(
+    db: tauri::State<'_, DatabasePool>,
+    lista_id: i32,
+    precio: PrecioListaNuevo,
+) -> Result<String, String> {
+    let conn = db.get_conn();
+
+    let cantidad_minima = precio.cantidad_minima.unwrap_or(1);
+    if cantidad_minima < 1 {
+        return Err("❌ La cantidad mínima debe ser al menos 1".to_string());
+    }
+    if precio.precio < 0.0 {
+        return Err("❌ El precio no puede ser negativo".to_string());
+    }
+
+    let existe_lista: bool = conn
+        .query_row("SELECT 1 FROM listas_precios WHERE id = ?", params![lista_id], |_| Ok(true))
+        .optional()
+        .map_err(|e| format!("Error al obtener lista de precios: {}", e))?
+        .unwrap_or(false);
+    if !existe_lista {
+        return Err("❌ Lista de precios no encontrada".to_string());
+    }
+
+    // La talla debe ser del producto
+    let producto_valido: bool = match precio.variante_id {
+        Some(variante_id) => conn.query_row(
+            "SELECT 1 FROM producto_variantes WHERE id = ? AND producto_id = ?",
+            params![variante_id, precio.producto_id],
+            |_| Ok(true),
+        ),
+        None => conn.query_row("SELECT 1 FROM productos WHERE id = ?", params![precio.producto_id], |_| Ok(true)),
+    }
+    .optional()
+    .map_err(|e| format!("Error al verificar producto: {}", e))?
+    .unwrap_or(false);
+    if !producto_valido {
+        return Err("❌ Producto o talla no encontrado".to_string());
+    }
+
+    let filas = conn
+        .execute(
+            r"UPDATE precios_lista
+              SET precio = ?, fecha_actualizacion = datetime('now', 'localtime')
+              WHERE lista_id = ? AND producto_id = ? AND COALESCE(variante_id, 0) = COALESCE(?, 0)
+                AND cantidad_minima = ?",
+            params![precio.precio, lista_id, precio.producto_id, precio.variante_id, cantidad_minima],
+        )
+        .map_err(|e| format!("Error al actualizar precio: {}", e))?;
+
+    if filas == 0 {
+        conn.execute(
+            r"INSERT INTO precios_lista (lista_id, producto_id, variante_id, cantidad_minima, precio)
+              VALUES (?, ?, ?, ?, ?)",
+            params![lista_id, precio.producto_id, precio.variante_id, cantidad_minima, precio.precio],
+        )
+        .map_err(|e| format!("Error al guardar precio: {}", e))?;
+    }
+
+    Ok("✅ Precio guardado exitosamente".to_string())
+}
+
+// =====================================================
+// COMANDO: Quitar precio de la lista
+// =====================================================
+#[tauri::command]
+pub fn eliminar_precio_lista(
+    db: tauri::State<'_, DatabasePool>,
+    precio_id: i32,
+) -> Result<String, String> {
+    let conn = db.get_conn();
+
+    let filas = conn
+        .execute("DELETE FROM precios_lista WHERE id = ?", params![precio_id])
+        .map_err(|e| format!("Error al eliminar precio: {}", e))?;
+
+    if filas == 0 {
+        return Err("❌ Precio no encontrado".to_string());
+    }
+
+    Ok("✅ Precio eliminado de la lista".to_string())
+}
+
+// =====================================================
+// COMANDO: Aplicar la lista al carrito del POS
+// Mismo cálculo que procesar_venta, para mostrar los precios antes de cobrar.
+// =====================================================
+#[tauri::command]
+pub fn resolver_precios_carrito(
+    db: tauri::State<'_, DatabasePool>,
+    mut productos: Vec<ProductoVenta>,
+    cliente_id: Option<i32>,
+    lista_precio_id: Option<i32>,
+) -> Result<CarritoConPrecios, String> {
+    let conn = db.get_conn();
+
+    let lista = lista_precios_venta(&conn, lista_precio_id, cliente_id)?;
+    aplicar_lista_precios(&conn, lista.as_ref().map(|(id, _)| *id), &mut productos)?;
+
+    Ok(CarritoConPrecios {
+        lista_precio_id: lista.as_ref().map(|(id, _)| *id),
+        lista_nombre: lista.map(|(_, nombre)| nombre),
+        productos,
+    })
+}
+
+// =====================================================
+// FUNCIONES AUXILIARES
+// =====================================================
+
+fn validar_lista(lista: &ListaPreciosNueva) -> Result<(), String> {
+    if lista.nombre.trim().is_empty() {
+        return Err("❌ El nombre de la lista es obligatorio".to_string());
+    }
+    Ok(())
+}
+
+// Lista con la que se cobra: la elegida en el POS, la del cliente o la
+// predeterminada. Devuelve (id, nombre) o None si no hay ninguna.
+pub fn lista_precios_venta(
+    conn: &rusqlite::Connection,
+    lista_precio_id: Option<i32>,
+    cliente_id: Option<i32>,
+) -> Result<Option<(i32, String)>, String> {
+    if let Some(id) = lista_precio_id {
+        let lista: Option<(String, bool)> = conn
+            .query_row(
+                "SELECT nombre, activo FROM listas_precios WHERE id = ?",
+                params![id],
+                |row| Ok((row.get(0)?, row.get::<_, i32>(1)? == 1)),
+            )
+            .optional()
+            .map_err(|e| format!("Error al obtener lista de precios: {}", e))?;
+        return match lista {
+            Some((nombre, true)) => Ok(Some((id, nombre))),
+            Some((nombre, false)) => Err(format!("❌ La lista de precios {} está inactiva", nombre)),
+            None => Err("❌ Lista de precios no encontrada".to_string()),
+        };
+    }
+
+    if let Some(id) = cliente_id {
+        let lista: Option<(i32, String)> = conn
+            .query_row(
+                r"SELECT l.id, l.nombre FROM clientes c
+                  JOIN listas_precios l ON l.id = c.lista_precio_id
+                  WHERE c.id = ? AND l.activo = 1",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+            .map_err(|e| format!("Error al obtener lista del cliente: {}", e))?;
+        if lista.is_some() {
+            return Ok(lista);
+        }
+    }
+
+    conn.query_row(
+        "SELECT id, nombre FROM listas_precios WHERE predeterminada = 1 AND activo = 1 LIMIT 1",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .optional()
+    .map_err(|e| format!("Error al obtener lista predeterminada: {}", e))
+}
+
+// Reemplaza el precio de cada línea por el de la lista. Las escalas se
+// alcanzan con las unidades del producto en todo el carrito (todas las tallas).
+pub fn aplicar_lista_precios(
+    conn: &rusqlite::Connection,
+    lista_id: Option<i32>,
+    productos: &mut [ProductoVenta],
+) -> Result<(), String> {
+    let mut unidades: HashMap<i32, i32> = HashMap::new();
+    for p in productos.iter() {
+        *unidades.entry(p.id).or_insert(0) += p.cantidad;
+    }
+
+    for p in productos.iter_mut() {
+        p.precio = precio_efectivo(conn, lista_id, p.id, p.variante_id, unidades[&p.id])?;
+    }
+    Ok(())
+}
+
+// Precio de un producto (o talla) para una cantidad, según la lista
+pub fn precio_efectivo(
+    conn: &rusqlite::Connection,
+    lista_id: Option<i32>,
+    producto_id: i32,
+    variante_id: Option<i32>,
+    cantidad: i32,
+) -> Result<f64, String> {
+    let de_lista: Option<f64> = conn
+        .query_row(
+            r"SELECT pl.precio
+              FROM precios_lista pl
+              JOIN listas_precios l ON l.id = pl.lista_id
+              WHERE pl.producto_id = ?1
+                AND (pl.variante_id IS NULL OR pl.variante_id = ?2)
+                AND pl.cantidad_minima <= ?3
+                AND (pl.lista_id = ?4 OR (l.predeterminada = 1 AND l.activo = 1))
+              ORDER BY (pl.lista_id = ?4) DESC, pl.cantidad_minima DESC, pl.variante_id IS NULL
+              LIMIT 1",
+            params![producto_id, variante_id, cantidad, lista_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Error al obtener precio de lista: {}", e))?;
+
+    if let Some(precio) = de_lista {
+        return Ok(precio);
+    }
+
+    conn.query_row(
+        "SELECT precio FROM productos WHERE id = ?",
+        params![producto_id],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(|e| format!("Error al obtener precio del producto: {}", e))?
+    .ok_or_else(|| format!("❌ Producto {} no encontrado", producto_id))
+}
@@ -7,10 +7,31 @@ pub mod ventas;
 pub mod reportes;
 pub mod configuracion;
 pub mod devoluciones;
+pub mod cambios;
+pub mod cuarentena;
+pub mod comisiones;
 pub mod licencias;
 pub mod cajas;
 pub mod proveedores;
 pub mod impresora; // 🆕
+pub mod suspendidas;
+pub mod cotizaciones;
+pub mod promociones;
+pub mod cupones;
+pub mod clientes;
+pub mod creditos;
+pub mod listas_precios;
+pub mod padron;
+pub mod puntos;
+pub mod vales;
+pub mod impuestos;
+pub mod numeracion;
+pub mod idempotencia;
+pub mod ubl;
+pub mod firma_digital;
+pub mod envio_sunat;
+pub mod facturacion;
+pub mod notas_credito;
 
 pub use auth::{login, test_database_connection};
 pub use productos::{
@@ -26,13 +47,16 @@ pub use productos::{
     obtener_variantes_producto,
     obtener_producto_con_variantes,
 };
-pub use ventas::{procesar_venta};
+pub use ventas::{procesar_venta, anular_venta, obtener_venta_detalle};
 pub use reportes::{
     obtener_ventas_rango,
     obtener_productos_mas_vendidos,
     obtener_estadisticas_ventas,
     obtener_ventas_hoy,
     obtener_estadisticas_con_devoluciones,
+    obtener_reporte_cupones,
+    obtener_reporte_vales,
+    obtener_resumen_comprobantes,
 };
 pub use configuracion::{
     obtener_configuracion_tienda,
@@ -48,6 +72,15 @@ pub use devoluciones::{
     buscar_venta_para_devolucion,
     procesar_devolucion,
 };
+pub use cambios::{procesar_cambio, obtener_cambio};
+pub use cuarentena::{obtener_stock_cuarentena, registrar_merma_cuarentena};
+pub use comisiones::{
+    obtener_reglas_comision,
+    agregar_regla_comision,
+    actualizar_regla_comision,
+    obtener_reporte_comisiones,
+};
+pub use notas_credito::{obtener_notas_credito_venta, obtener_nota_credito};
 pub use cajas::{
     abrir_caja,
     cerrar_caja,
@@ -73,4 +106,81 @@ pub use proveedores::{
     resolver_devolucion_proveedor,
     obtener_devoluciones_proveedor,
 };
-pub use impresora::{imprimir_boleta, probar_impresora}; // 🆕
\ No newline at end of file
+pub use impresora::{imprimir_boleta, imprimir_nota_credito, imprimir_cotizacion, reimprimir_boleta, probar_impresora}; // 🆕
+pub use suspendidas::{
+    suspender_venta,
+    obtener_ventas_suspendidas,
+    recuperar_venta_suspendida,
+    descartar_venta_suspendida,
+};
+pub use cotizaciones::{
+    crear_cotizacion,
+    obtener_cotizaciones,
+    obtener_cotizacion,
+    anular_cotizacion,
+    cargar_cotizacion,
+};
+pub use promociones::{
+    obtener_promociones,
+    agregar_promocion,
+    actualizar_promocion,
+    evaluar_promociones,
+};
+pub use cupones::{
+    obtener_cupones,
+    agregar_cupon,
+    actualizar_cupon,
+    validar_cupon,
+};
+pub use clientes::{
+    buscar_clientes,
+    obtener_cliente,
+    agregar_cliente,
+    actualizar_cliente,
+    historial_cliente,
+    validar_documento_identidad,
+};
+pub use padron::{importar_padron_ruc, consultar_ruc, obtener_estado_padron};
+pub use creditos::{
+    registrar_pago_cliente,
+    obtener_cuentas_por_cobrar,
+    obtener_antiguedad_saldos,
+    obtener_estado_cuenta_cliente,
+};
+pub use listas_precios::{
+    obtener_listas_precios,
+    agregar_lista_precios,
+    actualizar_lista_precios,
+    obtener_precios_lista,
+    guardar_precio_lista,
+    eliminar_precio_lista,
+    resolver_precios_carrito,
+};
+pub use puntos::{
+    obtener_configuracion_puntos,
+    actualizar_configuracion_puntos,
+    obtener_saldo_puntos,
+    obtener_estado_cuenta_puntos,
+};
+pub use vales::{
+    vender_vale,
+    obtener_vale,
+    obtener_vales,
+};
+pub use numeracion::{
+    obtener_numeracion_documentos,
+    actualizar_numeracion_documento,
+    obtener_series_comprobante,
+    crear_serie_comprobante,
+    actualizar_serie_comprobante,
+};
+pub use facturacion::{
+    obtener_configuracion_facturacion,
+    actualizar_configuracion_facturacion,
+    emitir_comprobante,
+    emitir_nota_credito,
+    enviar_comprobante,
+    obtener_comprobante_venta,
+    obtener_comprobantes_pendientes,
+    obtener_xml_comprobante,
+};
@@ -0,0 +1,288 @@
+// commands/notas_credito.rs
+// Notas de crédito de ventas con boleta o factura. procesar_devolucion genera
+// una por devolución dentro de su propia transacción.
+//
+// Los montos de cada línea son los que se reembolsan (monto_neto_devolucion,
+// con los descuentos de la venta prorrateados), así la nota coincide con la
+// devolución y la suma de las notas nunca pasa de lo facturado.
+
+use crate::commands::impuestos::{desglosar_igv, TotalesIgv};
+use crate::commands::numeracion::{numero_comprobante, siguiente_correlativo};
+use crate::commands::promociones::redondear;
+use crate::database::DatabasePool;
+use crate::models::comprobante::*;
+use rusqlite::{params, OptionalExtension};
+
+// Catálogo 09 de SUNAT: tipo de nota de crédito
+const MOTIVOS_NOTA_CREDITO: [(&str, &str); 11] = [
+    ("01", "Anulación de la operación"),
+    ("02", "Anulación por error en el RUC"),
+    ("03", "Corrección por error en la descripción"),
+    ("04", "Descuento global"),
+    ("05", "Descuento por ítem"),
+    ("06", "Devolución total"),
+    ("07", "Devolución por ítem"),
+    ("08", "Bonificación"),
+    ("09", "Disminución en el valor"),
+    ("10", "Otros conceptos"),
+    ("13", "Ajustes - montos y/o fechas de pago"),
+];
+
+// =====================================================
+// COMANDO: Notas de crédito de una venta
+// =====================================================
+#[tauri::command]
+pub fn obtener_notas_credito_venta(
+    db: tauri::State<'_, DatabasePool>,
+    venta_id: i32,
+) -> Result<Vec<NotaCredito>, String> {
+    let conn = db.get_conn();
+
+    let ids: Vec<i32> = {
+        let mut stmt = conn
+            .prepare("SELECT id FROM notas_credito WHERE venta_id = ? ORDER BY id")
+            .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+        let ids = stmt
+            .query_map(params![venta_id], |row| row.get(0))
+            .map_err(|e| format!("Error al obtener notas de crédito: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+        ids
+    };
+
+    ids.into_iter().map(|id| obtener_nota_credito_por_id(&conn, id)).collect()
+}
+
+// =====================================================
+// COMANDO: Obtener nota de crédito
+// =====================================================
+#[tauri::command]
+pub fn obtener_nota_credito(
+    db: tauri::State<'_, DatabasePool>,
+    nota_credito_id: i32,
+) -> Result<NotaCredito, String> {
+    let conn = db.get_conn();
+    obtener_nota_credito_por_id(&conn, nota_credito_id)
+}
+
+// =====================================================
+// FUNCIONES AUXILIARES
+// =====================================================
+
+pub fn descripcion_motivo(codigo: &str) -> Option<&'static str> {
+    MOTIVOS_NOTA_CREDITO
+        .iter()
+        .find(|(c, _)| *c == codigo)
+        .map(|(_, descripcion)| *descripcion)
+}
+
+// Genera la nota de crédito de una devolución: `lineas` son (detalle_venta_id,
+// cantidad, monto reembolsado). Sin motivo se usa 06 si se devuelve toda la venta de una vez y 07
+// si no. Devuelve None si la venta fue con nota de venta. Debe llamarse con la
+// transacción de la devolución abierta.
+pub fn generar_nota_credito(
+    conn: &rusqlite::Connection,
+    devolucion_id: i32,
+    venta_id: i32,
+    lineas: &[(i32, i32, f64)],
+    codigo_motivo: Option<&str>,
+    sustento: &str,
+    usuario_id: i32,
+) -> Result<Option<(i32, String)>, String> {
+    let (tipo, serie_ref, correlativo_ref, caja_venta, tasa_igv): (String, Option<String>, Option<i64>, Option<i32>, f64) = conn
+        .query_row(
+            r"SELECT COALESCE(tipo_comprobante, 'NOTA_VENTA'), serie, correlativo, caja_id, COALESCE(igv_porcentaje, 0)
+              FROM ventas WHERE id = ?",
+            params![venta_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+        )
+        .map_err(|e| format!("Error al obtener venta: {}", e))?;
+
+    let (serie_ref, correlativo_ref) = match (tipo.as_str(), serie_ref, correlativo_ref) {
+        ("BOLETA" | "FACTURA", Some(s), Some(c)) => (s, c),
+        _ => return Ok(None),
+    };
+
+    // Serie de la caja donde se atiende la devolución (o la de la venta)
+    let caja_id: i32 = conn
+        .query_row(
+            "SELECT id FROM cajas WHERE usuario_id = ? AND estado = 'ABIERTA'",
+            params![usuario_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Error al verificar caja: {}", e))?
+        .or(caja_venta)
+        .ok_or_else(|| "❌ Abre una caja para emitir la nota de crédito".to_string())?;
+
+    // Montos de lo reembolsado por línea
+    let mut totales = TotalesIgv::default();
+    let mut total = 0.0f64;
+    let mut detalles = Vec::with_capacity(lineas.len());
+    for &(detalle_id, cantidad, monto) in lineas {
+        let (producto_id, cantidad_original, afectacion): (i32, i32, String) = conn
+            .query_row(
+                r"SELECT producto_id, cantidad, COALESCE(afectacion_igv, 'GRAVADO')
+                  FROM detalles_venta WHERE id = ? AND venta_id = ?",
+                params![detalle_id, venta_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()
+            .map_err(|e| format!("Error al obtener detalle de la venta: {}", e))?
+            .ok_or_else(|| "❌ La línea devuelta no pertenece a la venta".to_string())?;
+
+        let cantidad_previa: i32 = conn
+            .query_row(
+                "SELECT COALESCE(SUM(cantidad), 0) FROM detalles_nota_credito WHERE detalle_venta_id = ?",
+                params![detalle_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("Error al obtener notas previas: {}", e))?;
+
+        if cantidad_previa + cantidad > cantidad_original {
+            return Err("❌ La nota de crédito supera lo vendido en la línea".to_string());
+        }
+
+        let total_nota = redondear(monto);
+        let (valor_nota, igv_nota) = desglosar_igv(total_nota, &afectacion, tasa_igv);
+
+        totales.agregar(&afectacion, valor_nota, igv_nota);
+        total += total_nota;
+        detalles.push((detalle_id, producto_id, cantidad, total_nota, valor_nota, igv_nota, afectacion));
+    }
+    let totales = totales.redondeados();
+
+    let codigo_motivo = match codigo_motivo.map(str::trim).filter(|c| !c.is_empty()) {
+        Some(codigo) => codigo.to_string(),
+        None => {
+            let (vendidas, notas_previas): (i32, i32) = conn
+                .query_row(
+                    r"SELECT (SELECT COALESCE(SUM(cantidad), 0) FROM detalles_venta WHERE venta_id = ?1),
+                             (SELECT COUNT(*) FROM notas_credito WHERE venta_id = ?1)",
+                    params![venta_id],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .map_err(|e| format!("Error al obtener venta: {}", e))?;
+            let devueltas: i32 = lineas.iter().map(|(_, c, _)| c).sum();
+            if notas_previas == 0 && devueltas == vendidas { "06" } else { "07" }.to_string()
+        }
+    };
+    if descripcion_motivo(&codigo_motivo).is_none() {
+        return Err(format!("❌ Motivo de nota de crédito no válido: {}", codigo_motivo));
+    }
+
+    let (serie, correlativo) = siguiente_correlativo(conn, &format!("NOTA_CREDITO_{}", tipo), caja_id)?;
+
+    conn.execute(
+        r"INSERT INTO notas_credito
+            (devolucion_id, venta_id, serie, correlativo, tipo_documento_ref, serie_ref, correlativo_ref,
+             codigo_motivo, sustento, op_gravada, op_exonerada, op_inafecta, igv, igv_porcentaje,
+             total, usuario_id)
+          VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        params![
+            devolucion_id,
+            venta_id,
+            serie,
+            correlativo,
+            tipo,
+            serie_ref,
+            correlativo_ref,
+            codigo_motivo,
+            sustento,
+            totales.op_gravada,
+            totales.op_exonerada,
+            totales.op_inafecta,
+            totales.igv,
+            tasa_igv,
+            redondear(total),
+            usuario_id,
+        ],
+    )
+    .map_err(|e| format!("Error al registrar nota de crédito: {}", e))?;
+    let nota_credito_id = conn.last_insert_rowid() as i32;
+
+    for (detalle_id, producto_id, cantidad, total_nota, valor_nota, igv_nota, afectacion) in detalles {
+        conn.execute(
+            r"INSERT INTO detalles_nota_credito
+                (nota_credito_id, detalle_venta_id, producto_id, cantidad, total_linea, valor_venta, igv, afectacion_igv)
+              VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            params![nota_credito_id, detalle_id, producto_id, cantidad, total_nota, valor_nota, igv_nota, afectacion],
+        )
+        .map_err(|e| format!("Error al registrar detalle de nota de crédito: {}", e))?;
+    }
+
+    Ok(Some((nota_credito_id, numero_comprobante(&serie, correlativo))))
+}
+
+pub fn obtener_nota_credito_por_id(conn: &rusqlite::Connection, id: i32) -> Result<NotaCredito, String> {
+    let mut nota = conn
+        .query_row(
+            r"SELECT id, devolucion_id, venta_id, serie, correlativo, tipo_documento_ref, serie_ref,
+                     correlativo_ref, codigo_motivo, sustento, op_gravada, op_exonerada, op_inafecta,
+                     igv, igv_porcentaje, total, fecha_emision
+              FROM notas_credito WHERE id = ?",
+            params![id],
+            |row| {
+                let codigo_motivo: String = row.get(8)?;
+                Ok(NotaCredito {
+                    id: row.get(0)?,
+                    devolucion_id: row.get(1)?,
+                    venta_id: row.get(2)?,
+                    numero: numero_comprobante(&row.get::<_, String>(3)?, row.get(4)?),
+                    tipo_documento_ref: row.get(5)?,
+                    numero_ref: numero_comprobante(&row.get::<_, String>(6)?, row.get(7)?),
+                    descripcion_motivo: descripcion_motivo(&codigo_motivo).unwrap_or("").to_string(),
+                    codigo_motivo,
+                    sustento: row.get(9)?,
+                    op_gravada: row.get(10)?,
+                    op_exonerada: row.get(11)?,
+                    op_inafecta: row.get(12)?,
+                    igv: row.get(13)?,
+                    igv_porcentaje: row.get(14)?,
+                    total: row.get(15)?,
+                    fecha_emision: row.get(16)?,
+                    detalles: Vec::new(),
+                })
+            },
+        )
+        .optional()
+        .map_err(|e| format!("Error al obtener nota de crédito: {}", e))?
+        .ok_or_else(|| "❌ Nota de crédito no encontrada".to_string())?;
+
+    let mut stmt = conn
+        .prepare(
+            r"SELECT dn.detalle_venta_id, dn.producto_id, p.nombre, dv.talla, dn.cantidad,
+                     dn.total_linea, dn.valor_venta, dn.igv, COALESCE(dn.afectacion_igv, 'GRAVADO'), p.codigo
+              FROM detalles_nota_credito dn
+              JOIN productos p ON p.id = dn.producto_id
+              JOIN detalles_venta dv ON dv.id = dn.detalle_venta_id
+              WHERE dn.nota_credito_id = ?
+              ORDER BY dn.id",
+        )
+        .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+
+    nota.detalles = stmt
+        .query_map(params![id], |row| {
+            let nombre: String = row.get(2)?;
+            let talla: Option<String> = row.get(3)?;
+            Ok(DetalleNotaCredito {
+                detalle_venta_id: row.get(0)?,
+                producto_id: row.get(1)?,
+                codigo: row.get(9)?,
+                descripcion: match talla {
+                    Some(t) if !t.is_empty() => format!("{} - Talla {}", nombre, t),
+                    _ => nombre,
+                },
+                cantidad: row.get(4)?,
+                total_linea: row.get(5)?,
+                valor_venta: row.get(6)?,
+                igv: row.get(7)?,
+                afectacion_igv: row.get(8)?,
+            })
+        })
+        .map_err(|e| format!("Error al obtener detalle de nota de crédito: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(nota)
+}
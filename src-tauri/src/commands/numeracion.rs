@@ -0,0 +1,620 @@
+// commands/numeracion.rs
+// Numeración central de documentos. Cada tipo tiene su formato y política de
+// reinicio en numeracion_documentos; el último número de cada periodo vive en
+// contadores_documentos.
+//
+// siguiente_folio se llama DENTRO de la transacción del documento: si la
+// operación hace ROLLBACK el contador también vuelve atrás, así que no quedan
+// huecos ni folios repetidos.
+//
+// Formato: {YYYY} año, {YY} año corto, {MM} mes, {DD} día y {####} el
+// correlativo (tantos # como dígitos mínimos; si se pasa, crece).
+//
+// 🆕 Boletas, facturas y notas de venta se numeran aparte, por serie
+// (series_comprobante): cada caja usa su serie activa de cada tipo y cada
+// serie lleva su propio correlativo.
+
+use crate::database::DatabasePool;
+use crate::models::numeracion::*;
+use chrono::{DateTime, Local};
+use rusqlite::{params, OptionalExtension};
+
+// =====================================================
+// COMANDO: Listar numeración de documentos
+// =====================================================
+#[tauri::command]
+pub fn obtener_numeracion_documentos(
+    db: tauri::State<'_, DatabasePool>,
+) -> Result<Vec<NumeracionDocumento>, String> {
+    let conn = db.get_conn();
+    let ahora = Local::now();
+
+    let mut stmt = conn
+        .prepare(
+            r"SELECT tipo_documento, descripcion, formato, reinicio
+              FROM numeracion_documentos
+              ORDER BY tipo_documento",
+        )
+        .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+
+    let filas = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })
+        .map_err(|e| format!("Error al obtener numeración: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect::<Vec<_>>();
+
+    let mut documentos = Vec::new();
+    for (tipo_documento, descripcion, formato, reinicio) in filas {
+        let ultimo_numero = ultimo_numero(&conn, &tipo_documento, &clave_periodo(&reinicio, &ahora))?;
+        let proximo_folio = formatear_folio(&formato, &ahora, ultimo_numero + 1)?;
+        documentos.push(NumeracionDocumento {
+            tipo_documento,
+            descripcion,
+            formato,
+            reinicio,
+            ultimo_numero,
+            proximo_folio,
+        });
+    }
+
+    Ok(documentos)
+}
+
+// =====================================================
+// COMANDO: Cambiar formato / reinicio de un documento
+// =====================================================
+#[tauri::command]
+pub fn actualizar_numeracion_documento(
+    db: tauri::State<'_, DatabasePool>,
+    tipo_documento: String,
+    formato: String,
+    reinicio: String,
+) -> Result<String, String> {
+    let conn = db.get_conn();
+
+    let formato = formato.trim().to_string();
+    let reinicio = reinicio.trim().to_uppercase();
+    validar_formato(&formato, &reinicio)?;
+
+    let reinicio_actual: String = conn
+        .query_row(
+            "SELECT reinicio FROM numeracion_documentos WHERE tipo_documento = ?",
+            params![tipo_documento],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Error al obtener numeración: {}", e))?
+        .ok_or_else(|| format!("❌ Tipo de documento {} no existe", tipo_documento))?;
+
+    conn.execute("BEGIN TRANSACTION", [])
+        .map_err(|e| format!("Error al iniciar transacción: {}", e))?;
+
+    let rollback = |conn: &rusqlite::Connection, msg: String| -> String {
+        let _ = conn.execute("ROLLBACK", []);
+        msg
+    };
+
+    if let Err(e) = conn.execute(
+        r"UPDATE numeracion_documentos
+          SET formato = ?, reinicio = ?, fecha_actualizacion = datetime('now', 'localtime')
+          WHERE tipo_documento = ?",
+        params![formato, reinicio, tipo_documento],
+    ) {
+        return Err(rollback(&conn, format!("Error al actualizar numeración: {}", e)));
+    }
+
+    // Al cambiar el reinicio el contador pasa a otro periodo: arranca desde lo
+    // ya emitido en el periodo actual para no repetir los folios de hoy
+    let ahora = Local::now();
+    let periodo_actual = clave_periodo(&reinicio_actual, &ahora);
+    let periodo_nuevo = clave_periodo(&reinicio, &ahora);
+    if let Err(e) = continuar_contador(&conn, &tipo_documento, &periodo_actual, &periodo_nuevo) {
+        return Err(rollback(&conn, e));
+    }
+
+    // El formato de ventas también se guarda en la configuración de la tienda
+    if tipo_documento == "VENTA" {
+        if let Err(e) = conn.execute(
+            "UPDATE configuracion_tienda SET formato_folio = ?, fecha_actualizacion = datetime('now', 'localtime') WHERE id = 1",
+            params![formato],
+        ) {
+            return Err(rollback(&conn, format!("Error al actualizar formato de folio: {}", e)));
+        }
+    }
+
+    let proximo = match ultimo_numero(&conn, &tipo_documento, &periodo_nuevo) {
+        Ok(n) => n + 1,
+        Err(e) => return Err(rollback(&conn, e)),
+    };
+
+    conn.execute("COMMIT", [])
+        .map_err(|e| rollback(&conn, format!("Error al confirmar transacción: {}", e)))?;
+
+    Ok(format!(
+        "✅ Numeración de {} actualizada. Próximo folio: {}",
+        tipo_documento,
+        formatear_folio(&formato, &ahora, proximo)?
+    ))
+}
+
+// =====================================================
+// 🆕 COMANDO: Listar series de comprobante
+// =====================================================
+#[tauri::command]
+pub fn obtener_series_comprobante(
+    db: tauri::State<'_, DatabasePool>,
+) -> Result<Vec<SerieComprobante>, String> {
+    let conn = db.get_conn();
+
+    let mut stmt = conn
+        .prepare(
+            r"SELECT id, tipo_comprobante, serie, numero_caja, ultimo_correlativo, activo
+              FROM series_comprobante
+              ORDER BY numero_caja, tipo_comprobante, activo DESC, serie",
+        )
+        .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+
+    let series = stmt
+        .query_map([], |row| {
+            let serie: String = row.get(2)?;
+            let ultimo_correlativo: i64 = row.get(4)?;
+            Ok(SerieComprobante {
+                id: row.get(0)?,
+                tipo_comprobante: row.get(1)?,
+                proximo_numero: numero_comprobante(&serie, ultimo_correlativo + 1),
+                serie,
+                numero_caja: row.get(3)?,
+                ultimo_correlativo,
+                activo: row.get::<_, i32>(5)? == 1,
+            })
+        })
+        .map_err(|e| format!("Error al obtener series: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(series)
+}
+
+// =====================================================
+// 🆕 COMANDO: Crear serie (queda como la activa de su caja)
+// =====================================================
+#[tauri::command]
+pub fn crear_serie_comprobante(
+    db: tauri::State<'_, DatabasePool>,
+    serie: SerieComprobanteNueva,
+) -> Result<String, String> {
+    let conn = db.get_conn();
+
+    let tipo = serie.tipo_comprobante.trim().to_uppercase();
+    let codigo = serie.serie.trim().to_uppercase();
+    validar_serie(&tipo, &codigo)?;
+    if serie.numero_caja < 1 {
+        return Err("❌ El número de caja debe ser mayor a 0".to_string());
+    }
+    let ultimo_correlativo = serie.ultimo_correlativo.unwrap_or(0);
+    if !(0..99_999_999).contains(&ultimo_correlativo) {
+        return Err("❌ El correlativo inicial no es válido".to_string());
+    }
+
+    let existe: bool = conn
+        .query_row("SELECT COUNT(*) FROM series_comprobante WHERE serie = ?", params![codigo], |row| row.get::<_, i32>(0))
+        .map(|n| n > 0)
+        .map_err(|e| format!("Error al verificar serie: {}", e))?;
+    if existe {
+        return Err(format!("❌ La serie {} ya existe", codigo));
+    }
+
+    conn.execute("BEGIN TRANSACTION", [])
+        .map_err(|e| format!("Error al iniciar transacción: {}", e))?;
+
+    let rollback = |conn: &rusqlite::Connection, msg: String| -> String {
+        let _ = conn.execute("ROLLBACK", []);
+        msg
+    };
+
+    // La serie anterior de la caja queda inactiva (conserva su correlativo)
+    if let Err(e) = conn.execute(
+        "UPDATE series_comprobante SET activo = 0 WHERE tipo_comprobante = ? AND numero_caja = ? AND activo = 1",
+        params![tipo, serie.numero_caja],
+    ) {
+        return Err(rollback(&conn, format!("Error al desactivar serie anterior: {}", e)));
+    }
+
+    if let Err(e) = conn.execute(
+        "INSERT INTO series_comprobante (tipo_comprobante, serie, numero_caja, ultimo_correlativo) VALUES (?, ?, ?, ?)",
+        params![tipo, codigo, serie.numero_caja, ultimo_correlativo],
+    ) {
+        return Err(rollback(&conn, format!("Error al crear serie: {}", e)));
+    }
+
+    conn.execute("COMMIT", [])
+        .map_err(|e| rollback(&conn, format!("Error al confirmar transacción: {}", e)))?;
+
+    Ok(format!(
+        "✅ Serie {} asignada a la caja {}. Próximo número: {}",
+        codigo,
+        serie.numero_caja,
+        numero_comprobante(&codigo, ultimo_correlativo + 1)
+    ))
+}
+
+// =====================================================
+// 🆕 COMANDO: Mover una serie de caja o activarla/desactivarla
+// El correlativo no se edita: una serie usada solo avanza.
+// =====================================================
+#[tauri::command]
+pub fn actualizar_serie_comprobante(
+    db: tauri::State<'_, DatabasePool>,
+    serie_id: i32,
+    numero_caja: i32,
+    activo: bool,
+) -> Result<String, String> {
+    let conn = db.get_conn();
+
+    if numero_caja < 1 {
+        return Err("❌ El número de caja debe ser mayor a 0".to_string());
+    }
+
+    let (tipo, codigo): (String, String) = conn
+        .query_row(
+            "SELECT tipo_comprobante, serie FROM series_comprobante WHERE id = ?",
+            params![serie_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(|e| format!("Error al obtener serie: {}", e))?
+        .ok_or_else(|| "❌ Serie no encontrada".to_string())?;
+
+    conn.execute("BEGIN TRANSACTION", [])
+        .map_err(|e| format!("Error al iniciar transacción: {}", e))?;
+
+    let rollback = |conn: &rusqlite::Connection, msg: String| -> String {
+        let _ = conn.execute("ROLLBACK", []);
+        msg
+    };
+
+    if activo {
+        if let Err(e) = conn.execute(
+            "UPDATE series_comprobante SET activo = 0 WHERE tipo_comprobante = ? AND numero_caja = ? AND activo = 1 AND id != ?",
+            params![tipo, numero_caja, serie_id],
+        ) {
+            return Err(rollback(&conn, format!("Error al desactivar serie anterior: {}", e)));
+        }
+    }
+
+    if let Err(e) = conn.execute(
+        "UPDATE series_comprobante SET numero_caja = ?, activo = ? WHERE id = ?",
+        params![numero_caja, if activo { 1 } else { 0 }, serie_id],
+    ) {
+        return Err(rollback(&conn, format!("Error al actualizar serie: {}", e)));
+    }
+
+    conn.execute("COMMIT", [])
+        .map_err(|e| rollback(&conn, format!("Error al confirmar transacción: {}", e)))?;
+
+    Ok(if activo {
+        format!("✅ Serie {} activa en la caja {}", codigo, numero_caja)
+    } else {
+        format!("✅ Serie {} desactivada", codigo)
+    })
+}
+
+// =====================================================
+// FUNCIONES AUXILIARES
+// =====================================================
+
+// Asigna el siguiente folio del tipo de documento. Debe llamarse con una
+// transacción abierta.
+pub fn siguiente_folio(conn: &rusqlite::Connection, tipo_documento: &str) -> Result<String, String> {
+    let (formato, reinicio): (String, String) = conn
+        .query_row(
+            "SELECT formato, reinicio FROM numeracion_documentos WHERE tipo_documento = ?",
+            params![tipo_documento],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(|e| format!("Error al obtener numeración: {}", e))?
+        .ok_or_else(|| format!("No hay numeración configurada para {}", tipo_documento))?;
+
+    let ahora = Local::now();
+    let periodo = clave_periodo(&reinicio, &ahora);
+
+    let numero: i64 = conn
+        .query_row(
+            r"INSERT INTO contadores_documentos (tipo_documento, periodo, ultimo_numero)
+              VALUES (?1, ?2, 1)
+              ON CONFLICT(tipo_documento, periodo) DO UPDATE
+                SET ultimo_numero = ultimo_numero + 1,
+                    fecha_actualizacion = datetime('now', 'localtime')
+              RETURNING ultimo_numero",
+            params![tipo_documento, periodo],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Error al asignar folio: {}", e))?;
+
+    formatear_folio(&formato, &ahora, numero)
+}
+
+// 🆕 Siguiente número de la serie activa de la caja para el tipo de
+// comprobante. Igual que siguiente_folio, va dentro de la transacción.
+pub fn siguiente_correlativo(
+    conn: &rusqlite::Connection,
+    tipo_comprobante: &str,
+    caja_id: i32,
+) -> Result<(String, i64), String> {
+    conn.query_row(
+        r"UPDATE series_comprobante
+          SET ultimo_correlativo = ultimo_correlativo + 1
+          WHERE tipo_comprobante = ?1 AND activo = 1
+            AND numero_caja = (SELECT numero_caja FROM cajas WHERE id = ?2)
+          RETURNING serie, ultimo_correlativo",
+        params![tipo_comprobante, caja_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .optional()
+    .map_err(|e| format!("Error al asignar correlativo: {}", e))?
+    .ok_or_else(|| format!("❌ La caja no tiene una serie activa de {}", nombre_tipo_comprobante(tipo_comprobante)))
+}
+
+// B001-00000123
+pub fn numero_comprobante(serie: &str, correlativo: i64) -> String {
+    format!("{}-{:08}", serie, correlativo)
+}
+
+pub fn nombre_tipo_comprobante(tipo_comprobante: &str) -> &'static str {
+    match tipo_comprobante {
+        "BOLETA" => "boleta",
+        "FACTURA" => "factura",
+        "NOTA_CREDITO_BOLETA" => "nota de crédito de boletas",
+        "NOTA_CREDITO_FACTURA" => "nota de crédito de facturas",
+        _ => "nota de venta",
+    }
+}
+
+// Boleta B + 3, factura F + 3 (formato SUNAT; sus notas de crédito llevan
+// la misma letra); nota de venta 4 caracteres que no se confundan con una
+// serie electrónica
+fn validar_serie(tipo_comprobante: &str, serie: &str) -> Result<(), String> {
+    let alfanumerica = serie.len() == 4 && serie.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+    let valida = match tipo_comprobante {
+        "BOLETA" | "NOTA_CREDITO_BOLETA" => alfanumerica && serie.starts_with('B'),
+        "FACTURA" | "NOTA_CREDITO_FACTURA" => alfanumerica && serie.starts_with('F'),
+        "NOTA_VENTA" => alfanumerica && !serie.starts_with(['B', 'F']),
+        _ => {
+            return Err(
+                "❌ El tipo debe ser BOLETA, FACTURA, NOTA_VENTA, NOTA_CREDITO_BOLETA o NOTA_CREDITO_FACTURA".to_string(),
+            )
+        }
+    };
+
+    if valida {
+        Ok(())
+    } else {
+        Err(match tipo_comprobante {
+            "BOLETA" => "❌ La serie de boleta debe ser B más 3 caracteres (ej. B001)",
+            "FACTURA" => "❌ La serie de factura debe ser F más 3 caracteres (ej. F001)",
+            "NOTA_CREDITO_BOLETA" => "❌ La serie de nota de crédito de boletas debe ser B más 3 caracteres (ej. BC01)",
+            "NOTA_CREDITO_FACTURA" => "❌ La serie de nota de crédito de facturas debe ser F más 3 caracteres (ej. FC01)",
+            _ => "❌ La serie de nota de venta debe tener 4 caracteres y no empezar con B ni F (ej. NV01)",
+        }
+        .to_string())
+    }
+}
+
+fn ultimo_numero(conn: &rusqlite::Connection, tipo_documento: &str, periodo: &str) -> Result<i64, String> {
+    conn.query_row(
+        "SELECT ultimo_numero FROM contadores_documentos WHERE tipo_documento = ? AND periodo = ?",
+        params![tipo_documento, periodo],
+        |row| row.get(0),
+    )
+    .optional()
+    .map(|n| n.unwrap_or(0))
+    .map_err(|e| format!("Error al obtener contador: {}", e))
+}
+
+// El contador del periodo nuevo no baja de lo emitido en el periodo actual
+fn continuar_contador(
+    conn: &rusqlite::Connection,
+    tipo_documento: &str,
+    periodo_actual: &str,
+    periodo_nuevo: &str,
+) -> Result<(), String> {
+    if periodo_nuevo == periodo_actual {
+        return Ok(());
+    }
+
+    let emitidos = ultimo_numero(conn, tipo_documento, periodo_actual)?;
+    if emitidos == 0 {
+        return Ok(());
+    }
+
+    conn.execute(
+        r"INSERT INTO contadores_documentos (tipo_documento, periodo, ultimo_numero)
+          VALUES (?1, ?2, ?3)
+          ON CONFLICT(tipo_documento, periodo) DO UPDATE
+            SET ultimo_numero = MAX(ultimo_numero, excluded.ultimo_numero),
+                fecha_actualizacion = datetime('now', 'localtime')",
+        params![tipo_documento, periodo_nuevo, emitidos],
+    )
+    .map_err(|e| format!("Error al ajustar contador: {}", e))?;
+
+    Ok(())
+}
+
+// Periodo del contador: un día, un mes o uno solo para siempre
+fn clave_periodo(reinicio: &str, fecha: &DateTime<Local>) -> String {
+    match reinicio {
+        "MENSUAL" => fecha.format("%Y%m").to_string(),
+        "NUNCA" => String::new(),
+        _ => fecha.format("%Y%m%d").to_string(),
+    }
+}
+
+fn formatear_folio(formato: &str, fecha: &DateTime<Local>, numero: i64) -> Result<String, String> {
+    let mut folio = String::new();
+    let mut resto = formato;
+
+    while let Some(inicio) = resto.find('{') {
+        folio.push_str(&resto[..inicio]);
+        let fin = resto[inicio..]
+            .find('}')
+            .ok_or_else(|| format!("Formato de folio inválido: falta '}}' en {}", formato))?
+            + inicio;
+        let token = &resto[inicio + 1..fin];
+
+        match token {
+            "YYYY" => folio.push_str(&fecha.format("%Y").to_string()),
+            "YY" => folio.push_str(&fecha.format("%y").to_string()),
+            "MM" => folio.push_str(&fecha.format("%m").to_string()),
+            "DD" => folio.push_str(&fecha.format("%d").to_string()),
+            t if !t.is_empty() && t.chars().all(|c| c == '#') => {
+                folio.push_str(&format!("{:0ancho$}", numero, ancho = t.len()))
+            }
+            _ => return Err(format!("Formato de folio inválido: {{{}}} no se reconoce", token)),
+        }
+
+        resto = &resto[fin + 1..];
+    }
+    folio.push_str(resto);
+
+    Ok(folio)
+}
+
+// El formato debe tener exactamente un correlativo y las partes de fecha que
+// distinguen cada periodo; si no, al reiniciar se repetirían folios.
+fn validar_formato(formato: &str, reinicio: &str) -> Result<(), String> {
+    if !["DIARIO", "MENSUAL", "NUNCA"].contains(&reinicio) {
+        return Err("❌ El reinicio debe ser DIARIO, MENSUAL o NUNCA".to_string());
+    }
+
+    formatear_folio(formato, &Local::now(), 1).map_err(|e| format!("❌ {}", e))?;
+
+    let correlativos = formato
+        .split('{')
+        .skip(1)
+        .filter(|t| t.split('}').next().is_some_and(|t| !t.is_empty() && t.chars().all(|c| c == '#')))
+        .count();
+    if correlativos != 1 {
+        return Err("❌ El formato debe incluir un correlativo {####}".to_string());
+    }
+
+    let tiene_anio = formato.contains("{YYYY}") || formato.contains("{YY}");
+    match reinicio {
+        "DIARIO" if !(tiene_anio && formato.contains("{MM}") && formato.contains("{DD}")) => {
+            Err("❌ Con reinicio DIARIO el formato debe incluir {YYYY}, {MM} y {DD}".to_string())
+        }
+        "MENSUAL" if !(tiene_anio && formato.contains("{MM}")) => {
+            Err("❌ Con reinicio MENSUAL el formato debe incluir {YYYY} y {MM}".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn fecha() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 3, 7, 10, 30, 0).unwrap()
+    }
+
+    #[test]
+    fn formatea_fecha_y_correlativo() {
+        assert_eq!(formatear_folio("V-{YYYY}{MM}{DD}-{####}", &fecha(), 12).unwrap(), "V-20260307-0012");
+        assert_eq!(formatear_folio("{YY}{MM}/{###}", &fecha(), 5).unwrap(), "2603/005");
+        assert_eq!(formatear_folio("DEV-{##}", &fecha(), 123).unwrap(), "DEV-123");
+    }
+
+    #[test]
+    fn rechaza_tokens_desconocidos_o_sin_cerrar() {
+        assert!(formatear_folio("V-{HH}-{####}", &fecha(), 1).is_err());
+        assert!(formatear_folio("V-{####", &fecha(), 1).is_err());
+    }
+
+    #[test]
+    fn periodo_segun_reinicio() {
+        assert_eq!(clave_periodo("DIARIO", &fecha()), "20260307");
+        assert_eq!(clave_periodo("MENSUAL", &fecha()), "202603");
+        assert_eq!(clave_periodo("NUNCA", &fecha()), "");
+    }
+
+    #[test]
+    fn formato_debe_distinguir_cada_periodo() {
+        assert!(validar_formato("V-{YYYY}{MM}{DD}-{####}", "DIARIO").is_ok());
+        assert!(validar_formato("V-{YYYY}{MM}-{####}", "DIARIO").is_err());
+        assert!(validar_formato("V-{YYYY}{MM}-{####}", "MENSUAL").is_ok());
+        assert!(validar_formato("V-{####}", "NUNCA").is_ok());
+        assert!(validar_formato("V-{####}", "SEMANAL").is_err());
+    }
+
+    #[test]
+    fn formato_con_un_solo_correlativo() {
+        assert!(validar_formato("V-{YYYY}", "NUNCA").is_err());
+        assert!(validar_formato("{##}-{####}", "NUNCA").is_err());
+    }
+
+    fn contadores(numeros: &[(&str, i64)]) -> rusqlite::Connection {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            r"CREATE TABLE contadores_documentos (
+                tipo_documento TEXT NOT NULL,
+                periodo TEXT NOT NULL,
+                ultimo_numero INTEGER NOT NULL DEFAULT 0,
+                fecha_actualizacion TEXT,
+                PRIMARY KEY (tipo_documento, periodo)
+              );",
+        )
+        .unwrap();
+        for (periodo, numero) in numeros {
+            conn.execute(
+                "INSERT INTO contadores_documentos (tipo_documento, periodo, ultimo_numero) VALUES ('VENTA', ?, ?)",
+                params![periodo, numero],
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    #[test]
+    fn cambiar_reinicio_no_repite_folios_del_dia() {
+        let conn = contadores(&[("20260307", 5)]);
+        continuar_contador(&conn, "VENTA", "20260307", "202603").unwrap();
+        assert_eq!(ultimo_numero(&conn, "VENTA", "202603").unwrap(), 5);
+    }
+
+    #[test]
+    fn volver_a_un_periodo_usado_toma_el_mayor() {
+        let conn = contadores(&[("20260307", 5), ("202603", 8)]);
+        continuar_contador(&conn, "VENTA", "202603", "20260307").unwrap();
+        assert_eq!(ultimo_numero(&conn, "VENTA", "20260307").unwrap(), 8);
+
+        continuar_contador(&conn, "VENTA", "20260307", "202603").unwrap();
+        assert_eq!(ultimo_numero(&conn, "VENTA", "202603").unwrap(), 8);
+    }
+
+    #[test]
+    fn sin_emitidos_el_contador_queda_igual() {
+        let conn = contadores(&[]);
+        continuar_contador(&conn, "VENTA", "20260307", "").unwrap();
+        assert_eq!(ultimo_numero(&conn, "VENTA", "").unwrap(), 0);
+    }
+
+    #[test]
+    fn series_con_formato_sunat() {
+        assert!(validar_serie("BOLETA", "B001").is_ok());
+        assert!(validar_serie("BOLETA", "F001").is_err());
+        assert!(validar_serie("FACTURA", "F01").is_err());
+        assert!(validar_serie("NOTA_CREDITO_FACTURA", "FC01").is_ok());
+        assert!(validar_serie("NOTA_VENTA", "NV01").is_ok());
+        assert!(validar_serie("NOTA_VENTA", "B001").is_err());
+        assert_eq!(numero_comprobante("B001", 123), "B001-00000123");
+    }
+}
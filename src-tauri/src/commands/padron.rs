@@ -0,0 +1,194 @@
+// commands/padron.rs
+// Copia local del padrón reducido de SUNAT (padron_reducido_ruc.txt) para
+// consultar la razón social y la dirección de un RUC sin internet.
+//
+// Formato: una línea por contribuyente separada por "|" (ISO-8859-1):
+// RUC|RAZÓN SOCIAL|ESTADO|CONDICIÓN|UBIGEO|TIPO VÍA|NOMBRE VÍA|CÓDIGO ZONA|
+// TIPO ZONA|NÚMERO|INTERIOR|LOTE|DEPARTAMENTO|MANZANA|KILÓMETRO|
+// Los campos vacíos vienen como "-".
+
+use crate::commands::clientes::validar_ruc;
+use crate::database::DatabasePool;
+use crate::models::cliente::*;
+use rusqlite::{params, OptionalExtension};
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+// =====================================================
+// COMANDO: Importar el padrón reducido
+// =====================================================
+// El archivo completo tiene millones de líneas: se lee en streaming y se
+// reemplazan los RUC que ya estaban
+#[tauri::command]
+pub fn importar_padron_ruc(
+    db: tauri::State<'_, DatabasePool>,
+    ruta: String,
+) -> Result<ResultadoImportacionPadron, String> {
+    let conn = db.get_conn();
+
+    let archivo = File::open(ruta.trim()).map_err(|e| format!("❌ No se pudo abrir el padrón: {}", e))?;
+    let mut lector = BufReader::with_capacity(1 << 20, archivo);
+
+    conn.execute("BEGIN TRANSACTION", [])
+        .map_err(|e| format!("Error al iniciar transacción: {}", e))?;
+
+    let rollback = |e: String| -> String {
+        let _ = conn.execute("ROLLBACK", []);
+        e
+    };
+
+    let (importados, omitidos) = {
+        let mut stmt = conn
+            .prepare(
+                r"INSERT OR REPLACE INTO padron_ruc
+                    (ruc, razon_social, estado, condicion, ubigeo, direccion, fecha_actualizacion)
+                  VALUES (?, ?, ?, ?, ?, ?, datetime('now', 'localtime'))",
+            )
+            .map_err(|e| rollback(format!("Error al preparar importación: {}", e)))?;
+
+        let mut importados = 0i64;
+        let mut omitidos = 0i64;
+        let mut buffer = Vec::new();
+        loop {
+            buffer.clear();
+            let leidos = lector
+                .read_until(b'\n', &mut buffer)
+                .map_err(|e| rollback(format!("❌ Error al leer el padrón: {}", e)))?;
+            if leidos == 0 {
+                break;
+            }
+
+            // El padrón viene en ISO-8859-1; si la línea ya es UTF-8 se usa tal cual
+            let linea = match std::str::from_utf8(&buffer) {
+                Ok(texto) => Cow::Borrowed(texto),
+                Err(_) => Cow::Owned(buffer.iter().map(|&b| b as char).collect::<String>()),
+            };
+            let campos: Vec<&str> = linea.trim_end_matches(['\r', '\n']).split('|').map(str::trim).collect();
+
+            // Encabezado, líneas vacías o RUC inválido
+            let ruc = campos[0];
+            if campos.len() < 5 || validar_ruc(ruc).is_err() || valor(campos[1]).is_none() {
+                if !ruc.is_empty() && !ruc.starts_with("RUC") {
+                    omitidos += 1;
+                }
+                continue;
+            }
+
+            stmt.execute(params![
+                ruc,
+                campos[1],
+                valor(campos[2]),
+                valor(campos[3]),
+                valor(campos[4]),
+                armar_direccion(&campos),
+            ])
+            .map_err(|e| rollback(format!("Error al guardar RUC {}: {}", ruc, e)))?;
+            importados += 1;
+        }
+        (importados, omitidos)
+    };
+
+    if importados == 0 {
+        return Err(rollback("❌ El archivo no tiene contribuyentes válidos".to_string()));
+    }
+
+    conn.execute("COMMIT", [])
+        .map_err(|e| rollback(format!("Error al confirmar importación: {}", e)))?;
+
+    let registros: i64 = conn
+        .query_row("SELECT COUNT(*) FROM padron_ruc", [], |row| row.get(0))
+        .map_err(|e| format!("Error al contar padrón: {}", e))?;
+
+    Ok(ResultadoImportacionPadron { importados, omitidos, registros })
+}
+
+// =====================================================
+// COMANDO: Consultar un RUC (autocompletar razón social y dirección)
+// =====================================================
+// Error si el RUC no es válido; None si no está en el padrón local
+#[tauri::command]
+pub fn consultar_ruc(
+    db: tauri::State<'_, DatabasePool>,
+    ruc: String,
+) -> Result<Option<Contribuyente>, String> {
+    let conn = db.get_conn();
+    let ruc = validar_ruc(&ruc)?;
+    buscar_contribuyente(&conn, &ruc)
+}
+
+// =====================================================
+// COMANDO: Estado del padrón local
+// =====================================================
+#[tauri::command]
+pub fn obtener_estado_padron(
+    db: tauri::State<'_, DatabasePool>,
+) -> Result<EstadoPadron, String> {
+    let conn = db.get_conn();
+
+    conn.query_row(
+        "SELECT COUNT(*), MAX(fecha_actualizacion) FROM padron_ruc",
+        [],
+        |row| {
+            Ok(EstadoPadron {
+                registros: row.get(0)?,
+                fecha_actualizacion: row.get(1)?,
+            })
+        },
+    )
+    .map_err(|e| format!("Error al obtener estado del padrón: {}", e))
+}
+
+// =====================================================
+// FUNCIONES AUXILIARES
+// =====================================================
+
+pub fn buscar_contribuyente(conn: &rusqlite::Connection, ruc: &str) -> Result<Option<Contribuyente>, String> {
+    conn.query_row(
+        r"SELECT ruc, razon_social, estado, condicion, ubigeo, direccion, fecha_actualizacion
+          FROM padron_ruc WHERE ruc = ?",
+        params![ruc],
+        |row| {
+            Ok(Contribuyente {
+                ruc: row.get(0)?,
+                razon_social: row.get(1)?,
+                estado: row.get(2)?,
+                condicion: row.get(3)?,
+                ubigeo: row.get(4)?,
+                direccion: row.get(5)?,
+                fecha_actualizacion: row.get(6)?,
+            })
+        },
+    )
+    .optional()
+    .map_err(|e| format!("Error al consultar padrón: {}", e))
+}
+
+fn valor(campo: &str) -> Option<&str> {
+    match campo {
+        "" | "-" | "----" => None,
+        c => Some(c),
+    }
+}
+
+// Ej. "AV. LOS OLIVOS NRO. 123 INT. 4 URB. LAS FLORES"
+fn armar_direccion(campos: &[&str]) -> Option<String> {
+    let campo = |i: usize| campos.get(i).copied().and_then(valor);
+
+    let mut partes: Vec<String> = Vec::new();
+    partes.extend(campo(5).map(str::to_string)); // tipo de vía
+    partes.extend(campo(6).map(str::to_string)); // nombre de vía
+    for (indice, etiqueta) in [(9, "NRO."), (10, "INT."), (13, "MZA."), (11, "LOTE"), (12, "DPTO."), (14, "KM.")] {
+        if let Some(v) = campo(indice) {
+            partes.push(format!("{} {}", etiqueta, v));
+        }
+    }
+    partes.extend(campo(7).map(str::to_string)); // código de zona (URB., A.H., ...)
+    partes.extend(campo(8).map(str::to_string)); // nombre de la zona
+
+    if partes.is_empty() {
+        None
+    } else {
+        Some(partes.join(" "))
+    }
+}
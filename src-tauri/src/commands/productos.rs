@@ -18,7 +18,8 @@ pub fn obtener_productos(db: tauri::State<'_, DatabasePool>) -> ProductosRespons
         SELECT 
             p.id, p.codigo, p.nombre, p.descripcion, p.precio, 
             p.stock, p.stock_minimo, p.categoria_id, c.nombre as categoria_nombre, 
-            p.descuento_porcentaje, p.tiene_variantes, p.activo
+            p.descuento_porcentaje, p.tiene_variantes, p.activo,
+            COALESCE(p.afectacion_igv, 'GRAVADO')
         FROM productos p
         LEFT JOIN categorias c ON p.categoria_id = c.id
         WHERE p.activo = 1
@@ -44,6 +45,7 @@ pub fn obtener_productos(db: tauri::State<'_, DatabasePool>) -> ProductosRespons
             descuento_porcentaje: row.get(9)?,
             tiene_variantes: row.get::<_, i32>(10)? == 1,
             activo: row.get::<_, i32>(11)? == 1,
+            afectacion_igv: row.get(12)?,
         })
     });
 
@@ -70,7 +72,8 @@ pub fn buscar_producto_por_codigo(
         SELECT 
             p.id, p.codigo, p.nombre, p.descripcion, p.precio, 
             p.stock, p.stock_minimo, p.categoria_id, c.nombre as categoria_nombre, 
-            p.descuento_porcentaje, p.tiene_variantes, p.activo
+            p.descuento_porcentaje, p.tiene_variantes, p.activo,
+            COALESCE(p.afectacion_igv, 'GRAVADO')
         FROM productos p
         LEFT JOIN categorias c ON p.categoria_id = c.id
         WHERE p.codigo = ? AND p.activo = 1
@@ -99,6 +102,7 @@ pub fn buscar_producto_por_codigo(
             descuento_porcentaje: row.get(9)?,
             tiene_variantes: row.get::<_, i32>(10)? == 1,
             activo: row.get::<_, i32>(11)? == 1,
+            afectacion_igv: row.get(12)?,
         })
     }).optional();
 
@@ -133,12 +137,21 @@ pub fn agregar_producto(
 
     let tiene_variantes = producto.tiene_variantes.unwrap_or(false);
 
+    let afectacion_igv = producto.afectacion_igv.as_deref().unwrap_or("GRAVADO");
+    if !["GRAVADO", "EXONERADO", "INAFECTO"].contains(&afectacion_igv) {
+        return ProductoResponse {
+            success: false,
+            message: format!("Afectación de IGV no válida: {}", afectacion_igv),
+            producto: None,
+        };
+    }
+
     // Si tiene variantes el stock inicial es 0 (lo calculan los triggers)
     let stock_inicial = if tiene_variantes { 0 } else { producto.stock };
 
     let query = r"
-        INSERT INTO productos (codigo, nombre, descripcion, precio, stock, stock_minimo, categoria_id, descuento_porcentaje, tiene_variantes)
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        INSERT INTO productos (codigo, nombre, descripcion, precio, stock, stock_minimo, categoria_id, descuento_porcentaje, tiene_variantes, afectacion_igv)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
     ";
 
     let result = conn.execute(
@@ -153,6 +166,7 @@ pub fn agregar_producto(
             producto.categoria_id,
             producto.descuento_porcentaje.unwrap_or(0.0),
             if tiene_variantes { 1 } else { 0 },
+            afectacion_igv,
         ],
     );
 
@@ -204,11 +218,20 @@ pub fn actualizar_producto(
     descuento_porcentaje: Option<f64>,
     tiene_variantes: Option<bool>,
     variantes: Option<Vec<VarianteInput>>,
+    afectacion_igv: Option<String>, // 🆕 None = se mantiene la actual
 ) -> ProductoResponse {
     let conn = db.get_conn();
 
     let con_variantes = tiene_variantes.unwrap_or(false);
 
+    if let Some(a) = afectacion_igv.as_deref().filter(|a| !["GRAVADO", "EXONERADO", "INAFECTO"].contains(a)) {
+        return ProductoResponse {
+            success: false,
+            message: format!("Afectación de IGV no válida: {}", a),
+            producto: None,
+        };
+    }
+
     // Si tiene variantes el stock lo calculan los triggers automáticamente
     let stock_a_guardar = if con_variantes { 
         // Obtener el stock actual calculado
@@ -226,6 +249,7 @@ pub fn actualizar_producto(
         SET codigo = ?, nombre = ?, descripcion = ?, precio = ?,
             stock = ?, stock_minimo = ?, categoria_id = ?,
             descuento_porcentaje = ?, tiene_variantes = ?,
+            afectacion_igv = COALESCE(?, afectacion_igv),
             fecha_actualizacion = datetime('now', 'localtime')
         WHERE id = ?
     ";
@@ -237,6 +261,7 @@ pub fn actualizar_producto(
             stock_a_guardar, stock_minimo, categoria_id,
             descuento_porcentaje.unwrap_or(0.0),
             if con_variantes { 1 } else { 0 },
+            &afectacion_igv,
             producto_id,
         ],
     );
@@ -306,6 +331,7 @@ pub fn obtener_productos_stock_bajo(db: tauri::State<'_, DatabasePool>) -> Produ
             descuento_porcentaje: 0.0,
             tiene_variantes: false,
             activo: true,
+            afectacion_igv: "GRAVADO".to_string(),
         })
     });
 
@@ -394,7 +420,8 @@ pub fn buscar_productos_filtrado(
         SELECT 
             p.id, p.codigo, p.nombre, p.descripcion, p.precio, 
             p.stock, p.stock_minimo, p.categoria_id, c.nombre as categoria_nombre, 
-            p.descuento_porcentaje, p.tiene_variantes, p.activo
+            p.descuento_porcentaje, p.tiene_variantes, p.activo,
+            COALESCE(p.afectacion_igv, 'GRAVADO')
         FROM productos p
         LEFT JOIN categorias c ON p.categoria_id = c.id
         WHERE p.activo = 1 AND p.stock > 0
@@ -441,6 +468,7 @@ pub fn buscar_productos_filtrado(
                 descuento_porcentaje: row.get(9)?,
                 tiene_variantes: row.get::<_, i32>(10)? == 1,
                 activo: row.get::<_, i32>(11)? == 1,
+                afectacion_igv: row.get(12)?,
             })
         })
         .map_err(|e| format!("Error al ejecutar consulta: {}", e))?;
@@ -496,7 +524,8 @@ pub fn obtener_producto_con_variantes(
     let producto = conn.query_row(
         r"SELECT p.id, p.codigo, p.nombre, p.descripcion, p.precio,
                  p.stock, p.stock_minimo, p.categoria_id, c.nombre,
-                 p.descuento_porcentaje, p.tiene_variantes, p.activo
+                 p.descuento_porcentaje, p.tiene_variantes, p.activo,
+                 COALESCE(p.afectacion_igv, 'GRAVADO')
           FROM productos p
           LEFT JOIN categorias c ON c.id = p.categoria_id
           WHERE p.id = ?",
@@ -515,6 +544,7 @@ pub fn obtener_producto_con_variantes(
                 descuento_porcentaje: row.get(9)?,
                 tiene_variantes: row.get::<_, i32>(10)? == 1,
                 activo: row.get::<_, i32>(11)? == 1,
+                afectacion_igv: row.get(12)?,
             })
         },
     )
@@ -0,0 +1,653 @@
+// commands/promociones.rs
+// Motor de promociones: 2x1 / 3x2, segunda unidad al X%, descuento por
+// categoría y descuento por monto de compra, con vigencia por fecha, día y hora.
+//
+// Reglas de combinación:
+//  - Se evalúan de mayor a menor prioridad.
+//  - Una promoción NO acumulable solo entra en líneas sin otra promoción, y
+//    después de aplicarse bloquea esas líneas para las siguientes.
+//  - El descuento manual del cajero se aplica primero; las promociones
+//    trabajan sobre el precio ya rebajado.
+
+use crate::database::DatabasePool;
+use crate::commands::ventas::ProductoVenta;
+use crate::models::promocion::*;
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
+use rusqlite::params;
+
+const TIPOS_PROMOCION: [&str; 4] = ["NXM", "SEGUNDA_UNIDAD", "DESCUENTO_CATEGORIA", "DESCUENTO_MONTO"];
+
+// =====================================================
+// COMANDO: Listar promociones
+// =====================================================
+#[tauri::command]
+pub fn obtener_promociones(
+    db: tauri::State<'_, DatabasePool>,
+    solo_vigentes: Option<bool>,
+) -> Result<Vec<Promocion>, String> {
+    let conn = db.get_conn();
+
+    let promociones = cargar_promociones(&conn, false)?;
+
+    if solo_vigentes.unwrap_or(false) {
+        let ahora = chrono::Local::now().naive_local();
+        Ok(promociones.into_iter().filter(|p| p.activo && esta_vigente(p, &ahora)).collect())
+    } else {
+        Ok(promociones)
+    }
+}
+
+// =====================================================
+// COMANDO: Agregar promoción
+// =====================================================
+#[tauri::command]
+pub fn agregar_promocion(
+    db: tauri::State<'_, DatabasePool>,
+    promocion: PromocionNueva,
+) -> Result<String, String> {
+    let conn = db.get_conn();
+
+    validar_promocion(&promocion)?;
+
+    conn.execute(
+        r"INSERT INTO promociones
+            (nombre, tipo, lleva, paga, porcentaje, monto_minimo, monto_descuento,
+             categoria_id, producto_id, fecha_inicio, fecha_fin, dias_semana,
+             hora_inicio, hora_fin, prioridad, acumulable, activo)
+          VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        params![
+            promocion.nombre.trim(),
+            &promocion.tipo,
+            promocion.lleva,
+            promocion.paga,
+            promocion.porcentaje,
+            promocion.monto_minimo,
+            promocion.monto_descuento,
+            promocion.categoria_id,
+            promocion.producto_id,
+            &promocion.fecha_inicio,
+            &promocion.fecha_fin,
+            &promocion.dias_semana,
+            &promocion.hora_inicio,
+            &promocion.hora_fin,
+            promocion.prioridad.unwrap_or(0),
+            promocion.acumulable.unwrap_or(false) as i32,
+            promocion.activo.unwrap_or(true) as i32,
+        ],
+    )
+    .map_err(|e| format!("Error al agregar promoción: {}", e))?;
+
+    Ok("✅ Promoción agregada exitosamente".to_string())
+}
+
+// =====================================================
+// COMANDO: Actualizar promoción (también para activar/desactivar)
+// =====================================================
+#[tauri::command]
+pub fn actualizar_promocion(
+    db: tauri::State<'_, DatabasePool>,
+    promocion_id: i32,
+    promocion: PromocionNueva,
+) -> Result<String, String> {
+    let conn = db.get_conn();
+
+    validar_promocion(&promocion)?;
+
+    let filas = conn
+        .execute(
+            r"UPDATE promociones
+              SET nombre = ?, tipo = ?, lleva = ?, paga = ?, porcentaje = ?,
+                  monto_minimo = ?, monto_descuento = ?, categoria_id = ?, producto_id = ?,
+                  fecha_inicio = ?, fecha_fin = ?, dias_semana = ?, hora_inicio = ?, hora_fin = ?,
+                  prioridad = ?, acumulable = ?, activo = ?
+              WHERE id = ?",
+            params![
+                promocion.nombre.trim(),
+                &promocion.tipo,
+                promocion.lleva,
+                promocion.paga,
+                promocion.porcentaje,
+                promocion.monto_minimo,
+                promocion.monto_descuento,
+                promocion.categoria_id,
+                promocion.producto_id,
+                &promocion.fecha_inicio,
+                &promocion.fecha_fin,
+                &promocion.dias_semana,
+                &promocion.hora_inicio,
+                &promocion.hora_fin,
+                promocion.prioridad.unwrap_or(0),
+                promocion.acumulable.unwrap_or(false) as i32,
+                promocion.activo.unwrap_or(true) as i32,
+                promocion_id,
+            ],
+        )
+        .map_err(|e| format!("Error al actualizar promoción: {}", e))?;
+
+    if filas == 0 {
+        return Err("❌ Promoción no encontrada".to_string());
+    }
+
+    Ok("✅ Promoción actualizada exitosamente".to_string())
+}
+
+// =====================================================
+// COMANDO: Evaluar promociones sobre el carrito del POS
+// =====================================================
+#[tauri::command]
+pub fn evaluar_promociones(
+    db: tauri::State<'_, DatabasePool>,
+    productos: Vec<ProductoVenta>,
+) -> Result<ResultadoPromociones, String> {
+    let conn = db.get_conn();
+    evaluar_carrito(&conn, &productos)
+}
+
+// =====================================================
+// MOTOR
+// =====================================================
+
+// Estado de trabajo de una línea mientras se aplican las promociones
+struct LineaTrabajo {
+    producto_id: i32,
+    categoria_id: Option<i32>,
+    cantidad: i32,
+    subtotal: f64,
+    descuento_manual: f64,
+    descuento_promocion: f64,
+    promociones: Vec<PromocionAplicada>,
+    bloqueada: bool,
+}
+
+impl LineaTrabajo {
+    // Lo que falta cobrar de la línea
+    fn neto(&self) -> f64 {
+        self.subtotal - self.descuento_manual - self.descuento_promocion
+    }
+}
+
+// Calcula los descuentos de promociones vigentes para un carrito.
+// procesar_venta usa esta misma función, así el POS y la venta siempre coinciden.
+pub fn evaluar_carrito(
+    conn: &rusqlite::Connection,
+    productos: &[ProductoVenta],
+) -> Result<ResultadoPromociones, String> {
+    let ahora = chrono::Local::now().naive_local();
+
+    let promociones: Vec<Promocion> = cargar_promociones(conn, true)?
+        .into_iter()
+        .filter(|p| esta_vigente(p, &ahora))
+        .collect();
+
+    let mut lineas: Vec<LineaTrabajo> = Vec::with_capacity(productos.len());
+    for p in productos {
+        let categoria_id: Option<i32> = conn
+            .query_row(
+                "SELECT categoria_id FROM productos WHERE id = ?",
+                params![p.id],
+                |row| row.get(0),
+            )
+            .ok();
+        let subtotal = p.precio * p.cantidad as f64;
+        lineas.push(LineaTrabajo {
+            producto_id: p.id,
+            categoria_id,
+            cantidad: p.cantidad,
+            subtotal,
+            descuento_manual: subtotal * (p.descuento_porcentaje.unwrap_or(0.0) / 100.0),
+            descuento_promocion: 0.0,
+            promociones: Vec::new(),
+            bloqueada: false,
+        });
+    }
+
+    Ok(aplicar_promociones(promociones, lineas))
+}
+
+// Aplica las promociones en orden de prioridad sobre las líneas ya armadas
+fn aplicar_promociones(mut promociones: Vec<Promocion>, mut lineas: Vec<LineaTrabajo>) -> ResultadoPromociones {
+    promociones.sort_by(|a, b| b.prioridad.cmp(&a.prioridad).then(a.id.cmp(&b.id)));
+
+    for promo in &promociones {
+        let elegibles: Vec<usize> = lineas
+            .iter()
+            .enumerate()
+            .filter(|(_, l)| {
+                !l.bloqueada
+                    && (promo.acumulable || l.promociones.is_empty())
+                    && promo.producto_id.is_none_or(|id| id == l.producto_id)
+                    && promo.categoria_id.is_none_or(|id| Some(id) == l.categoria_id)
+                    && l.neto() > 0.0
+            })
+            .map(|(i, _)| i)
+            .collect();
+
+        if elegibles.is_empty() {
+            continue;
+        }
+
+        let descuentos: Vec<(usize, f64)> = match promo.tipo.as_str() {
+            "NXM" => descuento_por_grupos(
+                &lineas,
+                &elegibles,
+                promo.lleva.unwrap_or(0),
+                promo.paga.unwrap_or(0),
+                100.0,
+            ),
+            "SEGUNDA_UNIDAD" => descuento_por_grupos(&lineas, &elegibles, 2, 1, promo.porcentaje.unwrap_or(0.0)),
+            "DESCUENTO_CATEGORIA" => elegibles
+                .iter()
+                .map(|&i| (i, lineas[i].neto() * promo.porcentaje.unwrap_or(0.0) / 100.0))
+                .collect(),
+            "DESCUENTO_MONTO" => descuento_por_monto(&lineas, &elegibles, promo),
+            _ => Vec::new(),
+        };
+
+        for (i, descuento) in descuentos {
+            let descuento = redondear(descuento.min(lineas[i].neto()));
+            if descuento <= 0.0 {
+                continue;
+            }
+            let linea = &mut lineas[i];
+            linea.descuento_promocion += descuento;
+            linea.promociones.push(PromocionAplicada {
+                promocion_id: promo.id,
+                nombre: promo.nombre.clone(),
+                descuento,
+            });
+            if !promo.acumulable {
+                linea.bloqueada = true;
+            }
+        }
+    }
+
+    let lineas: Vec<LineaPromocion> = lineas
+        .into_iter()
+        .enumerate()
+        .map(|(indice, l)| LineaPromocion {
+            indice,
+            subtotal: redondear(l.subtotal),
+            descuento_manual: redondear(l.descuento_manual),
+            descuento_promocion: redondear(l.descuento_promocion),
+            total_linea: redondear(l.subtotal - l.descuento_manual - l.descuento_promocion),
+            promociones: l.promociones,
+        })
+        .collect();
+
+    let subtotal = lineas.iter().map(|l| l.subtotal).sum::<f64>();
+    let descuento_manual = lineas.iter().map(|l| l.descuento_manual).sum::<f64>();
+    let descuento_promociones = lineas.iter().map(|l| l.descuento_promocion).sum::<f64>();
+    let total = lineas.iter().map(|l| l.total_linea).sum::<f64>();
+
+    ResultadoPromociones {
+        lineas,
+        subtotal: redondear(subtotal),
+        descuento_manual: redondear(descuento_manual),
+        descuento_promociones: redondear(descuento_promociones),
+        total: redondear(total),
+    }
+}
+
+// NXM y segunda unidad: se juntan las unidades de todas las líneas elegibles
+// (p. ej. polos de distintas tallas), de la más cara a la más barata, y en cada
+// grupo de `lleva` unidades las `lleva - paga` más baratas reciben el descuento.
+fn descuento_por_grupos(
+    lineas: &[LineaTrabajo],
+    elegibles: &[usize],
+    lleva: i32,
+    paga: i32,
+    porcentaje: f64,
+) -> Vec<(usize, f64)> {
+    if lleva < 2 || paga < 1 || paga >= lleva {
+        return Vec::new();
+    }
+
+    let mut unidades: Vec<(f64, usize)> = Vec::new();
+    for &i in elegibles {
+        let precio_unidad = lineas[i].neto() / lineas[i].cantidad as f64;
+        for _ in 0..lineas[i].cantidad {
+            unidades.push((precio_unidad, i));
+        }
+    }
+    unidades.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut descuentos: Vec<(usize, f64)> = Vec::new();
+    for grupo in unidades.chunks(lleva as usize) {
+        if grupo.len() < lleva as usize {
+            break;
+        }
+        for &(precio, i) in &grupo[paga as usize..] {
+            let d = precio * porcentaje / 100.0;
+            match descuentos.iter_mut().find(|(j, _)| *j == i) {
+                Some((_, acumulado)) => *acumulado += d,
+                None => descuentos.push((i, d)),
+            }
+        }
+    }
+    descuentos
+}
+
+// Compra desde S/ X: S/ Y menos (o Z%), repartido en proporción a cada línea
+fn descuento_por_monto(lineas: &[LineaTrabajo], elegibles: &[usize], promo: &Promocion) -> Vec<(usize, f64)> {
+    let base: f64 = elegibles.iter().map(|&i| lineas[i].neto()).sum();
+    if base <= 0.0 || base + 0.001 < promo.monto_minimo.unwrap_or(0.0) {
+        return Vec::new();
+    }
+
+    let descuento_total = match (promo.monto_descuento, promo.porcentaje) {
+        (Some(monto), _) => monto.min(base),
+        (None, Some(pct)) => base * pct / 100.0,
+        _ => return Vec::new(),
+    };
+    let descuento_total = redondear(descuento_total);
+
+    // La última línea absorbe el redondeo para que la suma cuadre exacta
+    let mut repartido = 0.0;
+    let mut descuentos = Vec::new();
+    for (n, &i) in elegibles.iter().enumerate() {
+        let d = if n + 1 == elegibles.len() {
+            descuento_total - repartido
+        } else {
+            redondear(descuento_total * lineas[i].neto() / base)
+        };
+        repartido += d;
+        descuentos.push((i, d));
+    }
+    descuentos
+}
+
+// =====================================================
+// FUNCIONES AUXILIARES
+// =====================================================
+
+fn cargar_promociones(conn: &rusqlite::Connection, solo_activas: bool) -> Result<Vec<Promocion>, String> {
+    let query = format!(
+        r"SELECT id, nombre, tipo, lleva, paga, porcentaje, monto_minimo, monto_descuento,
+                 categoria_id, producto_id, fecha_inicio, fecha_fin, dias_semana,
+                 hora_inicio, hora_fin, prioridad, acumulable, activo
+          FROM promociones {}
+          ORDER BY prioridad DESC, id",
+        if solo_activas { "WHERE activo = 1" } else { "" }
+    );
+
+    let mut stmt = conn
+        .prepare(&query)
+        .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+
+    let promociones = stmt
+        .query_map([], |row| {
+            Ok(Promocion {
+                id: row.get(0)?,
+                nombre: row.get(1)?,
+                tipo: row.get(2)?,
+                lleva: row.get(3)?,
+                paga: row.get(4)?,
+                porcentaje: row.get(5)?,
+                monto_minimo: row.get(6)?,
+                monto_descuento: row.get(7)?,
+                categoria_id: row.get(8)?,
+                producto_id: row.get(9)?,
+                fecha_inicio: row.get(10)?,
+                fecha_fin: row.get(11)?,
+                dias_semana: row.get(12)?,
+                hora_inicio: row.get(13)?,
+                hora_fin: row.get(14)?,
+                prioridad: row.get::<_, Option<i32>>(15)?.unwrap_or(0),
+                acumulable: row.get::<_, Option<i32>>(16)?.unwrap_or(0) == 1,
+                activo: row.get::<_, Option<i32>>(17)?.unwrap_or(1) == 1,
+            })
+        })
+        .map_err(|e| format!("Error al obtener promociones: {}", e))?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| format!("Error al leer promociones: {}", e))?;
+
+    Ok(promociones)
+}
+
+fn esta_vigente(promo: &Promocion, ahora: &NaiveDateTime) -> bool {
+    let hoy = ahora.format("%Y-%m-%d").to_string();
+    if promo.fecha_inicio.as_deref().is_some_and(|f| hoy.as_str() < f) {
+        return false;
+    }
+    if promo.fecha_fin.as_deref().is_some_and(|f| hoy.as_str() > f) {
+        return false;
+    }
+
+    if let Some(dias) = promo.dias_semana.as_deref().filter(|d| !d.trim().is_empty()) {
+        let dia = ahora.weekday().number_from_monday();
+        if !dias.split(',').any(|d| d.trim().parse::<u32>().ok() == Some(dia)) {
+            return false;
+        }
+    }
+
+    let hora = ahora.format("%H:%M").to_string();
+    match (promo.hora_inicio.as_deref(), promo.hora_fin.as_deref()) {
+        (Some(inicio), Some(fin)) if inicio <= fin => inicio <= hora.as_str() && hora.as_str() < fin,
+        // Franja que cruza la medianoche (ej. 22:00 - 02:00)
+        (Some(inicio), Some(fin)) => hora.as_str() >= inicio || hora.as_str() < fin,
+        (Some(inicio), None) => hora.as_str() >= inicio,
+        (None, Some(fin)) => hora.as_str() < fin,
+        (None, None) => true,
+    }
+}
+
+fn validar_promocion(p: &PromocionNueva) -> Result<(), String> {
+    if p.nombre.trim().is_empty() {
+        return Err("❌ La promoción debe tener un nombre".to_string());
+    }
+    if !TIPOS_PROMOCION.contains(&p.tipo.as_str()) {
+        return Err(format!("❌ Tipo de promoción no válido: {}", p.tipo));
+    }
+
+    match p.tipo.as_str() {
+        "NXM" => match (p.lleva, p.paga) {
+            (Some(lleva), Some(paga)) if lleva >= 2 && paga >= 1 && paga < lleva => {}
+            _ => return Err("❌ En una promoción NxM se lleva más de lo que se paga (ej. 2x1, 3x2)".to_string()),
+        },
+        "SEGUNDA_UNIDAD" | "DESCUENTO_CATEGORIA" if p.porcentaje.is_none() => {
+            return Err("❌ Indica el porcentaje de descuento".to_string());
+        }
+        "DESCUENTO_MONTO" => {
+            if p.monto_minimo.unwrap_or(0.0) <= 0.0 {
+                return Err("❌ Indica el monto mínimo de compra".to_string());
+            }
+            if p.monto_descuento.is_some() == p.porcentaje.is_some() {
+                return Err("❌ Indica un descuento en soles o un porcentaje (solo uno)".to_string());
+            }
+            if p.monto_descuento.is_some_and(|m| m <= 0.0) {
+                return Err("❌ El descuento debe ser mayor a 0".to_string());
+            }
+        }
+        _ => {}
+    }
+
+    if p.porcentaje.is_some_and(|pct| pct <= 0.0 || pct > 100.0) {
+        return Err("❌ El porcentaje debe estar entre 0 y 100".to_string());
+    }
+
+    for fecha in [&p.fecha_inicio, &p.fecha_fin].into_iter().flatten() {
+        if NaiveDate::parse_from_str(fecha, "%Y-%m-%d").is_err() {
+            return Err(format!("❌ Fecha no válida: {} (usa AAAA-MM-DD)", fecha));
+        }
+    }
+    if let (Some(inicio), Some(fin)) = (&p.fecha_inicio, &p.fecha_fin) {
+        if inicio > fin {
+            return Err("❌ La fecha de inicio es posterior a la fecha de fin".to_string());
+        }
+    }
+
+    for hora in [&p.hora_inicio, &p.hora_fin].into_iter().flatten() {
+        if NaiveTime::parse_from_str(hora, "%H:%M").is_err() {
+            return Err(format!("❌ Hora no válida: {} (usa HH:MM)", hora));
+        }
+    }
+
+    if let Some(dias) = &p.dias_semana {
+        if dias.split(',').any(|d| !matches!(d.trim().parse::<u32>(), Ok(1..=7))) {
+            return Err("❌ Días de la semana no válidos (usa 1 = lunes ... 7 = domingo)".to_string());
+        }
+    }
+
+    Ok(())
+}
+
+// Redondeo a céntimos
+pub fn redondear(valor: f64) -> f64 {
+    (valor * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn promo(id: i32, tipo: &str, prioridad: i32, acumulable: bool) -> Promocion {
+        Promocion {
+            id,
+            nombre: format!("Promo {}", id),
+            tipo: tipo.to_string(),
+            lleva: None,
+            paga: None,
+            porcentaje: None,
+            monto_minimo: None,
+            monto_descuento: None,
+            categoria_id: None,
+            producto_id: None,
+            fecha_inicio: None,
+            fecha_fin: None,
+            dias_semana: None,
+            hora_inicio: None,
+            hora_fin: None,
+            prioridad,
+            acumulable,
+            activo: true,
+        }
+    }
+
+    fn linea(producto_id: i32, categoria_id: i32, precio: f64, cantidad: i32, descuento_pct: f64) -> LineaTrabajo {
+        let subtotal = precio * cantidad as f64;
+        LineaTrabajo {
+            producto_id,
+            categoria_id: Some(categoria_id),
+            cantidad,
+            subtotal,
+            descuento_manual: subtotal * descuento_pct / 100.0,
+            descuento_promocion: 0.0,
+            promociones: Vec::new(),
+            bloqueada: false,
+        }
+    }
+
+    fn dos_por_uno(id: i32, prioridad: i32, acumulable: bool) -> Promocion {
+        Promocion { lleva: Some(2), paga: Some(1), ..promo(id, "NXM", prioridad, acumulable) }
+    }
+
+    fn categoria(id: i32, prioridad: i32, acumulable: bool, categoria_id: i32, pct: f64) -> Promocion {
+        Promocion {
+            categoria_id: Some(categoria_id),
+            porcentaje: Some(pct),
+            ..promo(id, "DESCUENTO_CATEGORIA", prioridad, acumulable)
+        }
+    }
+
+    #[test]
+    fn dos_por_uno_descuenta_la_unidad_mas_barata() {
+        let r = aplicar_promociones(
+            vec![dos_por_uno(1, 0, false)],
+            vec![linea(1, 1, 50.0, 1, 0.0), linea(2, 1, 30.0, 1, 0.0)],
+        );
+        assert_eq!(r.lineas[0].descuento_promocion, 0.0);
+        assert_eq!(r.lineas[1].descuento_promocion, 30.0);
+        assert_eq!(r.total, 50.0);
+    }
+
+    #[test]
+    fn prioridad_mayor_no_acumulable_bloquea_las_siguientes() {
+        // Se pasan desordenadas: el motor las ordena por prioridad
+        let r = aplicar_promociones(
+            vec![categoria(2, 5, true, 1, 10.0), dos_por_uno(1, 10, false)],
+            vec![linea(1, 1, 40.0, 2, 0.0)],
+        );
+        assert_eq!(r.lineas[0].descuento_promocion, 40.0);
+        assert_eq!(r.lineas[0].promociones.len(), 1);
+        assert_eq!(r.lineas[0].promociones[0].promocion_id, 1);
+        assert_eq!(r.total, 40.0);
+    }
+
+    #[test]
+    fn no_acumulable_no_entra_en_lineas_con_otra_promocion() {
+        let r = aplicar_promociones(
+            vec![categoria(1, 10, true, 1, 10.0), categoria(2, 5, false, 1, 50.0)],
+            vec![linea(1, 1, 100.0, 1, 0.0), linea(2, 2, 100.0, 1, 0.0)],
+        );
+        assert_eq!(r.lineas[0].descuento_promocion, 10.0);
+        assert_eq!(r.lineas[1].descuento_promocion, 0.0);
+        assert_eq!(r.total, 190.0);
+    }
+
+    #[test]
+    fn acumulables_se_aplican_sobre_el_neto_restante() {
+        let r = aplicar_promociones(
+            vec![
+                categoria(1, 10, true, 1, 10.0),
+                Promocion {
+                    monto_minimo: Some(50.0),
+                    porcentaje: Some(20.0),
+                    ..promo(2, "DESCUENTO_MONTO", 5, true)
+                },
+            ],
+            vec![linea(1, 1, 100.0, 1, 0.0)],
+        );
+        // 100 - 10% = 90, luego 20% de 90 = 18
+        assert_eq!(r.lineas[0].descuento_promocion, 28.0);
+        assert_eq!(r.lineas[0].promociones.len(), 2);
+        assert_eq!(r.total, 72.0);
+    }
+
+    #[test]
+    fn misma_prioridad_se_ordena_por_id() {
+        let r = aplicar_promociones(
+            vec![categoria(7, 0, false, 1, 50.0), categoria(3, 0, false, 1, 10.0)],
+            vec![linea(1, 1, 100.0, 1, 0.0)],
+        );
+        assert_eq!(r.lineas[0].promociones[0].promocion_id, 3);
+        assert_eq!(r.lineas[0].descuento_promocion, 10.0);
+    }
+
+    #[test]
+    fn descuento_manual_va_antes_que_las_promociones() {
+        let r = aplicar_promociones(
+            vec![categoria(1, 0, false, 1, 10.0)],
+            vec![linea(1, 1, 100.0, 1, 20.0)],
+        );
+        assert_eq!(r.lineas[0].descuento_manual, 20.0);
+        assert_eq!(r.lineas[0].descuento_promocion, 8.0);
+        assert_eq!(r.total, 72.0);
+    }
+
+    #[test]
+    fn descuento_por_monto_reparte_y_cuadra_al_centimo() {
+        let r = aplicar_promociones(
+            vec![Promocion {
+                monto_minimo: Some(100.0),
+                monto_descuento: Some(10.0),
+                ..promo(1, "DESCUENTO_MONTO", 0, false)
+            }],
+            vec![linea(1, 1, 33.33, 1, 0.0), linea(2, 1, 33.33, 1, 0.0), linea(3, 1, 33.34, 1, 0.0)],
+        );
+        assert_eq!(r.descuento_promociones, 10.0);
+        assert_eq!(r.total, 90.0);
+    }
+
+    #[test]
+    fn descuento_por_monto_no_aplica_bajo_el_minimo() {
+        let r = aplicar_promociones(
+            vec![Promocion {
+                monto_minimo: Some(200.0),
+                monto_descuento: Some(10.0),
+                ..promo(1, "DESCUENTO_MONTO", 0, false)
+            }],
+            vec![linea(1, 1, 150.0, 1, 0.0)],
+        );
+        assert_eq!(r.descuento_promociones, 0.0);
+    }
+}
@@ -11,6 +11,9 @@ use crate::models::proveedor::{
     RegistrarDevolucionProveedorRequest, ResolverDevolucionRequest,
     DevolucionProveedorResponse, DevolucionesProveedorResponse,
 };
+use crate::commands::numeracion::siguiente_folio;
+use crate::commands::cuarentena::{reingresar_a_cuarentena, retirar_de_cuarentena};
+use crate::commands::idempotencia::{normalizar_clave, registrar_resultado, resultado_previo};
 use rusqlite::params;
 use rusqlite::OptionalExtension;
 
@@ -300,6 +303,22 @@ pub fn crear_compra(
 ) -> CompraResponse {
     let conn = db.get_conn();
 
+    // 🆕 Reintento de una compra ya registrada: mismo resultado, sin duplicarla
+    let clave = match normalizar_clave(request.clave_idempotencia.clone())
+        .and_then(|c| resultado_previo::<CompraResponse>(&conn, "COMPRA", c.as_deref()).map(|previa| (c, previa)))
+    {
+        Ok((_, Some(previa))) => return previa,
+        Ok((c, None)) => c,
+        Err(e) => {
+            return CompraResponse {
+                success: false,
+                message: e,
+                compra_id: None,
+                folio: None,
+            }
+        }
+    };
+
     let subtotal: f64 = request.items.iter()
         .map(|i| i.precio_compra * i.cantidad as f64)
         .sum();
@@ -328,18 +347,31 @@ pub fn crear_compra(
         }
     }
 
-    // Generar folio C-YYYYMMDD-####
-    let fecha = chrono::Local::now().format("%Y%m%d").to_string();
-    let siguiente: i32 = conn.query_row(
-        &format!(
-            "SELECT COALESCE(MAX(CAST(substr(folio,-4) AS INTEGER)),0)+1
-             FROM compras WHERE folio LIKE 'C-{}%'",
-            fecha
-        ),
-        [],
-        |row| row.get(0),
-    ).unwrap_or(1);
-    let folio = format!("C-{}-{:04}", fecha, siguiente);
+    // 🆕 Compra, items y folio en una sola transacción
+    if let Err(e) = conn.execute("BEGIN TRANSACTION", []) {
+        return CompraResponse {
+            success: false,
+            message: format!("Error al iniciar transacción: {}", e),
+            compra_id: None,
+            folio: None,
+        };
+    }
+
+    let rollback = |conn: &rusqlite::Connection, msg: String| -> CompraResponse {
+        let _ = conn.execute("ROLLBACK", []);
+        CompraResponse {
+            success: false,
+            message: msg,
+            compra_id: None,
+            folio: None,
+        }
+    };
+
+    // Generar folio (numeración central)
+    let folio = match siguiente_folio(&conn, "COMPRA") {
+        Ok(f) => f,
+        Err(e) => return rollback(&conn, e),
+    };
 
     let saldo_pendiente = if request.tipo_pago == "CREDITO" { total } else { 0.0 };
     let monto_pagado    = if request.tipo_pago != "CREDITO" { total } else { 0.0 };
@@ -369,12 +401,7 @@ pub fn crear_compra(
     );
 
     if let Err(e) = result {
-        return CompraResponse {
-            success: false,
-            message: format!("Error al crear compra: {}", e),
-            compra_id: None,
-            folio: None,
-        };
+        return rollback(&conn, format!("Error al crear compra: {}", e));
     }
 
     let compra_id = conn.last_insert_rowid() as i32;
@@ -399,21 +426,26 @@ pub fn crear_compra(
                 subtotal_item,
             ],
         ) {
-            return CompraResponse {
-                success: false,
-                message: format!("Error al insertar item: {}", e),
-                compra_id: None,
-                folio: None,
-            };
+            return rollback(&conn, format!("Error al insertar item: {}", e));
         }
     }
 
-    CompraResponse {
+    let resultado = CompraResponse {
         success: true,
         message: "Compra registrada exitosamente".to_string(),
         compra_id: Some(compra_id),
         folio: Some(folio),
+    };
+
+    if let Err(e) = registrar_resultado(&conn, "COMPRA", clave.as_deref(), compra_id, &resultado) {
+        return rollback(&conn, e);
+    }
+
+    if let Err(e) = conn.execute("COMMIT", []) {
+        return rollback(&conn, format!("Error al confirmar compra: {}", e));
     }
+
+    resultado
 }
 
 #[tauri::command]
@@ -512,6 +544,22 @@ pub fn registrar_pago_compra(
 ) -> CompraResponse {
     let conn = db.get_conn();
 
+    // 🆕 Reintento de un pago ya registrado: no se paga dos veces
+    let clave = match normalizar_clave(request.clave_idempotencia.clone())
+        .and_then(|c| resultado_previo::<CompraResponse>(&conn, "PAGO_COMPRA", c.as_deref()).map(|previo| (c, previo)))
+    {
+        Ok((_, Some(previo))) => return previo,
+        Ok((c, None)) => c,
+        Err(e) => {
+            return CompraResponse {
+                success: false,
+                message: e,
+                compra_id: None,
+                folio: None,
+            }
+        }
+    };
+
     let saldo: f64 = conn.query_row(
         "SELECT saldo_pendiente FROM compras WHERE id = ?",
         params![request.compra_id],
@@ -530,8 +578,28 @@ pub fn registrar_pago_compra(
         };
     }
 
+    // 🆕 Pago y clave de idempotencia juntos
+    if let Err(e) = conn.execute("BEGIN TRANSACTION", []) {
+        return CompraResponse {
+            success: false,
+            message: format!("Error al iniciar transacción: {}", e),
+            compra_id: None,
+            folio: None,
+        };
+    }
+
+    let rollback = |conn: &rusqlite::Connection, msg: String| -> CompraResponse {
+        let _ = conn.execute("ROLLBACK", []);
+        CompraResponse {
+            success: false,
+            message: msg,
+            compra_id: None,
+            folio: None,
+        }
+    };
+
     // El trigger trg_actualizar_saldo_compra actualiza el saldo automáticamente
-    match conn.execute(
+    if let Err(e) = conn.execute(
         r"INSERT INTO pagos_compra
             (compra_id, monto, metodo_pago, referencia, notas, usuario_id)
           VALUES (?,?,?,?,?,?)",
@@ -544,19 +612,26 @@ pub fn registrar_pago_compra(
             usuario_id,
         ],
     ) {
-        Ok(_) => CompraResponse {
-            success: true,
-            message: format!("Pago de S/ {:.2} registrado. Saldo actualizado.", request.monto),
-            compra_id: Some(request.compra_id),
-            folio: None,
-        },
-        Err(e) => CompraResponse {
-            success: false,
-            message: format!("Error al registrar pago: {}", e),
-            compra_id: None,
-            folio: None,
-        },
+        return rollback(&conn, format!("Error al registrar pago: {}", e));
+    }
+    let pago_id = conn.last_insert_rowid() as i32;
+
+    let resultado = CompraResponse {
+        success: true,
+        message: format!("Pago de S/ {:.2} registrado. Saldo actualizado.", request.monto),
+        compra_id: Some(request.compra_id),
+        folio: None,
+    };
+
+    if let Err(e) = registrar_resultado(&conn, "PAGO_COMPRA", clave.as_deref(), pago_id, &resultado) {
+        return rollback(&conn, e);
+    }
+
+    if let Err(e) = conn.execute("COMMIT", []) {
+        return rollback(&conn, format!("Error al confirmar pago: {}", e));
     }
+
+    resultado
 }
 
 #[tauri::command]
@@ -659,19 +734,6 @@ pub fn registrar_devolucion_proveedor(
         .map(|i| i.precio_compra * i.cantidad_devuelta as f64)
         .sum();
 
-    // Generar folio DP-YYYYMMDD-####
-    let fecha = chrono::Local::now().format("%Y%m%d").to_string();
-    let siguiente: i32 = conn.query_row(
-        &format!(
-            "SELECT COALESCE(MAX(CAST(substr(folio,-4) AS INTEGER)),0)+1
-             FROM devoluciones_proveedor WHERE folio LIKE 'DP-{}%'",
-            fecha
-        ),
-        [],
-        |row| row.get(0),
-    ).unwrap_or(1);
-    let folio = format!("DP-{}-{:04}", fecha, siguiente);
-
     // Obtener proveedor_id de la compra
     let proveedor_id: i32 = match conn.query_row(
         "SELECT proveedor_id FROM compras WHERE id = ?",
@@ -688,6 +750,34 @@ pub fn registrar_devolucion_proveedor(
         },
     };
 
+    // 🆕 Cabecera, items y folio en una sola transacción
+    if let Err(e) = conn.execute("BEGIN TRANSACTION", []) {
+        return DevolucionProveedorResponse {
+            success: false,
+            message: format!("Error al iniciar transacción: {}", e),
+            devolucion_id: None,
+            folio: None,
+            credito_disponible: None,
+        };
+    }
+
+    let rollback = |conn: &rusqlite::Connection, msg: String| -> DevolucionProveedorResponse {
+        let _ = conn.execute("ROLLBACK", []);
+        DevolucionProveedorResponse {
+            success: false,
+            message: msg,
+            devolucion_id: None,
+            folio: None,
+            credito_disponible: None,
+        }
+    };
+
+    // Generar folio (numeración central)
+    let folio = match siguiente_folio(&conn, "DEVOLUCION_PROVEEDOR") {
+        Ok(f) => f,
+        Err(e) => return rollback(&conn, e),
+    };
+
     // Insertar devolución cabecera
     if let Err(e) = conn.execute(
         r"INSERT INTO devoluciones_proveedor
@@ -706,13 +796,7 @@ pub fn registrar_devolucion_proveedor(
             &request.notas,
         ],
     ) {
-        return DevolucionProveedorResponse {
-            success: false,
-            message: format!("Error al registrar devolución: {}", e),
-            devolucion_id: None,
-            folio: None,
-            credito_disponible: None,
-        };
+        return rollback(&conn, format!("Error al registrar devolución: {}", e));
     }
 
     let devolucion_id = conn.last_insert_rowid() as i32;
@@ -720,12 +804,30 @@ pub fn registrar_devolucion_proveedor(
     // Insertar items de la devolución
     for item in &request.items {
         let subtotal_item = item.precio_compra * item.cantidad_devuelta as f64;
+        let desde_cuarentena = item.desde_cuarentena.unwrap_or(false);
+
+        // 🆕 Prendas en cuarentena: salen del stock no vendible al enviarlas
+        if desde_cuarentena {
+            if let Err(e) = retirar_de_cuarentena(
+                &conn,
+                item.producto_id,
+                item.variante_id,
+                item.cantidad_devuelta,
+                "DEVOLUCION_PROVEEDOR",
+                Some(&folio),
+                &format!("Devolución a proveedor - {}", request.motivo),
+                usuario_id,
+            ) {
+                return rollback(&conn, e);
+            }
+        }
+
         if let Err(e) = conn.execute(
             r"INSERT INTO detalles_devolucion_proveedor
                 (devolucion_proveedor_id, detalle_compra_id, producto_id,
                  variante_id, talla, cantidad_devuelta, precio_compra,
-                 subtotal, motivo_item)
-              VALUES (?,?,?,?,?,?,?,?,?)",
+                 subtotal, motivo_item, desde_cuarentena)
+              VALUES (?,?,?,?,?,?,?,?,?,?)",
             params![
                 devolucion_id,
                 item.detalle_compra_id,
@@ -736,18 +838,17 @@ pub fn registrar_devolucion_proveedor(
                 item.precio_compra,
                 subtotal_item,
                 &item.motivo_item,
+                desde_cuarentena,
             ],
         ) {
-            return DevolucionProveedorResponse {
-                success: false,
-                message: format!("Error al insertar item: {}", e),
-                devolucion_id: None,
-                folio: None,
-                credito_disponible: None,
-            };
+            return rollback(&conn, format!("Error al insertar item: {}", e));
         }
     }
 
+    if let Err(e) = conn.execute("COMMIT", []) {
+        return rollback(&conn, format!("Error al confirmar devolución: {}", e));
+    }
+
     DevolucionProveedorResponse {
         success: true,
         message: format!(
@@ -803,6 +904,28 @@ pub fn resolver_devolucion_proveedor(
         };
     }
 
+    // 🆕 Estado y regreso a cuarentena en una sola transacción
+    if let Err(e) = conn.execute("BEGIN TRANSACTION", []) {
+        return DevolucionProveedorResponse {
+            success: false,
+            message: format!("Error al iniciar transacción: {}", e),
+            devolucion_id: None,
+            folio: None,
+            credito_disponible: None,
+        };
+    }
+
+    let rollback = |conn: &rusqlite::Connection, msg: String| -> DevolucionProveedorResponse {
+        let _ = conn.execute("ROLLBACK", []);
+        DevolucionProveedorResponse {
+            success: false,
+            message: msg,
+            devolucion_id: None,
+            folio: None,
+            credito_disponible: None,
+        }
+    };
+
     // Actualizar — si estado=ACEPTADA y tipo=CREDITO,
     // el trigger trg_credito_proveedor_devolucion suma el crédito automáticamente
     if let Err(e) = conn.execute(
@@ -818,13 +941,51 @@ pub fn resolver_devolucion_proveedor(
             request.devolucion_id,
         ],
     ) {
-        return DevolucionProveedorResponse {
-            success: false,
-            message: format!("Error al resolver devolución: {}", e),
-            devolucion_id: None,
-            folio: None,
-            credito_disponible: None,
+        return rollback(&conn, format!("Error al resolver devolución: {}", e));
+    }
+
+    // 🆕 Rechazada: lo que salió de cuarentena vuelve a cuarentena
+    if request.estado == "RECHAZADA" {
+        let (folio, usuario_id): (String, i32) = match conn.query_row(
+            "SELECT folio, usuario_id FROM devoluciones_proveedor WHERE id = ?",
+            params![request.devolucion_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ) {
+            Ok(d) => d,
+            Err(e) => return rollback(&conn, format!("Error al obtener devolución: {}", e)),
+        };
+
+        let items: Vec<(i32, Option<i32>, i32)> = match conn
+            .prepare(
+                r"SELECT producto_id, variante_id, cantidad_devuelta
+                  FROM detalles_devolucion_proveedor
+                  WHERE devolucion_proveedor_id = ? AND desde_cuarentena = 1",
+            )
+            .and_then(|mut stmt| {
+                stmt.query_map(params![request.devolucion_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+                    .collect()
+            }) {
+            Ok(i) => i,
+            Err(e) => return rollback(&conn, format!("Error al obtener items: {}", e)),
         };
+
+        for (producto_id, variante_id, cantidad) in items {
+            if let Err(e) = reingresar_a_cuarentena(
+                &conn,
+                producto_id,
+                variante_id,
+                cantidad,
+                Some(&folio),
+                "Devolución a proveedor rechazada",
+                usuario_id,
+            ) {
+                return rollback(&conn, e);
+            }
+        }
+    }
+
+    if let Err(e) = conn.execute("COMMIT", []) {
+        return rollback(&conn, format!("Error al confirmar: {}", e));
     }
 
     // Obtener crédito actualizado del proveedor
@@ -1000,7 +1161,8 @@ fn cargar_items_devolucion(conn: &rusqlite::Connection, devolucion_id: i32) -> V
     let mut stmt = match conn.prepare(r"
         SELECT dd.id, dd.devolucion_proveedor_id, dd.detalle_compra_id,
                dd.producto_id, pr.nombre, dd.variante_id, dd.talla,
-               dd.cantidad_devuelta, dd.precio_compra, dd.subtotal, dd.motivo_item
+               dd.cantidad_devuelta, dd.precio_compra, dd.subtotal, dd.motivo_item,
+               COALESCE(dd.desde_cuarentena, 0)
         FROM detalles_devolucion_proveedor dd
         JOIN productos pr ON dd.producto_id = pr.id
         WHERE dd.devolucion_proveedor_id = ?
@@ -1023,6 +1185,7 @@ fn cargar_items_devolucion(conn: &rusqlite::Connection, devolucion_id: i32) -> V
             precio_compra:           row.get(8)?,
             subtotal:                row.get(9)?,
             motivo_item:             row.get(10)?,
+            desde_cuarentena:        row.get(11)?,
         })
     }) {
         Ok(r) => r.filter_map(|r| r.ok()).collect::<Vec<_>>(),
@@ -0,0 +1,329 @@
+// commands/puntos.rs
+// Programa de puntos. Todo movimiento es una fila en puntos_movimientos;
+// el saldo siempre se calcula como la suma del libro.
+//
+// Vencimiento: los puntos se consumen del más antiguo al más nuevo, así que
+// lo vencido es lo acreditado (acumulaciones, canjes devueltos, ajustes) con
+// fecha_vencimiento pasada que los débitos (canjes, reversiones, vencimientos
+// previos) todavía no cubrieron.
+
+use crate::database::DatabasePool;
+use crate::models::puntos::*;
+use rusqlite::params;
+
+// =====================================================
+// COMANDO: Configuración del programa
+// =====================================================
+#[tauri::command]
+pub fn obtener_configuracion_puntos(
+    db: tauri::State<'_, DatabasePool>,
+) -> Result<ConfiguracionPuntos, String> {
+    let conn = db.get_conn();
+    configuracion_puntos(&conn)
+}
+
+#[tauri::command]
+pub fn actualizar_configuracion_puntos(
+    db: tauri::State<'_, DatabasePool>,
+    configuracion: ConfiguracionPuntos,
+) -> Result<String, String> {
+    let conn = db.get_conn();
+
+    if configuracion.puntos_por_sol < 0.0 {
+        return Err("❌ Los puntos por sol no pueden ser negativos".to_string());
+    }
+    if configuracion.valor_punto <= 0.0 {
+        return Err("❌ El valor del punto debe ser mayor a 0".to_string());
+    }
+    if configuracion.meses_vencimiento < 1 {
+        return Err("❌ Los puntos deben vencer en al menos 1 mes".to_string());
+    }
+
+    conn.execute(
+        r"UPDATE configuracion_tienda
+          SET puntos_por_sol = ?, valor_punto = ?, puntos_excluir_descuentos = ?,
+              puntos_meses_vencimiento = ?, fecha_actualizacion = datetime('now', 'localtime')
+          WHERE id = 1",
+        params![
+            configuracion.puntos_por_sol,
+            configuracion.valor_punto,
+            configuracion.excluir_descuentos as i32,
+            configuracion.meses_vencimiento,
+        ],
+    )
+    .map_err(|e| format!("Error al actualizar configuración de puntos: {}", e))?;
+
+    Ok("✅ Configuración de puntos actualizada".to_string())
+}
+
+// =====================================================
+// COMANDO: Saldo de puntos del cliente
+// =====================================================
+#[tauri::command]
+pub fn obtener_saldo_puntos(
+    db: tauri::State<'_, DatabasePool>,
+    cliente_id: i32,
+) -> Result<SaldoPuntos, String> {
+    let conn = db.get_conn();
+
+    let config = configuracion_puntos(&conn)?;
+    let puntos = saldo_puntos(&conn, cliente_id)?;
+
+    // Lo que vence en los próximos 30 días y no está cubierto por débitos
+    let por_vencer: i64 = conn
+        .query_row(
+            r"SELECT MAX(0,
+                COALESCE((SELECT SUM(puntos) FROM puntos_movimientos
+                          WHERE cliente_id = ?1 AND puntos > 0
+                            AND COALESCE(fecha_vencimiento, date(fecha_hora, '+' || ?2 || ' months'))
+                                <= date('now', 'localtime', '+30 days')), 0)
+              + COALESCE((SELECT SUM(puntos) FROM puntos_movimientos
+                          WHERE cliente_id = ?1 AND puntos < 0), 0))",
+            params![cliente_id, config.meses_vencimiento],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Error al calcular puntos por vencer: {}", e))?;
+
+    Ok(SaldoPuntos {
+        cliente_id,
+        puntos,
+        valor_soles: (puntos as f64 * config.valor_punto * 100.0).round() / 100.0,
+        por_vencer_30_dias: por_vencer.min(puntos),
+    })
+}
+
+// =====================================================
+// COMANDO: Estado de cuenta (todos los movimientos con saldo)
+// =====================================================
+#[tauri::command]
+pub fn obtener_estado_cuenta_puntos(
+    db: tauri::State<'_, DatabasePool>,
+    cliente_id: i32,
+) -> Result<Vec<MovimientoPuntos>, String> {
+    let conn = db.get_conn();
+
+    vencer_puntos(&conn, cliente_id)?;
+
+    let mut stmt = conn
+        .prepare(
+            r"SELECT pm.id, pm.tipo, pm.puntos, v.folio, pm.fecha_vencimiento, pm.descripcion,
+                     strftime('%Y-%m-%d %H:%M', pm.fecha_hora)
+              FROM puntos_movimientos pm
+              LEFT JOIN ventas v ON v.id = pm.venta_id
+              WHERE pm.cliente_id = ?
+              ORDER BY pm.id",
+        )
+        .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+
+    let mut saldo = 0i64;
+    let movimientos = stmt
+        .query_map(params![cliente_id], |row| {
+            Ok((
+                row.get::<_, i32>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, Option<String>>(4)?,
+                row.get::<_, Option<String>>(5)?,
+                row.get::<_, String>(6)?,
+            ))
+        })
+        .map_err(|e| format!("Error al obtener movimientos: {}", e))?
+        .filter_map(|r| r.ok())
+        .map(|(id, tipo, puntos, folio_venta, fecha_vencimiento, descripcion, fecha_hora)| {
+            saldo += puntos;
+            MovimientoPuntos { id, tipo, puntos, saldo, folio_venta, fecha_vencimiento, descripcion, fecha_hora }
+        })
+        .collect();
+
+    Ok(movimientos)
+}
+
+// =====================================================
+// FUNCIONES AUXILIARES (usadas por ventas y devoluciones)
+// =====================================================
+
+pub fn configuracion_puntos(conn: &rusqlite::Connection) -> Result<ConfiguracionPuntos, String> {
+    conn.query_row(
+        r"SELECT COALESCE(puntos_por_sol, 1), COALESCE(valor_punto, 0.05),
+                 COALESCE(puntos_excluir_descuentos, 0), COALESCE(puntos_meses_vencimiento, 12)
+          FROM configuracion_tienda LIMIT 1",
+        [],
+        |row| {
+            Ok(ConfiguracionPuntos {
+                puntos_por_sol: row.get(0)?,
+                valor_punto: row.get(1)?,
+                excluir_descuentos: row.get::<_, i32>(2)? == 1,
+                meses_vencimiento: row.get(3)?,
+            })
+        },
+    )
+    .map_err(|e| format!("Error al obtener configuración de puntos: {}", e))
+}
+
+// Saldo actual (primero registra lo que haya vencido)
+pub fn saldo_puntos(conn: &rusqlite::Connection, cliente_id: i32) -> Result<i64, String> {
+    vencer_puntos(conn, cliente_id)?;
+
+    conn.query_row(
+        "SELECT COALESCE(SUM(puntos), 0) FROM puntos_movimientos WHERE cliente_id = ?",
+        params![cliente_id],
+        |row| row.get(0),
+    )
+    .map_err(|e| format!("Error al calcular saldo de puntos: {}", e))
+}
+
+fn vencer_puntos(conn: &rusqlite::Connection, cliente_id: i32) -> Result<(), String> {
+    let meses = configuracion_puntos(conn)?.meses_vencimiento;
+
+    // Créditos sin fecha (registrados antes de que todo abono venciera) vencen
+    // a los meses configurados desde su registro
+    let pendiente: i64 = conn
+        .query_row(
+            r"SELECT COALESCE((SELECT SUM(puntos) FROM puntos_movimientos
+                               WHERE cliente_id = ?1 AND puntos > 0
+                                 AND COALESCE(fecha_vencimiento, date(fecha_hora, '+' || ?2 || ' months'))
+                                     < date('now', 'localtime')), 0)
+                   + COALESCE((SELECT SUM(puntos) FROM puntos_movimientos
+                               WHERE cliente_id = ?1 AND puntos < 0), 0)",
+            params![cliente_id, meses],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Error al calcular vencimiento de puntos: {}", e))?;
+
+    if pendiente > 0 {
+        conn.execute(
+            r"INSERT INTO puntos_movimientos (cliente_id, tipo, puntos, descripcion)
+              VALUES (?, 'VENCIMIENTO', ?, 'Puntos vencidos')",
+            params![cliente_id, -pendiente],
+        )
+        .map_err(|e| format!("Error al registrar vencimiento de puntos: {}", e))?;
+    }
+
+    Ok(())
+}
+
+// Puntos necesarios para cubrir un monto en soles (redondeo hacia arriba)
+pub fn puntos_para_monto(config: &ConfiguracionPuntos, monto: f64) -> i64 {
+    ((monto / config.valor_punto) - 0.000001).ceil() as i64
+}
+
+// Registra un movimiento del libro (todo abono lleva fecha de vencimiento)
+#[allow(clippy::too_many_arguments)]
+pub fn registrar_movimiento_puntos(
+    conn: &rusqlite::Connection,
+    cliente_id: i32,
+    tipo: &str,
+    puntos: i64,
+    venta_id: Option<i32>,
+    devolucion_id: Option<i32>,
+    descripcion: &str,
+    usuario_id: i32,
+) -> Result<(), String> {
+    if puntos == 0 {
+        return Ok(());
+    }
+
+    let meses = if puntos > 0 {
+        Some(configuracion_puntos(conn)?.meses_vencimiento)
+    } else {
+        None
+    };
+
+    conn.execute(
+        r"INSERT INTO puntos_movimientos
+            (cliente_id, tipo, puntos, venta_id, devolucion_id, fecha_vencimiento, descripcion, usuario_id)
+          VALUES (?1, ?2, ?3, ?4, ?5,
+                  CASE WHEN ?6 IS NULL THEN NULL ELSE date('now', 'localtime', '+' || ?6 || ' months') END,
+                  ?7, ?8)",
+        params![cliente_id, tipo, puntos, venta_id, devolucion_id, meses, descripcion, usuario_id],
+    )
+    .map_err(|e| format!("Error al registrar puntos: {}", e))?;
+
+    Ok(())
+}
+
+// Devolución parcial: se quita la parte proporcional de lo ganado con la venta
+pub fn revertir_puntos_devolucion(
+    conn: &rusqlite::Connection,
+    venta_id: i32,
+    devolucion_id: i32,
+    monto_devuelto: f64,
+    usuario_id: i32,
+) -> Result<(), String> {
+    let (cliente_id, total_venta, folio): (Option<i32>, f64, String) = conn
+        .query_row(
+            "SELECT cliente_id, total, folio FROM ventas WHERE id = ?",
+            params![venta_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|e| format!("Error al obtener venta: {}", e))?;
+
+    let cliente_id = match cliente_id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    let (ganados, revertidos): (i64, i64) = conn
+        .query_row(
+            r"SELECT COALESCE(SUM(CASE WHEN tipo = 'ACUMULACION' THEN puntos END), 0),
+                     COALESCE(-SUM(CASE WHEN tipo = 'REVERSION' AND puntos < 0 THEN puntos END), 0)
+              FROM puntos_movimientos WHERE venta_id = ?",
+            params![venta_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| format!("Error al obtener puntos de la venta: {}", e))?;
+
+    if ganados <= 0 || total_venta <= 0.0 {
+        return Ok(());
+    }
+
+    let proporcion = (monto_devuelto / total_venta).min(1.0);
+    let a_revertir = ((ganados as f64 * proporcion).round() as i64).min(ganados - revertidos);
+
+    registrar_movimiento_puntos(
+        conn,
+        cliente_id,
+        "REVERSION",
+        -a_revertir,
+        Some(venta_id),
+        Some(devolucion_id),
+        &format!("Devolución de la venta {}", folio),
+        usuario_id,
+    )
+}
+
+// Venta anulada: se quitan los puntos ganados y se devuelven los canjeados
+pub fn revertir_puntos_venta(conn: &rusqlite::Connection, venta_id: i32, usuario_id: i32) -> Result<(), String> {
+    let (cliente_id, folio): (Option<i32>, String) = conn
+        .query_row(
+            "SELECT cliente_id, folio FROM ventas WHERE id = ?",
+            params![venta_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| format!("Error al obtener venta: {}", e))?;
+
+    let cliente_id = match cliente_id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    let neto: i64 = conn
+        .query_row(
+            "SELECT COALESCE(SUM(puntos), 0) FROM puntos_movimientos WHERE venta_id = ?",
+            params![venta_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Error al obtener puntos de la venta: {}", e))?;
+
+    registrar_movimiento_puntos(
+        conn,
+        cliente_id,
+        "REVERSION",
+        -neto,
+        Some(venta_id),
+        None,
+        &format!("Anulación de la venta {}", folio),
+        usuario_id,
+    )
+}
@@ -1,6 +1,7 @@
 
 use rusqlite::OptionalExtension;
 use crate::database::DatabasePool;
+use crate::commands::vales::vencer_vales;
 use rusqlite::params;
 use serde::{Deserialize, Serialize};
 
@@ -13,6 +14,8 @@ pub struct VentaResumen {
     pub metodo_pago: String,
     pub cajero: String,
     pub estado: String,
+    pub tipo_comprobante: String,           // 🆕 BOLETA | FACTURA | NOTA_VENTA
+    pub numero_comprobante: Option<String>, // 🆕 B001-00000123 (ventas anteriores no tienen)
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -32,6 +35,40 @@ pub struct EstadisticasVentas {
     pub ventas_transferencia: f64,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReporteCupon {
+    pub cupon_id: i32,
+    pub codigo: String,
+    pub campana: Option<String>,
+    pub usos: i32,
+    pub descuento_total: f64,
+    pub ventas_total: f64,
+    pub unidades_vendidas: i32,
+}
+
+// 🆕 Pasivo por vales: lo vendido en tarjetas de regalo no es venta
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReporteVales {
+    pub vales_activos: i32,
+    pub saldo_pendiente: f64, // pasivo actual (saldo de vales activos)
+    pub emitido_tarjetas_regalo: f64,
+    pub emitido_devoluciones: f64,
+    pub canjeado: f64,
+    pub vencido: f64,
+}
+
+// 🆕 Comprobantes emitidos por serie (rango de números, anulados y monto)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResumenSerie {
+    pub tipo_comprobante: String,
+    pub serie: String,
+    pub cantidad: i32,
+    pub primer_numero: String,
+    pub ultimo_numero: String,
+    pub anulados: i32,
+    pub total: f64, // solo comprobantes no anulados
+}
+
 // Comando: Obtener ventas por rango de fechas
 #[tauri::command]
 pub fn obtener_ventas_rango(
@@ -49,7 +86,9 @@ pub fn obtener_ventas_rango(
             v.total,
             v.metodo_pago,
             u.nombre_completo as cajero,
-            v.estado
+            v.estado,
+            COALESCE(v.tipo_comprobante, 'NOTA_VENTA'),
+            CASE WHEN v.serie IS NOT NULL THEN v.serie || '-' || printf('%08d', v.correlativo) END
         FROM ventas v
         JOIN usuarios u ON v.usuario_id = u.id
         WHERE date(v.fecha_hora) BETWEEN ? AND ?
@@ -70,6 +109,8 @@ pub fn obtener_ventas_rango(
                 metodo_pago: row.get(4)?,
                 cajero: row.get(5)?,
                 estado: row.get(6)?,
+                tipo_comprobante: row.get(7)?,
+                numero_comprobante: row.get(8)?,
             })
         })
         .map_err(|e| format!("Error al obtener ventas: {}", e))?;
@@ -136,17 +177,27 @@ pub fn obtener_estadisticas_ventas(
 ) -> Result<EstadisticasVentas, String> {
     let conn = db.get_conn();
 
+    // Totales por método desde pagos_venta: las ventas MIXTO se reparten
+    // según sus líneas de pago
     let query = r"
         SELECT 
             COUNT(*) as total_ventas,
-            COALESCE(SUM(total), 0) as monto_total,
-            COALESCE(AVG(total), 0) as ticket_promedio,
-            COALESCE(SUM(CASE WHEN metodo_pago = 'EFECTIVO' THEN total ELSE 0 END), 0) as ventas_efectivo,
-            COALESCE(SUM(CASE WHEN metodo_pago = 'TARJETA' THEN total ELSE 0 END), 0) as ventas_tarjeta,
-            COALESCE(SUM(CASE WHEN metodo_pago = 'TRANSFERENCIA' THEN total ELSE 0 END), 0) as ventas_transferencia
-        FROM ventas
-        WHERE date(fecha_hora) BETWEEN ? AND ?
-            AND estado = 'COMPLETADA'
+            COALESCE(SUM(v.total), 0) as monto_total,
+            COALESCE(AVG(v.total), 0) as ticket_promedio,
+            COALESCE(SUM(p.efectivo), 0) as ventas_efectivo,
+            COALESCE(SUM(p.tarjeta), 0) as ventas_tarjeta,
+            COALESCE(SUM(p.transferencia), 0) as ventas_transferencia
+        FROM ventas v
+        LEFT JOIN (
+            SELECT venta_id,
+                   SUM(CASE WHEN metodo_pago = 'EFECTIVO' THEN monto ELSE 0 END) as efectivo,
+                   SUM(CASE WHEN metodo_pago = 'TARJETA' THEN monto ELSE 0 END) as tarjeta,
+                   SUM(CASE WHEN metodo_pago = 'TRANSFERENCIA' THEN monto ELSE 0 END) as transferencia
+            FROM pagos_venta
+            GROUP BY venta_id
+        ) p ON p.venta_id = v.id
+        WHERE date(v.fecha_hora) BETWEEN ? AND ?
+            AND v.estado = 'COMPLETADA'
     ";
 
     let result = conn
@@ -190,7 +241,9 @@ pub fn obtener_ventas_hoy(db: tauri::State<DatabasePool>) -> Result<Vec<VentaRes
             v.total,
             v.metodo_pago,
             u.nombre_completo as cajero,
-            v.estado
+            v.estado,
+            COALESCE(v.tipo_comprobante, 'NOTA_VENTA'),
+            CASE WHEN v.serie IS NOT NULL THEN v.serie || '-' || printf('%08d', v.correlativo) END
         FROM ventas v
         JOIN usuarios u ON v.usuario_id = u.id
         WHERE date(v.fecha_hora) = date('now')
@@ -211,6 +264,8 @@ pub fn obtener_ventas_hoy(db: tauri::State<DatabasePool>) -> Result<Vec<VentaRes
                 metodo_pago: row.get(4)?,
                 cajero: row.get(5)?,
                 estado: row.get(6)?,
+                tipo_comprobante: row.get(7)?,
+                numero_comprobante: row.get(8)?,
             })
         })
         .map_err(|e| format!("Error al obtener ventas: {}", e))?;
@@ -301,4 +356,146 @@ pub fn obtener_estadisticas_con_devoluciones(
     });
 
     Ok(resultado)
-}
\ No newline at end of file
+}
+
+// Comando: Reporte de cupones - qué campañas realmente vendieron
+#[tauri::command]
+pub fn obtener_reporte_cupones(
+    db: tauri::State<DatabasePool>,
+    fecha_inicio: String,
+    fecha_fin: String,
+) -> Result<Vec<ReporteCupon>, String> {
+    let conn = db.get_conn();
+
+    let query = r"
+        SELECT 
+            c.id,
+            c.codigo,
+            c.campana,
+            COUNT(v.id) as usos,
+            COALESCE(SUM(r.monto_descuento), 0) as descuento_total,
+            COALESCE(SUM(v.total), 0) as ventas_total,
+            COALESCE(SUM((SELECT SUM(cantidad) FROM detalles_venta WHERE venta_id = v.id)), 0) as unidades
+        FROM cupones c
+        JOIN cupones_redenciones r ON r.cupon_id = c.id
+        JOIN ventas v ON v.id = r.venta_id
+        WHERE date(v.fecha_hora) BETWEEN ? AND ?
+            AND v.estado = 'COMPLETADA'
+        GROUP BY c.id, c.codigo, c.campana
+        ORDER BY ventas_total DESC
+    ";
+
+    let mut stmt = conn
+        .prepare(query)
+        .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+
+    let cupones_iter = stmt
+        .query_map(params![&fecha_inicio, &fecha_fin], |row| {
+            Ok(ReporteCupon {
+                cupon_id: row.get(0)?,
+                codigo: row.get(1)?,
+                campana: row.get(2)?,
+                usos: row.get(3)?,
+                descuento_total: row.get(4)?,
+                ventas_total: row.get(5)?,
+                unidades_vendidas: row.get(6)?,
+            })
+        })
+        .map_err(|e| format!("Error al obtener reporte de cupones: {}", e))?;
+
+    let cupones: Vec<ReporteCupon> = cupones_iter
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(cupones)
+}
+
+// 🆕 Comando: Reporte de vales (pasivo pendiente y movimientos del período)
+#[tauri::command]
+pub fn obtener_reporte_vales(
+    db: tauri::State<DatabasePool>,
+    fecha_inicio: String,
+    fecha_fin: String,
+) -> Result<ReporteVales, String> {
+    let conn = db.get_conn();
+
+    vencer_vales(&conn)?;
+
+    let (vales_activos, saldo_pendiente): (i32, f64) = conn
+        .query_row(
+            "SELECT COUNT(*), COALESCE(SUM(saldo), 0) FROM vales WHERE estado = 'ACTIVO'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| format!("Error al obtener saldo de vales: {}", e))?;
+
+    let query = r"
+        SELECT 
+            COALESCE(SUM(CASE WHEN m.tipo = 'EMISION' AND va.origen = 'VENTA' THEN m.monto END), 0),
+            COALESCE(SUM(CASE WHEN m.tipo = 'EMISION' AND va.origen = 'DEVOLUCION' THEN m.monto END), 0),
+            COALESCE(-SUM(CASE WHEN m.tipo IN ('CANJE', 'REVERSION') THEN m.monto END), 0),
+            COALESCE(-SUM(CASE WHEN m.tipo = 'VENCIMIENTO' THEN m.monto END), 0)
+        FROM vales_movimientos m
+        JOIN vales va ON va.id = m.vale_id
+        WHERE date(m.fecha_hora) BETWEEN ? AND ?
+    ";
+
+    conn.query_row(query, params![&fecha_inicio, &fecha_fin], |row| {
+        Ok(ReporteVales {
+            vales_activos,
+            saldo_pendiente,
+            emitido_tarjetas_regalo: row.get(0)?,
+            emitido_devoluciones: row.get(1)?,
+            canjeado: row.get(2)?,
+            vencido: row.get(3)?,
+        })
+    })
+    .map_err(|e| format!("Error al obtener reporte de vales: {}", e))
+}
+
+// 🆕 Comando: Comprobantes por serie en el período
+#[tauri::command]
+pub fn obtener_resumen_comprobantes(
+    db: tauri::State<DatabasePool>,
+    fecha_inicio: String,
+    fecha_fin: String,
+) -> Result<Vec<ResumenSerie>, String> {
+    let conn = db.get_conn();
+
+    let query = r"
+        SELECT 
+            tipo_comprobante,
+            serie,
+            COUNT(*),
+            serie || '-' || printf('%08d', MIN(correlativo)),
+            serie || '-' || printf('%08d', MAX(correlativo)),
+            SUM(CASE WHEN estado = 'CANCELADA' THEN 1 ELSE 0 END),
+            COALESCE(SUM(CASE WHEN estado != 'CANCELADA' THEN total END), 0)
+        FROM ventas
+        WHERE serie IS NOT NULL AND date(fecha_hora) BETWEEN ? AND ?
+        GROUP BY tipo_comprobante, serie
+        ORDER BY tipo_comprobante, serie
+    ";
+
+    let mut stmt = conn
+        .prepare(query)
+        .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+
+    let series = stmt
+        .query_map(params![&fecha_inicio, &fecha_fin], |row| {
+            Ok(ResumenSerie {
+                tipo_comprobante: row.get(0)?,
+                serie: row.get(1)?,
+                cantidad: row.get(2)?,
+                primer_numero: row.get(3)?,
+                ultimo_numero: row.get(4)?,
+                anulados: row.get(5)?,
+                total: row.get(6)?,
+            })
+        })
+        .map_err(|e| format!("Error al obtener comprobantes: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(series)
+}
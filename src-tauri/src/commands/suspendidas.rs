@@ -0,0 +1,329 @@
+// commands/suspendidas.rs
+// Ventas suspendidas: carritos en espera mientras el cliente está en el probador.
+// Se guardan en la caja abierta del cajero y expiran al cerrarla
+// (trigger trg_expirar_ventas_suspendidas).
+
+use crate::database::DatabasePool;
+use crate::commands::ventas::ProductoVenta;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+// =====================================================
+// ESTRUCTURAS
+// =====================================================
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VentaSuspendida {
+    pub id: i32,
+    pub etiqueta_cliente: Option<String>,
+    pub total: f64,
+    pub reservar_stock: bool,
+    pub fecha_suspension: String,
+    pub usuario_id: i32,
+    pub productos: Vec<ProductoVenta>,
+}
+
+// =====================================================
+// COMANDO: Suspender venta (poner carrito en espera)
+// =====================================================
+#[tauri::command]
+pub fn suspender_venta(
+    db: tauri::State<'_, DatabasePool>,
+    productos: Vec<ProductoVenta>,
+    usuario_id: i32,
+    etiqueta_cliente: Option<String>,
+    reservar_stock: Option<bool>,
+) -> Result<VentaSuspendida, String> {
+    let conn = db.get_conn();
+
+    if productos.is_empty() {
+        return Err("❌ El carrito está vacío".to_string());
+    }
+
+    let caja_id = caja_abierta_usuario(&conn, usuario_id)?;
+    let reservar = reservar_stock.unwrap_or(false);
+
+    // Con reserva, no se puede apartar más de lo que está libre
+    if reservar {
+        for p in &productos {
+            let disponible = stock_disponible(&conn, p.id, p.variante_id)?;
+            if disponible < p.cantidad {
+                return Err(format!(
+                    "❌ No hay stock libre para reservar {}{} (disponible: {}, solicitado: {})",
+                    p.nombre,
+                    p.talla.as_deref().map(|t| format!(" talla {}", t)).unwrap_or_default(),
+                    disponible,
+                    p.cantidad
+                ));
+            }
+        }
+    }
+
+    let total: f64 = productos
+        .iter()
+        .map(|p| p.precio * p.cantidad as f64 * (1.0 - p.descuento_porcentaje.unwrap_or(0.0) / 100.0))
+        .sum();
+
+    let etiqueta = etiqueta_cliente
+        .map(|e| e.trim().to_string())
+        .filter(|e| !e.is_empty());
+
+    conn.execute("BEGIN TRANSACTION", [])
+        .map_err(|e| format!("Error al iniciar transacción: {}", e))?;
+
+    let rollback = |conn: &rusqlite::Connection, msg: String| -> String {
+        let _ = conn.execute("ROLLBACK", []);
+        msg
+    };
+
+    if let Err(e) = conn.execute(
+        r"INSERT INTO ventas_suspendidas (caja_id, usuario_id, etiqueta_cliente, total, reservar_stock)
+          VALUES (?, ?, ?, ?, ?)",
+        params![caja_id, usuario_id, &etiqueta, total, reservar as i32],
+    ) {
+        return Err(rollback(&conn, format!("Error al suspender venta: {}", e)));
+    }
+
+    let suspendida_id = conn.last_insert_rowid() as i32;
+
+    for p in &productos {
+        if let Err(e) = conn.execute(
+            r"INSERT INTO detalles_venta_suspendida
+                (venta_suspendida_id, producto_id, variante_id, talla, nombre, codigo,
+                 cantidad, precio_unitario, descuento_porcentaje)
+              VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                suspendida_id,
+                p.id,
+                p.variante_id,
+                p.talla,
+                &p.nombre,
+                &p.codigo,
+                p.cantidad,
+                p.precio,
+                p.descuento_porcentaje.unwrap_or(0.0),
+            ],
+        ) {
+            return Err(rollback(&conn, format!("Error al guardar línea: {}", e)));
+        }
+    }
+
+    conn.execute("COMMIT", [])
+        .map_err(|e| format!("Error al confirmar: {}", e))?;
+
+    obtener_suspendida_por_id(&conn, suspendida_id)
+}
+
+// =====================================================
+// COMANDO: Listar carritos en espera de la caja abierta
+// =====================================================
+#[tauri::command]
+pub fn obtener_ventas_suspendidas(
+    db: tauri::State<'_, DatabasePool>,
+    usuario_id: i32,
+) -> Result<Vec<VentaSuspendida>, String> {
+    let conn = db.get_conn();
+
+    let caja_id = caja_abierta_usuario(&conn, usuario_id)?;
+
+    let ids: Vec<i32> = {
+        let mut stmt = conn
+            .prepare(
+                "SELECT id FROM ventas_suspendidas
+                 WHERE caja_id = ? AND estado = 'ACTIVA'
+                 ORDER BY fecha_suspension, id",
+            )
+            .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+        let ids = stmt
+            .query_map(params![caja_id], |row| row.get(0))
+            .map_err(|e| format!("Error al obtener ventas suspendidas: {}", e))?
+            .collect::<rusqlite::Result<Vec<i32>>>()
+            .map_err(|e| format!("Error al leer ventas suspendidas: {}", e))?;
+        ids
+    };
+
+    ids.into_iter()
+        .map(|id| obtener_suspendida_por_id(&conn, id))
+        .collect()
+}
+
+// =====================================================
+// COMANDO: Recuperar carrito (vuelve al POS y libera la reserva)
+// =====================================================
+#[tauri::command]
+pub fn recuperar_venta_suspendida(
+    db: tauri::State<'_, DatabasePool>,
+    venta_suspendida_id: i32,
+    usuario_id: i32,
+) -> Result<VentaSuspendida, String> {
+    let conn = db.get_conn();
+
+    cerrar_suspendida(&conn, venta_suspendida_id, usuario_id, "RECUPERADA")?;
+
+    obtener_suspendida_por_id(&conn, venta_suspendida_id)
+}
+
+// =====================================================
+// COMANDO: Descartar carrito (el cliente no se lleva nada)
+// =====================================================
+#[tauri::command]
+pub fn descartar_venta_suspendida(
+    db: tauri::State<'_, DatabasePool>,
+    venta_suspendida_id: i32,
+    usuario_id: i32,
+) -> Result<String, String> {
+    let conn = db.get_conn();
+
+    cerrar_suspendida(&conn, venta_suspendida_id, usuario_id, "DESCARTADA")?;
+
+    Ok("✅ Venta en espera descartada".to_string())
+}
+
+// =====================================================
+// FUNCIONES AUXILIARES
+// =====================================================
+
+// Stock que se puede vender: el físico menos lo apartado por carritos en espera
+// con reserva. Un carrito recuperado ya no reserva.
+pub fn stock_disponible(
+    conn: &rusqlite::Connection,
+    producto_id: i32,
+    variante_id: Option<i32>,
+) -> Result<i32, String> {
+    let stock: Option<i32> = match variante_id {
+        Some(vid) => conn.query_row(
+            "SELECT stock FROM producto_variantes WHERE id = ? AND activo = 1",
+            params![vid],
+            |row| row.get(0),
+        ),
+        None => conn.query_row(
+            "SELECT stock FROM productos WHERE id = ? AND activo = 1",
+            params![producto_id],
+            |row| row.get(0),
+        ),
+    }
+    .optional()
+    .map_err(|e| format!("Error al verificar stock: {}", e))?;
+
+    let stock = match stock {
+        Some(s) => s,
+        None => return Ok(0),
+    };
+
+    let reservado: i32 = conn
+        .query_row(
+            r"SELECT COALESCE(SUM(d.cantidad), 0)
+              FROM detalles_venta_suspendida d
+              JOIN ventas_suspendidas vs ON vs.id = d.venta_suspendida_id
+              WHERE vs.estado = 'ACTIVA' AND vs.reservar_stock = 1
+                AND d.producto_id = ?1
+                AND ((?2 IS NULL AND d.variante_id IS NULL) OR d.variante_id = ?2)",
+            params![producto_id, variante_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Error al calcular stock reservado: {}", e))?;
+
+    Ok(stock - reservado)
+}
+
+fn caja_abierta_usuario(conn: &rusqlite::Connection, usuario_id: i32) -> Result<i32, String> {
+    let caja_id: Option<i32> = conn
+        .query_row(
+            "SELECT id FROM cajas WHERE usuario_id = ? AND estado = 'ABIERTA'",
+            params![usuario_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Error al verificar caja: {}", e))?;
+
+    caja_id.ok_or_else(|| "⚠️ Debes abrir una caja antes de usar ventas en espera".to_string())
+}
+
+// Pasa un carrito ACTIVO de la caja del usuario a RECUPERADA / DESCARTADA
+fn cerrar_suspendida(
+    conn: &rusqlite::Connection,
+    venta_suspendida_id: i32,
+    usuario_id: i32,
+    nuevo_estado: &str,
+) -> Result<(), String> {
+    let caja_id = caja_abierta_usuario(conn, usuario_id)?;
+
+    let estado: Option<(String, i32)> = conn
+        .query_row(
+            "SELECT estado, caja_id FROM ventas_suspendidas WHERE id = ?",
+            params![venta_suspendida_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(|e| format!("Error al buscar venta suspendida: {}", e))?;
+
+    match estado {
+        None => return Err("❌ Venta en espera no encontrada".to_string()),
+        Some((_, caja)) if caja != caja_id => {
+            return Err("❌ La venta en espera pertenece a otra caja".to_string())
+        }
+        Some((estado, _)) if estado != "ACTIVA" => {
+            return Err(format!("❌ La venta en espera ya no está disponible ({})", estado))
+        }
+        _ => {}
+    }
+
+    conn.execute(
+        r"UPDATE ventas_suspendidas
+          SET estado = ?, fecha_cierre = datetime('now', 'localtime')
+          WHERE id = ? AND estado = 'ACTIVA'",
+        params![nuevo_estado, venta_suspendida_id],
+    )
+    .map_err(|e| format!("Error al actualizar venta suspendida: {}", e))?;
+
+    Ok(())
+}
+
+fn obtener_suspendida_por_id(conn: &rusqlite::Connection, id: i32) -> Result<VentaSuspendida, String> {
+    let (etiqueta_cliente, total, reservar, fecha_suspension, usuario_id): (Option<String>, f64, i32, String, i32) = conn
+        .query_row(
+            r"SELECT etiqueta_cliente, total, reservar_stock, fecha_suspension, usuario_id
+              FROM ventas_suspendidas WHERE id = ?",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+        )
+        .map_err(|e| format!("Error al obtener venta suspendida: {}", e))?;
+
+    let mut stmt = conn
+        .prepare(
+            r"SELECT producto_id, nombre, codigo, precio_unitario, cantidad,
+                     descuento_porcentaje, variante_id, talla
+              FROM detalles_venta_suspendida
+              WHERE venta_suspendida_id = ?
+              ORDER BY id",
+        )
+        .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+
+    let productos = stmt
+        .query_map(params![id], |row| {
+            Ok(ProductoVenta {
+                id: row.get(0)?,
+                nombre: row.get(1)?,
+                codigo: row.get(2)?,
+                precio: row.get(3)?,
+                cantidad: row.get(4)?,
+                descuento_porcentaje: row.get(5)?,
+                variante_id: row.get(6)?,
+                talla: row.get(7)?,
+                vendedor_id: None,
+            })
+        })
+        .map_err(|e| format!("Error al obtener líneas: {}", e))?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| format!("Error al leer líneas: {}", e))?;
+
+    Ok(VentaSuspendida {
+        id,
+        etiqueta_cliente,
+        total,
+        reservar_stock: reservar == 1,
+        fecha_suspension,
+        usuario_id,
+        productos,
+    })
+}
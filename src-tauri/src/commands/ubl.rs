@@ -0,0 +1,391 @@
+// commands/ubl.rs
+// Generación del XML UBL 2.1 de boletas, facturas y notas de crédito
+// (formato SUNAT).
+//
+// El XML se escribe ya en forma canónica (C14N): sin espacios entre
+// etiquetas, atributos en orden y etiquetas vacías abiertas y cerradas.
+// Así la firma puede calcular el digest directamente sobre el texto.
+
+use crate::commands::impuestos::TotalesIgv;
+
+// Aquí va la firma digital (ver firma_digital.rs)
+pub const MARCADOR_FIRMA: &str = "<!--FIRMA-->";
+
+const NS_CAC: &str = "urn:oasis:names:specification:ubl:schema:xsd:CommonAggregateComponents-2";
+const NS_CBC: &str = "urn:oasis:names:specification:ubl:schema:xsd:CommonBasicComponents-2";
+const NS_DS: &str = "http://www.w3.org/2000/09/xmldsig#";
+const NS_EXT: &str = "urn:oasis:names:specification:ubl:schema:xsd:CommonExtensionComponents-2";
+
+pub struct EmisorUbl {
+    pub ruc: String,
+    pub razon_social: String,
+    pub nombre_comercial: Option<String>,
+    pub direccion: String,
+    pub ubigeo: String,
+}
+
+pub struct ReceptorUbl {
+    pub tipo_documento: String, // catálogo 06: 0 sin documento, 1 DNI, 4 CE, 6 RUC
+    pub numero_documento: String,
+    pub nombre: String,
+}
+
+pub struct LineaUbl {
+    pub codigo: String,
+    pub descripcion: String,
+    pub cantidad: i32,
+    pub precio_unitario: f64, // con IGV
+    pub valor_venta: f64,     // total de la línea sin IGV
+    pub igv: f64,
+    pub afectacion: String,   // GRAVADO | EXONERADO | INAFECTO
+}
+
+// 🆕 Nota de crédito: comprobante que modifica y motivo (catálogo 09)
+pub struct ReferenciaUbl {
+    pub tipo_comprobante: String, // 01 | 03
+    pub numero: String,
+    pub codigo_motivo: String,
+    pub sustento: String,
+}
+
+// 🆕 Cuota de una factura al crédito
+pub struct CuotaUbl {
+    pub monto: f64,
+    pub fecha_vencimiento: String, // YYYY-MM-DD
+}
+
+pub struct DocumentoUbl {
+    pub tipo_comprobante: String, // 01 factura | 03 boleta | 07 nota de crédito
+    pub numero: String,           // F001-00000001
+    pub fecha_emision: String,    // YYYY-MM-DD
+    pub hora_emision: String,     // HH:MM:SS
+    pub moneda: String,
+    pub tasa_igv: f64,
+    pub emisor: EmisorUbl,
+    pub receptor: ReceptorUbl,
+    pub lineas: Vec<LineaUbl>,
+    pub totales: TotalesIgv,
+    pub total: f64,
+    pub referencia: Option<ReferenciaUbl>, // solo notas de crédito
+    pub cuotas: Vec<CuotaUbl>,             // 🆕 vacío = al contado
+}
+
+// =====================================================
+// XML DEL COMPROBANTE (con MARCADOR_FIRMA sin reemplazar)
+// =====================================================
+pub fn generar_xml(doc: &DocumentoUbl) -> String {
+    let m = &doc.moneda;
+    let mut x = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+
+    // 🆕 La nota de crédito cambia la raíz y las líneas
+    let (raiz, etiqueta_linea, etiqueta_cantidad) = match doc.referencia {
+        Some(_) => ("CreditNote", "CreditNoteLine", "CreditedQuantity"),
+        None => ("Invoice", "InvoiceLine", "InvoicedQuantity"),
+    };
+
+    x.push_str(&format!(
+        r#"<{raiz} xmlns="urn:oasis:names:specification:ubl:schema:xsd:{raiz}-2" xmlns:cac="{}" xmlns:cbc="{}" xmlns:ds="{}" xmlns:ext="{}">"#,
+        NS_CAC, NS_CBC, NS_DS, NS_EXT
+    ));
+    x.push_str(&format!(
+        "<ext:UBLExtensions><ext:UBLExtension><ext:ExtensionContent>{}</ext:ExtensionContent></ext:UBLExtension></ext:UBLExtensions>",
+        MARCADOR_FIRMA
+    ));
+    x.push_str("<cbc:UBLVersionID>2.1</cbc:UBLVersionID><cbc:CustomizationID>2.0</cbc:CustomizationID>");
+    x.push_str(&format!("<cbc:ID>{}</cbc:ID>", escapar(&doc.numero)));
+    x.push_str(&format!("<cbc:IssueDate>{}</cbc:IssueDate>", doc.fecha_emision));
+    x.push_str(&format!("<cbc:IssueTime>{}</cbc:IssueTime>", doc.hora_emision));
+    // 0101 = venta interna
+    if doc.referencia.is_none() {
+        x.push_str(&format!(r#"<cbc:InvoiceTypeCode listID="0101">{}</cbc:InvoiceTypeCode>"#, doc.tipo_comprobante));
+    }
+    x.push_str(&format!(r#"<cbc:Note languageLocaleID="1000">{}</cbc:Note>"#, monto_en_letras(doc.total)));
+    x.push_str(&format!("<cbc:DocumentCurrencyCode>{}</cbc:DocumentCurrencyCode>", m));
+
+    // 🆕 Motivo y comprobante que modifica la nota de crédito
+    if let Some(r) = &doc.referencia {
+        x.push_str(&format!(
+            "<cac:DiscrepancyResponse><cbc:ReferenceID>{numero}</cbc:ReferenceID><cbc:ResponseCode>{}</cbc:ResponseCode><cbc:Description>{}</cbc:Description></cac:DiscrepancyResponse><cac:BillingReference><cac:InvoiceDocumentReference><cbc:ID>{numero}</cbc:ID><cbc:DocumentTypeCode>{}</cbc:DocumentTypeCode></cac:InvoiceDocumentReference></cac:BillingReference>",
+            r.codigo_motivo,
+            escapar(&r.sustento),
+            r.tipo_comprobante,
+            numero = escapar(&r.numero)
+        ));
+    }
+
+    // Referencia a la firma
+    let ruc = escapar(&doc.emisor.ruc);
+    let razon_social = escapar(&doc.emisor.razon_social);
+    x.push_str(&format!(
+        "<cac:Signature><cbc:ID>{ruc}</cbc:ID><cac:SignatoryParty><cac:PartyIdentification><cbc:ID>{ruc}</cbc:ID></cac:PartyIdentification><cac:PartyName><cbc:Name>{razon_social}</cbc:Name></cac:PartyName></cac:SignatoryParty><cac:DigitalSignatureAttachment><cac:ExternalReference><cbc:URI>#SignatureSP</cbc:URI></cac:ExternalReference></cac:DigitalSignatureAttachment></cac:Signature>"
+    ));
+
+    // Emisor
+    x.push_str(&format!(
+        r#"<cac:AccountingSupplierParty><cac:Party><cac:PartyIdentification><cbc:ID schemeID="6">{ruc}</cbc:ID></cac:PartyIdentification>"#
+    ));
+    if let Some(nombre) = doc.emisor.nombre_comercial.as_deref().filter(|n| !n.is_empty()) {
+        x.push_str(&format!("<cac:PartyName><cbc:Name>{}</cbc:Name></cac:PartyName>", escapar(nombre)));
+    }
+    x.push_str(&format!("<cac:PartyLegalEntity><cbc:RegistrationName>{}</cbc:RegistrationName><cac:RegistrationAddress>", razon_social));
+    if !doc.emisor.ubigeo.is_empty() {
+        x.push_str(&format!("<cbc:ID>{}</cbc:ID>", escapar(&doc.emisor.ubigeo)));
+    }
+    // 0000 = domicilio fiscal
+    x.push_str(&format!(
+        "<cbc:AddressTypeCode>0000</cbc:AddressTypeCode><cac:AddressLine><cbc:Line>{}</cbc:Line></cac:AddressLine><cac:Country><cbc:IdentificationCode>PE</cbc:IdentificationCode></cac:Country></cac:RegistrationAddress></cac:PartyLegalEntity></cac:Party></cac:AccountingSupplierParty>",
+        escapar(&doc.emisor.direccion)
+    ));
+
+    // Cliente
+    x.push_str(&format!(
+        r#"<cac:AccountingCustomerParty><cac:Party><cac:PartyIdentification><cbc:ID schemeID="{}">{}</cbc:ID></cac:PartyIdentification><cac:PartyLegalEntity><cbc:RegistrationName>{}</cbc:RegistrationName></cac:PartyLegalEntity></cac:Party></cac:AccountingCustomerParty>"#,
+        doc.receptor.tipo_documento,
+        escapar(&doc.receptor.numero_documento),
+        escapar(&doc.receptor.nombre)
+    ));
+
+    // Forma de pago (obligatoria en facturas). Al crédito: el monto pendiente
+    // y cada cuota con su vencimiento (Cuota001, Cuota002...)
+    if doc.tipo_comprobante == "01" {
+        if doc.cuotas.is_empty() {
+            x.push_str("<cac:PaymentTerms><cbc:ID>FormaPago</cbc:ID><cbc:PaymentMeansID>Contado</cbc:PaymentMeansID></cac:PaymentTerms>");
+        } else {
+            let pendiente: f64 = doc.cuotas.iter().map(|c| c.monto).sum();
+            x.push_str(&format!(
+                r#"<cac:PaymentTerms><cbc:ID>FormaPago</cbc:ID><cbc:PaymentMeansID>Credito</cbc:PaymentMeansID><cbc:Amount currencyID="{m}">{:.2}</cbc:Amount></cac:PaymentTerms>"#,
+                pendiente
+            ));
+            for (i, cuota) in doc.cuotas.iter().enumerate() {
+                x.push_str(&format!(
+                    r#"<cac:PaymentTerms><cbc:ID>FormaPago</cbc:ID><cbc:PaymentMeansID>Cuota{:03}</cbc:PaymentMeansID><cbc:Amount currencyID="{m}">{:.2}</cbc:Amount><cbc:PaymentDueDate>{}</cbc:PaymentDueDate></cac:PaymentTerms>"#,
+                    i + 1,
+                    cuota.monto,
+                    cuota.fecha_vencimiento
+                ));
+            }
+        }
+    }
+
+    // Impuestos del comprobante: un subtotal por tipo de operación presente
+    x.push_str(&format!(r#"<cac:TaxTotal><cbc:TaxAmount currencyID="{}">{:.2}</cbc:TaxAmount>"#, m, doc.totales.igv));
+    for (afectacion, base, igv) in [
+        ("GRAVADO", doc.totales.op_gravada, doc.totales.igv),
+        ("EXONERADO", doc.totales.op_exonerada, 0.0),
+        ("INAFECTO", doc.totales.op_inafecta, 0.0),
+    ] {
+        if base > 0.0 || (afectacion == "GRAVADO" && doc.lineas.iter().all(|l| l.afectacion == "GRAVADO")) {
+            x.push_str(&format!(
+                r#"<cac:TaxSubtotal><cbc:TaxableAmount currencyID="{m}">{:.2}</cbc:TaxableAmount><cbc:TaxAmount currencyID="{m}">{:.2}</cbc:TaxAmount><cac:TaxCategory>{}</cac:TaxCategory></cac:TaxSubtotal>"#,
+                base,
+                igv,
+                esquema_tributo(afectacion)
+            ));
+        }
+    }
+    x.push_str("</cac:TaxTotal>");
+
+    let valor_venta = doc.totales.op_gravada + doc.totales.op_exonerada + doc.totales.op_inafecta;
+    x.push_str(&format!(
+        r#"<cac:LegalMonetaryTotal><cbc:LineExtensionAmount currencyID="{m}">{:.2}</cbc:LineExtensionAmount><cbc:TaxInclusiveAmount currencyID="{m}">{:.2}</cbc:TaxInclusiveAmount><cbc:PayableAmount currencyID="{m}">{:.2}</cbc:PayableAmount></cac:LegalMonetaryTotal>"#,
+        valor_venta, doc.total, doc.total
+    ));
+
+    // Líneas
+    for (i, linea) in doc.lineas.iter().enumerate() {
+        let tasa = if linea.afectacion == "GRAVADO" { doc.tasa_igv } else { 0.0 };
+        let valor_unitario = if linea.cantidad > 0 { linea.valor_venta / linea.cantidad as f64 } else { 0.0 };
+        x.push_str(&format!(
+            r#"<cac:{etiqueta_linea}><cbc:ID>{}</cbc:ID><cbc:{etiqueta_cantidad} unitCode="NIU">{}</cbc:{etiqueta_cantidad}><cbc:LineExtensionAmount currencyID="{m}">{:.2}</cbc:LineExtensionAmount>"#,
+            i + 1,
+            linea.cantidad,
+            linea.valor_venta
+        ));
+        x.push_str(&format!(
+            r#"<cac:PricingReference><cac:AlternativeConditionPrice><cbc:PriceAmount currencyID="{m}">{:.2}</cbc:PriceAmount><cbc:PriceTypeCode>01</cbc:PriceTypeCode></cac:AlternativeConditionPrice></cac:PricingReference>"#,
+            linea.precio_unitario
+        ));
+        x.push_str(&format!(
+            r#"<cac:TaxTotal><cbc:TaxAmount currencyID="{m}">{:.2}</cbc:TaxAmount><cac:TaxSubtotal><cbc:TaxableAmount currencyID="{m}">{:.2}</cbc:TaxableAmount><cbc:TaxAmount currencyID="{m}">{:.2}</cbc:TaxAmount><cac:TaxCategory><cbc:Percent>{}</cbc:Percent><cbc:TaxExemptionReasonCode>{}</cbc:TaxExemptionReasonCode>{}</cac:TaxCategory></cac:TaxSubtotal></cac:TaxTotal>"#,
+            linea.igv,
+            linea.valor_venta,
+            linea.igv,
+            tasa,
+            codigo_afectacion(&linea.afectacion),
+            esquema_tributo(&linea.afectacion)
+        ));
+        x.push_str(&format!(
+            r#"<cac:Item><cbc:Description>{}</cbc:Description><cac:SellersItemIdentification><cbc:ID>{}</cbc:ID></cac:SellersItemIdentification></cac:Item><cac:Price><cbc:PriceAmount currencyID="{m}">{:.6}</cbc:PriceAmount></cac:Price></cac:{etiqueta_linea}>"#,
+            escapar(&linea.descripcion),
+            escapar(&linea.codigo),
+            valor_unitario
+        ));
+    }
+
+    x.push_str(&format!("</{}>", raiz));
+    x
+}
+
+// Catálogo 07: tipo de afectación del IGV
+fn codigo_afectacion(afectacion: &str) -> &'static str {
+    match afectacion {
+        "EXONERADO" => "20",
+        "INAFECTO" => "30",
+        _ => "10",
+    }
+}
+
+// Catálogo 05: tributo
+fn esquema_tributo(afectacion: &str) -> &'static str {
+    match afectacion {
+        "EXONERADO" => "<cac:TaxScheme><cbc:ID>9997</cbc:ID><cbc:Name>EXO</cbc:Name><cbc:TaxTypeCode>VAT</cbc:TaxTypeCode></cac:TaxScheme>",
+        "INAFECTO" => "<cac:TaxScheme><cbc:ID>9998</cbc:ID><cbc:Name>INA</cbc:Name><cbc:TaxTypeCode>FRE</cbc:TaxTypeCode></cac:TaxScheme>",
+        _ => "<cac:TaxScheme><cbc:ID>1000</cbc:ID><cbc:Name>IGV</cbc:Name><cbc:TaxTypeCode>VAT</cbc:TaxTypeCode></cac:TaxScheme>",
+    }
+}
+
+// Escapado de texto tal como lo deja C14N
+fn escapar(texto: &str) -> String {
+    texto
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\r', "&#xD;")
+}
+
+// =====================================================
+// MONTO EN LETRAS (leyenda 1000)
+// =====================================================
+pub fn monto_en_letras(monto: f64) -> String {
+    let centimos_totales = (monto * 100.0).round() as u64;
+    format!("{} CON {:02}/100 SOLES", numero_en_letras(centimos_totales / 100), centimos_totales % 100)
+}
+
+fn numero_en_letras(n: u64) -> String {
+    if n == 0 {
+        return "CERO".to_string();
+    }
+
+    let millones = n / 1_000_000;
+    let miles = (n / 1000) % 1000;
+    let resto = n % 1000;
+    let mut partes = Vec::new();
+
+    if millones == 1 {
+        partes.push("UN MILLON".to_string());
+    } else if millones > 1 {
+        partes.push(format!("{} MILLONES", apocopar(&numero_en_letras(millones))));
+    }
+    if miles == 1 {
+        partes.push("MIL".to_string());
+    } else if miles > 1 {
+        partes.push(format!("{} MIL", apocopar(&centenas(miles))));
+    }
+    if resto > 0 {
+        partes.push(centenas(resto));
+    }
+
+    partes.join(" ")
+}
+
+// "VEINTIUNO MIL" -> "VEINTIUN MIL"
+fn apocopar(texto: &str) -> String {
+    match texto.strip_suffix("UNO") {
+        Some(inicio) => format!("{}UN", inicio),
+        None => texto.to_string(),
+    }
+}
+
+fn centenas(n: u64) -> String {
+    const UNIDADES: [&str; 30] = [
+        "", "UNO", "DOS", "TRES", "CUATRO", "CINCO", "SEIS", "SIETE", "OCHO", "NUEVE",
+        "DIEZ", "ONCE", "DOCE", "TRECE", "CATORCE", "QUINCE", "DIECISEIS", "DIECISIETE", "DIECIOCHO", "DIECINUEVE",
+        "VEINTE", "VEINTIUNO", "VEINTIDOS", "VEINTITRES", "VEINTICUATRO", "VEINTICINCO", "VEINTISEIS", "VEINTISIETE",
+        "VEINTIOCHO", "VEINTINUEVE",
+    ];
+    const DECENAS: [&str; 10] = [
+        "", "", "", "TREINTA", "CUARENTA", "CINCUENTA", "SESENTA", "SETENTA", "OCHENTA", "NOVENTA",
+    ];
+    const CENTENAS: [&str; 10] = [
+        "", "CIENTO", "DOSCIENTOS", "TRESCIENTOS", "CUATROCIENTOS", "QUINIENTOS", "SEISCIENTOS",
+        "SETECIENTOS", "OCHOCIENTOS", "NOVECIENTOS",
+    ];
+
+    if n == 100 {
+        return "CIEN".to_string();
+    }
+
+    let c = (n / 100) as usize;
+    let d = (n % 100) as usize;
+    let decenas = if d < 30 {
+        UNIDADES[d].to_string()
+    } else if d.is_multiple_of(10) {
+        DECENAS[d / 10].to_string()
+    } else {
+        format!("{} Y {}", DECENAS[d / 10], UNIDADES[d % 10])
+    };
+
+    match (CENTENAS[c], decenas.as_str()) {
+        ("", d) => d.to_string(),
+        (c, "") => c.to_string(),
+        (c, d) => format!("{} {}", c, d),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn factura(cuotas: Vec<CuotaUbl>) -> DocumentoUbl {
+        DocumentoUbl {
+            tipo_comprobante: "01".to_string(),
+            numero: "F001-00000001".to_string(),
+            fecha_emision: "2026-03-07".to_string(),
+            hora_emision: "10:30:00".to_string(),
+            moneda: "PEN".to_string(),
+            tasa_igv: 18.0,
+            emisor: EmisorUbl {
+                ruc: "20131312955".to_string(),
+                razon_social: "TIENDA SAC".to_string(),
+                nombre_comercial: None,
+                direccion: "AV. LIMA 123".to_string(),
+                ubigeo: "150101".to_string(),
+            },
+            receptor: ReceptorUbl {
+                tipo_documento: "6".to_string(),
+                numero_documento: "20100070970".to_string(),
+                nombre: "CLIENTE SAC".to_string(),
+            },
+            lineas: vec![LineaUbl {
+                codigo: "P001".to_string(),
+                descripcion: "PANTALÓN".to_string(),
+                cantidad: 1,
+                precio_unitario: 118.0,
+                valor_venta: 100.0,
+                igv: 18.0,
+                afectacion: "GRAVADO".to_string(),
+            }],
+            totales: TotalesIgv { op_gravada: 100.0, op_exonerada: 0.0, op_inafecta: 0.0, igv: 18.0 },
+            total: 118.0,
+            referencia: None,
+            cuotas,
+        }
+    }
+
+    #[test]
+    fn factura_al_contado() {
+        let xml = generar_xml(&factura(Vec::new()));
+        assert!(xml.contains("<cbc:PaymentMeansID>Contado</cbc:PaymentMeansID>"));
+        assert!(!xml.contains("Cuota001"));
+    }
+
+    #[test]
+    fn factura_al_credito_con_cuotas() {
+        let xml = generar_xml(&factura(vec![CuotaUbl { monto: 80.0, fecha_vencimiento: "2026-04-06".to_string() }]));
+        assert!(!xml.contains("Contado"));
+        assert!(xml.contains(r#"<cbc:PaymentMeansID>Credito</cbc:PaymentMeansID><cbc:Amount currencyID="PEN">80.00</cbc:Amount>"#));
+        assert!(xml.contains(
+            r#"<cbc:PaymentMeansID>Cuota001</cbc:PaymentMeansID><cbc:Amount currencyID="PEN">80.00</cbc:Amount><cbc:PaymentDueDate>2026-04-06</cbc:PaymentDueDate>"#
+        ));
+    }
+}
@@ -0,0 +1,358 @@
+// commands/vales.rs
+// Vales: tarjetas de regalo (se venden) y vales por devolución.
+// El saldo del vale cambia siempre junto con una fila en vales_movimientos.
+// Lo vendido en tarjetas de regalo no es venta: es un pasivo hasta que se canjea.
+
+use crate::database::DatabasePool;
+use crate::commands::promociones::redondear;
+use crate::models::vale::*;
+use chrono::NaiveDate;
+use rusqlite::{params, OptionalExtension};
+
+// =====================================================
+// COMANDO: Vender tarjeta de regalo
+// El efectivo cobrado entra a la caja como INGRESO (no suma a ventas)
+// =====================================================
+#[tauri::command]
+pub fn vender_vale(
+    db: tauri::State<'_, DatabasePool>,
+    request: VentaValeRequest,
+    usuario_id: i32,
+) -> Result<Vale, String> {
+    let conn = db.get_conn();
+
+    let caja_id: i32 = conn
+        .query_row(
+            "SELECT id FROM cajas WHERE usuario_id = ? AND estado = 'ABIERTA'",
+            params![usuario_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Error al verificar caja: {}", e))?
+        .ok_or("⚠️ Debes abrir una caja antes de vender tarjetas de regalo")?;
+
+    if request.monto <= 0.0 {
+        return Err("❌ El monto de la tarjeta de regalo debe ser mayor a 0".to_string());
+    }
+    if !["EFECTIVO", "TARJETA", "TRANSFERENCIA"].contains(&request.metodo_pago.as_str()) {
+        return Err(format!("❌ Método de pago no válido: {}", request.metodo_pago));
+    }
+    if request.metodo_pago == "EFECTIVO" && request.monto_recibido.is_some_and(|r| r + 0.001 < request.monto) {
+        return Err(format!("❌ Efectivo insuficiente para S/ {:.2}", request.monto));
+    }
+    if let Some(fecha) = request.fecha_vencimiento.as_deref().filter(|f| !f.is_empty()) {
+        if NaiveDate::parse_from_str(fecha, "%Y-%m-%d").is_err() {
+            return Err(format!("❌ Fecha de vencimiento no válida: {} (usa AAAA-MM-DD)", fecha));
+        }
+    }
+
+    conn.execute("BEGIN TRANSACTION", [])
+        .map_err(|e| format!("Error al iniciar transacción: {}", e))?;
+
+    let rollback = |conn: &rusqlite::Connection, msg: String| -> String {
+        let _ = conn.execute("ROLLBACK", []);
+        msg
+    };
+
+    let vale = match emitir_vale(
+        &conn,
+        "VENTA",
+        redondear(request.monto),
+        request.cliente_id,
+        None,
+        Some((caja_id, request.metodo_pago.as_str())),
+        request.fecha_vencimiento.as_deref().filter(|f| !f.is_empty()),
+        usuario_id,
+        "Venta de tarjeta de regalo",
+    ) {
+        Ok(v) => v,
+        Err(e) => return Err(rollback(&conn, e)),
+    };
+
+    if request.metodo_pago == "EFECTIVO" {
+        if let Err(e) = conn.execute(
+            r"INSERT INTO movimientos_caja (caja_id, tipo, monto, motivo, usuario_id)
+              VALUES (?, 'INGRESO', ?, ?, ?)",
+            params![caja_id, vale.monto_inicial, format!("Venta de tarjeta de regalo {}", vale.codigo), usuario_id],
+        ) {
+            return Err(rollback(&conn, format!("Error al registrar ingreso en caja: {}", e)));
+        }
+    }
+
+    conn.execute("COMMIT", [])
+        .map_err(|e| format!("Error al confirmar: {}", e))?;
+
+    Ok(vale)
+}
+
+// =====================================================
+// COMANDO: Consultar vale por código (con sus movimientos)
+// =====================================================
+#[tauri::command]
+pub fn obtener_vale(
+    db: tauri::State<'_, DatabasePool>,
+    codigo: String,
+) -> Result<ValeDetalle, String> {
+    let conn = db.get_conn();
+
+    vencer_vales(&conn)?;
+    let vale = obtener_vale_por_codigo(&conn, &codigo)?;
+
+    let mut stmt = conn
+        .prepare(
+            r"SELECT m.id, m.tipo, m.monto, m.saldo_resultante, v.folio, m.descripcion,
+                     strftime('%Y-%m-%d %H:%M', m.fecha_hora)
+              FROM vales_movimientos m
+              LEFT JOIN ventas v ON v.id = m.venta_id
+              WHERE m.vale_id = ?
+              ORDER BY m.id",
+        )
+        .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+
+    let movimientos = stmt
+        .query_map(params![vale.id], |row| {
+            Ok(MovimientoVale {
+                id: row.get(0)?,
+                tipo: row.get(1)?,
+                monto: row.get(2)?,
+                saldo_resultante: row.get(3)?,
+                folio_venta: row.get(4)?,
+                descripcion: row.get(5)?,
+                fecha_hora: row.get(6)?,
+            })
+        })
+        .map_err(|e| format!("Error al obtener movimientos: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(ValeDetalle { vale, movimientos })
+}
+
+// =====================================================
+// COMANDO: Listar vales (opcionalmente de un cliente / solo activos)
+// =====================================================
+#[tauri::command]
+pub fn obtener_vales(
+    db: tauri::State<'_, DatabasePool>,
+    cliente_id: Option<i32>,
+    solo_activos: Option<bool>,
+) -> Result<Vec<Vale>, String> {
+    let conn = db.get_conn();
+
+    vencer_vales(&conn)?;
+
+    let query = format!(
+        r"{}
+          WHERE (?1 IS NULL OR va.cliente_id = ?1)
+            AND (?2 = 0 OR va.estado = 'ACTIVO')
+          ORDER BY va.fecha_emision DESC",
+        SELECT_VALE
+    );
+
+    let mut stmt = conn
+        .prepare(&query)
+        .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+
+    let vales = stmt
+        .query_map(params![cliente_id, solo_activos.unwrap_or(false) as i32], mapear_vale)
+        .map_err(|e| format!("Error al obtener vales: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(vales)
+}
+
+// =====================================================
+// FUNCIONES AUXILIARES (usadas por ventas y devoluciones)
+// =====================================================
+
+const SELECT_VALE: &str = r"
+    SELECT va.id, va.codigo, va.origen, va.monto_inicial, va.saldo, va.estado,
+           va.fecha_emision, va.fecha_vencimiento, va.cliente_id, c.nombre, va.devolucion_id
+    FROM vales va
+    LEFT JOIN clientes c ON c.id = va.cliente_id";
+
+fn mapear_vale(row: &rusqlite::Row) -> rusqlite::Result<Vale> {
+    Ok(Vale {
+        id: row.get(0)?,
+        codigo: row.get(1)?,
+        origen: row.get(2)?,
+        monto_inicial: row.get(3)?,
+        saldo: row.get(4)?,
+        estado: row.get(5)?,
+        fecha_emision: row.get(6)?,
+        fecha_vencimiento: row.get(7)?,
+        cliente_id: row.get(8)?,
+        cliente_nombre: row.get(9)?,
+        devolucion_id: row.get(10)?,
+    })
+}
+
+pub fn obtener_vale_por_codigo(conn: &rusqlite::Connection, codigo: &str) -> Result<Vale, String> {
+    conn.query_row(
+        &format!("{} WHERE va.codigo = ?", SELECT_VALE),
+        params![codigo.trim().to_uppercase()],
+        mapear_vale,
+    )
+    .optional()
+    .map_err(|e| format!("Error al obtener vale: {}", e))?
+    .ok_or_else(|| format!("❌ El vale {} no existe", codigo.trim().to_uppercase()))
+}
+
+// Crea el vale con código único y su movimiento de EMISION.
+// `venta_vale` = (caja, método de pago) cuando es una tarjeta de regalo vendida.
+#[allow(clippy::too_many_arguments)]
+pub fn emitir_vale(
+    conn: &rusqlite::Connection,
+    origen: &str,
+    monto: f64,
+    cliente_id: Option<i32>,
+    devolucion_id: Option<i32>,
+    venta_vale: Option<(i32, &str)>,
+    fecha_vencimiento: Option<&str>,
+    usuario_id: i32,
+    descripcion: &str,
+) -> Result<Vale, String> {
+    let codigo = loop {
+        let codigo: String = conn
+            .query_row("SELECT 'VALE-' || upper(hex(randomblob(4)))", [], |row| row.get(0))
+            .map_err(|e| format!("Error al generar código: {}", e))?;
+        let existe = conn
+            .query_row("SELECT 1 FROM vales WHERE codigo = ?", params![&codigo], |_| Ok(()))
+            .optional()
+            .map_err(|e| format!("Error al verificar código: {}", e))?;
+        if existe.is_none() {
+            break codigo;
+        }
+    };
+
+    conn.execute(
+        r"INSERT INTO vales
+            (codigo, origen, monto_inicial, saldo, fecha_vencimiento, cliente_id,
+             devolucion_id, caja_id, metodo_pago, usuario_id)
+          VALUES (?1, ?2, ?3, ?3,
+                  COALESCE(?4, date('now', 'localtime', '+' ||
+                      (SELECT COALESCE(vales_meses_vencimiento, 12) FROM configuracion_tienda LIMIT 1) || ' months')),
+                  ?5, ?6, ?7, ?8, ?9)",
+        params![
+            &codigo,
+            origen,
+            monto,
+            fecha_vencimiento,
+            cliente_id,
+            devolucion_id,
+            venta_vale.map(|v| v.0),
+            venta_vale.map(|v| v.1),
+            usuario_id,
+        ],
+    )
+    .map_err(|e| format!("Error al emitir vale: {}", e))?;
+
+    let vale_id = conn.last_insert_rowid() as i32;
+
+    conn.execute(
+        r"INSERT INTO vales_movimientos (vale_id, tipo, monto, saldo_resultante, devolucion_id, descripcion, usuario_id)
+          VALUES (?1, 'EMISION', ?2, ?2, ?3, ?4, ?5)",
+        params![vale_id, monto, devolucion_id, descripcion, usuario_id],
+    )
+    .map_err(|e| format!("Error al registrar emisión del vale: {}", e))?;
+
+    obtener_vale_por_codigo(conn, &codigo)
+}
+
+// Marca como VENCIDO todo vale activo con fecha pasada, dejando su movimiento
+pub fn vencer_vales(conn: &rusqlite::Connection) -> Result<(), String> {
+    conn.execute_batch(
+        r"INSERT INTO vales_movimientos (vale_id, tipo, monto, saldo_resultante, descripcion)
+          SELECT id, 'VENCIMIENTO', -saldo, 0, 'Vale vencido'
+          FROM vales
+          WHERE estado = 'ACTIVO' AND saldo > 0 AND fecha_vencimiento < date('now', 'localtime');
+
+          UPDATE vales SET saldo = 0, estado = 'VENCIDO'
+          WHERE estado = 'ACTIVO' AND fecha_vencimiento < date('now', 'localtime');",
+    )
+    .map_err(|e| format!("Error al vencer vales: {}", e))
+}
+
+// Verifica que el vale pueda pagar `monto` (activo, vigente y con saldo)
+pub fn validar_vale(conn: &rusqlite::Connection, codigo: &str, monto: f64) -> Result<Vale, String> {
+    vencer_vales(conn)?;
+    let vale = obtener_vale_por_codigo(conn, codigo)?;
+
+    match vale.estado.as_str() {
+        "VENCIDO" => return Err(format!("❌ El vale {} venció el {}", vale.codigo, vale.fecha_vencimiento.as_deref().unwrap_or("-"))),
+        "AGOTADO" => return Err(format!("❌ El vale {} ya no tiene saldo", vale.codigo)),
+        _ => {}
+    }
+
+    if monto > vale.saldo + 0.001 {
+        return Err(format!(
+            "❌ Saldo insuficiente en el vale {}: disponible S/ {:.2}, a pagar S/ {:.2}",
+            vale.codigo, vale.saldo, monto
+        ));
+    }
+
+    Ok(vale)
+}
+
+// Descuenta `monto` del vale (canje total o parcial) dentro de la transacción de la venta
+pub fn canjear_vale(
+    conn: &rusqlite::Connection,
+    vale_id: i32,
+    monto: f64,
+    venta_id: i32,
+    folio: &str,
+    usuario_id: i32,
+) -> Result<(), String> {
+    conn.execute(
+        r"UPDATE vales
+          SET saldo = ROUND(saldo - ?1, 2),
+              estado = CASE WHEN ROUND(saldo - ?1, 2) <= 0 THEN 'AGOTADO' ELSE 'ACTIVO' END
+          WHERE id = ?2",
+        params![monto, vale_id],
+    )
+    .map_err(|e| format!("Error al descontar vale: {}", e))?;
+
+    conn.execute(
+        r"INSERT INTO vales_movimientos (vale_id, tipo, monto, saldo_resultante, venta_id, descripcion, usuario_id)
+          VALUES (?, 'CANJE', ?, (SELECT saldo FROM vales WHERE id = ?), ?, ?, ?)",
+        params![vale_id, -monto, vale_id, venta_id, format!("Pago de la venta {}", folio), usuario_id],
+    )
+    .map_err(|e| format!("Error al registrar canje del vale: {}", e))?;
+
+    Ok(())
+}
+
+// Venta anulada: devuelve a cada vale lo que se canjeó en ella
+pub fn revertir_vales_venta(conn: &rusqlite::Connection, venta_id: i32, usuario_id: i32) -> Result<(), String> {
+    let canjes: Vec<(i32, f64)> = {
+        let mut stmt = conn
+            .prepare(
+                r"SELECT vale_id, -SUM(monto) FROM vales_movimientos
+                  WHERE venta_id = ? GROUP BY vale_id HAVING SUM(monto) < 0",
+            )
+            .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+        let canjes = stmt
+            .query_map(params![venta_id], |row| Ok((row.get(0)?, row.get(1)?)))
+            .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+            .map_err(|e| format!("Error al obtener canjes: {}", e))?;
+        canjes
+    };
+
+    for (vale_id, monto) in canjes {
+        conn.execute(
+            "UPDATE vales SET saldo = ROUND(saldo + ?, 2), estado = 'ACTIVO' WHERE id = ?",
+            params![monto, vale_id],
+        )
+        .map_err(|e| format!("Error al reponer vale: {}", e))?;
+
+        conn.execute(
+            r"INSERT INTO vales_movimientos (vale_id, tipo, monto, saldo_resultante, venta_id, descripcion, usuario_id)
+              VALUES (?, 'REVERSION', ?, (SELECT saldo FROM vales WHERE id = ?), ?, 'Anulación de la venta', ?)",
+            params![vale_id, monto, vale_id, venta_id, usuario_id],
+        )
+        .map_err(|e| format!("Error al registrar reversión del vale: {}", e))?;
+    }
+
+    Ok(())
+}
@@ -2,6 +2,25 @@
 // Comandos de ventas - SQLite
 
 use crate::database::DatabasePool;
+use crate::commands::auth::autorizar_operacion;
+use crate::commands::suspendidas::stock_disponible;
+use crate::commands::promociones::{evaluar_carrito, redondear};
+use crate::commands::cupones::aplicar_cupon;
+use crate::commands::clientes::obtener_cliente_por_id;
+use crate::commands::puntos::{
+    configuracion_puntos, puntos_para_monto, registrar_movimiento_puntos, revertir_puntos_venta, saldo_puntos,
+};
+use crate::commands::impuestos::{afectacion_producto, desglosar_igv, tasa_igv, TotalesIgv};
+use crate::commands::numeracion::{numero_comprobante, siguiente_correlativo, siguiente_folio};
+use crate::commands::facturacion::{codigo_tipo_comprobante, receptor_venta};
+use crate::commands::cotizaciones::{marcar_cotizacion_convertida, validar_carrito_cotizacion};
+use crate::commands::creditos::{tiene_cobros, validar_credito};
+use crate::commands::listas_precios::{aplicar_lista_precios, lista_precios_venta};
+use crate::commands::idempotencia::{normalizar_clave, registrar_resultado, resultado_previo};
+use crate::commands::comisiones::{registrar_comisiones_venta, revertir_comisiones_venta, validar_vendedor};
+use crate::commands::vales::{canjear_vale, obtener_vale_por_codigo, revertir_vales_venta, validar_vale};
+use crate::models::AutorizacionSupervisor;
+use crate::models::venta::{DevolucionDeVenta, LineaVentaDetalle, PagoVentaDetalle, VentaDetalleCompleto};
 use rusqlite::params;
 use rusqlite::OptionalExtension;
 use serde::{Deserialize, Serialize};
@@ -23,31 +42,70 @@ pub struct ProductoVenta {
     #[serde(rename = "varianteId")]
     pub variante_id: Option<i32>,
     pub talla: Option<String>,
+    // 🆕 Quien vendió esta línea, si no es el vendedor de la venta
+    #[serde(rename = "vendedorId")]
+    pub vendedor_id: Option<i32>,
+}
+
+// 🆕 Línea de pago: una por método (ventas MIXTO)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PagoVenta {
+    #[serde(rename = "metodoPago")]
+    pub metodo_pago: String,
+    pub monto: f64,
+    // Solo EFECTIVO: lo que entregó el cliente (el cambio sale de aquí)
+    #[serde(rename = "montoRecibido")]
+    pub monto_recibido: Option<f64>,
+    // Voucher de tarjeta o N° de operación de la transferencia
+    pub referencia: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct VentaResult {
     pub venta_id: i32,
     pub folio: String,
+    pub tipo_comprobante: String,   // 🆕 BOLETA | FACTURA | NOTA_VENTA
+    pub numero_comprobante: String, // 🆕 B001-00000123
+    #[serde(default)]
+    pub subtotal: f64,              // 🆕 calculados en el servidor (listas, promociones y cupón)
+    #[serde(default)]
+    pub descuento: f64,
+    #[serde(default)]
+    pub total: f64,
+    pub cambio: Option<f64>,        // 🆕 ya calculado sobre el efectivo redondeado
+    pub redondeo: f64,              // 🆕 cobrado en efectivo - monto exacto
 }
 
 // =====================================================
 // COMANDO: Procesar venta
 // =====================================================
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn procesar_venta(
     db: tauri::State<'_, DatabasePool>,
-    productos: Vec<ProductoVenta>,
-    total: f64,
+    mut productos: Vec<ProductoVenta>,
     metodo_pago: String,
     monto_recibido: Option<f64>,
-    cambio: Option<f64>,
     usuario_id: i32,
+    pagos: Option<Vec<PagoVenta>>,
+    codigo_cupon: Option<String>,
+    cliente_id: Option<i32>,
+    tipo_comprobante: Option<String>, // 🆕 BOLETA | FACTURA | NOTA_VENTA (por defecto)
+    cotizacion_id: Option<i32>,       // 🆕 venta de una cotización (cargar_cotizacion)
+    lista_precio_id: Option<i32>,     // 🆕 lista elegida en el POS (si no, la del cliente)
+    vendedor_id: Option<i32>,         // 🆕 quien atendió (comisiones); usuario_id es el cajero
+    clave_idempotencia: Option<String>, // 🆕 misma clave en un reintento = misma venta
 ) -> Result<VentaResult, String> {
     let conn = db.get_conn();
 
+    // 🆕 Reintento (doble clic, respuesta lenta): se devuelve la venta ya registrada
+    let clave_idempotencia = normalizar_clave(clave_idempotencia)?;
+    if let Some(previa) = resultado_previo(&conn, "VENTA", clave_idempotencia.as_deref())? {
+        return Ok(previa);
+    }
+
     // Verificar caja abierta
-    let caja_abierta: Option<i32> = conn
+    let caja_id: Option<i32> = conn
         .query_row(
             "SELECT id FROM cajas WHERE usuario_id = ? AND estado = 'ABIERTA'",
             params![usuario_id],
@@ -56,39 +114,189 @@ pub fn procesar_venta(
         .optional()
         .map_err(|e| format!("Error al verificar caja: {}", e))?;
 
-    if caja_abierta.is_none() {
-        return Err("⚠️ Debes abrir una caja antes de procesar ventas".to_string());
+    let caja_id = match caja_id {
+        Some(id) => id,
+        None => return Err("⚠️ Debes abrir una caja antes de procesar ventas".to_string()),
+    };
+
+    // 🆕 Desde una cotización: el carrito debe ser el cotizado (con precios
+    //    revalidados) y el cliente, el de la cotización
+    let cliente_id = match cotizacion_id {
+        Some(id) => validar_carrito_cotizacion(&conn, id, &productos, cliente_id)?,
+        None => cliente_id,
+    };
+
+    // 🆕 Cliente opcional (venta anónima si no se indica)
+    if let Some(id) = cliente_id {
+        let cliente = obtener_cliente_por_id(&conn, id)?;
+        if !cliente.activo {
+            return Err(format!("❌ El cliente {} está inactivo", cliente.nombre));
+        }
+    }
+
+    // 🆕 Vendedor de la venta y de las líneas atendidas por otra persona
+    for id in vendedor_id.iter().chain(productos.iter().filter_map(|p| p.vendedor_id.as_ref())) {
+        validar_vendedor(&conn, *id)?;
+    }
+
+    // 🆕 Precio de cada línea según la lista de precios y su escala por cantidad
+    //    (el de la cotización se respeta tal cual fue revalidado)
+    let lista_precio_id = match cotizacion_id {
+        Some(_) => None,
+        None => {
+            let lista = lista_precios_venta(&conn, lista_precio_id, cliente_id)?.map(|(id, _)| id);
+            aplicar_lista_precios(&conn, lista, &mut productos)?;
+            lista
+        }
+    };
+
+    // 🆕 Promociones vigentes y cupón: el total lo calcula el servidor (el POS
+    //    no conoce promociones, cupones ni listas) y se devuelve en el resultado
+    let promociones = evaluar_carrito(&conn, &productos)?;
+    let cupon = match codigo_cupon.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+        Some(codigo) => Some(aplicar_cupon(&conn, codigo, &productos, &promociones, cliente_id)?),
+        None => None,
+    };
+    let descuento_cupon = cupon.as_ref().map_or(0.0, |c| c.descuento);
+    let total = redondear(promociones.total - descuento_cupon);
+
+    // Sin líneas de pago: venta de un solo método (compatibilidad con el POS)
+    let mut pagos = match pagos {
+        Some(p) if !p.is_empty() => p,
+        _ => {
+            if metodo_pago == "MIXTO" {
+                return Err("❌ Una venta MIXTO debe indicar el detalle de pagos".to_string());
+            }
+            vec![PagoVenta {
+                metodo_pago: metodo_pago.clone(),
+                monto: total,
+                monto_recibido: if metodo_pago == "EFECTIVO" { monto_recibido } else { None },
+                referencia: None,
+            }]
+        }
+    };
+
+    // 🆕 El efectivo se cobra redondeado a S/ 0.10 según la configuración
+    let regla_redondeo = regla_redondeo_efectivo(&conn)?;
+    let (metodo_venta, monto_recibido_total, cambio, redondeo) = validar_pagos(&pagos, total, &regla_redondeo)?;
+
+    // 🆕 Parte al crédito: cliente con crédito autorizado, sin deuda vencida
+    //    y dentro de su límite
+    let monto_credito = redondear(pagos.iter().filter(|p| p.metodo_pago == "CREDITO").map(|p| p.monto).sum());
+    let fecha_vencimiento_pago = if monto_credito > 0.0 {
+        Some(validar_credito(&conn, cliente_id, monto_credito)?)
+    } else {
+        None
+    };
+
+    // 🆕 Tipo de comprobante: la factura exige cliente con RUC y la boleta
+    // desde S/ 700 exige identificar al cliente
+    let tipo_comprobante = tipo_comprobante
+        .map(|t| t.trim().to_uppercase())
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| "NOTA_VENTA".to_string());
+    match codigo_tipo_comprobante(&tipo_comprobante) {
+        Some(codigo) => {
+            receptor_venta(&conn, cliente_id, codigo, total)?;
+        }
+        None if tipo_comprobante == "NOTA_VENTA" => {}
+        None => return Err("❌ El comprobante debe ser BOLETA, FACTURA o NOTA_VENTA".to_string()),
+    }
+
+    // 🆕 Pago con puntos: requiere cliente y saldo suficiente
+    let config_puntos = configuracion_puntos(&conn)?;
+    let monto_puntos: f64 = pagos.iter().filter(|p| p.metodo_pago == "PUNTOS").map(|p| p.monto).sum();
+    let puntos_canjeados = if monto_puntos > 0.0 {
+        let id = cliente_id.ok_or("❌ Para pagar con puntos debes indicar el cliente")?;
+        let necesarios = puntos_para_monto(&config_puntos, monto_puntos);
+        let saldo = saldo_puntos(&conn, id)?;
+        if necesarios > saldo {
+            return Err(format!(
+                "❌ Puntos insuficientes: se necesitan {} y el cliente tiene {}",
+                necesarios, saldo
+            ));
+        }
+        necesarios
+    } else {
+        0
+    };
+
+    // 🆕 Pago con vales: el código va en `referencia`; un mismo vale puede
+    //    venir en varias líneas, así que se valida el total por vale
+    let mut vales_a_canjear: Vec<(i32, f64)> = Vec::new();
+    for pago in pagos.iter_mut().filter(|p| p.metodo_pago == "VALE") {
+        let codigo = pago.referencia.as_deref().unwrap_or_default().trim().to_uppercase();
+        pago.referencia = Some(codigo.clone());
+
+        let vale_id = obtener_vale_por_codigo(&conn, &codigo)?.id;
+        let acumulado = match vales_a_canjear.iter_mut().find(|(id, _)| *id == vale_id) {
+            Some((_, monto)) => {
+                *monto += pago.monto;
+                *monto
+            }
+            None => {
+                vales_a_canjear.push((vale_id, pago.monto));
+                pago.monto
+            }
+        };
+        validar_vale(&conn, &codigo, acumulado)?;
     }
 
-    // 🆕 Validar stock de variantes antes de iniciar transacción
+    // 🆕 Validar stock antes de iniciar transacción, descontando lo reservado
+    //    por carritos en espera (ventas suspendidas con reserva)
     for producto in &productos {
         if let Some(variante_id) = producto.variante_id {
-            let stock_variante: Option<i32> = conn
+            let existe: bool = conn
                 .query_row(
-                    "SELECT stock FROM producto_variantes WHERE id = ? AND activo = 1",
+                    "SELECT 1 FROM producto_variantes WHERE id = ? AND activo = 1",
                     params![variante_id],
-                    |row| row.get(0),
+                    |_| Ok(true),
                 )
                 .optional()
-                .map_err(|e| format!("Error al verificar stock de talla: {}", e))?;
+                .map_err(|e| format!("Error al verificar stock de talla: {}", e))?
+                .unwrap_or(false);
 
-            match stock_variante {
-                None => return Err(format!(
+            if !existe {
+                return Err(format!(
                     "❌ Talla {} de '{}' no encontrada",
                     producto.talla.as_deref().unwrap_or("?"),
                     producto.nombre
-                )),
-                Some(stock) if stock < producto.cantidad => return Err(format!(
-                    "❌ Stock insuficiente para {} talla {} (disponible: {}, solicitado: {})",
-                    producto.nombre,
-                    producto.talla.as_deref().unwrap_or("?"),
-                    stock,
-                    producto.cantidad
-                )),
-                _ => {}
+                ));
             }
         }
+
+        let disponible = stock_disponible(&conn, producto.id, producto.variante_id)?;
+        if disponible < producto.cantidad {
+            return Err(match &producto.talla {
+                Some(talla) => format!(
+                    "❌ Stock insuficiente para {} talla {} (disponible: {}, solicitado: {})",
+                    producto.nombre, talla, disponible, producto.cantidad
+                ),
+                None => format!(
+                    "❌ Stock insuficiente para {} (disponible: {}, solicitado: {})",
+                    producto.nombre, disponible, producto.cantidad
+                ),
+            });
+        }
+    }
+
+    // 🆕 IGV por línea: los precios lo incluyen, se separa base e impuesto
+    let cupon_de = |indice: usize| {
+        cupon
+            .as_ref()
+            .and_then(|c| c.lineas.iter().find(|l| l.indice == indice))
+            .map_or(0.0, |l| l.descuento)
+    };
+    let tasa = tasa_igv(&conn)?;
+    let mut totales_igv = TotalesIgv::default();
+    let mut igv_lineas: Vec<(String, f64, f64)> = Vec::with_capacity(productos.len());
+    for (p, linea) in productos.iter().zip(&promociones.lineas) {
+        let afectacion = afectacion_producto(&conn, p.id)?;
+        let (valor_venta, igv) = desglosar_igv(linea.total_linea - cupon_de(linea.indice), &afectacion, tasa);
+        totales_igv.agregar(&afectacion, valor_venta, igv);
+        igv_lineas.push((afectacion, valor_venta, igv));
     }
+    let totales_igv = totales_igv.redondeados();
 
     // Iniciar transacción
     conn.execute("BEGIN TRANSACTION", [])
@@ -100,55 +308,71 @@ pub fn procesar_venta(
         msg
     };
 
-    // 1. Generar folio único
-    let fecha_actual = chrono::Local::now().format("%Y%m%d").to_string();
-    let folio_query = format!(
-        "SELECT COALESCE(MAX(CAST(substr(folio, -4) AS INTEGER)), 0) + 1
-         FROM ventas WHERE folio LIKE 'V-{}%'",
-        fecha_actual
-    );
-
-    let siguiente_numero: i32 = conn
-        .query_row(&folio_query, [], |row| row.get(0))
-        .unwrap_or(1);
-
-    let folio = format!("V-{}-{:04}", fecha_actual, siguiente_numero);
+    // 1. Generar folio único (🆕 numeración central, dentro de la transacción)
+    let folio = match siguiente_folio(&conn, "VENTA") {
+        Ok(f) => f,
+        Err(e) => return Err(rollback(&conn, e)),
+    };
 
-    // 2. Calcular subtotal y descuento total
-    let mut subtotal = 0.0f64;
-    let mut descuento_total = 0.0f64;
+    // 🆕 Serie y número del comprobante según la caja
+    let (serie, correlativo) = match siguiente_correlativo(&conn, &tipo_comprobante, caja_id) {
+        Ok(s) => s,
+        Err(e) => return Err(rollback(&conn, e)),
+    };
 
-    for p in &productos {
-        let sub = p.precio * p.cantidad as f64;
-        let desc = sub * (p.descuento_porcentaje.unwrap_or(0.0) / 100.0);
-        subtotal += sub;
-        descuento_total += desc;
-    }
+    // 2. Subtotal y descuento total (manual + promociones + cupón, ya calculados)
+    let subtotal = promociones.subtotal;
+    let descuento_total = promociones.descuento_manual + promociones.descuento_promociones + descuento_cupon;
 
     // 3. Insertar venta
     if let Err(e) = conn.execute(
         r"INSERT INTO ventas (folio, subtotal, descuento, total, metodo_pago,
-                              monto_recibido, cambio, usuario_id, estado)
-          VALUES (?, ?, ?, ?, ?, ?, ?, ?, 'COMPLETADA')",
-        params![&folio, subtotal, descuento_total, total, &metodo_pago,
-                monto_recibido, cambio, usuario_id],
+                              monto_recibido, cambio, usuario_id, caja_id, cliente_id,
+                              cupon_id, descuento_cupon, op_gravada, op_exonerada,
+                              op_inafecta, igv, igv_porcentaje, tipo_comprobante,
+                              serie, correlativo, cotizacion_id, saldo_pendiente,
+                              fecha_vencimiento_pago, estado_pago, redondeo, lista_precio_id, vendedor_id, estado)
+          VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 'COMPLETADA')",
+        params![&folio, subtotal, descuento_total, total, &metodo_venta,
+                monto_recibido_total, cambio, usuario_id, caja_id, cliente_id,
+                cupon.as_ref().map(|c| c.cupon_id), descuento_cupon,
+                totales_igv.op_gravada, totales_igv.op_exonerada, totales_igv.op_inafecta,
+                totales_igv.igv, tasa, &tipo_comprobante, &serie, correlativo, cotizacion_id,
+                monto_credito, &fecha_vencimiento_pago,
+                fecha_vencimiento_pago.as_ref().map(|_| "PENDIENTE"), redondeo, lista_precio_id, vendedor_id],
     ) {
         return Err(rollback(&conn, format!("Error al insertar venta: {}", e)));
     }
 
     let venta_id = conn.last_insert_rowid() as i32;
 
-    // 4. Insertar detalles con variante_id y talla
-    for p in &productos {
-        let sub = p.precio * p.cantidad as f64;
-        let desc = sub * (p.descuento_porcentaje.unwrap_or(0.0) / 100.0);
-        let total_linea = sub - desc;
+    // 🆕 La cotización queda CONVERTIDA y enlazada a la venta
+    if let Some(id) = cotizacion_id {
+        if let Err(e) = marcar_cotizacion_convertida(&conn, id, venta_id) {
+            return Err(rollback(&conn, e));
+        }
+    }
+
+    // 4. Insertar detalles con variante_id, talla y promociones aplicadas
+    let mut base_puntos = 0.0f64;
+    for ((p, linea), (afectacion, valor_venta, igv)) in productos.iter().zip(&promociones.lineas).zip(&igv_lineas) {
+        let cupon_linea = cupon_de(linea.indice);
+
+        let promociones_aplicadas = if linea.promociones.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_string(
+                &linea.promociones.iter().map(|a| a.promocion_id).collect::<Vec<_>>(),
+            ).unwrap_or_default())
+        };
 
         if let Err(e) = conn.execute(
             r"INSERT INTO detalles_venta
                 (venta_id, producto_id, variante_id, talla, cantidad,
-                 precio_unitario, subtotal, descuento_linea, total_linea)
-              VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                 precio_unitario, subtotal, descuento_linea, descuento_promocion,
+                 descuento_cupon, promociones_aplicadas, total_linea,
+                 afectacion_igv, valor_venta, igv, vendedor_id)
+              VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
             params![
                 venta_id,
                 p.id,
@@ -156,19 +380,663 @@ pub fn procesar_venta(
                 p.talla,         // NULL si no tiene tallas
                 p.cantidad,
                 p.precio,
-                sub,
-                desc,
-                total_linea,
+                linea.subtotal,
+                redondear(linea.descuento_manual + linea.descuento_promocion + cupon_linea),
+                linea.descuento_promocion,
+                cupon_linea,
+                promociones_aplicadas,
+                redondear(linea.total_linea - cupon_linea),
+                afectacion,
+                valor_venta,
+                igv,
+                p.vendedor_id,
             ],
         ) {
             return Err(rollback(&conn, format!("Error al insertar detalle: {}", e)));
         }
+
+        let con_descuento = linea.descuento_manual + linea.descuento_promocion + cupon_linea > 0.0;
+        if !(config_puntos.excluir_descuentos && con_descuento) {
+            base_puntos += linea.total_linea - cupon_linea;
+        }
     }
 
-    // 5. Commit
+    // 5. Insertar líneas de pago — trg_actualizar_caja_pago_venta suma cada
+    //    método a su total en la caja (la línea CREDITO no entra a la caja)
+    for pago in &pagos {
+        if let Err(e) = conn.execute(
+            r"INSERT INTO pagos_venta (venta_id, metodo_pago, monto, monto_recibido, referencia)
+              VALUES (?, ?, ?, ?, ?)",
+            params![venta_id, &pago.metodo_pago, pago.monto, pago.monto_recibido, &pago.referencia],
+        ) {
+            return Err(rollback(&conn, format!("Error al registrar pago: {}", e)));
+        }
+    }
+
+    // 6. Registrar el uso del cupón (cuenta para sus límites)
+    if let Some(c) = &cupon {
+        if let Err(e) = conn.execute(
+            "INSERT INTO cupones_redenciones (cupon_id, venta_id, cliente_id, monto_descuento) VALUES (?, ?, ?, ?)",
+            params![c.cupon_id, venta_id, cliente_id, c.descuento],
+        ) {
+            return Err(rollback(&conn, format!("Error al registrar cupón: {}", e)));
+        }
+    }
+
+    // 7. 🆕 Descontar los vales usados como pago
+    for (vale_id, monto) in &vales_a_canjear {
+        if let Err(e) = canjear_vale(&conn, *vale_id, *monto, venta_id, &folio, usuario_id) {
+            return Err(rollback(&conn, e));
+        }
+    }
+
+    // 8. 🆕 Puntos: canje y acumulación. Lo pagado con puntos no genera puntos
+    if let Some(id) = cliente_id {
+        if let Err(e) = registrar_movimiento_puntos(
+            &conn, id, "CANJE", -puntos_canjeados, Some(venta_id), None,
+            &format!("Pago de la venta {}", folio), usuario_id,
+        ) {
+            return Err(rollback(&conn, e));
+        }
+
+        let fraccion_pagada = if total > 0.0 { 1.0 - (monto_puntos / total).min(1.0) } else { 0.0 };
+        let ganados = (base_puntos * fraccion_pagada * config_puntos.puntos_por_sol + 0.000001).floor() as i64;
+        if let Err(e) = registrar_movimiento_puntos(
+            &conn, id, "ACUMULACION", ganados, Some(venta_id), None,
+            &format!("Compra {}", folio), usuario_id,
+        ) {
+            return Err(rollback(&conn, e));
+        }
+    }
+
+    // 9. 🆕 Comisión de cada línea con vendedor, con la regla vigente hoy
+    if let Err(e) = registrar_comisiones_venta(&conn, venta_id) {
+        return Err(rollback(&conn, e));
+    }
+
+    let resultado = VentaResult {
+        venta_id,
+        folio,
+        tipo_comprobante,
+        numero_comprobante: numero_comprobante(&serie, correlativo),
+        subtotal,
+        descuento: redondear(descuento_total),
+        total,
+        cambio,
+        redondeo,
+    };
+
+    // 10. 🆕 La clave queda con el resultado, en la misma transacción
+    if let Err(e) = registrar_resultado(&conn, "VENTA", clave_idempotencia.as_deref(), venta_id, &resultado) {
+        return Err(rollback(&conn, e));
+    }
+
+    // 11. Commit
     if let Err(e) = conn.execute("COMMIT", []) {
         return Err(format!("Error al confirmar transacción: {}", e));
     }
 
-    Ok(VentaResult { venta_id, folio })
-}
\ No newline at end of file
+    Ok(resultado)
+}
+
+// =====================================================
+// 🆕 COMANDO: Anular venta
+// Solo ventas de la caja abierta; las anteriores van por devolución.
+// Los triggers trg_after_venta_cancelar y trg_actualizar_caja_cancelar_venta
+// reponen el stock y descuentan la venta de la caja.
+// =====================================================
+#[tauri::command]
+pub fn anular_venta(
+    db: tauri::State<'_, DatabasePool>,
+    venta_id: i32,
+    motivo: String,
+    usuario_id: i32,
+    autorizacion: Option<AutorizacionSupervisor>,
+) -> Result<String, String> {
+    let conn = db.get_conn();
+
+    if motivo.trim().is_empty() {
+        return Err("❌ Debes indicar el motivo de la anulación".to_string());
+    }
+
+    let venta: Option<(String, String, Option<i32>)> = conn
+        .query_row(
+            "SELECT folio, estado, caja_id FROM ventas WHERE id = ?",
+            params![venta_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()
+        .map_err(|e| format!("Error al buscar venta: {}", e))?;
+
+    let (folio, estado, caja_venta) = match venta {
+        Some(v) => v,
+        None => return Err("❌ Venta no encontrada".to_string()),
+    };
+
+    if estado != "COMPLETADA" {
+        return Err(format!("❌ No se puede anular una venta en estado {}", estado));
+    }
+
+    let autorizado_por = autorizar_operacion(&conn, usuario_id, autorizacion.as_ref())?;
+
+    // Solo ventas cuya propia caja sigue abierta
+    let caja_abierta: bool = match caja_venta {
+        Some(caja_id) => conn
+            .query_row(
+                "SELECT COUNT(*) FROM cajas WHERE id = ? AND estado = 'ABIERTA'",
+                params![caja_id],
+                |row| row.get::<_, i32>(0),
+            )
+            .map_err(|e| format!("Error al verificar caja: {}", e))?
+            > 0,
+        None => false,
+    };
+
+    if !caja_abierta {
+        return Err("❌ Solo se pueden anular ventas de la caja abierta. Para ventas anteriores registra una devolución".to_string());
+    }
+
+    // Con devoluciones parciales el stock y la caja ya fueron ajustados una vez
+    let devoluciones: i32 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM devoluciones WHERE venta_original_id = ? AND estado = 'PROCESADA'",
+            params![venta_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Error al verificar devoluciones: {}", e))?;
+
+    if devoluciones > 0 {
+        return Err("❌ La venta tiene devoluciones registradas; no puede anularse".to_string());
+    }
+
+    // 🆕 La venta de un cambio de talla va unida a su devolución
+    let cambio: Option<String> = conn
+        .query_row(
+            "SELECT c.folio FROM ventas v JOIN cambios c ON c.id = v.cambio_id WHERE v.id = ?",
+            params![venta_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Error al verificar cambio: {}", e))?;
+
+    if let Some(folio_cambio) = cambio {
+        return Err(format!(
+            "❌ La venta es parte del cambio {}; no puede anularse. Registra una devolución",
+            folio_cambio
+        ));
+    }
+
+    // 🆕 Venta al crédito con cobros: el dinero ya entró por otro lado
+    if tiene_cobros(&conn, venta_id)? {
+        return Err("❌ La venta al crédito ya tiene cobros registrados; no puede anularse".to_string());
+    }
+
+    conn.execute("BEGIN TRANSACTION", [])
+        .map_err(|e| format!("Error al iniciar transacción: {}", e))?;
+
+    let rollback = |conn: &rusqlite::Connection, msg: String| -> String {
+        let _ = conn.execute("ROLLBACK", []);
+        msg
+    };
+
+    if let Err(e) = conn.execute(
+        r"UPDATE ventas SET estado = 'CANCELADA', motivo_cancelacion = ?
+          WHERE id = ? AND estado = 'COMPLETADA'",
+        params![motivo.trim(), venta_id],
+    ) {
+        return Err(rollback(&conn, format!("Error al anular venta: {}", e)));
+    }
+
+    // 🆕 Se quitan los puntos ganados y se devuelven los canjeados
+    if let Err(e) = revertir_puntos_venta(&conn, venta_id, usuario_id) {
+        return Err(rollback(&conn, e));
+    }
+
+    // 🆕 Los vales usados como pago recuperan su saldo
+    if let Err(e) = revertir_vales_venta(&conn, venta_id, usuario_id) {
+        return Err(rollback(&conn, e));
+    }
+
+    // 🆕 La comisión del vendedor se revierte
+    if let Err(e) = revertir_comisiones_venta(&conn, venta_id) {
+        return Err(rollback(&conn, e));
+    }
+
+    // Movimiento de inventario por cada línea repuesta
+    let lineas: Vec<(i32, Option<i32>, Option<String>, i32)> = {
+        let mut stmt = match conn.prepare(
+            "SELECT producto_id, variante_id, talla, cantidad FROM detalles_venta WHERE venta_id = ?",
+        ) {
+            Ok(s) => s,
+            Err(e) => return Err(rollback(&conn, format!("Error al obtener detalle: {}", e))),
+        };
+        let lineas = stmt
+            .query_map(params![venta_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))
+            .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>());
+        match lineas {
+            Ok(l) => l,
+            Err(e) => return Err(rollback(&conn, format!("Error al obtener detalle: {}", e))),
+        }
+    };
+
+    for (producto_id, variante_id, talla, cantidad) in &lineas {
+        let stock_nuevo: i32 = match variante_id {
+            Some(vid) => conn.query_row(
+                "SELECT stock FROM producto_variantes WHERE id = ?",
+                params![vid],
+                |row| row.get(0),
+            ),
+            None => conn.query_row(
+                "SELECT stock FROM productos WHERE id = ?",
+                params![producto_id],
+                |row| row.get(0),
+            ),
+        }
+        .unwrap_or(0);
+
+        if let Err(e) = conn.execute(
+            r"INSERT INTO movimientos_inventario
+                (producto_id, variante_id, talla, tipo_movimiento, cantidad,
+                 stock_anterior, stock_nuevo, venta_id, usuario_id, referencia, motivo)
+              VALUES (?, ?, ?, 'ANULACION', ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                producto_id,
+                variante_id,
+                talla,
+                cantidad,
+                stock_nuevo - cantidad,
+                stock_nuevo,
+                venta_id,
+                usuario_id,
+                &folio,
+                format!("Anulación - {}", motivo.trim()),
+            ],
+        ) {
+            return Err(rollback(&conn, format!("Error al registrar movimiento: {}", e)));
+        }
+    }
+
+    let valores = serde_json::json!({
+        "folio": &folio,
+        "motivo": motivo.trim(),
+        "autorizado_por": autorizado_por,
+    });
+
+    if let Err(e) = conn.execute(
+        r"INSERT INTO auditoria (usuario_id, accion, tabla_afectada, registro_id, valores_anteriores, valores_nuevos)
+          VALUES (?, 'ANULAR_VENTA', 'ventas', ?, ?, ?)",
+        params![usuario_id, venta_id, r#"{"estado":"COMPLETADA"}"#, valores.to_string()],
+    ) {
+        return Err(rollback(&conn, format!("Error al registrar auditoría: {}", e)));
+    }
+
+    conn.execute("COMMIT", [])
+        .map_err(|e| format!("Error al confirmar: {}", e))?;
+
+    Ok(format!("✅ Venta {} anulada. Stock y caja restablecidos.", folio))
+}
+
+// =====================================================
+// 🆕 COMANDO: Detalle completo de una venta
+// Por id o por folio (también acepta el número del comprobante, B001-00000123)
+// =====================================================
+#[tauri::command]
+pub fn obtener_venta_detalle(
+    db: tauri::State<'_, DatabasePool>,
+    venta_id: Option<i32>,
+    folio: Option<String>,
+) -> Result<VentaDetalleCompleto, String> {
+    let conn = db.get_conn();
+
+    let venta_id = match (venta_id, folio.as_deref().map(str::trim).filter(|f| !f.is_empty())) {
+        (Some(id), _) => id,
+        (None, Some(folio)) => conn
+            .query_row(
+                r"SELECT id FROM ventas
+                  WHERE folio = ?1
+                     OR (serie IS NOT NULL AND serie || '-' || printf('%08d', correlativo) = ?1)",
+                params![folio.to_uppercase()],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| format!("Error al buscar venta: {}", e))?
+            .ok_or_else(|| format!("❌ Venta {} no encontrada", folio))?,
+        (None, None) => return Err("❌ Indica el folio o el id de la venta".to_string()),
+    };
+
+    venta_detalle_por_id(&conn, venta_id)
+}
+
+// =====================================================
+// FUNCIONES AUXILIARES
+// =====================================================
+
+// 🆕 Regla de redondeo del efectivo: NINGUNO | CERCANO | ABAJO
+pub fn regla_redondeo_efectivo(conn: &rusqlite::Connection) -> Result<String, String> {
+    conn.query_row(
+        "SELECT COALESCE(redondeo_efectivo, 'NINGUNO') FROM configuracion_tienda LIMIT 1",
+        [],
+        |row| row.get(0),
+    )
+    .optional()
+    .map(|r| r.unwrap_or_else(|| "NINGUNO".to_string()))
+    .map_err(|e| format!("Error al leer redondeo de efectivo: {}", e))
+}
+
+// 🆕 Monto a cobrar en efectivo: múltiplo de S/ 0.10 (no circulan monedas
+// de 1 y 5 céntimos). Se trabaja en céntimos para no arrastrar decimales.
+pub fn redondear_efectivo(monto: f64, regla: &str) -> f64 {
+    let centimos = (monto * 100.0).round() as i64;
+    let redondeado = match regla {
+        "CERCANO" => (centimos + 5) / 10 * 10,
+        "ABAJO" => centimos / 10 * 10,
+        _ => centimos,
+    };
+    redondeado as f64 / 100.0
+}
+
+// Valida que los pagos cubran exactamente el total y calcula el cambio
+// solo sobre la parte en efectivo.
+// 🆕 El efectivo se cobra redondeado: el cambio sale del monto redondeado y
+// la diferencia (cobrado - exacto) se devuelve aparte; pagos_venta guarda el exacto.
+// Devuelve (metodo_pago para la venta, efectivo recibido, cambio, redondeo).
+pub fn validar_pagos(
+    pagos: &[PagoVenta],
+    total: f64,
+    regla_redondeo: &str,
+) -> Result<(String, Option<f64>, Option<f64>, f64), String> {
+    let mut suma = 0.0f64;
+    let mut efectivo_recibido = 0.0f64;
+    let mut cambio = 0.0f64;
+    let mut redondeo = 0.0f64;
+    let mut hay_efectivo = false;
+
+    for pago in pagos {
+        if !["EFECTIVO", "TARJETA", "TRANSFERENCIA", "PUNTOS", "VALE", "CREDITO"].contains(&pago.metodo_pago.as_str()) {
+            return Err(format!("❌ Método de pago no válido: {}", pago.metodo_pago));
+        }
+        if pago.metodo_pago == "VALE" && pago.referencia.as_deref().is_none_or(|r| r.trim().is_empty()) {
+            return Err("❌ Indica el código del vale".to_string());
+        }
+        if pago.monto <= 0.0 {
+            return Err("❌ Cada pago debe ser mayor a 0".to_string());
+        }
+        suma += pago.monto;
+
+        if pago.metodo_pago == "EFECTIVO" {
+            hay_efectivo = true;
+            let a_cobrar = redondear_efectivo(pago.monto, regla_redondeo);
+            let recibido = pago.monto_recibido.unwrap_or(a_cobrar);
+            if recibido + 0.001 < a_cobrar {
+                return Err(format!(
+                    "❌ Efectivo insuficiente: recibido S/ {:.2}, a cobrar S/ {:.2}",
+                    recibido, a_cobrar
+                ));
+            }
+            efectivo_recibido += recibido;
+            cambio += recibido - a_cobrar;
+            redondeo += a_cobrar - pago.monto;
+        } else if pago.monto_recibido.is_some() {
+            return Err(format!("❌ Solo el pago en efectivo puede generar cambio ({})", pago.metodo_pago));
+        }
+    }
+
+    if (suma - total).abs() > 0.01 {
+        return Err(format!(
+            "❌ Los pagos (S/ {:.2}) no coinciden con el total de la venta (S/ {:.2})",
+            suma, total
+        ));
+    }
+
+    let mut metodos: Vec<&str> = pagos.iter().map(|p| p.metodo_pago.as_str()).collect();
+    metodos.sort();
+    metodos.dedup();
+    let metodo_venta = if metodos.len() == 1 { metodos[0].to_string() } else { "MIXTO".to_string() };
+
+    if hay_efectivo {
+        Ok((metodo_venta, Some(efectivo_recibido), Some(redondear(cambio)), redondear(redondeo)))
+    } else {
+        Ok((metodo_venta, None, None, 0.0))
+    }
+}
+// 🆕 Venta con sus líneas, pagos y devoluciones (consulta y reimpresión)
+pub fn venta_detalle_por_id(conn: &rusqlite::Connection, venta_id: i32) -> Result<VentaDetalleCompleto, String> {
+    let mut venta = conn
+        .query_row(
+            r"SELECT v.id, v.folio, strftime('%Y-%m-%d %H:%M:%S', v.fecha_hora), v.estado, v.motivo_cancelacion,
+                     COALESCE(v.tipo_comprobante, 'NOTA_VENTA'),
+                     CASE WHEN v.serie IS NOT NULL THEN v.serie || '-' || printf('%08d', v.correlativo) END,
+                     v.subtotal, COALESCE(v.descuento, 0), v.total, COALESCE(v.redondeo, 0),
+                     COALESCE(v.op_gravada, 0), COALESCE(v.op_exonerada, 0), COALESCE(v.op_inafecta, 0),
+                     COALESCE(v.igv, 0), COALESCE(v.igv_porcentaje, 0),
+                     v.metodo_pago, v.monto_recibido, v.cambio, COALESCE(v.saldo_pendiente, 0),
+                     v.usuario_id, u.nombre_completo, v.vendedor_id, ve.nombre_completo, v.caja_id,
+                     v.cliente_id, c.nombre,
+                     CASE WHEN c.numero_documento IS NOT NULL THEN c.tipo_documento || ' ' || c.numero_documento END,
+                     ca.folio,
+                     (SELECT COUNT(*) FROM auditoria
+                      WHERE accion = 'REIMPRIMIR_BOLETA' AND tabla_afectada = 'ventas' AND registro_id = v.id)
+              FROM ventas v
+              JOIN usuarios u ON u.id = v.usuario_id
+              LEFT JOIN usuarios ve ON ve.id = v.vendedor_id
+              LEFT JOIN clientes c ON c.id = v.cliente_id
+              LEFT JOIN cambios ca ON ca.id = v.cambio_id
+              WHERE v.id = ?",
+            params![venta_id],
+            |row| {
+                Ok(VentaDetalleCompleto {
+                    id: row.get(0)?,
+                    folio: row.get(1)?,
+                    fecha_hora: row.get(2)?,
+                    estado: row.get(3)?,
+                    motivo_cancelacion: row.get(4)?,
+                    tipo_comprobante: row.get(5)?,
+                    numero_comprobante: row.get(6)?,
+                    subtotal: row.get(7)?,
+                    descuento: row.get(8)?,
+                    total: row.get(9)?,
+                    redondeo: row.get(10)?,
+                    op_gravada: row.get(11)?,
+                    op_exonerada: row.get(12)?,
+                    op_inafecta: row.get(13)?,
+                    igv: row.get(14)?,
+                    igv_porcentaje: row.get(15)?,
+                    metodo_pago: row.get(16)?,
+                    monto_recibido: row.get(17)?,
+                    cambio: row.get(18)?,
+                    saldo_pendiente: row.get(19)?,
+                    usuario_id: row.get(20)?,
+                    cajero: row.get(21)?,
+                    vendedor_id: row.get(22)?,
+                    vendedor: row.get(23)?,
+                    caja_id: row.get(24)?,
+                    cliente_id: row.get(25)?,
+                    cliente_nombre: row.get(26)?,
+                    cliente_documento: row.get(27)?,
+                    folio_cambio: row.get(28)?,
+                    lineas: Vec::new(),
+                    pagos: Vec::new(),
+                    devoluciones: Vec::new(),
+                    monto_devuelto: 0.0,
+                    reimpresiones: row.get(29)?,
+                })
+            },
+        )
+        .optional()
+        .map_err(|e| format!("Error al obtener venta: {}", e))?
+        .ok_or("❌ Venta no encontrada")?;
+
+    let mut stmt = conn
+        .prepare(
+            r"SELECT d.id, d.producto_id, p.codigo, p.nombre, d.variante_id, d.talla, d.cantidad,
+                     d.precio_unitario, d.subtotal, COALESCE(d.descuento_linea, 0), d.total_linea,
+                     (SELECT COALESCE(SUM(dd.cantidad_devuelta), 0)
+                      FROM detalles_devolucion dd
+                      JOIN devoluciones dv ON dv.id = dd.devolucion_id
+                      WHERE dd.detalle_venta_id = d.id AND dv.estado = 'PROCESADA'),
+                     ve.nombre_completo
+              FROM detalles_venta d
+              JOIN productos p ON p.id = d.producto_id
+              LEFT JOIN usuarios ve ON ve.id = d.vendedor_id
+              WHERE d.venta_id = ?
+              ORDER BY d.id",
+        )
+        .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+    venta.lineas = stmt
+        .query_map(params![venta_id], |row| {
+            Ok(LineaVentaDetalle {
+                detalle_id: row.get(0)?,
+                producto_id: row.get(1)?,
+                codigo: row.get(2)?,
+                nombre: row.get(3)?,
+                variante_id: row.get(4)?,
+                talla: row.get(5)?,
+                cantidad: row.get(6)?,
+                precio_unitario: row.get(7)?,
+                subtotal: row.get(8)?,
+                descuento_linea: row.get(9)?,
+                total_linea: row.get(10)?,
+                cantidad_devuelta: row.get(11)?,
+                vendedor: row.get(12)?,
+            })
+        })
+        .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+        .map_err(|e| format!("Error al obtener líneas: {}", e))?;
+
+    let mut stmt = conn
+        .prepare(
+            r"SELECT metodo_pago, monto, monto_recibido, referencia
+              FROM pagos_venta WHERE venta_id = ? ORDER BY id",
+        )
+        .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+    venta.pagos = stmt
+        .query_map(params![venta_id], |row| {
+            Ok(PagoVentaDetalle {
+                metodo_pago: row.get(0)?,
+                monto: row.get(1)?,
+                monto_recibido: row.get(2)?,
+                referencia: row.get(3)?,
+            })
+        })
+        .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+        .map_err(|e| format!("Error al obtener pagos: {}", e))?;
+
+    let mut stmt = conn
+        .prepare(
+            r"SELECT d.id, d.folio_devolucion, strftime('%Y-%m-%d %H:%M:%S', d.fecha_hora), d.monto_reembolsado,
+                     d.metodo_reembolso, d.motivo, d.estado, u.nombre_completo,
+                     CASE WHEN nc.serie IS NOT NULL THEN nc.serie || '-' || printf('%08d', nc.correlativo) END
+              FROM devoluciones d
+              JOIN usuarios u ON u.id = d.usuario_id
+              LEFT JOIN notas_credito nc ON nc.devolucion_id = d.id
+              WHERE d.venta_original_id = ?
+              ORDER BY d.id",
+        )
+        .map_err(|e| format!("Error al preparar consulta: {}", e))?;
+    venta.devoluciones = stmt
+        .query_map(params![venta_id], |row| {
+            Ok(DevolucionDeVenta {
+                id: row.get(0)?,
+                folio_devolucion: row.get(1)?,
+                fecha_hora: row.get(2)?,
+                monto_reembolsado: row.get(3)?,
+                metodo_reembolso: row.get(4)?,
+                motivo: row.get(5)?,
+                estado: row.get(6)?,
+                usuario: row.get(7)?,
+                nota_credito: row.get(8)?,
+            })
+        })
+        .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+        .map_err(|e| format!("Error al obtener devoluciones: {}", e))?;
+
+    venta.monto_devuelto = redondear(
+        venta
+            .devoluciones
+            .iter()
+            .filter(|d| d.estado == "PROCESADA")
+            .map(|d| d.monto_reembolsado)
+            .sum(),
+    );
+
+    Ok(venta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pago(metodo: &str, monto: f64, recibido: Option<f64>) -> PagoVenta {
+        PagoVenta {
+            metodo_pago: metodo.to_string(),
+            monto,
+            monto_recibido: recibido,
+            referencia: None,
+        }
+    }
+
+    #[test]
+    fn redondeo_al_cercano() {
+        assert_eq!(redondear_efectivo(12.34, "CERCANO"), 12.30);
+        assert_eq!(redondear_efectivo(12.35, "CERCANO"), 12.40);
+        assert_eq!(redondear_efectivo(12.36, "CERCANO"), 12.40);
+        assert_eq!(redondear_efectivo(12.30, "CERCANO"), 12.30);
+        assert_eq!(redondear_efectivo(0.04, "CERCANO"), 0.00);
+    }
+
+    #[test]
+    fn redondeo_hacia_abajo() {
+        assert_eq!(redondear_efectivo(12.39, "ABAJO"), 12.30);
+        assert_eq!(redondear_efectivo(12.30, "ABAJO"), 12.30);
+    }
+
+    #[test]
+    fn sin_redondeo_conserva_los_centimos() {
+        assert_eq!(redondear_efectivo(12.37, "NINGUNO"), 12.37);
+        // Errores de coma flotante no mueven el céntimo
+        assert_eq!(redondear_efectivo(0.1 + 0.2, "NINGUNO"), 0.30);
+    }
+
+    #[test]
+    fn cambio_sobre_el_efectivo_redondeado() {
+        let (metodo, recibido, cambio, redondeo) =
+            validar_pagos(&[pago("EFECTIVO", 47.83, Some(50.0))], 47.83, "CERCANO").unwrap();
+        assert_eq!(metodo, "EFECTIVO");
+        assert_eq!(recibido, Some(50.0));
+        assert_eq!(cambio, Some(2.20));
+        assert_eq!(redondeo, -0.03);
+    }
+
+    #[test]
+    fn efectivo_insuficiente_segun_el_monto_redondeado() {
+        assert!(validar_pagos(&[pago("EFECTIVO", 47.86, Some(47.86))], 47.86, "CERCANO").is_err());
+        assert!(validar_pagos(&[pago("EFECTIVO", 47.86, Some(47.90))], 47.86, "CERCANO").is_ok());
+    }
+
+    #[test]
+    fn pago_mixto_solo_redondea_el_efectivo() {
+        let (metodo, _, cambio, redondeo) = validar_pagos(
+            &[pago("TARJETA", 30.0, None), pago("EFECTIVO", 17.83, Some(20.0))],
+            47.83,
+            "CERCANO",
+        )
+        .unwrap();
+        assert_eq!(metodo, "MIXTO");
+        assert_eq!(cambio, Some(2.20));
+        assert_eq!(redondeo, -0.03);
+    }
+
+    #[test]
+    fn sin_efectivo_no_hay_cambio_ni_redondeo() {
+        let (metodo, recibido, cambio, redondeo) = validar_pagos(&[pago("TARJETA", 47.83, None)], 47.83, "CERCANO").unwrap();
+        assert_eq!(metodo, "TARJETA");
+        assert_eq!((recibido, cambio, redondeo), (None, None, 0.0));
+    }
+
+    #[test]
+    fn pagos_deben_cuadrar_con_el_total() {
+        assert!(validar_pagos(&[pago("TARJETA", 40.0, None)], 47.83, "NINGUNO").is_err());
+        assert!(validar_pagos(&[pago("TARJETA", 47.83, Some(50.0))], 47.83, "NINGUNO").is_err());
+        assert!(validar_pagos(&[pago("CHEQUE", 47.83, None)], 47.83, "NINGUNO").is_err());
+    }
+}
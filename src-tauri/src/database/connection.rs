@@ -189,10 +189,1581 @@ pub fn run_migrations(db_path: &str) -> Result<()> {
         println!("Columnas de impresora agregadas");
     }
 
+    // Corrección: procesar_devolucion siempre insertó detalle_venta_id (para
+    // controlar cuánto se devolvió de cada línea), pero la columna no existía
+    if !existe_columna(&conn, "detalles_devolucion", "detalle_venta_id") {
+        println!("Agregando detalle_venta_id a detalles_devolucion...");
+        conn.execute("ALTER TABLE detalles_devolucion ADD COLUMN detalle_venta_id INTEGER REFERENCES detalles_venta(id)", [])?;
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_detalles_devolucion_detalle ON detalles_devolucion(detalle_venta_id)", [])?;
+    }
+
+    // 🆕 Migración: pagos por método (ventas MIXTO) + caja_id en ventas
+    if !existe_columna(&conn, "ventas", "caja_id") {
+        println!("Agregando caja_id a ventas...");
+        conn.execute("ALTER TABLE ventas ADD COLUMN caja_id INTEGER REFERENCES cajas(id)", [])?;
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_ventas_caja ON ventas(caja_id)", [])?;
+    }
+
+    if !existe_tabla(&conn, "pagos_venta")? {
+        println!("Ejecutando migracion: Agregar pagos por metodo...");
+        conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS pagos_venta (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                venta_id INTEGER NOT NULL,
+                metodo_pago TEXT NOT NULL CHECK(metodo_pago IN ('EFECTIVO', 'TARJETA', 'TRANSFERENCIA')),
+                monto REAL NOT NULL CHECK (monto > 0),
+                monto_recibido REAL,
+                referencia TEXT,
+                fecha_hora TEXT DEFAULT (datetime('now', 'localtime')),
+                FOREIGN KEY (venta_id) REFERENCES ventas(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_pagos_venta_venta ON pagos_venta(venta_id);
+            CREATE INDEX IF NOT EXISTS idx_pagos_venta_metodo ON pagos_venta(metodo_pago);
+
+            -- Ventas antiguas de un solo método: una línea por venta (caja_id es NULL,
+            -- así que el trigger de caja no vuelve a sumarlas)
+            INSERT INTO pagos_venta (venta_id, metodo_pago, monto, monto_recibido, fecha_hora)
+            SELECT id, metodo_pago, total,
+                   CASE WHEN metodo_pago = 'EFECTIVO' THEN monto_recibido END, fecha_hora
+            FROM ventas
+            WHERE metodo_pago IN ('EFECTIVO', 'TARJETA', 'TRANSFERENCIA') AND total > 0;
+
+            DROP TRIGGER IF EXISTS trg_actualizar_caja_venta;
+            CREATE TRIGGER trg_actualizar_caja_venta
+            AFTER INSERT ON ventas
+            FOR EACH ROW
+            WHEN NEW.estado = 'COMPLETADA'
+            BEGIN
+              UPDATE cajas
+              SET
+                total_ventas         = total_ventas         + NEW.total,
+                numero_transacciones = numero_transacciones + 1,
+                cambio_total         = cambio_total         + COALESCE(NEW.cambio, 0),
+                ticket_promedio      = CASE WHEN numero_transacciones + 1 > 0
+                                       THEN (total_ventas + NEW.total) / (numero_transacciones + 1) ELSE 0 END
+              WHERE id = NEW.caja_id;
+            END;
+
+            DROP TRIGGER IF EXISTS trg_actualizar_caja_pago_venta;
+            CREATE TRIGGER trg_actualizar_caja_pago_venta
+            AFTER INSERT ON pagos_venta
+            FOR EACH ROW
+            WHEN (SELECT estado FROM ventas WHERE id = NEW.venta_id) = 'COMPLETADA'
+            BEGIN
+              UPDATE cajas
+              SET
+                ventas_efectivo      = ventas_efectivo      + CASE WHEN NEW.metodo_pago = 'EFECTIVO'      THEN NEW.monto ELSE 0 END,
+                ventas_tarjeta       = ventas_tarjeta       + CASE WHEN NEW.metodo_pago = 'TARJETA'       THEN NEW.monto ELSE 0 END,
+                ventas_transferencia = ventas_transferencia + CASE WHEN NEW.metodo_pago = 'TRANSFERENCIA' THEN NEW.monto ELSE 0 END
+              WHERE id = (SELECT caja_id FROM ventas WHERE id = NEW.venta_id);
+            END;
+
+            DROP TRIGGER IF EXISTS trg_actualizar_caja_cancelar_venta;
+            CREATE TRIGGER trg_actualizar_caja_cancelar_venta
+            AFTER UPDATE ON ventas
+            FOR EACH ROW
+            WHEN OLD.estado = 'COMPLETADA' AND NEW.estado = 'CANCELADA'
+            BEGIN
+              UPDATE cajas
+              SET
+                ventas_efectivo      = ventas_efectivo      - COALESCE(
+                                         (SELECT SUM(monto) FROM pagos_venta WHERE venta_id = NEW.id AND metodo_pago = 'EFECTIVO'),
+                                         CASE WHEN NEW.metodo_pago = 'EFECTIVO' THEN NEW.total ELSE 0 END),
+                ventas_tarjeta       = ventas_tarjeta       - COALESCE(
+                                         (SELECT SUM(monto) FROM pagos_venta WHERE venta_id = NEW.id AND metodo_pago = 'TARJETA'),
+                                         CASE WHEN NEW.metodo_pago = 'TARJETA' THEN NEW.total ELSE 0 END),
+                ventas_transferencia = ventas_transferencia - COALESCE(
+                                         (SELECT SUM(monto) FROM pagos_venta WHERE venta_id = NEW.id AND metodo_pago = 'TRANSFERENCIA'),
+                                         CASE WHEN NEW.metodo_pago = 'TRANSFERENCIA' THEN NEW.total ELSE 0 END),
+                total_ventas         = total_ventas         - NEW.total,
+                numero_transacciones = numero_transacciones - 1,
+                cambio_total         = cambio_total         - COALESCE(NEW.cambio, 0),
+                ticket_promedio      = CASE WHEN numero_transacciones - 1 > 0
+                                       THEN (total_ventas - NEW.total) / (numero_transacciones - 1) ELSE 0 END
+              WHERE id = NEW.caja_id;
+            END;
+        "#)?;
+        println!("Migracion completada: Pagos por metodo agregados");
+    }
+
+    // 🆕 Migración: tipo de movimiento ANULACION (ventas anuladas)
+    if !check_incluye(&conn, "movimientos_inventario", "'ANULACION'") {
+        println!("Agregando tipo de movimiento ANULACION...");
+        reconstruir_tabla(&conn, "movimientos_inventario", r#"
+            CREATE TABLE movimientos_inventario_nueva (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                producto_id INTEGER NOT NULL,
+                variante_id INTEGER,
+                talla TEXT,
+                tipo_movimiento TEXT NOT NULL CHECK(tipo_movimiento IN ('VENTA', 'DEVOLUCION', 'ENTRADA', 'SALIDA', 'AJUSTE', 'MERMA', 'ANULACION')),
+                cantidad INTEGER NOT NULL,
+                stock_anterior INTEGER NOT NULL,
+                stock_nuevo INTEGER NOT NULL,
+                venta_id INTEGER,
+                devolucion_id INTEGER,
+                compra_id INTEGER,
+                usuario_id INTEGER,
+                referencia TEXT,
+                motivo TEXT,
+                fecha_hora TEXT DEFAULT (datetime('now', 'localtime')),
+                FOREIGN KEY (producto_id) REFERENCES productos(id),
+                FOREIGN KEY (variante_id) REFERENCES producto_variantes(id),
+                FOREIGN KEY (venta_id) REFERENCES ventas(id) ON DELETE SET NULL,
+                FOREIGN KEY (devolucion_id) REFERENCES devoluciones(id) ON DELETE SET NULL,
+                FOREIGN KEY (usuario_id) REFERENCES usuarios(id)
+            );
+        "#, r#"
+            CREATE INDEX IF NOT EXISTS idx_movimientos_inventario_producto ON movimientos_inventario(producto_id);
+            CREATE INDEX IF NOT EXISTS idx_movimientos_inventario_tipo ON movimientos_inventario(tipo_movimiento);
+            CREATE INDEX IF NOT EXISTS idx_movimientos_inventario_fecha ON movimientos_inventario(fecha_hora);
+        "#)?;
+        println!("Tipo de movimiento ANULACION agregado");
+    }
+
+    // 🆕 Migración: ventas suspendidas (carritos en espera)
+    if !existe_tabla(&conn, "ventas_suspendidas")? {
+        println!("Ejecutando migracion: Agregar ventas suspendidas...");
+        conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS ventas_suspendidas (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                caja_id INTEGER NOT NULL,
+                usuario_id INTEGER NOT NULL,
+                etiqueta_cliente TEXT,
+                total REAL NOT NULL DEFAULT 0,
+                reservar_stock INTEGER DEFAULT 0,
+                estado TEXT DEFAULT 'ACTIVA' CHECK(estado IN ('ACTIVA', 'RECUPERADA', 'DESCARTADA', 'EXPIRADA')),
+                fecha_suspension TEXT DEFAULT (datetime('now', 'localtime')),
+                fecha_cierre TEXT,
+                FOREIGN KEY (caja_id) REFERENCES cajas(id),
+                FOREIGN KEY (usuario_id) REFERENCES usuarios(id)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_ventas_suspendidas_caja ON ventas_suspendidas(caja_id);
+            CREATE INDEX IF NOT EXISTS idx_ventas_suspendidas_estado ON ventas_suspendidas(estado);
+
+            CREATE TABLE IF NOT EXISTS detalles_venta_suspendida (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                venta_suspendida_id INTEGER NOT NULL,
+                producto_id INTEGER NOT NULL,
+                variante_id INTEGER,
+                talla TEXT,
+                nombre TEXT NOT NULL,
+                codigo TEXT NOT NULL,
+                cantidad INTEGER NOT NULL CHECK (cantidad > 0),
+                precio_unitario REAL NOT NULL CHECK (precio_unitario >= 0),
+                descuento_porcentaje REAL DEFAULT 0,
+                FOREIGN KEY (venta_suspendida_id) REFERENCES ventas_suspendidas(id) ON DELETE CASCADE,
+                FOREIGN KEY (producto_id) REFERENCES productos(id),
+                FOREIGN KEY (variante_id) REFERENCES producto_variantes(id)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_detalles_suspendida ON detalles_venta_suspendida(venta_suspendida_id);
+            CREATE INDEX IF NOT EXISTS idx_detalles_suspendida_variante ON detalles_venta_suspendida(variante_id);
+
+            DROP TRIGGER IF EXISTS trg_expirar_ventas_suspendidas;
+            CREATE TRIGGER trg_expirar_ventas_suspendidas
+            AFTER UPDATE OF estado ON cajas
+            FOR EACH ROW
+            WHEN OLD.estado = 'ABIERTA' AND NEW.estado = 'CERRADA'
+            BEGIN
+              UPDATE ventas_suspendidas
+              SET estado = 'EXPIRADA', fecha_cierre = datetime('now', 'localtime')
+              WHERE caja_id = NEW.id AND estado = 'ACTIVA';
+            END;
+        "#)?;
+        println!("Migracion completada: Ventas suspendidas agregadas");
+    }
+
+    // 🆕 Migración: promociones
+    if !existe_tabla(&conn, "promociones")? {
+        println!("Ejecutando migracion: Agregar promociones...");
+        conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS promociones (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                nombre TEXT NOT NULL,
+                tipo TEXT NOT NULL CHECK(tipo IN ('NXM', 'SEGUNDA_UNIDAD', 'DESCUENTO_CATEGORIA', 'DESCUENTO_MONTO')),
+                lleva INTEGER,
+                paga INTEGER,
+                porcentaje REAL CHECK (porcentaje IS NULL OR (porcentaje > 0 AND porcentaje <= 100)),
+                monto_minimo REAL,
+                monto_descuento REAL,
+                categoria_id INTEGER,
+                producto_id INTEGER,
+                fecha_inicio TEXT,
+                fecha_fin TEXT,
+                dias_semana TEXT,
+                hora_inicio TEXT,
+                hora_fin TEXT,
+                prioridad INTEGER DEFAULT 0,
+                acumulable INTEGER DEFAULT 0,
+                activo INTEGER DEFAULT 1,
+                fecha_creacion TEXT DEFAULT (datetime('now', 'localtime')),
+                FOREIGN KEY (categoria_id) REFERENCES categorias(id),
+                FOREIGN KEY (producto_id) REFERENCES productos(id)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_promociones_activo ON promociones(activo);
+            CREATE INDEX IF NOT EXISTS idx_promociones_vigencia ON promociones(fecha_inicio, fecha_fin);
+        "#)?;
+        println!("Migracion completada: Promociones agregadas");
+    }
+
+    if !existe_columna(&conn, "detalles_venta", "promociones_aplicadas") {
+        println!("Agregando columnas de promociones a detalles_venta...");
+        conn.execute("ALTER TABLE detalles_venta ADD COLUMN descuento_promocion REAL DEFAULT 0", [])?;
+        conn.execute("ALTER TABLE detalles_venta ADD COLUMN promociones_aplicadas TEXT", [])?;
+    }
+
+    // 🆕 Migración: cupones de descuento
+    if !existe_tabla(&conn, "cupones")? {
+        println!("Ejecutando migracion: Agregar cupones...");
+        conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS cupones (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                codigo TEXT NOT NULL UNIQUE COLLATE NOCASE,
+                campana TEXT,
+                tipo_descuento TEXT NOT NULL CHECK(tipo_descuento IN ('PORCENTAJE', 'MONTO')),
+                valor REAL NOT NULL CHECK (valor > 0),
+                monto_minimo REAL DEFAULT 0,
+                categoria_id INTEGER,
+                limite_usos INTEGER,
+                limite_por_cliente INTEGER,
+                fecha_inicio TEXT,
+                fecha_expiracion TEXT,
+                activo INTEGER DEFAULT 1,
+                fecha_creacion TEXT DEFAULT (datetime('now', 'localtime')),
+                FOREIGN KEY (categoria_id) REFERENCES categorias(id)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_cupones_codigo ON cupones(codigo);
+
+            CREATE TABLE IF NOT EXISTS cupones_redenciones (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                cupon_id INTEGER NOT NULL,
+                venta_id INTEGER NOT NULL,
+                cliente_id INTEGER,
+                monto_descuento REAL NOT NULL,
+                fecha_hora TEXT DEFAULT (datetime('now', 'localtime')),
+                FOREIGN KEY (cupon_id) REFERENCES cupones(id),
+                FOREIGN KEY (venta_id) REFERENCES ventas(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_cupones_redenciones_cupon ON cupones_redenciones(cupon_id);
+            CREATE INDEX IF NOT EXISTS idx_cupones_redenciones_cliente ON cupones_redenciones(cliente_id);
+        "#)?;
+        println!("Migracion completada: Cupones agregados");
+    }
+
+    if !existe_columna(&conn, "ventas", "cupon_id") {
+        println!("Agregando columnas de cupon a ventas...");
+        conn.execute("ALTER TABLE ventas ADD COLUMN cupon_id INTEGER REFERENCES cupones(id)", [])?;
+        conn.execute("ALTER TABLE ventas ADD COLUMN descuento_cupon REAL DEFAULT 0", [])?;
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_ventas_cupon ON ventas(cupon_id)", [])?;
+    }
+
+    if !existe_columna(&conn, "detalles_venta", "descuento_cupon") {
+        conn.execute("ALTER TABLE detalles_venta ADD COLUMN descuento_cupon REAL DEFAULT 0", [])?;
+    }
+
+    // 🆕 Migración: clientes
+    if !existe_tabla(&conn, "clientes")? {
+        println!("Ejecutando migracion: Agregar clientes...");
+        conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS clientes (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                tipo_documento TEXT NOT NULL CHECK(tipo_documento IN ('DNI', 'RUC', 'CE')),
+                numero_documento TEXT NOT NULL,
+                nombre TEXT NOT NULL,
+                telefono TEXT,
+                email TEXT,
+                fecha_nacimiento TEXT,
+                tallas_preferidas TEXT,
+                notas TEXT,
+                activo INTEGER DEFAULT 1,
+                fecha_registro TEXT DEFAULT (datetime('now', 'localtime')),
+                UNIQUE(tipo_documento, numero_documento)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_clientes_documento ON clientes(numero_documento);
+            CREATE INDEX IF NOT EXISTS idx_clientes_nombre ON clientes(nombre);
+            CREATE INDEX IF NOT EXISTS idx_clientes_telefono ON clientes(telefono);
+        "#)?;
+        println!("Migracion completada: Clientes agregados");
+    }
+
+    if !existe_columna(&conn, "ventas", "cliente_id") {
+        println!("Agregando cliente_id a ventas...");
+        conn.execute("ALTER TABLE ventas ADD COLUMN cliente_id INTEGER REFERENCES clientes(id)", [])?;
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_ventas_cliente ON ventas(cliente_id)", [])?;
+    }
+
+    // 🆕 Migración: programa de puntos
+    if !existe_tabla(&conn, "puntos_movimientos")? {
+        println!("Ejecutando migracion: Agregar programa de puntos...");
+        conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS puntos_movimientos (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                cliente_id INTEGER NOT NULL,
+                tipo TEXT NOT NULL CHECK(tipo IN ('ACUMULACION', 'CANJE', 'REVERSION', 'VENCIMIENTO', 'AJUSTE')),
+                puntos INTEGER NOT NULL CHECK (puntos != 0),
+                venta_id INTEGER,
+                devolucion_id INTEGER,
+                fecha_vencimiento TEXT,
+                descripcion TEXT,
+                usuario_id INTEGER,
+                fecha_hora TEXT DEFAULT (datetime('now', 'localtime')),
+                FOREIGN KEY (cliente_id) REFERENCES clientes(id),
+                FOREIGN KEY (venta_id) REFERENCES ventas(id),
+                FOREIGN KEY (devolucion_id) REFERENCES devoluciones(id),
+                FOREIGN KEY (usuario_id) REFERENCES usuarios(id)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_puntos_cliente ON puntos_movimientos(cliente_id);
+            CREATE INDEX IF NOT EXISTS idx_puntos_venta ON puntos_movimientos(venta_id);
+        "#)?;
+        println!("Migracion completada: Programa de puntos agregado");
+    }
+
+    if !existe_columna(&conn, "configuracion_tienda", "puntos_por_sol") {
+        println!("Agregando configuracion de puntos...");
+        conn.execute_batch(r#"
+            ALTER TABLE configuracion_tienda ADD COLUMN puntos_por_sol REAL DEFAULT 1;
+            ALTER TABLE configuracion_tienda ADD COLUMN valor_punto REAL DEFAULT 0.05;
+            ALTER TABLE configuracion_tienda ADD COLUMN puntos_excluir_descuentos INTEGER DEFAULT 0;
+            ALTER TABLE configuracion_tienda ADD COLUMN puntos_meses_vencimiento INTEGER DEFAULT 12;
+        "#)?;
+    }
+
+    // Método de pago PUNTOS en ventas y pagos_venta
+    if !check_incluye(&conn, "pagos_venta", "'PUNTOS'") {
+        println!("Agregando metodo de pago PUNTOS...");
+        reconstruir_tabla(&conn, "pagos_venta", r#"
+            CREATE TABLE pagos_venta_nueva (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                venta_id INTEGER NOT NULL,
+                metodo_pago TEXT NOT NULL CHECK(metodo_pago IN ('EFECTIVO', 'TARJETA', 'TRANSFERENCIA', 'PUNTOS')),
+                monto REAL NOT NULL CHECK (monto > 0),
+                monto_recibido REAL,
+                referencia TEXT,
+                fecha_hora TEXT DEFAULT (datetime('now', 'localtime')),
+                FOREIGN KEY (venta_id) REFERENCES ventas(id) ON DELETE CASCADE
+            );
+        "#, "")?;
+    }
+
+    if !check_incluye(&conn, "ventas", "'PUNTOS'") {
+        reconstruir_tabla(&conn, "ventas", r#"
+            CREATE TABLE ventas_nueva (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                folio TEXT NOT NULL UNIQUE,
+                fecha_hora TEXT DEFAULT (datetime('now', 'localtime')),
+                subtotal REAL NOT NULL CHECK (subtotal >= 0),
+                descuento REAL DEFAULT 0 CHECK (descuento >= 0),
+                total REAL NOT NULL CHECK (total >= 0),
+                metodo_pago TEXT NOT NULL CHECK(metodo_pago IN ('EFECTIVO', 'TARJETA', 'TRANSFERENCIA', 'PUNTOS', 'MIXTO')),
+                monto_recibido REAL,
+                cambio REAL,
+                usuario_id INTEGER NOT NULL,
+                caja_id INTEGER,
+                cliente_id INTEGER,
+                cupon_id INTEGER,
+                descuento_cupon REAL DEFAULT 0,
+                estado TEXT DEFAULT 'COMPLETADA' CHECK(estado IN ('COMPLETADA', 'CANCELADA', 'PENDIENTE')),
+                motivo_cancelacion TEXT,
+                notas TEXT,
+                licencia_tipo TEXT,
+                FOREIGN KEY (usuario_id) REFERENCES usuarios(id),
+                FOREIGN KEY (caja_id) REFERENCES cajas(id),
+                FOREIGN KEY (cliente_id) REFERENCES clientes(id),
+                FOREIGN KEY (cupon_id) REFERENCES cupones(id)
+            );
+        "#, "")?;
+        println!("Metodo de pago PUNTOS agregado");
+    }
+
+    // 🆕 Migración: vales (tarjetas de regalo y vales por devolución)
+    if !existe_tabla(&conn, "vales")? {
+        println!("Ejecutando migracion: Agregar vales...");
+        conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS vales (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                codigo TEXT NOT NULL UNIQUE,
+                origen TEXT NOT NULL CHECK(origen IN ('VENTA', 'DEVOLUCION')),
+                monto_inicial REAL NOT NULL CHECK (monto_inicial > 0),
+                saldo REAL NOT NULL CHECK (saldo >= 0),
+                estado TEXT DEFAULT 'ACTIVO' CHECK(estado IN ('ACTIVO', 'AGOTADO', 'VENCIDO')),
+                fecha_emision TEXT DEFAULT (datetime('now', 'localtime')),
+                fecha_vencimiento TEXT,
+                cliente_id INTEGER,
+                devolucion_id INTEGER,
+                caja_id INTEGER,
+                metodo_pago TEXT,
+                usuario_id INTEGER NOT NULL,
+                FOREIGN KEY (cliente_id) REFERENCES clientes(id),
+                FOREIGN KEY (devolucion_id) REFERENCES devoluciones(id),
+                FOREIGN KEY (caja_id) REFERENCES cajas(id),
+                FOREIGN KEY (usuario_id) REFERENCES usuarios(id)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_vales_estado ON vales(estado);
+            CREATE INDEX IF NOT EXISTS idx_vales_cliente ON vales(cliente_id);
+
+            CREATE TABLE IF NOT EXISTS vales_movimientos (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                vale_id INTEGER NOT NULL,
+                tipo TEXT NOT NULL CHECK(tipo IN ('EMISION', 'CANJE', 'REVERSION', 'VENCIMIENTO')),
+                monto REAL NOT NULL CHECK (monto != 0),
+                saldo_resultante REAL NOT NULL,
+                venta_id INTEGER,
+                devolucion_id INTEGER,
+                descripcion TEXT,
+                usuario_id INTEGER,
+                fecha_hora TEXT DEFAULT (datetime('now', 'localtime')),
+                FOREIGN KEY (vale_id) REFERENCES vales(id),
+                FOREIGN KEY (venta_id) REFERENCES ventas(id),
+                FOREIGN KEY (devolucion_id) REFERENCES devoluciones(id),
+                FOREIGN KEY (usuario_id) REFERENCES usuarios(id)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_vales_movimientos_vale ON vales_movimientos(vale_id);
+            CREATE INDEX IF NOT EXISTS idx_vales_movimientos_venta ON vales_movimientos(venta_id);
+        "#)?;
+        println!("Migracion completada: Vales agregados");
+    }
+
+    if !existe_columna(&conn, "configuracion_tienda", "vales_meses_vencimiento") {
+        conn.execute("ALTER TABLE configuracion_tienda ADD COLUMN vales_meses_vencimiento INTEGER DEFAULT 12", [])?;
+    }
+
+    // Método de pago VALE en ventas y pagos_venta
+    if !check_incluye(&conn, "pagos_venta", "'VALE'") {
+        println!("Agregando metodo de pago VALE...");
+        reconstruir_tabla(&conn, "pagos_venta", r#"
+            CREATE TABLE pagos_venta_nueva (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                venta_id INTEGER NOT NULL,
+                metodo_pago TEXT NOT NULL CHECK(metodo_pago IN ('EFECTIVO', 'TARJETA', 'TRANSFERENCIA', 'PUNTOS', 'VALE')),
+                monto REAL NOT NULL CHECK (monto > 0),
+                monto_recibido REAL,
+                referencia TEXT,
+                fecha_hora TEXT DEFAULT (datetime('now', 'localtime')),
+                FOREIGN KEY (venta_id) REFERENCES ventas(id) ON DELETE CASCADE
+            );
+        "#, "")?;
+    }
+
+    if !check_incluye(&conn, "ventas", "'VALE'") {
+        reconstruir_tabla(&conn, "ventas", r#"
+            CREATE TABLE ventas_nueva (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                folio TEXT NOT NULL UNIQUE,
+                fecha_hora TEXT DEFAULT (datetime('now', 'localtime')),
+                subtotal REAL NOT NULL CHECK (subtotal >= 0),
+                descuento REAL DEFAULT 0 CHECK (descuento >= 0),
+                total REAL NOT NULL CHECK (total >= 0),
+                metodo_pago TEXT NOT NULL CHECK(metodo_pago IN ('EFECTIVO', 'TARJETA', 'TRANSFERENCIA', 'PUNTOS', 'VALE', 'MIXTO')),
+                monto_recibido REAL,
+                cambio REAL,
+                usuario_id INTEGER NOT NULL,
+                caja_id INTEGER,
+                cliente_id INTEGER,
+                cupon_id INTEGER,
+                descuento_cupon REAL DEFAULT 0,
+                estado TEXT DEFAULT 'COMPLETADA' CHECK(estado IN ('COMPLETADA', 'CANCELADA', 'PENDIENTE')),
+                motivo_cancelacion TEXT,
+                notas TEXT,
+                licencia_tipo TEXT,
+                FOREIGN KEY (usuario_id) REFERENCES usuarios(id),
+                FOREIGN KEY (caja_id) REFERENCES cajas(id),
+                FOREIGN KEY (cliente_id) REFERENCES clientes(id),
+                FOREIGN KEY (cupon_id) REFERENCES cupones(id)
+            );
+        "#, "")?;
+        println!("Metodo de pago VALE agregado");
+    }
+
+    // 🆕 Migración: IGV en productos, ventas y devoluciones
+    if !existe_columna(&conn, "productos", "afectacion_igv") {
+        println!("Agregando afectacion de IGV a productos...");
+        conn.execute(
+            "ALTER TABLE productos ADD COLUMN afectacion_igv TEXT DEFAULT 'GRAVADO' CHECK(afectacion_igv IN ('GRAVADO', 'EXONERADO', 'INAFECTO'))",
+            [],
+        )?;
+    }
+
+    if !existe_columna(&conn, "ventas", "igv") {
+        println!("Agregando columnas de IGV a ventas y devoluciones...");
+        conn.execute_batch(r#"
+            ALTER TABLE ventas ADD COLUMN op_gravada REAL DEFAULT 0;
+            ALTER TABLE ventas ADD COLUMN op_exonerada REAL DEFAULT 0;
+            ALTER TABLE ventas ADD COLUMN op_inafecta REAL DEFAULT 0;
+            ALTER TABLE ventas ADD COLUMN igv REAL DEFAULT 0;
+            ALTER TABLE ventas ADD COLUMN igv_porcentaje REAL DEFAULT 0;
+
+            ALTER TABLE detalles_venta ADD COLUMN afectacion_igv TEXT DEFAULT 'GRAVADO';
+            ALTER TABLE detalles_venta ADD COLUMN valor_venta REAL DEFAULT 0;
+            ALTER TABLE detalles_venta ADD COLUMN igv REAL DEFAULT 0;
+
+            ALTER TABLE devoluciones ADD COLUMN op_gravada REAL DEFAULT 0;
+            ALTER TABLE devoluciones ADD COLUMN op_exonerada REAL DEFAULT 0;
+            ALTER TABLE devoluciones ADD COLUMN op_inafecta REAL DEFAULT 0;
+            ALTER TABLE devoluciones ADD COLUMN igv REAL DEFAULT 0;
+
+            ALTER TABLE detalles_devolucion ADD COLUMN valor_venta REAL DEFAULT 0;
+            ALTER TABLE detalles_devolucion ADD COLUMN igv REAL DEFAULT 0;
+        "#)?;
+        println!("Columnas de IGV agregadas");
+    }
+
+    // 🆕 Migración: numeración central de documentos
+    if !existe_tabla(&conn, "numeracion_documentos")? {
+        println!("Ejecutando migracion: Agregar numeracion de documentos...");
+        conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS numeracion_documentos (
+                tipo_documento TEXT PRIMARY KEY,
+                descripcion TEXT,
+                formato TEXT NOT NULL,
+                reinicio TEXT NOT NULL DEFAULT 'DIARIO' CHECK(reinicio IN ('DIARIO', 'MENSUAL', 'NUNCA')),
+                fecha_actualizacion TEXT DEFAULT (datetime('now', 'localtime'))
+            );
+
+            CREATE TABLE IF NOT EXISTS contadores_documentos (
+                tipo_documento TEXT NOT NULL,
+                periodo TEXT NOT NULL,
+                ultimo_numero INTEGER NOT NULL DEFAULT 0,
+                fecha_actualizacion TEXT DEFAULT (datetime('now', 'localtime')),
+                PRIMARY KEY (tipo_documento, periodo),
+                FOREIGN KEY (tipo_documento) REFERENCES numeracion_documentos(tipo_documento)
+            );
+
+            INSERT INTO numeracion_documentos (tipo_documento, descripcion, formato, reinicio) VALUES
+            ('VENTA', 'Ventas',
+             COALESCE((SELECT formato_folio FROM configuracion_tienda LIMIT 1), 'V-{YYYY}{MM}{DD}-{####}'), 'DIARIO'),
+            ('DEVOLUCION', 'Devoluciones de clientes', 'DEV-{YYYY}{MM}{DD}-{####}', 'DIARIO'),
+            ('COMPRA', 'Compras a proveedores', 'C-{YYYY}{MM}{DD}-{####}', 'DIARIO'),
+            ('DEVOLUCION_PROVEEDOR', 'Devoluciones a proveedores', 'DP-{YYYY}{MM}{DD}-{####}', 'DIARIO');
+        "#)?;
+
+        // Los folios de hoy ya se generaron con el cálculo anterior: el
+        // contador del día arranca donde quedaron
+        for (tipo, tabla, columna, prefijo) in [
+            ("VENTA", "ventas", "folio", "V-"),
+            ("DEVOLUCION", "devoluciones", "folio_devolucion", "DEV-"),
+            ("COMPRA", "compras", "folio", "C-"),
+            ("DEVOLUCION_PROVEEDOR", "devoluciones_proveedor", "folio", "DP-"),
+        ] {
+            if !existe_tabla(&conn, tabla)? {
+                continue;
+            }
+            conn.execute(
+                &format!(
+                    r"INSERT INTO contadores_documentos (tipo_documento, periodo, ultimo_numero)
+                      SELECT ?1, strftime('%Y%m%d', 'now', 'localtime'), MAX(CAST(substr({col}, -4) AS INTEGER))
+                      FROM {tabla}
+                      WHERE {col} LIKE ?2 || strftime('%Y%m%d', 'now', 'localtime') || '-%'
+                      HAVING COUNT(*) > 0",
+                    col = columna,
+                    tabla = tabla
+                ),
+                rusqlite::params![tipo, prefijo],
+            )?;
+        }
+        println!("Migracion completada: Numeracion de documentos agregada");
+    }
+
+    // 🆕 Migración: comprobantes electrónicos SUNAT
+    if !existe_columna(&conn, "configuracion_tienda", "cpe_endpoint") {
+        println!("Agregando configuracion de facturacion electronica...");
+        conn.execute_batch(r#"
+            ALTER TABLE configuracion_tienda ADD COLUMN razon_social TEXT;
+            ALTER TABLE configuracion_tienda ADD COLUMN nombre_comercial TEXT;
+            ALTER TABLE configuracion_tienda ADD COLUMN ubigeo TEXT DEFAULT '';
+            ALTER TABLE configuracion_tienda ADD COLUMN cpe_certificado_ruta TEXT;
+            ALTER TABLE configuracion_tienda ADD COLUMN cpe_certificado_clave TEXT;
+            ALTER TABLE configuracion_tienda ADD COLUMN cpe_usuario_sol TEXT;
+            ALTER TABLE configuracion_tienda ADD COLUMN cpe_clave_sol TEXT;
+            ALTER TABLE configuracion_tienda ADD COLUMN cpe_endpoint TEXT DEFAULT 'https://e-beta.sunat.gob.pe/ol-ti-itcpfegem-beta/billService';
+        "#)?;
+    }
+
+    if !existe_tabla(&conn, "comprobantes_electronicos")? {
+        println!("Ejecutando migracion: Agregar comprobantes electronicos...");
+        conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS comprobantes_electronicos (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                venta_id INTEGER NOT NULL,
+                tipo_comprobante TEXT NOT NULL CHECK(tipo_comprobante IN ('01', '03')),
+                serie TEXT NOT NULL,
+                correlativo INTEGER NOT NULL,
+                nombre_archivo TEXT NOT NULL,
+                xml_firmado TEXT NOT NULL,
+                hash TEXT,
+                zip BLOB,
+                estado TEXT DEFAULT 'FIRMADO' CHECK(estado IN ('FIRMADO', 'ACEPTADO', 'OBSERVADO', 'RECHAZADO', 'ERROR')),
+                codigo_respuesta TEXT,
+                descripcion_respuesta TEXT,
+                observaciones TEXT,
+                cdr_zip BLOB,
+                intentos_envio INTEGER DEFAULT 0,
+                fecha_emision TEXT DEFAULT (datetime('now', 'localtime')),
+                fecha_envio TEXT,
+                usuario_id INTEGER,
+                UNIQUE(tipo_comprobante, serie, correlativo),
+                FOREIGN KEY (venta_id) REFERENCES ventas(id),
+                FOREIGN KEY (usuario_id) REFERENCES usuarios(id)
+            );
+
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_comprobantes_venta ON comprobantes_electronicos(venta_id);
+            CREATE INDEX IF NOT EXISTS idx_comprobantes_estado ON comprobantes_electronicos(estado);
+        "#)?;
+        println!("Migracion completada: Comprobantes electronicos agregados");
+    }
+
+    // 🆕 Migración: tipo de comprobante y series por caja
+    if !existe_columna(&conn, "ventas", "tipo_comprobante") {
+        println!("Ejecutando migracion: Agregar tipo de comprobante a ventas...");
+        conn.execute_batch(r#"
+            ALTER TABLE ventas ADD COLUMN tipo_comprobante TEXT DEFAULT 'NOTA_VENTA' CHECK(tipo_comprobante IN ('BOLETA', 'FACTURA', 'NOTA_VENTA'));
+            ALTER TABLE ventas ADD COLUMN serie TEXT;
+            ALTER TABLE ventas ADD COLUMN correlativo INTEGER;
+
+            -- Las ventas que ya tienen comprobante electrónico toman su número
+            UPDATE ventas
+            SET tipo_comprobante = CASE (SELECT tipo_comprobante FROM comprobantes_electronicos ce WHERE ce.venta_id = ventas.id)
+                                       WHEN '01' THEN 'FACTURA' ELSE 'BOLETA' END,
+                serie = (SELECT serie FROM comprobantes_electronicos ce WHERE ce.venta_id = ventas.id),
+                correlativo = (SELECT correlativo FROM comprobantes_electronicos ce WHERE ce.venta_id = ventas.id)
+            WHERE id IN (SELECT venta_id FROM comprobantes_electronicos);
+
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_ventas_comprobante ON ventas(serie, correlativo) WHERE serie IS NOT NULL;
+        "#)?;
+        println!("Migracion completada: Tipo de comprobante agregado");
+    }
+
+    if !existe_tabla(&conn, "series_comprobante")? {
+        println!("Ejecutando migracion: Agregar series de comprobantes...");
+        conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS series_comprobante (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                tipo_comprobante TEXT NOT NULL CHECK(tipo_comprobante IN ('BOLETA', 'FACTURA', 'NOTA_VENTA')),
+                serie TEXT NOT NULL UNIQUE,
+                numero_caja INTEGER NOT NULL DEFAULT 1,
+                ultimo_correlativo INTEGER NOT NULL DEFAULT 0,
+                activo INTEGER DEFAULT 1,
+                fecha_creacion TEXT DEFAULT (datetime('now', 'localtime'))
+            );
+
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_series_caja_activa ON series_comprobante(tipo_comprobante, numero_caja) WHERE activo = 1;
+
+            INSERT OR IGNORE INTO series_comprobante (tipo_comprobante, serie, numero_caja) VALUES
+            ('BOLETA', 'B001', 1),
+            ('FACTURA', 'F001', 1),
+            ('NOTA_VENTA', 'NV01', 1);
+
+            -- Series ya usadas: se continúa desde el último número emitido
+            INSERT OR IGNORE INTO series_comprobante (tipo_comprobante, serie, numero_caja, activo)
+            SELECT DISTINCT tipo_comprobante, serie, 1, 0 FROM ventas WHERE serie IS NOT NULL;
+
+            UPDATE series_comprobante
+            SET ultimo_correlativo = (SELECT COALESCE(MAX(correlativo), 0) FROM ventas v WHERE v.serie = series_comprobante.serie);
+
+            -- Boletas y facturas ya no se numeran con numeracion_documentos
+            DELETE FROM contadores_documentos WHERE tipo_documento IN ('BOLETA', 'FACTURA');
+            DELETE FROM numeracion_documentos WHERE tipo_documento IN ('BOLETA', 'FACTURA');
+        "#)?;
+        println!("Migracion completada: Series de comprobantes agregadas");
+    }
+
+    // 🆕 Migración: notas de crédito
+    if !check_incluye(&conn, "series_comprobante", "'NOTA_CREDITO_BOLETA'") {
+        println!("Agregando series de notas de credito...");
+        reconstruir_tabla(&conn, "series_comprobante", r#"
+            CREATE TABLE series_comprobante_nueva (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                tipo_comprobante TEXT NOT NULL CHECK(tipo_comprobante IN ('BOLETA', 'FACTURA', 'NOTA_VENTA', 'NOTA_CREDITO_BOLETA', 'NOTA_CREDITO_FACTURA')),
+                serie TEXT NOT NULL UNIQUE,
+                numero_caja INTEGER NOT NULL DEFAULT 1,
+                ultimo_correlativo INTEGER NOT NULL DEFAULT 0,
+                activo INTEGER DEFAULT 1,
+                fecha_creacion TEXT DEFAULT (datetime('now', 'localtime'))
+            );
+        "#, r#"
+            INSERT OR IGNORE INTO series_comprobante (tipo_comprobante, serie, numero_caja) VALUES
+            ('NOTA_CREDITO_BOLETA', 'BC01', 1),
+            ('NOTA_CREDITO_FACTURA', 'FC01', 1);
+        "#)?;
+    }
+
+    if !existe_tabla(&conn, "notas_credito")? {
+        println!("Ejecutando migracion: Agregar notas de credito...");
+        conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS notas_credito (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                devolucion_id INTEGER UNIQUE,
+                venta_id INTEGER NOT NULL,
+                serie TEXT NOT NULL,
+                correlativo INTEGER NOT NULL,
+                tipo_documento_ref TEXT NOT NULL CHECK(tipo_documento_ref IN ('BOLETA', 'FACTURA')),
+                serie_ref TEXT NOT NULL,
+                correlativo_ref INTEGER NOT NULL,
+                codigo_motivo TEXT NOT NULL,
+                sustento TEXT NOT NULL,
+                op_gravada REAL DEFAULT 0,
+                op_exonerada REAL DEFAULT 0,
+                op_inafecta REAL DEFAULT 0,
+                igv REAL DEFAULT 0,
+                igv_porcentaje REAL DEFAULT 0,
+                total REAL NOT NULL CHECK (total >= 0),
+                usuario_id INTEGER NOT NULL,
+                fecha_emision TEXT DEFAULT (datetime('now', 'localtime')),
+                UNIQUE(serie, correlativo),
+                FOREIGN KEY (devolucion_id) REFERENCES devoluciones(id),
+                FOREIGN KEY (venta_id) REFERENCES ventas(id),
+                FOREIGN KEY (usuario_id) REFERENCES usuarios(id)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_notas_credito_venta ON notas_credito(venta_id);
+
+            CREATE TABLE IF NOT EXISTS detalles_nota_credito (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                nota_credito_id INTEGER NOT NULL,
+                detalle_venta_id INTEGER NOT NULL,
+                producto_id INTEGER NOT NULL,
+                cantidad INTEGER NOT NULL CHECK (cantidad > 0),
+                total_linea REAL NOT NULL,
+                valor_venta REAL NOT NULL,
+                igv REAL NOT NULL,
+                afectacion_igv TEXT DEFAULT 'GRAVADO',
+                FOREIGN KEY (nota_credito_id) REFERENCES notas_credito(id) ON DELETE CASCADE,
+                FOREIGN KEY (detalle_venta_id) REFERENCES detalles_venta(id),
+                FOREIGN KEY (producto_id) REFERENCES productos(id)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_detalles_nota_credito ON detalles_nota_credito(nota_credito_id);
+            CREATE INDEX IF NOT EXISTS idx_detalles_nota_credito_detalle ON detalles_nota_credito(detalle_venta_id);
+        "#)?;
+        println!("Migracion completada: Notas de credito agregadas");
+    }
+
+    // Comprobantes electrónicos de tipo 07 (una venta puede tener varias notas)
+    if !check_incluye(&conn, "comprobantes_electronicos", "'07'") {
+        reconstruir_tabla(&conn, "comprobantes_electronicos", r#"
+            CREATE TABLE comprobantes_electronicos_nueva (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                venta_id INTEGER NOT NULL,
+                nota_credito_id INTEGER,
+                tipo_comprobante TEXT NOT NULL CHECK(tipo_comprobante IN ('01', '03', '07')),
+                serie TEXT NOT NULL,
+                correlativo INTEGER NOT NULL,
+                nombre_archivo TEXT NOT NULL,
+                xml_firmado TEXT NOT NULL,
+                hash TEXT,
+                zip BLOB,
+                estado TEXT DEFAULT 'FIRMADO' CHECK(estado IN ('FIRMADO', 'ACEPTADO', 'OBSERVADO', 'RECHAZADO', 'ERROR')),
+                codigo_respuesta TEXT,
+                descripcion_respuesta TEXT,
+                observaciones TEXT,
+                cdr_zip BLOB,
+                intentos_envio INTEGER DEFAULT 0,
+                fecha_emision TEXT DEFAULT (datetime('now', 'localtime')),
+                fecha_envio TEXT,
+                usuario_id INTEGER,
+                UNIQUE(tipo_comprobante, serie, correlativo),
+                FOREIGN KEY (venta_id) REFERENCES ventas(id),
+                FOREIGN KEY (nota_credito_id) REFERENCES notas_credito(id),
+                FOREIGN KEY (usuario_id) REFERENCES usuarios(id)
+            );
+        "#, r#"
+            DROP INDEX IF EXISTS idx_comprobantes_venta;
+            CREATE UNIQUE INDEX idx_comprobantes_venta ON comprobantes_electronicos(venta_id) WHERE nota_credito_id IS NULL;
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_comprobantes_nota_credito ON comprobantes_electronicos(nota_credito_id) WHERE nota_credito_id IS NOT NULL;
+        "#)?;
+        println!("Comprobantes electronicos: notas de credito habilitadas");
+    }
+
+    // 🆕 Migración: padrón RUC y dirección del cliente
+    if !existe_columna(&conn, "clientes", "direccion") {
+        println!("Agregando direccion a clientes...");
+        conn.execute("ALTER TABLE clientes ADD COLUMN direccion TEXT", [])?;
+    }
+
+    if !existe_tabla(&conn, "padron_ruc")? {
+        println!("Ejecutando migracion: Agregar padron RUC...");
+        conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS padron_ruc (
+                ruc TEXT PRIMARY KEY,
+                razon_social TEXT NOT NULL,
+                estado TEXT,
+                condicion TEXT,
+                ubigeo TEXT,
+                direccion TEXT,
+                fecha_actualizacion TEXT DEFAULT (datetime('now', 'localtime'))
+            ) WITHOUT ROWID;
+        "#)?;
+        println!("Migracion completada: Padron RUC agregado");
+    }
+
+    // 🆕 Migración: cotizaciones
+    if !existe_tabla(&conn, "cotizaciones")? {
+        println!("Ejecutando migracion: Agregar cotizaciones...");
+        conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS cotizaciones (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                folio TEXT NOT NULL UNIQUE,
+                cliente_id INTEGER,
+                contacto TEXT,
+                subtotal REAL NOT NULL DEFAULT 0,
+                descuento REAL DEFAULT 0,
+                total REAL NOT NULL DEFAULT 0,
+                fecha_emision TEXT DEFAULT (datetime('now', 'localtime')),
+                fecha_validez TEXT NOT NULL,
+                estado TEXT DEFAULT 'VIGENTE' CHECK(estado IN ('VIGENTE', 'CONVERTIDA', 'ANULADA')),
+                notas TEXT,
+                usuario_id INTEGER NOT NULL,
+                venta_id INTEGER,
+                fecha_conversion TEXT,
+                FOREIGN KEY (cliente_id) REFERENCES clientes(id),
+                FOREIGN KEY (usuario_id) REFERENCES usuarios(id),
+                FOREIGN KEY (venta_id) REFERENCES ventas(id)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_cotizaciones_cliente ON cotizaciones(cliente_id);
+            CREATE INDEX IF NOT EXISTS idx_cotizaciones_estado ON cotizaciones(estado);
+
+            CREATE TABLE IF NOT EXISTS detalles_cotizacion (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                cotizacion_id INTEGER NOT NULL,
+                producto_id INTEGER NOT NULL,
+                variante_id INTEGER,
+                talla TEXT,
+                nombre TEXT NOT NULL,
+                codigo TEXT NOT NULL,
+                cantidad INTEGER NOT NULL CHECK (cantidad > 0),
+                precio_unitario REAL NOT NULL CHECK (precio_unitario >= 0),
+                descuento_porcentaje REAL DEFAULT 0,
+                total_linea REAL NOT NULL,
+                FOREIGN KEY (cotizacion_id) REFERENCES cotizaciones(id) ON DELETE CASCADE,
+                FOREIGN KEY (producto_id) REFERENCES productos(id),
+                FOREIGN KEY (variante_id) REFERENCES producto_variantes(id)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_detalles_cotizacion ON detalles_cotizacion(cotizacion_id);
+
+            INSERT OR IGNORE INTO numeracion_documentos (tipo_documento, descripcion, formato, reinicio)
+            VALUES ('COTIZACION', 'Cotizaciones', 'COT-{YYYY}{MM}-{####}', 'MENSUAL');
+        "#)?;
+        println!("Migracion completada: Cotizaciones agregadas");
+    }
+
+    if !existe_columna(&conn, "ventas", "cotizacion_id") {
+        println!("Agregando cotizacion_id a ventas...");
+        conn.execute("ALTER TABLE ventas ADD COLUMN cotizacion_id INTEGER REFERENCES cotizaciones(id)", [])?;
+    }
+
+    // 🆕 Migración: ventas al crédito y cuentas por cobrar
+    if !existe_columna(&conn, "clientes", "limite_credito") {
+        println!("Agregando limite de credito a clientes...");
+        conn.execute_batch(r#"
+            ALTER TABLE clientes ADD COLUMN limite_credito REAL DEFAULT 0 CHECK (limite_credito >= 0);
+            ALTER TABLE clientes ADD COLUMN dias_credito INTEGER DEFAULT 30 CHECK (dias_credito > 0);
+        "#)?;
+    }
+
+    if !check_incluye(&conn, "pagos_venta", "'CREDITO'") {
+        println!("Agregando metodo de pago CREDITO...");
+        reconstruir_tabla(&conn, "pagos_venta", r#"
+            CREATE TABLE pagos_venta_nueva (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                venta_id INTEGER NOT NULL,
+                metodo_pago TEXT NOT NULL CHECK(metodo_pago IN ('EFECTIVO', 'TARJETA', 'TRANSFERENCIA', 'PUNTOS', 'VALE', 'CREDITO')),
+                monto REAL NOT NULL CHECK (monto > 0),
+                monto_recibido REAL,
+                referencia TEXT,
+                fecha_hora TEXT DEFAULT (datetime('now', 'localtime')),
+                FOREIGN KEY (venta_id) REFERENCES ventas(id) ON DELETE CASCADE
+            );
+        "#, "")?;
+    }
+
+    if !check_incluye(&conn, "ventas", "'CREDITO'") {
+        reconstruir_tabla(&conn, "ventas", r#"
+            CREATE TABLE ventas_nueva (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                folio TEXT NOT NULL UNIQUE,
+                fecha_hora TEXT DEFAULT (datetime('now', 'localtime')),
+                subtotal REAL NOT NULL CHECK (subtotal >= 0),
+                descuento REAL DEFAULT 0 CHECK (descuento >= 0),
+                total REAL NOT NULL CHECK (total >= 0),
+                metodo_pago TEXT NOT NULL CHECK(metodo_pago IN ('EFECTIVO', 'TARJETA', 'TRANSFERENCIA', 'PUNTOS', 'VALE', 'CREDITO', 'MIXTO')),
+                monto_recibido REAL,
+                cambio REAL,
+                usuario_id INTEGER NOT NULL,
+                caja_id INTEGER,
+                cliente_id INTEGER,
+                cupon_id INTEGER,
+                descuento_cupon REAL DEFAULT 0,
+                op_gravada REAL DEFAULT 0,
+                op_exonerada REAL DEFAULT 0,
+                op_inafecta REAL DEFAULT 0,
+                igv REAL DEFAULT 0,
+                igv_porcentaje REAL DEFAULT 0,
+                tipo_comprobante TEXT DEFAULT 'NOTA_VENTA' CHECK(tipo_comprobante IN ('BOLETA', 'FACTURA', 'NOTA_VENTA')),
+                serie TEXT,
+                correlativo INTEGER,
+                cotizacion_id INTEGER,
+                saldo_pendiente REAL DEFAULT 0 CHECK (saldo_pendiente >= 0),
+                fecha_vencimiento_pago TEXT,
+                estado_pago TEXT CHECK(estado_pago IN ('PENDIENTE', 'PARCIAL', 'PAGADO')),
+                estado TEXT DEFAULT 'COMPLETADA' CHECK(estado IN ('COMPLETADA', 'CANCELADA', 'PENDIENTE')),
+                motivo_cancelacion TEXT,
+                notas TEXT,
+                licencia_tipo TEXT,
+                FOREIGN KEY (usuario_id) REFERENCES usuarios(id),
+                FOREIGN KEY (caja_id) REFERENCES cajas(id),
+                FOREIGN KEY (cliente_id) REFERENCES clientes(id),
+                FOREIGN KEY (cupon_id) REFERENCES cupones(id),
+                FOREIGN KEY (cotizacion_id) REFERENCES cotizaciones(id)
+            );
+        "#, r#"
+            CREATE INDEX IF NOT EXISTS idx_ventas_por_cobrar ON ventas(cliente_id, fecha_vencimiento_pago) WHERE saldo_pendiente > 0;
+        "#)?;
+        println!("Metodo de pago CREDITO agregado");
+    }
+
+    if !existe_tabla(&conn, "pagos_credito")? {
+        println!("Ejecutando migracion: Agregar cuentas por cobrar...");
+        conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS pagos_credito (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                venta_id INTEGER NOT NULL,
+                cliente_id INTEGER NOT NULL,
+                folio TEXT NOT NULL,
+                monto REAL NOT NULL CHECK (monto > 0),
+                fecha_pago TEXT DEFAULT (datetime('now', 'localtime')),
+                metodo_pago TEXT NOT NULL CHECK(metodo_pago IN ('EFECTIVO', 'TARJETA', 'TRANSFERENCIA', 'DEVOLUCION')),
+                referencia TEXT,
+                notas TEXT,
+                caja_id INTEGER,
+                devolucion_id INTEGER,
+                usuario_id INTEGER NOT NULL,
+                FOREIGN KEY (venta_id) REFERENCES ventas(id),
+                FOREIGN KEY (cliente_id) REFERENCES clientes(id),
+                FOREIGN KEY (caja_id) REFERENCES cajas(id),
+                FOREIGN KEY (devolucion_id) REFERENCES devoluciones(id),
+                FOREIGN KEY (usuario_id) REFERENCES usuarios(id)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_pagos_credito_venta ON pagos_credito(venta_id);
+            CREATE INDEX IF NOT EXISTS idx_pagos_credito_cliente ON pagos_credito(cliente_id);
+            CREATE INDEX IF NOT EXISTS idx_pagos_credito_folio ON pagos_credito(folio);
+
+            CREATE TRIGGER IF NOT EXISTS trg_actualizar_saldo_venta
+            AFTER INSERT ON pagos_credito
+            FOR EACH ROW
+            BEGIN
+              UPDATE ventas
+              SET
+                saldo_pendiente = MAX(0, ROUND(
+                  (SELECT COALESCE(SUM(monto), 0) FROM pagos_venta WHERE venta_id = NEW.venta_id AND metodo_pago = 'CREDITO')
+                  - (SELECT COALESCE(SUM(monto), 0) FROM pagos_credito WHERE venta_id = NEW.venta_id), 2)),
+                estado_pago = CASE
+                  WHEN (SELECT COALESCE(SUM(monto), 0) FROM pagos_venta WHERE venta_id = NEW.venta_id AND metodo_pago = 'CREDITO')
+                       <= (SELECT COALESCE(SUM(monto), 0) FROM pagos_credito WHERE venta_id = NEW.venta_id) + 0.005 THEN 'PAGADO'
+                  ELSE 'PARCIAL'
+                END
+              WHERE id = NEW.venta_id;
+            END;
+
+            INSERT OR IGNORE INTO numeracion_documentos (tipo_documento, descripcion, formato, reinicio)
+            VALUES ('COBRO', 'Cobros de ventas al crédito', 'COB-{YYYY}{MM}{DD}-{####}', 'DIARIO');
+        "#)?;
+        println!("Migracion completada: Cuentas por cobrar agregadas");
+    }
+
+    // 🆕 Migración: redondeo del efectivo a S/ 0.10 (diferencia por venta y total en caja)
+    if !existe_columna(&conn, "cajas", "redondeo_total") {
+        println!("Ejecutando migracion: Redondeo de efectivo...");
+        conn.execute_batch(r#"
+            ALTER TABLE configuracion_tienda ADD COLUMN redondeo_efectivo TEXT DEFAULT 'NINGUNO'
+                CHECK(redondeo_efectivo IN ('NINGUNO', 'CERCANO', 'ABAJO'));
+            ALTER TABLE ventas ADD COLUMN redondeo REAL DEFAULT 0;
+            ALTER TABLE cajas ADD COLUMN redondeo_total REAL DEFAULT 0;
+
+            DROP TRIGGER IF EXISTS trg_actualizar_caja_venta;
+            CREATE TRIGGER trg_actualizar_caja_venta
+            AFTER INSERT ON ventas
+            FOR EACH ROW
+            WHEN NEW.estado = 'COMPLETADA'
+            BEGIN
+              UPDATE cajas
+              SET
+                total_ventas         = total_ventas         + NEW.total,
+                numero_transacciones = numero_transacciones + 1,
+                cambio_total         = cambio_total         + COALESCE(NEW.cambio, 0),
+                redondeo_total       = redondeo_total       + COALESCE(NEW.redondeo, 0),
+                ticket_promedio      = CASE WHEN numero_transacciones + 1 > 0
+                                       THEN (total_ventas + NEW.total) / (numero_transacciones + 1) ELSE 0 END
+              WHERE id = NEW.caja_id;
+            END;
+
+            DROP TRIGGER IF EXISTS trg_actualizar_caja_cancelar_venta;
+            CREATE TRIGGER trg_actualizar_caja_cancelar_venta
+            AFTER UPDATE ON ventas
+            FOR EACH ROW
+            WHEN OLD.estado = 'COMPLETADA' AND NEW.estado = 'CANCELADA'
+            BEGIN
+              UPDATE cajas
+              SET
+                ventas_efectivo      = ventas_efectivo      - COALESCE(
+                                         (SELECT SUM(monto) FROM pagos_venta WHERE venta_id = NEW.id AND metodo_pago = 'EFECTIVO'),
+                                         CASE WHEN NEW.metodo_pago = 'EFECTIVO' THEN NEW.total ELSE 0 END),
+                ventas_tarjeta       = ventas_tarjeta       - COALESCE(
+                                         (SELECT SUM(monto) FROM pagos_venta WHERE venta_id = NEW.id AND metodo_pago = 'TARJETA'),
+                                         CASE WHEN NEW.metodo_pago = 'TARJETA' THEN NEW.total ELSE 0 END),
+                ventas_transferencia = ventas_transferencia - COALESCE(
+                                         (SELECT SUM(monto) FROM pagos_venta WHERE venta_id = NEW.id AND metodo_pago = 'TRANSFERENCIA'),
+                                         CASE WHEN NEW.metodo_pago = 'TRANSFERENCIA' THEN NEW.total ELSE 0 END),
+                total_ventas         = total_ventas         - NEW.total,
+                numero_transacciones = numero_transacciones - 1,
+                cambio_total         = cambio_total         - COALESCE(NEW.cambio, 0),
+                redondeo_total       = redondeo_total       - COALESCE(NEW.redondeo, 0),
+                ticket_promedio      = CASE WHEN numero_transacciones - 1 > 0
+                                       THEN (total_ventas - NEW.total) / (numero_transacciones - 1) ELSE 0 END
+              WHERE id = NEW.caja_id;
+            END;
+        "#)?;
+        println!("Migracion completada: Redondeo de efectivo agregado");
+    }
+
+    // 🆕 Migración: listas de precios (minorista/mayorista) y escalas por cantidad
+    if !existe_tabla(&conn, "listas_precios")? {
+        println!("Ejecutando migracion: Listas de precios...");
+        conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS listas_precios (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                nombre TEXT NOT NULL UNIQUE COLLATE NOCASE,
+                descripcion TEXT,
+                predeterminada INTEGER DEFAULT 0,
+                activo INTEGER DEFAULT 1,
+                fecha_creacion TEXT DEFAULT (datetime('now', 'localtime'))
+            );
+
+            CREATE TABLE IF NOT EXISTS precios_lista (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                lista_id INTEGER NOT NULL,
+                producto_id INTEGER NOT NULL,
+                variante_id INTEGER,
+                cantidad_minima INTEGER NOT NULL DEFAULT 1 CHECK (cantidad_minima >= 1),
+                precio REAL NOT NULL CHECK (precio >= 0),
+                fecha_actualizacion TEXT DEFAULT (datetime('now', 'localtime')),
+                FOREIGN KEY (lista_id) REFERENCES listas_precios(id) ON DELETE CASCADE,
+                FOREIGN KEY (producto_id) REFERENCES productos(id) ON DELETE CASCADE,
+                FOREIGN KEY (variante_id) REFERENCES producto_variantes(id) ON DELETE CASCADE
+            );
+
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_precios_lista_unico
+                ON precios_lista(lista_id, producto_id, COALESCE(variante_id, 0), cantidad_minima);
+            CREATE INDEX IF NOT EXISTS idx_precios_lista_producto ON precios_lista(producto_id);
+
+            ALTER TABLE clientes ADD COLUMN lista_precio_id INTEGER;
+            ALTER TABLE ventas ADD COLUMN lista_precio_id INTEGER;
+
+            INSERT INTO listas_precios (nombre, descripcion, predeterminada)
+            VALUES ('Minorista', 'Precio de tienda', 1);
+        "#)?;
+        println!("Migracion completada: Listas de precios agregadas");
+    }
+
+    // 🆕 Migración: cambios de talla (devolución + venta de reemplazo en un documento)
+    if !check_incluye(&conn, "pagos_venta", "'CAMBIO'") {
+        println!("Agregando metodo de pago CAMBIO...");
+        reconstruir_tabla(&conn, "pagos_venta", r#"
+            CREATE TABLE pagos_venta_nueva (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                venta_id INTEGER NOT NULL,
+                metodo_pago TEXT NOT NULL CHECK(metodo_pago IN ('EFECTIVO', 'TARJETA', 'TRANSFERENCIA', 'PUNTOS', 'VALE', 'CREDITO', 'CAMBIO')),
+                monto REAL NOT NULL CHECK (monto > 0),
+                monto_recibido REAL,
+                referencia TEXT,
+                fecha_hora TEXT DEFAULT (datetime('now', 'localtime')),
+                FOREIGN KEY (venta_id) REFERENCES ventas(id) ON DELETE CASCADE
+            );
+        "#, "")?;
+    }
+
+    if !check_incluye(&conn, "ventas", "'CAMBIO'") {
+        reconstruir_tabla(&conn, "ventas", r#"
+            CREATE TABLE ventas_nueva (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                folio TEXT NOT NULL UNIQUE,
+                fecha_hora TEXT DEFAULT (datetime('now', 'localtime')),
+                subtotal REAL NOT NULL CHECK (subtotal >= 0),
+                descuento REAL DEFAULT 0 CHECK (descuento >= 0),
+                total REAL NOT NULL CHECK (total >= 0),
+                metodo_pago TEXT NOT NULL CHECK(metodo_pago IN ('EFECTIVO', 'TARJETA', 'TRANSFERENCIA', 'PUNTOS', 'VALE', 'CREDITO', 'CAMBIO', 'MIXTO')),
+                monto_recibido REAL,
+                cambio REAL,
+                redondeo REAL DEFAULT 0,
+                lista_precio_id INTEGER,
+                usuario_id INTEGER NOT NULL,
+                caja_id INTEGER,
+                cliente_id INTEGER,
+                cupon_id INTEGER,
+                descuento_cupon REAL DEFAULT 0,
+                op_gravada REAL DEFAULT 0,
+                op_exonerada REAL DEFAULT 0,
+                op_inafecta REAL DEFAULT 0,
+                igv REAL DEFAULT 0,
+                igv_porcentaje REAL DEFAULT 0,
+                tipo_comprobante TEXT DEFAULT 'NOTA_VENTA' CHECK(tipo_comprobante IN ('BOLETA', 'FACTURA', 'NOTA_VENTA')),
+                serie TEXT,
+                correlativo INTEGER,
+                cotizacion_id INTEGER,
+                saldo_pendiente REAL DEFAULT 0 CHECK (saldo_pendiente >= 0),
+                fecha_vencimiento_pago TEXT,
+                estado_pago TEXT CHECK(estado_pago IN ('PENDIENTE', 'PARCIAL', 'PAGADO')),
+                cambio_id INTEGER,
+                estado TEXT DEFAULT 'COMPLETADA' CHECK(estado IN ('COMPLETADA', 'CANCELADA', 'PENDIENTE')),
+                motivo_cancelacion TEXT,
+                notas TEXT,
+                licencia_tipo TEXT,
+                FOREIGN KEY (usuario_id) REFERENCES usuarios(id),
+                FOREIGN KEY (caja_id) REFERENCES cajas(id),
+                FOREIGN KEY (cliente_id) REFERENCES clientes(id),
+                FOREIGN KEY (cupon_id) REFERENCES cupones(id),
+                FOREIGN KEY (cotizacion_id) REFERENCES cotizaciones(id),
+                FOREIGN KEY (cambio_id) REFERENCES cambios(id)
+            );
+        "#, "")?;
+    }
+
+    if !check_incluye(&conn, "devoluciones", "'CAMBIO'") {
+        reconstruir_tabla(&conn, "devoluciones", r#"
+            CREATE TABLE devoluciones_nueva (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                venta_original_id INTEGER NOT NULL,
+                folio_devolucion TEXT NOT NULL UNIQUE,
+                fecha_hora TEXT DEFAULT (datetime('now', 'localtime')),
+                usuario_id INTEGER NOT NULL,
+                monto_reembolsado REAL NOT NULL CHECK (monto_reembolsado >= 0),
+                metodo_reembolso TEXT NOT NULL CHECK(metodo_reembolso IN ('EFECTIVO', 'TARJETA', 'VALE', 'CREDITO', 'CAMBIO')),
+                motivo TEXT NOT NULL,
+                op_gravada REAL DEFAULT 0,
+                op_exonerada REAL DEFAULT 0,
+                op_inafecta REAL DEFAULT 0,
+                igv REAL DEFAULT 0,
+                estado TEXT DEFAULT 'PROCESADA' CHECK(estado IN ('PROCESADA', 'PENDIENTE', 'RECHAZADA')),
+                notas TEXT,
+                cambio_id INTEGER,
+                FOREIGN KEY (venta_original_id) REFERENCES ventas(id),
+                FOREIGN KEY (usuario_id) REFERENCES usuarios(id),
+                FOREIGN KEY (cambio_id) REFERENCES cambios(id)
+            );
+        "#, "")?;
+    }
+
+    if !existe_tabla(&conn, "cambios")? {
+        println!("Ejecutando migracion: Cambios de talla...");
+        conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS cambios (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                folio TEXT NOT NULL UNIQUE,
+                venta_original_id INTEGER NOT NULL,
+                monto_devuelto REAL NOT NULL CHECK (monto_devuelto >= 0),
+                monto_nuevo REAL NOT NULL CHECK (monto_nuevo >= 0),
+                diferencia REAL NOT NULL,
+                metodo_diferencia TEXT CHECK(metodo_diferencia IN ('EFECTIVO', 'TARJETA', 'TRANSFERENCIA', 'VALE')),
+                redondeo REAL DEFAULT 0,
+                motivo TEXT,
+                usuario_id INTEGER NOT NULL,
+                caja_id INTEGER NOT NULL,
+                fecha_hora TEXT DEFAULT (datetime('now', 'localtime')),
+                FOREIGN KEY (venta_original_id) REFERENCES ventas(id),
+                FOREIGN KEY (usuario_id) REFERENCES usuarios(id),
+                FOREIGN KEY (caja_id) REFERENCES cajas(id)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_cambios_venta ON cambios(venta_original_id);
+            CREATE INDEX IF NOT EXISTS idx_cambios_fecha ON cambios(fecha_hora);
+
+            DROP TRIGGER IF EXISTS trg_actualizar_caja_devolucion;
+            CREATE TRIGGER trg_actualizar_caja_devolucion
+            AFTER INSERT ON devoluciones
+            FOR EACH ROW
+            WHEN NEW.estado = 'PROCESADA' AND NEW.metodo_reembolso <> 'CAMBIO'
+            BEGIN
+              UPDATE cajas
+              SET
+                devoluciones_monto    = devoluciones_monto + NEW.monto_reembolsado,
+                devoluciones_cantidad = devoluciones_cantidad + 1,
+                ventas_efectivo       = ventas_efectivo - CASE WHEN NEW.metodo_reembolso = 'EFECTIVO' THEN NEW.monto_reembolsado ELSE 0 END
+              WHERE usuario_id = NEW.usuario_id AND estado = 'ABIERTA'
+                AND date(fecha_apertura) = date(NEW.fecha_hora);
+            END;
+
+            DROP TRIGGER IF EXISTS trg_actualizar_caja_cambio;
+            CREATE TRIGGER trg_actualizar_caja_cambio
+            AFTER INSERT ON cambios
+            FOR EACH ROW
+            WHEN NEW.diferencia < 0 AND NEW.metodo_diferencia = 'EFECTIVO'
+            BEGIN
+              UPDATE cajas
+              SET
+                ventas_efectivo = ventas_efectivo + NEW.diferencia,
+                redondeo_total  = redondeo_total  - COALESCE(NEW.redondeo, 0)
+              WHERE id = NEW.caja_id;
+            END;
+
+            INSERT OR IGNORE INTO numeracion_documentos (tipo_documento, descripcion, formato, reinicio)
+            VALUES ('CAMBIO', 'Cambios de talla', 'CAM-{YYYY}{MM}{DD}-{####}', 'DIARIO');
+        "#)?;
+        println!("Migracion completada: Cambios de talla agregados");
+    }
+
+    // 🆕 Migración: vendedor por venta/línea y comisiones
+    if !existe_tabla(&conn, "reglas_comision")? {
+        println!("Ejecutando migracion: Comisiones de vendedores...");
+        conn.execute_batch(r#"
+            ALTER TABLE ventas ADD COLUMN vendedor_id INTEGER REFERENCES usuarios(id);
+            ALTER TABLE detalles_venta ADD COLUMN vendedor_id INTEGER REFERENCES usuarios(id);
+            CREATE INDEX IF NOT EXISTS idx_ventas_vendedor ON ventas(vendedor_id);
+
+            CREATE TABLE IF NOT EXISTS reglas_comision (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                nombre TEXT NOT NULL,
+                tipo TEXT NOT NULL CHECK(tipo IN ('PORCENTAJE', 'BONO_META')),
+                categoria_id INTEGER,
+                vendedor_id INTEGER,
+                porcentaje REAL DEFAULT 0 CHECK (porcentaje >= 0 AND porcentaje <= 100),
+                meta REAL,
+                bono REAL DEFAULT 0 CHECK (bono >= 0),
+                activo INTEGER DEFAULT 1,
+                fecha_creacion TEXT DEFAULT (datetime('now', 'localtime')),
+                FOREIGN KEY (categoria_id) REFERENCES categorias(id),
+                FOREIGN KEY (vendedor_id) REFERENCES usuarios(id)
+            );
+
+            CREATE TABLE IF NOT EXISTS comisiones (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                vendedor_id INTEGER NOT NULL,
+                venta_id INTEGER NOT NULL,
+                detalle_venta_id INTEGER NOT NULL,
+                devolucion_id INTEGER,
+                regla_id INTEGER,
+                tipo TEXT NOT NULL CHECK(tipo IN ('VENTA', 'REVERSION')),
+                base REAL NOT NULL,
+                porcentaje REAL DEFAULT 0,
+                monto REAL NOT NULL,
+                fecha_hora TEXT DEFAULT (datetime('now', 'localtime')),
+                FOREIGN KEY (vendedor_id) REFERENCES usuarios(id),
+                FOREIGN KEY (venta_id) REFERENCES ventas(id),
+                FOREIGN KEY (detalle_venta_id) REFERENCES detalles_venta(id),
+                FOREIGN KEY (devolucion_id) REFERENCES devoluciones(id),
+                FOREIGN KEY (regla_id) REFERENCES reglas_comision(id)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_comisiones_vendedor ON comisiones(vendedor_id, fecha_hora);
+            CREATE INDEX IF NOT EXISTS idx_comisiones_detalle ON comisiones(detalle_venta_id);
+        "#)?;
+        println!("Migracion completada: Comisiones agregadas");
+    }
+
+    // 🆕 Migración: claves de idempotencia (reintentos de venta, devolución y compras)
+    if !existe_tabla(&conn, "operaciones_idempotentes")? {
+        println!("Ejecutando migracion: Claves de idempotencia...");
+        conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS operaciones_idempotentes (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                clave TEXT NOT NULL,
+                operacion TEXT NOT NULL CHECK(operacion IN ('VENTA', 'DEVOLUCION', 'COMPRA', 'PAGO_COMPRA')),
+                registro_id INTEGER NOT NULL,
+                resultado TEXT NOT NULL,
+                fecha_hora TEXT DEFAULT (datetime('now', 'localtime'))
+            );
+
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_operaciones_clave ON operaciones_idempotentes(clave);
+        "#)?;
+        println!("Migracion completada: Claves de idempotencia agregadas");
+    }
+
+    // 🆕 Migración: condición de la prenda devuelta y stock en cuarentena
+    if !check_incluye(&conn, "movimientos_inventario", "'CUARENTENA'") {
+        println!("Agregando tipos de movimiento CUARENTENA y DEVOLUCION_PROVEEDOR...");
+        reconstruir_tabla(&conn, "movimientos_inventario", r#"
+            CREATE TABLE movimientos_inventario_nueva (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                producto_id INTEGER NOT NULL,
+                variante_id INTEGER,
+                talla TEXT,
+                tipo_movimiento TEXT NOT NULL CHECK(tipo_movimiento IN ('VENTA', 'DEVOLUCION', 'ENTRADA', 'SALIDA', 'AJUSTE', 'MERMA', 'ANULACION', 'CUARENTENA', 'DEVOLUCION_PROVEEDOR')),
+                cantidad INTEGER NOT NULL,
+                stock_anterior INTEGER NOT NULL,
+                stock_nuevo INTEGER NOT NULL,
+                venta_id INTEGER,
+                devolucion_id INTEGER,
+                compra_id INTEGER,
+                usuario_id INTEGER,
+                referencia TEXT,
+                motivo TEXT,
+                fecha_hora TEXT DEFAULT (datetime('now', 'localtime')),
+                FOREIGN KEY (producto_id) REFERENCES productos(id),
+                FOREIGN KEY (variante_id) REFERENCES producto_variantes(id),
+                FOREIGN KEY (venta_id) REFERENCES ventas(id) ON DELETE SET NULL,
+                FOREIGN KEY (devolucion_id) REFERENCES devoluciones(id) ON DELETE SET NULL,
+                FOREIGN KEY (usuario_id) REFERENCES usuarios(id)
+            );
+        "#, r#"
+            CREATE INDEX IF NOT EXISTS idx_movimientos_inventario_producto ON movimientos_inventario(producto_id);
+            CREATE INDEX IF NOT EXISTS idx_movimientos_inventario_tipo ON movimientos_inventario(tipo_movimiento);
+            CREATE INDEX IF NOT EXISTS idx_movimientos_inventario_fecha ON movimientos_inventario(fecha_hora);
+        "#)?;
+        println!("Tipos de movimiento de cuarentena agregados");
+    }
+
+    if !existe_columna(&conn, "productos", "stock_cuarentena") {
+        println!("Ejecutando migracion: Stock en cuarentena...");
+        conn.execute_batch(r#"
+            ALTER TABLE productos ADD COLUMN stock_cuarentena INTEGER NOT NULL DEFAULT 0 CHECK (stock_cuarentena >= 0);
+            ALTER TABLE producto_variantes ADD COLUMN stock_cuarentena INTEGER NOT NULL DEFAULT 0 CHECK (stock_cuarentena >= 0);
+            ALTER TABLE detalles_devolucion_proveedor ADD COLUMN desde_cuarentena INTEGER DEFAULT 0;
+
+            CREATE TRIGGER IF NOT EXISTS trg_actualizar_cuarentena_producto
+            AFTER UPDATE OF stock_cuarentena ON producto_variantes
+            FOR EACH ROW
+            BEGIN
+              UPDATE productos
+              SET stock_cuarentena = (SELECT COALESCE(SUM(stock_cuarentena), 0) FROM producto_variantes WHERE producto_id = NEW.producto_id),
+                  fecha_actualizacion = datetime('now', 'localtime')
+              WHERE id = NEW.producto_id;
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS trg_after_devolucion_cuarentena
+            AFTER INSERT ON detalles_devolucion
+            FOR EACH ROW
+            WHEN NEW.condicion IN ('DEFECTUOSO', 'VENCIDO')
+            BEGIN
+              UPDATE producto_variantes
+              SET stock_cuarentena = stock_cuarentena + NEW.cantidad_devuelta,
+                  fecha_actualizacion = datetime('now', 'localtime')
+              WHERE id = NEW.variante_id AND NEW.variante_id IS NOT NULL;
+
+              UPDATE productos
+              SET stock_cuarentena = stock_cuarentena + NEW.cantidad_devuelta,
+                  fecha_actualizacion = datetime('now', 'localtime')
+              WHERE id = NEW.producto_id AND NEW.variante_id IS NULL;
+
+              INSERT INTO movimientos_inventario (
+                producto_id, variante_id, talla, tipo_movimiento,
+                cantidad, stock_anterior, stock_nuevo, devolucion_id, usuario_id, motivo
+              ) VALUES (
+                NEW.producto_id, NEW.variante_id, NEW.talla, 'CUARENTENA', NEW.cantidad_devuelta,
+                CASE WHEN NEW.variante_id IS NOT NULL
+                  THEN (SELECT stock_cuarentena - NEW.cantidad_devuelta FROM producto_variantes WHERE id = NEW.variante_id)
+                  ELSE (SELECT stock_cuarentena - NEW.cantidad_devuelta FROM productos WHERE id = NEW.producto_id) END,
+                CASE WHEN NEW.variante_id IS NOT NULL
+                  THEN (SELECT stock_cuarentena FROM producto_variantes WHERE id = NEW.variante_id)
+                  ELSE (SELECT stock_cuarentena FROM productos WHERE id = NEW.producto_id) END,
+                NEW.devolucion_id,
+                (SELECT usuario_id FROM devoluciones WHERE id = NEW.devolucion_id),
+                'Devolución - Condición: ' || NEW.condicion
+              );
+            END;
+        "#)?;
+        println!("Migracion completada: Stock en cuarentena agregado");
+    }
+
+    // 🆕 Migración: política de devolución por categoría
+    if !existe_columna(&conn, "categorias", "dias_devolucion") {
+        println!("Ejecutando migracion: Politica de devolucion por categoria...");
+        conn.execute(
+            "ALTER TABLE categorias ADD COLUMN dias_devolucion INTEGER CHECK (dias_devolucion >= 0)",
+            [],
+        )?;
+        println!("Migracion completada: Politica de devolucion agregada");
+    }
+
+    // 🆕 Migración: caja de cada devolución (IGV y reembolsos por caja, no por usuario)
+    if !existe_columna(&conn, "devoluciones", "caja_id") {
+        println!("Agregando caja_id a devoluciones...");
+        conn.execute_batch(r#"
+            ALTER TABLE devoluciones ADD COLUMN caja_id INTEGER REFERENCES cajas(id);
+            CREATE INDEX IF NOT EXISTS idx_devoluciones_caja ON devoluciones(caja_id);
+
+            UPDATE devoluciones
+            SET caja_id = (
+              SELECT c.id FROM cajas c
+              WHERE c.usuario_id = devoluciones.usuario_id
+                AND devoluciones.fecha_hora >= c.fecha_apertura
+                AND devoluciones.fecha_hora <= COALESCE(c.fecha_cierre, datetime('now', 'localtime'))
+              ORDER BY c.id DESC LIMIT 1
+            )
+            WHERE caja_id IS NULL;
+
+            DROP TRIGGER IF EXISTS trg_actualizar_caja_devolucion;
+            CREATE TRIGGER trg_actualizar_caja_devolucion
+            AFTER INSERT ON devoluciones
+            FOR EACH ROW
+            WHEN NEW.estado = 'PROCESADA' AND NEW.metodo_reembolso <> 'CAMBIO'
+            BEGIN
+              UPDATE cajas
+              SET
+                devoluciones_monto    = devoluciones_monto + NEW.monto_reembolsado,
+                devoluciones_cantidad = devoluciones_cantidad + 1,
+                ventas_efectivo       = ventas_efectivo - CASE WHEN NEW.metodo_reembolso = 'EFECTIVO' THEN NEW.monto_reembolsado ELSE 0 END
+              WHERE id = NEW.caja_id;
+            END;
+        "#)?;
+    }
+
+    // 🆕 Migración: la venta de reemplazo de un cambio suma a la caja solo la diferencia
+    if !existe_trigger(&conn, "trg_actualizar_caja_venta_cambio")? {
+        println!("Ejecutando migracion: Ventas de cambio en caja...");
+        conn.execute_batch(r#"
+            DROP TRIGGER IF EXISTS trg_actualizar_caja_venta;
+            CREATE TRIGGER trg_actualizar_caja_venta
+            AFTER INSERT ON ventas
+            FOR EACH ROW
+            WHEN NEW.estado = 'COMPLETADA' AND NEW.cambio_id IS NULL
+            BEGIN
+              UPDATE cajas
+              SET
+                total_ventas         = total_ventas         + NEW.total,
+                numero_transacciones = numero_transacciones + 1,
+                cambio_total         = cambio_total         + COALESCE(NEW.cambio, 0),
+                redondeo_total       = redondeo_total       + COALESCE(NEW.redondeo, 0),
+                ticket_promedio      = CASE WHEN numero_transacciones + 1 > 0
+                                       THEN (total_ventas + NEW.total) / (numero_transacciones + 1) ELSE 0 END
+              WHERE id = NEW.caja_id;
+            END;
+
+            CREATE TRIGGER trg_actualizar_caja_venta_cambio
+            AFTER INSERT ON ventas
+            FOR EACH ROW
+            WHEN NEW.estado = 'COMPLETADA' AND NEW.cambio_id IS NOT NULL
+                 AND (SELECT diferencia FROM cambios WHERE id = NEW.cambio_id) > 0
+            BEGIN
+              UPDATE cajas
+              SET
+                total_ventas         = total_ventas         + (SELECT diferencia FROM cambios WHERE id = NEW.cambio_id),
+                numero_transacciones = numero_transacciones + 1,
+                cambio_total         = cambio_total         + COALESCE(NEW.cambio, 0),
+                redondeo_total       = redondeo_total       + COALESCE(NEW.redondeo, 0),
+                ticket_promedio      = (total_ventas + (SELECT diferencia FROM cambios WHERE id = NEW.cambio_id))
+                                       / (numero_transacciones + 1)
+              WHERE id = NEW.caja_id;
+            END;
+
+            -- Cambios ya registrados: se quita lo pagado con la prenda devuelta
+            -- y la transacción de los cambios sin diferencia cobrada
+            UPDATE cajas
+            SET
+              total_ventas = total_ventas - COALESCE((
+                SELECT SUM(MIN(c.monto_nuevo, c.monto_devuelto))
+                FROM ventas v JOIN cambios c ON c.id = v.cambio_id
+                WHERE v.caja_id = cajas.id AND v.estado = 'COMPLETADA'), 0),
+              numero_transacciones = numero_transacciones - (
+                SELECT COUNT(*)
+                FROM ventas v JOIN cambios c ON c.id = v.cambio_id
+                WHERE v.caja_id = cajas.id AND v.estado = 'COMPLETADA' AND c.diferencia <= 0)
+            WHERE id IN (SELECT caja_id FROM ventas WHERE cambio_id IS NOT NULL);
+
+            UPDATE cajas
+            SET ticket_promedio = CASE WHEN numero_transacciones > 0
+                                  THEN total_ventas / numero_transacciones ELSE 0 END
+            WHERE id IN (SELECT caja_id FROM ventas WHERE cambio_id IS NOT NULL);
+        "#)?;
+    }
+
     println!("Base de datos actualizada");
     Ok(())
 }
 
+// SQLite no permite modificar un CHECK: se crea `{tabla}_nueva` con el esquema
+// actualizado, se copian las columnas en común y se reemplaza la tabla original.
+// Los índices y triggers de la tabla se pierden con el DROP, así que se guardan
+// antes y se vuelven a crear; `extras_sql` queda para lo que haya que agregar.
+fn reconstruir_tabla(conn: &Connection, tabla: &str, create_sql: &str, extras_sql: &str) -> Result<()> {
+    let columnas_viejas = columnas_de(conn, tabla)?;
+
+    let dependientes: Vec<String> = {
+        let mut stmt = conn.prepare(
+            "SELECT sql FROM sqlite_master
+             WHERE tbl_name = ? AND type IN ('index', 'trigger') AND sql IS NOT NULL",
+        )?;
+        let sql = stmt
+            .query_map([tabla], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>>>()?;
+        sql
+    };
+
+    conn.execute_batch("PRAGMA foreign_keys = OFF; PRAGMA legacy_alter_table = ON; BEGIN;")?;
+
+    let resultado = (|| -> Result<()> {
+        conn.execute_batch(create_sql)?;
+        let nueva = format!("{}_nueva", tabla);
+        let comunes: Vec<String> = columnas_de(conn, &nueva)?
+            .into_iter()
+            .filter(|c| columnas_viejas.contains(c))
+            .collect();
+        let lista = comunes.join(", ");
+        conn.execute_batch(&format!(
+            "INSERT INTO {nueva} ({lista}) SELECT {lista} FROM {tabla};
+             DROP TABLE {tabla};
+             ALTER TABLE {nueva} RENAME TO {tabla};"
+        ))?;
+        for sql in &dependientes {
+            conn.execute_batch(sql)?;
+        }
+        conn.execute_batch(extras_sql)
+    })();
+
+    match resultado {
+        Ok(()) => conn.execute_batch("COMMIT; PRAGMA legacy_alter_table = OFF;"),
+        Err(e) => {
+            let _ = conn.execute_batch("ROLLBACK; PRAGMA legacy_alter_table = OFF;");
+            Err(e)
+        }
+    }
+}
+
+fn columnas_de(conn: &Connection, tabla: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT name FROM pragma_table_info(?)")?;
+    let columnas = stmt
+        .query_map([tabla], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(columnas)
+}
+
+// ¿El CREATE TABLE guardado contiene el texto? (para saber si un CHECK ya fue ampliado)
+fn check_incluye(conn: &Connection, tabla: &str, texto: &str) -> bool {
+    conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name=? AND instr(sql, ?) > 0",
+        [tabla, texto],
+        |row| Ok(row.get::<_, i32>(0)? > 0),
+    )
+    .unwrap_or(false)
+}
+
+fn existe_tabla(conn: &Connection, tabla: &str) -> Result<bool> {
+    conn.prepare("SELECT name FROM sqlite_master WHERE type='table' AND name=?")?
+        .exists([tabla])
+}
+
+fn existe_trigger(conn: &Connection, trigger: &str) -> Result<bool> {
+    conn.prepare("SELECT name FROM sqlite_master WHERE type='trigger' AND name=?")?
+        .exists([trigger])
+}
+
+fn existe_columna(conn: &Connection, tabla: &str, columna: &str) -> bool {
+    conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info(?) WHERE name=?",
+        [tabla, columna],
+        |row| Ok(row.get::<_, i32>(0)? > 0),
+    )
+    .unwrap_or(false)
+}
+
 pub fn default_database_path() -> String {
     get_database_path().to_str().unwrap().to_string()
 }
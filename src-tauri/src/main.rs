@@ -65,6 +65,82 @@ fn main() {
 
             // Ventas
             procesar_venta,
+            anular_venta,
+            obtener_venta_detalle,
+
+            // Ventas en espera
+            suspender_venta,
+            obtener_ventas_suspendidas,
+            recuperar_venta_suspendida,
+            descartar_venta_suspendida,
+
+            // Cotizaciones
+            crear_cotizacion,
+            obtener_cotizaciones,
+            obtener_cotizacion,
+            anular_cotizacion,
+            cargar_cotizacion,
+
+            // Promociones
+            obtener_promociones,
+            agregar_promocion,
+            actualizar_promocion,
+            evaluar_promociones,
+
+            // Cupones
+            obtener_cupones,
+            agregar_cupon,
+            actualizar_cupon,
+            validar_cupon,
+
+            // Clientes
+            buscar_clientes,
+            obtener_cliente,
+            agregar_cliente,
+            actualizar_cliente,
+            historial_cliente,
+            validar_documento_identidad,
+
+            // Padrón RUC
+            importar_padron_ruc,
+            consultar_ruc,
+            obtener_estado_padron,
+
+            // Ventas al crédito / cuentas por cobrar
+            registrar_pago_cliente,
+            obtener_cuentas_por_cobrar,
+            obtener_antiguedad_saldos,
+            obtener_estado_cuenta_cliente,
+
+            // Listas de precios (minorista / mayorista)
+            obtener_listas_precios,
+            agregar_lista_precios,
+            actualizar_lista_precios,
+            obtener_precios_lista,
+            guardar_precio_lista,
+            eliminar_precio_lista,
+            resolver_precios_carrito,
+
+            // Puntos
+            obtener_configuracion_puntos,
+            actualizar_configuracion_puntos,
+            obtener_saldo_puntos,
+            obtener_estado_cuenta_puntos,
+
+            // Vales / tarjetas de regalo
+            vender_vale,
+            obtener_vale,
+            obtener_vales,
+
+            // Facturación electrónica
+            obtener_configuracion_facturacion,
+            actualizar_configuracion_facturacion,
+            emitir_comprobante,
+            emitir_nota_credito,
+            enviar_comprobante,
+            obtener_comprobante_venta,
+            obtener_comprobantes_pendientes,
+            obtener_xml_comprobante,
 
             // Reportes
             obtener_ventas_rango,
@@ -72,6 +148,9 @@ fn main() {
             obtener_estadisticas_ventas,
             obtener_ventas_hoy,
             obtener_estadisticas_con_devoluciones,
+            obtener_reporte_cupones,
+            obtener_reporte_vales,
+            obtener_resumen_comprobantes,
 
             // Configuración
             obtener_configuracion_tienda,
@@ -82,10 +161,31 @@ fn main() {
             obtener_roles,
             agregar_usuario,
             actualizar_usuario,
+            obtener_numeracion_documentos,
+            actualizar_numeracion_documento,
+            obtener_series_comprobante,
+            crear_serie_comprobante,
+            actualizar_serie_comprobante,
 
             // Devoluciones de clientes
             buscar_venta_para_devolucion,
             procesar_devolucion,
+            obtener_notas_credito_venta,
+            obtener_nota_credito,
+
+            // Cambios de talla
+            procesar_cambio,
+            obtener_cambio,
+
+            // Stock en cuarentena
+            obtener_stock_cuarentena,
+            registrar_merma_cuarentena,
+
+            // Vendedores y comisiones
+            obtener_reglas_comision,
+            agregar_regla_comision,
+            actualizar_regla_comision,
+            obtener_reporte_comisiones,
 
             // Licencias
             commands::licencias::obtener_estado_licencia,
@@ -126,6 +226,9 @@ fn main() {
 
             // 🆕 Impresora
             imprimir_boleta,
+            imprimir_nota_credito,
+            imprimir_cotizacion,
+            reimprimir_boleta,
             probar_impresora,
         ])
         .run(tauri::generate_context!())
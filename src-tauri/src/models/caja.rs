@@ -48,6 +48,7 @@ pub struct Caja {
     pub ingresos_total: f64,
     pub gastos_total: f64,
     pub cambio_total: f64,
+    pub redondeo_total: f64, // 🆕 redondeo del efectivo a S/ 0.10
     
     // Diferencia
     pub efectivo_esperado: Option<f64>,
@@ -154,6 +155,7 @@ pub struct ReporteCierreCaja {
     pub movimientos: Vec<MovimientoCaja>,
     pub resumen_puntualidad: ResumenPuntualidad,
     pub resumen_financiero: ResumenFinanciero,
+    pub resumen_impuestos: ResumenImpuestos, // 🆕
 }
 
 // =====================================================
@@ -180,6 +182,19 @@ pub struct ResumenFinanciero {
     pub porcentaje_diferencia: f64,
 }
 
+// =====================================================
+// 🆕 MODELO: Resumen de IGV del turno (ventas menos devoluciones)
+// =====================================================
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResumenImpuestos {
+    pub op_gravada: f64,
+    pub op_exonerada: f64,
+    pub op_inafecta: f64,
+    pub igv_ventas: f64,
+    pub igv_devoluciones: f64,
+    pub igv_neto: f64,
+}
+
 // =====================================================
 // RESPONSE: Caja Response
 // =====================================================
@@ -0,0 +1,57 @@
+// models/cambio.rs
+// Cambio de talla: devolución + venta de reemplazo en un solo documento
+
+use serde::{Deserialize, Serialize};
+
+// =====================================================
+// REQUEST: Cambio de talla
+// =====================================================
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LineaCambioDevuelta {
+    pub detalle_id: i32, // línea de la venta original
+    pub cantidad: i32,
+    pub condicion: Option<String>, // 🆕 REVENTA (por defecto) | DEFECTUOSO | VENCIDO
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LineaCambioNueva {
+    pub producto_id: i32,
+    pub variante_id: Option<i32>, // la talla que se lleva
+    pub cantidad: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CambioRequest {
+    pub venta_id: i32,
+    pub devueltos: Vec<LineaCambioDevuelta>,
+    pub nuevos: Vec<LineaCambioNueva>,
+    // Diferencia a favor de la tienda: EFECTIVO | TARJETA | TRANSFERENCIA
+    // Diferencia a favor del cliente: EFECTIVO | VALE
+    pub metodo_diferencia: Option<String>, // sin valor = EFECTIVO
+    pub monto_recibido: Option<f64>,       // solo EFECTIVO
+    pub referencia: Option<String>,        // voucher / N° de operación
+    pub motivo: Option<String>,
+    pub vendedor_id: Option<i32>,          // 🆕 sin valor = vendedor de la venta original
+    pub motivo_excepcion: Option<String>,  // 🆕 prendas fuera de la política de devolución
+}
+
+// =====================================================
+// RESULTADO
+// =====================================================
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CambioResult {
+    pub cambio_id: i32,
+    pub folio: String,               // CAM-20260301-0001
+    pub folio_devolucion: String,
+    pub venta_id: i32,               // venta de reemplazo
+    pub folio_venta: String,
+    pub numero_comprobante: String,
+    pub monto_devuelto: f64,
+    pub monto_nuevo: f64,
+    pub diferencia: f64,             // > 0 pagó el cliente, < 0 se le devolvió
+    pub metodo_diferencia: Option<String>,
+    pub cambio: Option<f64>,         // vuelto del efectivo recibido
+    pub redondeo: f64,
+    pub codigo_vale: Option<String>, // diferencia devuelta en vale
+    pub nota_credito: Option<String>,
+}
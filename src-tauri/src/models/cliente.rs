@@ -0,0 +1,117 @@
+// models/cliente.rs
+// Modelo de Cliente e historial de compras
+
+use serde::{Deserialize, Serialize};
+
+// =====================================================
+// MODELO PRINCIPAL: Cliente
+// =====================================================
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Cliente {
+    pub id: i32,
+    pub tipo_documento: String, // DNI | RUC | CE
+    pub numero_documento: String,
+    pub nombre: String,
+    pub direccion: Option<String>, // 🆕 domicilio fiscal
+    pub telefono: Option<String>,
+    pub email: Option<String>,
+    pub fecha_nacimiento: Option<String>,
+    pub tallas_preferidas: Option<String>,
+    pub notas: Option<String>,
+    pub limite_credito: f64, // 🆕 0 = no compra al crédito
+    pub dias_credito: i32,   // 🆕 plazo de pago de las ventas al crédito
+    pub lista_precio_id: Option<i32>, // 🆕 None = lista predeterminada
+    pub activo: bool,
+    pub fecha_registro: String,
+}
+
+// =====================================================
+// REQUEST: Nuevo cliente / edición
+// =====================================================
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClienteNuevo {
+    pub tipo_documento: String,
+    pub numero_documento: String,
+    pub nombre: String, // 🆕 con RUC vacío = se toma del padrón
+    pub direccion: Option<String>,
+    pub telefono: Option<String>,
+    pub email: Option<String>,
+    pub fecha_nacimiento: Option<String>,
+    pub tallas_preferidas: Option<String>,
+    pub notas: Option<String>,
+    pub limite_credito: Option<f64>, // 🆕 sin valor = se mantiene (0 al crear)
+    pub dias_credito: Option<i32>,   // 🆕 sin valor = se mantiene (30 al crear)
+    pub lista_precio_id: Option<i32>, // 🆕 None = lista predeterminada
+    pub activo: Option<bool>,
+}
+
+// =====================================================
+// 🆕 PADRÓN RUC (copia local del padrón reducido de SUNAT)
+// =====================================================
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Contribuyente {
+    pub ruc: String,
+    pub razon_social: String,
+    pub estado: Option<String>,    // ACTIVO, BAJA DE OFICIO, ...
+    pub condicion: Option<String>, // HABIDO, NO HALLADO, ...
+    pub ubigeo: Option<String>,
+    pub direccion: Option<String>,
+    pub fecha_actualizacion: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EstadoPadron {
+    pub registros: i64,
+    pub fecha_actualizacion: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResultadoImportacionPadron {
+    pub importados: i64,
+    pub omitidos: i64, // líneas con RUC inválido o incompletas
+    pub registros: i64,
+}
+
+// =====================================================
+// HISTORIAL DEL CLIENTE
+// =====================================================
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ArticuloComprado {
+    pub producto_id: i32,
+    pub producto_nombre: String,
+    pub talla: Option<String>,
+    pub cantidad: i32,
+    pub total_linea: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CompraCliente {
+    pub venta_id: i32,
+    pub folio: String,
+    pub fecha_hora: String,
+    pub total: f64,
+    pub metodo_pago: String,
+    pub estado: String,
+    pub articulos: Vec<ArticuloComprado>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DevolucionCliente {
+    pub devolucion_id: i32,
+    pub folio_devolucion: String,
+    pub folio_venta: String,
+    pub fecha_hora: String,
+    pub monto_reembolsado: f64,
+    pub motivo: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HistorialCliente {
+    pub cliente: Cliente,
+    pub compras: Vec<CompraCliente>,
+    pub devoluciones: Vec<DevolucionCliente>,
+    pub numero_compras: i32,
+    pub total_comprado: f64,
+    pub total_devuelto: f64,
+    pub ultima_compra: Option<String>,
+}
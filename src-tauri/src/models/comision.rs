@@ -0,0 +1,64 @@
+// models/comision.rs
+// Vendedores: reglas de comisión y reporte por periodo
+
+use serde::{Deserialize, Serialize};
+
+// =====================================================
+// MODELO PRINCIPAL: Regla de comisión
+// =====================================================
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReglaComision {
+    pub id: i32,
+    pub nombre: String,
+    pub tipo: String, // PORCENTAJE | BONO_META
+    pub categoria_id: Option<i32>,
+    pub categoria_nombre: Option<String>,
+    pub vendedor_id: Option<i32>,
+    pub vendedor_nombre: Option<String>,
+    pub porcentaje: f64,   // BONO_META: % sobre lo que pasa de la meta
+    pub meta: Option<f64>, // BONO_META: ventas netas del periodo
+    pub bono: f64,         // BONO_META: monto fijo
+    pub activo: bool,
+}
+
+// =====================================================
+// REQUEST: Nueva regla / edición
+// =====================================================
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReglaComisionNueva {
+    pub nombre: String,
+    pub tipo: String,
+    pub categoria_id: Option<i32>,
+    pub vendedor_id: Option<i32>,
+    pub porcentaje: Option<f64>,
+    pub meta: Option<f64>,
+    pub bono: Option<f64>,
+    pub activo: Option<bool>,
+}
+
+// =====================================================
+// REPORTE DE COMISIONES POR PERIODO
+// =====================================================
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ComisionVendedor {
+    pub vendedor_id: i32,
+    pub vendedor_nombre: String,
+    pub numero_ventas: i32,
+    pub ventas: f64,       // base de las líneas vendidas
+    pub devoluciones: f64, // base revertida por devoluciones y anulaciones
+    pub ventas_netas: f64,
+    pub comision: f64,     // por porcentaje, ya neta de reversiones
+    pub meta: Option<f64>, // meta alcanzada (la más alta), si hay
+    pub bono: f64,
+    pub total: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReporteComisiones {
+    pub fecha_inicio: String,
+    pub fecha_fin: String,
+    pub vendedores: Vec<ComisionVendedor>,
+    pub total_comisiones: f64,
+    pub total_bonos: f64,
+    pub total: f64,
+}
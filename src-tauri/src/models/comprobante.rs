@@ -0,0 +1,81 @@
+// models/comprobante.rs
+// Modelos de comprobantes electrónicos (SUNAT)
+
+use serde::{Deserialize, Serialize};
+
+// =====================================================
+// CONFIGURACIÓN (columnas en configuracion_tienda)
+// =====================================================
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConfiguracionFacturacion {
+    pub ruc: String,                     // configuracion_tienda.rfc
+    pub razon_social: String,
+    pub nombre_comercial: Option<String>,
+    pub direccion: String,
+    pub ubigeo: String,                  // código INEI del domicilio fiscal, ej. 150101
+    pub certificado_ruta: Option<String>, // archivo .pfx / .p12
+    pub certificado_clave: Option<String>,
+    pub usuario_sol: Option<String>,
+    pub clave_sol: Option<String>,
+    pub endpoint: String,                // SUNAT, OSE o un OSE de prueba local
+}
+
+// =====================================================
+// MODELO PRINCIPAL: Comprobante electrónico
+// =====================================================
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ComprobanteElectronico {
+    pub id: i32,
+    pub venta_id: i32,
+    pub nota_credito_id: Option<i32>, // 🆕 solo tipo 07
+    pub tipo_comprobante: String, // 01 factura | 03 boleta | 07 nota de crédito
+    pub serie: String,
+    pub correlativo: i64,
+    pub numero: String,           // F001-00000001
+    pub nombre_archivo: String,   // RUC-TIPO-SERIE-CORRELATIVO (sin extensión)
+    pub hash: Option<String>,     // DigestValue de la firma, va impreso en el comprobante
+    pub estado: String,           // FIRMADO | ACEPTADO | OBSERVADO | RECHAZADO | ERROR
+    pub codigo_respuesta: Option<String>,
+    pub descripcion_respuesta: Option<String>,
+    pub observaciones: Option<String>,
+    pub intentos_envio: i32,
+    pub fecha_emision: String,
+    pub fecha_envio: Option<String>,
+}
+
+// =====================================================
+// 🆕 NOTA DE CRÉDITO (generada por una devolución)
+// =====================================================
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NotaCredito {
+    pub id: i32,
+    pub devolucion_id: Option<i32>,
+    pub venta_id: i32,
+    pub numero: String,               // BC01-00000001
+    pub tipo_documento_ref: String,   // BOLETA | FACTURA
+    pub numero_ref: String,           // B001-00000123
+    pub codigo_motivo: String,        // catálogo 09
+    pub descripcion_motivo: String,   // ej. Devolución por ítem
+    pub sustento: String,
+    pub op_gravada: f64,
+    pub op_exonerada: f64,
+    pub op_inafecta: f64,
+    pub igv: f64,
+    pub igv_porcentaje: f64,
+    pub total: f64,
+    pub fecha_emision: String,
+    pub detalles: Vec<DetalleNotaCredito>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DetalleNotaCredito {
+    pub detalle_venta_id: i32,
+    pub producto_id: i32,
+    pub codigo: String,
+    pub descripcion: String, // nombre + talla
+    pub cantidad: i32,
+    pub total_linea: f64,
+    pub valor_venta: f64,
+    pub igv: f64,
+    pub afectacion_igv: String,
+}
@@ -0,0 +1,110 @@
+// models/credito.rs
+// Ventas al crédito (fiado): cuentas por cobrar, cobros y estado de cuenta
+
+use serde::{Deserialize, Serialize};
+
+// =====================================================
+// CUENTA POR COBRAR (una por venta al crédito)
+// =====================================================
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CuentaPorCobrar {
+    pub venta_id: i32,
+    pub folio: String,
+    pub cliente_id: i32,
+    pub cliente_nombre: String,
+    pub fecha_venta: String,
+    pub fecha_vencimiento: Option<String>,
+    pub total_venta: f64,
+    pub monto_credito: f64,  // parte de la venta que quedó al crédito
+    pub monto_cobrado: f64,  // cobros + devoluciones descontadas
+    pub saldo_pendiente: f64,
+    pub estado_pago: String, // PENDIENTE | PARCIAL | PAGADO
+    pub dias_vencido: i64,   // 0 si todavía no vence
+}
+
+// =====================================================
+// REQUEST: Cobro al cliente
+// =====================================================
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RegistrarPagoClienteRequest {
+    pub cliente_id: i32,
+    pub monto: f64,
+    pub metodo_pago: String, // EFECTIVO | TARJETA | TRANSFERENCIA
+    // Sin venta: se aplica a las deudas más antiguas primero
+    pub venta_id: Option<i32>,
+    pub referencia: Option<String>,
+    pub notas: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AplicacionPago {
+    pub venta_id: i32,
+    pub folio_venta: String,
+    pub monto: f64,
+    pub saldo_restante: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResultadoPagoCliente {
+    pub folio: String, // recibo de cobro
+    pub monto: f64,
+    pub aplicaciones: Vec<AplicacionPago>,
+    pub saldo_cliente: f64,
+}
+
+// =====================================================
+// REPORTE DE ANTIGÜEDAD DE SALDOS
+// =====================================================
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct AntiguedadSaldos {
+    pub corriente: f64,  // todavía no vence
+    pub dias_1_30: f64,
+    pub dias_31_60: f64,
+    pub dias_61_90: f64,
+    pub mas_de_90: f64,
+    pub total: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AntiguedadCliente {
+    pub cliente_id: i32,
+    pub cliente_nombre: String,
+    pub numero_documento: String,
+    pub limite_credito: f64,
+    pub saldos: AntiguedadSaldos,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReporteAntiguedad {
+    pub fecha_corte: String,
+    pub clientes: Vec<AntiguedadCliente>,
+    pub totales: AntiguedadSaldos,
+}
+
+// =====================================================
+// ESTADO DE CUENTA DEL CLIENTE
+// =====================================================
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MovimientoCuenta {
+    pub fecha: String,
+    pub tipo: String, // VENTA | COBRO | DEVOLUCION
+    pub folio: String,
+    pub folio_venta: String,
+    pub descripcion: Option<String>,
+    pub cargo: f64,
+    pub abono: f64,
+    pub saldo: f64, // saldo acumulado después del movimiento
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EstadoCuentaCliente {
+    pub cliente_id: i32,
+    pub cliente_nombre: String,
+    pub limite_credito: f64,
+    pub dias_credito: i32,
+    pub saldo_anterior: f64, // antes de fecha_desde
+    pub movimientos: Vec<MovimientoCuenta>,
+    pub saldo_actual: f64,
+    pub saldo_vencido: f64,
+    pub credito_disponible: f64,
+}
@@ -0,0 +1,61 @@
+// models/cupon.rs
+// Modelos de cupones / códigos de descuento
+
+use serde::{Deserialize, Serialize};
+
+// =====================================================
+// MODELO PRINCIPAL: Cupón
+// =====================================================
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Cupon {
+    pub id: i32,
+    pub codigo: String,
+    pub campana: Option<String>,
+    pub tipo_descuento: String, // PORCENTAJE | MONTO
+    pub valor: f64,
+    pub monto_minimo: f64,
+    pub categoria_id: Option<i32>,
+    pub limite_usos: Option<i32>,
+    pub limite_por_cliente: Option<i32>,
+    pub fecha_inicio: Option<String>,
+    pub fecha_expiracion: Option<String>,
+    pub activo: bool,
+    pub usos: i32, // ventas no anuladas que lo usaron
+}
+
+// =====================================================
+// REQUEST: Nuevo cupón / edición
+// =====================================================
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CuponNuevo {
+    pub codigo: String,
+    pub campana: Option<String>,
+    pub tipo_descuento: String,
+    pub valor: f64,
+    pub monto_minimo: Option<f64>,
+    pub categoria_id: Option<i32>,
+    pub limite_usos: Option<i32>,
+    pub limite_por_cliente: Option<i32>,
+    pub fecha_inicio: Option<String>,
+    pub fecha_expiracion: Option<String>,
+    pub activo: Option<bool>,
+}
+
+// =====================================================
+// RESULTADO: Cupón aplicado al carrito
+// =====================================================
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DescuentoCuponLinea {
+    pub indice: usize,
+    pub descuento: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CuponAplicado {
+    pub cupon_id: i32,
+    pub codigo: String,
+    pub campana: Option<String>,
+    pub descuento: f64,
+    pub total_con_cupon: f64,
+    pub lineas: Vec<DescuentoCuponLinea>,
+}
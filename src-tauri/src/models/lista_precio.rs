@@ -0,0 +1,53 @@
+// models/lista_precio.rs
+// Listas de precios (minorista, mayorista, ...) con escalas por cantidad
+
+use serde::{Deserialize, Serialize};
+
+// =====================================================
+// MODELO PRINCIPAL: Lista de precios
+// =====================================================
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ListaPrecios {
+    pub id: i32,
+    pub nombre: String,
+    pub descripcion: Option<String>,
+    pub predeterminada: bool, // se usa si el cliente no tiene lista
+    pub activo: bool,
+    pub precios: i32,  // filas de precio (producto/talla/escala)
+    pub clientes: i32, // clientes con la lista asignada
+}
+
+// =====================================================
+// REQUEST: Nueva lista / edición
+// =====================================================
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListaPreciosNueva {
+    pub nombre: String,
+    pub descripcion: Option<String>,
+    pub predeterminada: Option<bool>,
+    pub activo: Option<bool>,
+}
+
+// =====================================================
+// PRECIO DE UN PRODUCTO EN LA LISTA
+// =====================================================
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PrecioLista {
+    pub id: i32,
+    pub lista_id: i32,
+    pub producto_id: i32,
+    pub producto_nombre: String,
+    pub variante_id: Option<i32>, // None = todas las tallas
+    pub talla: Option<String>,
+    pub cantidad_minima: i32,     // 1 = precio normal; 6 = desde 6 unidades
+    pub precio: f64,
+    pub precio_base: f64,         // productos.precio, como referencia
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PrecioListaNuevo {
+    pub producto_id: i32,
+    pub variante_id: Option<i32>,
+    pub cantidad_minima: Option<i32>, // sin valor = 1
+    pub precio: f64,
+}
@@ -6,8 +6,19 @@ pub mod producto;
 pub mod venta;
 pub mod caja;
 pub mod proveedor; // 🆕
+pub mod promocion;
+pub mod cupon;
+pub mod cliente;
+pub mod puntos;
+pub mod vale;
+pub mod numeracion;
+pub mod comprobante;
+pub mod credito;
+pub mod lista_precio;
+pub mod cambio;
+pub mod comision;
 
-pub use usuario::{Usuario, UsuarioLogin, UsuarioResponse};
+pub use usuario::{Usuario, UsuarioLogin, UsuarioResponse, AutorizacionSupervisor};
 pub use producto::{Producto, ProductoNuevo, ProductoResponse, ProductosResponse};
 pub use venta::{Venta, DetalleVenta, VentaNueva, VentaResponse};
 pub use caja::{
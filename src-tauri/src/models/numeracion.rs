@@ -0,0 +1,39 @@
+// models/numeracion.rs
+// Modelos de la numeración de documentos (folios)
+
+use serde::{Deserialize, Serialize};
+
+// =====================================================
+// CONFIGURACIÓN POR TIPO DE DOCUMENTO
+// =====================================================
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NumeracionDocumento {
+    pub tipo_documento: String, // VENTA | DEVOLUCION | COMPRA | DEVOLUCION_PROVEEDOR | COTIZACION
+    pub descripcion: Option<String>,
+    pub formato: String,        // ej. V-{YYYY}{MM}{DD}-{####}
+    pub reinicio: String,       // DIARIO | MENSUAL | NUNCA
+    pub ultimo_numero: i64,     // último número asignado en el periodo actual
+    pub proximo_folio: String,  // vista previa del siguiente folio
+}
+
+// =====================================================
+// 🆕 SERIES DE COMPROBANTE POR CAJA
+// =====================================================
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SerieComprobante {
+    pub id: i32,
+    pub tipo_comprobante: String, // BOLETA | FACTURA | NOTA_VENTA | NOTA_CREDITO_BOLETA | NOTA_CREDITO_FACTURA
+    pub serie: String,            // B001, F001, NV01, BC01, FC01
+    pub numero_caja: i32,
+    pub ultimo_correlativo: i64,
+    pub proximo_numero: String,   // vista previa, ej. B001-00000124
+    pub activo: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SerieComprobanteNueva {
+    pub tipo_comprobante: String,
+    pub serie: String,
+    pub numero_caja: i32,
+    pub ultimo_correlativo: Option<i64>, // para continuar una serie usada en otro sistema
+}
@@ -20,6 +20,7 @@ pub struct Producto {
     pub descuento_porcentaje: f64,
     pub tiene_variantes: bool,  // 🆕
     pub activo: bool,
+    pub afectacion_igv: String, // 🆕 GRAVADO | EXONERADO | INAFECTO
 }
 
 // =====================================================
@@ -60,6 +61,7 @@ pub struct ProductoNuevo {
     pub descuento_porcentaje: Option<f64>,
     pub tiene_variantes: Option<bool>,      // 🆕
     pub variantes: Option<Vec<VarianteInput>>, // 🆕
+    pub afectacion_igv: Option<String>,        // 🆕 GRAVADO por defecto
 }
 
 // =====================================================
@@ -85,4 +87,16 @@ pub struct ProductosResponse {
 pub struct ProductoConVariantes {
     pub producto: Producto,
     pub variantes: Vec<ProductoVariante>,
-}
\ No newline at end of file
+}
+// =====================================================
+// 🆕 RESPONSE: Stock en cuarentena (devuelto defectuoso o vencido)
+// =====================================================
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StockCuarentena {
+    pub producto_id: i32,
+    pub codigo: String,
+    pub nombre: String,
+    pub variante_id: Option<i32>,
+    pub talla: Option<String>,
+    pub cantidad: i32,
+}
@@ -0,0 +1,83 @@
+// models/promocion.rs
+// Modelos del motor de promociones
+
+use serde::{Deserialize, Serialize};
+
+// =====================================================
+// MODELO PRINCIPAL: Promoción
+// =====================================================
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Promocion {
+    pub id: i32,
+    pub nombre: String,
+    pub tipo: String, // NXM | SEGUNDA_UNIDAD | DESCUENTO_CATEGORIA | DESCUENTO_MONTO
+    pub lleva: Option<i32>,
+    pub paga: Option<i32>,
+    pub porcentaje: Option<f64>,
+    pub monto_minimo: Option<f64>,
+    pub monto_descuento: Option<f64>,
+    pub categoria_id: Option<i32>,
+    pub producto_id: Option<i32>,
+    pub fecha_inicio: Option<String>,
+    pub fecha_fin: Option<String>,
+    pub dias_semana: Option<String>,
+    pub hora_inicio: Option<String>,
+    pub hora_fin: Option<String>,
+    pub prioridad: i32,
+    pub acumulable: bool,
+    pub activo: bool,
+}
+
+// =====================================================
+// REQUEST: Nueva promoción / edición
+// =====================================================
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PromocionNueva {
+    pub nombre: String,
+    pub tipo: String,
+    pub lleva: Option<i32>,
+    pub paga: Option<i32>,
+    pub porcentaje: Option<f64>,
+    pub monto_minimo: Option<f64>,
+    pub monto_descuento: Option<f64>,
+    pub categoria_id: Option<i32>,
+    pub producto_id: Option<i32>,
+    pub fecha_inicio: Option<String>,
+    pub fecha_fin: Option<String>,
+    pub dias_semana: Option<String>,
+    pub hora_inicio: Option<String>,
+    pub hora_fin: Option<String>,
+    pub prioridad: Option<i32>,
+    pub acumulable: Option<bool>,
+    pub activo: Option<bool>,
+}
+
+// =====================================================
+// RESULTADO: Promociones aplicadas a un carrito
+// =====================================================
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PromocionAplicada {
+    pub promocion_id: i32,
+    pub nombre: String,
+    pub descuento: f64,
+}
+
+// Una entrada por línea del carrito, en el mismo orden en que llegaron
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LineaPromocion {
+    pub indice: usize,
+    pub subtotal: f64,
+    pub descuento_manual: f64,
+    pub descuento_promocion: f64,
+    pub total_linea: f64,
+    pub promociones: Vec<PromocionAplicada>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ResultadoPromociones {
+    pub lineas: Vec<LineaPromocion>,
+    pub subtotal: f64,
+    pub descuento_manual: f64,
+    pub descuento_promociones: f64,
+    pub total: f64,
+}
@@ -107,6 +107,7 @@ pub struct NuevaCompraRequest {
     pub factura_numero: Option<String>,
     pub notas: Option<String>,
     pub items: Vec<ItemCompra>,
+    pub clave_idempotencia: Option<String>, // 🆕 reintento = misma compra
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -142,6 +143,7 @@ pub struct RegistrarPagoRequest {
     pub metodo_pago: String,
     pub referencia: Option<String>,
     pub notas: Option<String>,
+    pub clave_idempotencia: Option<String>, // 🆕 reintento = mismo pago
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -212,6 +214,7 @@ pub struct DetalleDevolucionProveedor {
     pub precio_compra: f64,
     pub subtotal: f64,
     pub motivo_item: Option<String>,
+    pub desde_cuarentena: bool, // 🆕
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -232,6 +235,7 @@ pub struct ItemDevolucionProveedor {
     pub cantidad_devuelta: i32,
     pub precio_compra: f64,
     pub motivo_item: Option<String>,
+    pub desde_cuarentena: Option<bool>, // 🆕 sale del stock en cuarentena
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -0,0 +1,38 @@
+// models/puntos.rs
+// Modelos del programa de puntos
+
+use serde::{Deserialize, Serialize};
+
+// =====================================================
+// CONFIGURACIÓN (columnas en configuracion_tienda)
+// =====================================================
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConfiguracionPuntos {
+    pub puntos_por_sol: f64,
+    pub valor_punto: f64,
+    pub excluir_descuentos: bool,
+    pub meses_vencimiento: i32,
+}
+
+// =====================================================
+// SALDO Y ESTADO DE CUENTA
+// =====================================================
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SaldoPuntos {
+    pub cliente_id: i32,
+    pub puntos: i64,
+    pub valor_soles: f64,
+    pub por_vencer_30_dias: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MovimientoPuntos {
+    pub id: i32,
+    pub tipo: String, // ACUMULACION | CANJE | REVERSION | VENCIMIENTO | AJUSTE
+    pub puntos: i64,
+    pub saldo: i64,   // saldo acumulado después del movimiento
+    pub folio_venta: Option<String>,
+    pub fecha_vencimiento: Option<String>,
+    pub descripcion: Option<String>,
+    pub fecha_hora: String,
+}
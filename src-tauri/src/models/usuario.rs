@@ -19,6 +19,13 @@ pub struct UsuarioLogin {
     pub password: String,
 }
 
+// 🆕 Credenciales de un administrador que autoriza una operación del cajero
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AutorizacionSupervisor {
+    pub username: String,
+    pub password: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UsuarioResponse {
     pub success: bool,
@@ -0,0 +1,55 @@
+// models/vale.rs
+// Modelos de vales: tarjetas de regalo y vales por devolución
+
+use serde::{Deserialize, Serialize};
+
+// =====================================================
+// MODELO PRINCIPAL: Vale
+// =====================================================
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Vale {
+    pub id: i32,
+    pub codigo: String,
+    pub origen: String, // VENTA (tarjeta de regalo) | DEVOLUCION
+    pub monto_inicial: f64,
+    pub saldo: f64,
+    pub estado: String, // ACTIVO | AGOTADO | VENCIDO
+    pub fecha_emision: String,
+    pub fecha_vencimiento: Option<String>,
+    pub cliente_id: Option<i32>,
+    pub cliente_nombre: Option<String>,
+    pub devolucion_id: Option<i32>,
+}
+
+// =====================================================
+// MOVIMIENTOS DEL VALE
+// =====================================================
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MovimientoVale {
+    pub id: i32,
+    pub tipo: String, // EMISION | CANJE | REVERSION | VENCIMIENTO
+    pub monto: f64,
+    pub saldo_resultante: f64,
+    pub folio_venta: Option<String>,
+    pub descripcion: Option<String>,
+    pub fecha_hora: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ValeDetalle {
+    pub vale: Vale,
+    pub movimientos: Vec<MovimientoVale>,
+}
+
+// =====================================================
+// REQUEST: Venta de tarjeta de regalo
+// =====================================================
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VentaValeRequest {
+    pub monto: f64,
+    pub metodo_pago: String, // EFECTIVO | TARJETA | TRANSFERENCIA
+    pub monto_recibido: Option<f64>,
+    pub referencia: Option<String>,
+    pub cliente_id: Option<i32>,
+    pub fecha_vencimiento: Option<String>, // por defecto: vales_meses_vencimiento
+}
@@ -47,3 +47,82 @@ pub struct VentaResponse {
     pub venta: Option<Venta>,
     pub folio: Option<String>,
 }
+
+// =====================================================
+// 🆕 DETALLE COMPLETO DE UNA VENTA (consulta y reimpresión)
+// =====================================================
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VentaDetalleCompleto {
+    pub id: i32,
+    pub folio: String,
+    pub fecha_hora: String,
+    pub estado: String,
+    pub motivo_cancelacion: Option<String>,
+    pub tipo_comprobante: String,
+    pub numero_comprobante: Option<String>, // B001-00000123 (None en notas de venta antiguas)
+    pub subtotal: f64,
+    pub descuento: f64,
+    pub total: f64,
+    pub redondeo: f64,
+    pub op_gravada: f64,
+    pub op_exonerada: f64,
+    pub op_inafecta: f64,
+    pub igv: f64,
+    pub igv_porcentaje: f64,
+    pub metodo_pago: String,
+    pub monto_recibido: Option<f64>,
+    pub cambio: Option<f64>,
+    pub saldo_pendiente: f64,
+    pub usuario_id: i32,
+    pub cajero: String,
+    pub vendedor_id: Option<i32>,
+    pub vendedor: Option<String>,
+    pub caja_id: Option<i32>,
+    pub cliente_id: Option<i32>,
+    pub cliente_nombre: Option<String>,
+    pub cliente_documento: Option<String>, // "DNI 12345678"
+    pub folio_cambio: Option<String>,       // venta de reemplazo de un cambio de talla
+    pub lineas: Vec<LineaVentaDetalle>,
+    pub pagos: Vec<PagoVentaDetalle>,
+    pub devoluciones: Vec<DevolucionDeVenta>,
+    pub monto_devuelto: f64,
+    pub reimpresiones: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LineaVentaDetalle {
+    pub detalle_id: i32,
+    pub producto_id: i32,
+    pub codigo: String,
+    pub nombre: String,
+    pub variante_id: Option<i32>,
+    pub talla: Option<String>,
+    pub cantidad: i32,
+    pub precio_unitario: f64,
+    pub subtotal: f64,
+    pub descuento_linea: f64,
+    pub total_linea: f64,
+    pub cantidad_devuelta: i32,
+    pub vendedor: Option<String>, // solo si la línea la vendió otra persona
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PagoVentaDetalle {
+    pub metodo_pago: String,
+    pub monto: f64,
+    pub monto_recibido: Option<f64>,
+    pub referencia: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DevolucionDeVenta {
+    pub id: i32,
+    pub folio_devolucion: String,
+    pub fecha_hora: String,
+    pub monto_reembolsado: f64,
+    pub metodo_reembolso: String,
+    pub motivo: String,
+    pub estado: String,
+    pub usuario: String,
+    pub nota_credito: Option<String>, // BC01-00000001
+}
@@ -367,9 +367,17 @@ function Caja({ usuario, onVolver, modoSoloLectura }) {
                     <span>+{formatearMoneda(cajaActual.monto_inicial)}</span>
                   </div>
                   <div className="efectivo-item">
-                    <span>Ventas en efectivo:</span>
+                    <span>Ventas en efectivo (neto de devoluciones):</span>
                     <span>+{formatearMoneda(cajaActual.ventas_efectivo)}</span>
                   </div>
+                  <div className="efectivo-item">
+                    <span>Cambio dado (ya descontado de las ventas):</span>
+                    <span>{formatearMoneda(cajaActual.cambio_total || 0)}</span>
+                  </div>
+                  <div className="efectivo-item">
+                    <span>Redondeo del efectivo:</span>
+                    <span>{(cajaActual.redondeo_total || 0) < 0 ? '' : '+'}{formatearMoneda(cajaActual.redondeo_total || 0)}</span>
+                  </div>
                   <div className="efectivo-item">
                     <span>Ingresos adicionales:</span>
                     <span>+{formatearMoneda(cajaActual.ingresos_total)}</span>
@@ -382,14 +390,6 @@ function Caja({ usuario, onVolver, modoSoloLectura }) {
                     <span>Gastos:</span>
                     <span>-{formatearMoneda(cajaActual.gastos_total)}</span>
                   </div>
-                  <div className="efectivo-item negativo">
-                    <span>Devoluciones:</span>
-                    <span>-{formatearMoneda(cajaActual.devoluciones_monto)}</span>
-                  </div>
-                  <div className="efectivo-item negativo">
-                    <span>Cambio dado:</span>
-                    <span>-{formatearMoneda(cajaActual.cambio_total || 0)}</span>
-                  </div>
                   <div className="efectivo-total">
                     <span>Efectivo esperado:</span>
                     <span className="total-efectivo">
@@ -1,6 +1,6 @@
 // Caja/CerrarCaja.jsx
 import { useState } from 'react';
-import { cerrarCaja, obtenerReporteCierre, formatearMoneda } from '../../services/cajaService';
+import { cerrarCaja, obtenerReporteCierre, formatearMoneda, calcularEfectivoEsperado } from '../../services/cajaService';
 import './CerrarCaja.css';
 
 function CerrarCaja({ caja, usuario, onCerrar, onCajaCerrada }) {
@@ -18,9 +18,7 @@ function CerrarCaja({ caja, usuario, onCerrar, onCajaCerrada }) {
   const totalDigital = (caja.ventas_tarjeta || 0) + (caja.ventas_transferencia || 0);
 
   // SECCIÓN 2: Efectivo físico
-  const efectivoEntradas = (caja.monto_inicial || 0) + (caja.ventas_efectivo || 0) + (caja.ingresos_total || 0);
-  const efectivoSalidas = (caja.retiros_total || 0) + (caja.gastos_total || 0) + (caja.devoluciones_monto || 0) + (caja.cambio_total || 0);
-  const efectivoEsperado = efectivoEntradas - efectivoSalidas;
+  const efectivoEsperado = calcularEfectivoEsperado(caja);
 
   // SECCIÓN 3: Total general
   const totalGeneral = totalDigital + efectivoEsperado;
@@ -115,22 +113,28 @@ function CerrarCaja({ caja, usuario, onCerrar, onCajaCerrada }) {
                   <span>+{formatearMoneda(caja.monto_inicial || 0)}</span>
                 </div>
                 <div className="linea-item positivo">
-                  <span>Ventas en efectivo:</span>
+                  <span>Ventas en efectivo (neto de devoluciones):</span>
                   <span>+{formatearMoneda(caja.ventas_efectivo || 0)}</span>
                 </div>
+                {(caja.redondeo_total || 0) !== 0 && (
+                  <div className={`linea-item ${caja.redondeo_total > 0 ? 'positivo' : 'negativo'}`}>
+                    <span>Redondeo del efectivo:</span>
+                    <span>{caja.redondeo_total > 0 ? '+' : ''}{formatearMoneda(caja.redondeo_total)}</span>
+                  </div>
+                )}
                 {(caja.ingresos_total || 0) > 0 && (
                   <div className="linea-item positivo">
                     <span>Ingresos adicionales:</span>
                     <span>+{formatearMoneda(caja.ingresos_total)}</span>
                   </div>
                 )}
-                <div className="linea-separador"></div>
                 {(caja.cambio_total || 0) > 0 && (
-                  <div className="linea-item negativo">
-                    <span>Cambio dado a clientes:</span>
-                    <span>-{formatearMoneda(caja.cambio_total)}</span>
+                  <div className="linea-item monto-neutral">
+                    <span>Cambio dado a clientes (ya descontado de las ventas):</span>
+                    <span>{formatearMoneda(caja.cambio_total)}</span>
                   </div>
                 )}
+                <div className="linea-separador"></div>
                 {(caja.retiros_total || 0) > 0 && (
                   <div className="linea-item negativo">
                     <span>Retiros (depósitos externos):</span>
@@ -143,12 +147,6 @@ function CerrarCaja({ caja, usuario, onCerrar, onCajaCerrada }) {
                     <span>-{formatearMoneda(caja.gastos_total)}</span>
                   </div>
                 )}
-                {(caja.devoluciones_monto || 0) > 0 && (
-                  <div className="linea-item negativo">
-                    <span>Devoluciones en efectivo:</span>
-                    <span>-{formatearMoneda(caja.devoluciones_monto)}</span>
-                  </div>
-                )}
               </div>
               <div className="seccion-total">
                 <span>Efectivo esperado en caja:</span>
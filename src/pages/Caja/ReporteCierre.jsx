@@ -1,6 +1,6 @@
 // Caja/ReporteCierre.jsx
 import { useRef } from 'react';
-import { formatearMoneda, formatearDuracion } from '../../services/cajaService';
+import { formatearMoneda, formatearDuracion, calcularEfectivoEsperado } from '../../services/cajaService';
 import './ReporteCierre.css';
 
 function ReporteCierre({ reporte, onCerrar }) {
@@ -24,14 +24,7 @@ function ReporteCierre({ reporte, onCerrar }) {
 
   // Cálculos de las 3 secciones
   const totalDigital = (reporte.caja.ventas_tarjeta || 0) + (reporte.caja.ventas_transferencia || 0);
-  const efectivoEsperado =
-    (reporte.caja.monto_inicial || 0) +
-    (reporte.caja.ventas_efectivo || 0) +
-    (reporte.caja.ingresos_total || 0) -
-    (reporte.caja.cambio_total || 0) -
-    (reporte.caja.retiros_total || 0) -
-    (reporte.caja.gastos_total || 0) -
-    (reporte.caja.devoluciones_monto || 0);
+  const efectivoEsperado = calcularEfectivoEsperado(reporte.caja);
   const totalGeneral = totalDigital + efectivoEsperado;
 
   return (
@@ -129,22 +122,28 @@ function ReporteCierre({ reporte, onCerrar }) {
                 <span>+{formatearMoneda(reporte.caja.monto_inicial || 0)}</span>
               </div>
               <div className="calculo-fila positivo">
-                <span>Ventas cobradas en efectivo</span>
+                <span>Ventas cobradas en efectivo (neto de devoluciones)</span>
                 <span>+{formatearMoneda(reporte.caja.ventas_efectivo || 0)}</span>
               </div>
+              {(reporte.caja.redondeo_total || 0) !== 0 && (
+                <div className={`calculo-fila ${reporte.caja.redondeo_total > 0 ? 'positivo' : 'negativo'}`}>
+                  <span>Redondeo del efectivo</span>
+                  <span>{reporte.caja.redondeo_total > 0 ? '+' : ''}{formatearMoneda(reporte.caja.redondeo_total)}</span>
+                </div>
+              )}
               {(reporte.caja.ingresos_total || 0) > 0 && (
                 <div className="calculo-fila positivo">
                   <span>Ingresos adicionales</span>
                   <span>+{formatearMoneda(reporte.caja.ingresos_total)}</span>
                 </div>
               )}
-              <div className="calculo-separador"></div>
               {(reporte.caja.cambio_total || 0) > 0 && (
-                <div className="calculo-fila negativo">
-                  <span>Cambio dado a clientes</span>
-                  <span>-{formatearMoneda(reporte.caja.cambio_total)}</span>
+                <div className="calculo-fila">
+                  <span>Cambio dado a clientes (ya descontado de las ventas)</span>
+                  <span>{formatearMoneda(reporte.caja.cambio_total)}</span>
                 </div>
               )}
+              <div className="calculo-separador"></div>
               {(reporte.caja.retiros_total || 0) > 0 && (
                 <div className="calculo-fila negativo">
                   <span>Retiros (depósitos externos)</span>
@@ -157,12 +156,6 @@ function ReporteCierre({ reporte, onCerrar }) {
                   <span>-{formatearMoneda(reporte.caja.gastos_total)}</span>
                 </div>
               )}
-              {(reporte.caja.devoluciones_monto || 0) > 0 && (
-                <div className="calculo-fila negativo">
-                  <span>Devoluciones en efectivo</span>
-                  <span>-{formatearMoneda(reporte.caja.devoluciones_monto)}</span>
-                </div>
-              )}
               <div className="calculo-fila subtotal-efectivo">
                 <span><strong>Efectivo esperado en caja</strong></span>
                 <span><strong>{formatearMoneda(efectivoEsperado)}</strong></span>
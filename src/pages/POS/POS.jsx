@@ -257,21 +257,20 @@ const getStockTexto = (stock) => {
 
       const resultado = await invoke('procesar_venta', {
         productos: productosVenta,
-        total: calcularTotal(),
         metodoPago,
         montoRecibido: metodoPago === 'EFECTIVO' ? parseFloat(montoRecibido) : null,
-        cambio: metodoPago === 'EFECTIVO' ? calcularCambio() : null,
         usuarioId: usuario.id,
       });
 
       const ventaParaRecibo = {
         folio: resultado.folio,
-        subtotal: calcularSubtotal(),
-        descuento: calcularDescuentoTotal(),
-        total: calcularTotal(),
+        // 🆕 Totales del servidor: incluyen promociones, cupón y lista de precios
+        subtotal: resultado.subtotal,
+        descuento: resultado.descuento,
+        total: resultado.total,
         metodoPago,
         montoRecibido: metodoPago === 'EFECTIVO' ? parseFloat(montoRecibido) : 0,
-        cambio: metodoPago === 'EFECTIVO' ? calcularCambio() : 0,
+        cambio: resultado.cambio ?? 0,
         cajero: usuario.nombre_completo,
         productos: carrito.map(item => ({
           nombre: item.nombre + (item.talla ? ` (${item.talla})` : ''),
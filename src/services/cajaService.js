@@ -145,18 +145,23 @@ export async function obtenerDetalleCaja(cajaId) {
 }
 
 /**
- * Calcular efectivo esperado en caja
+ * Calcular efectivo esperado en caja, término a término como cerrar_caja:
+ * ventas_efectivo ya viene neto del cambio entregado y de las devoluciones
+ * en efectivo; el redondeo del efectivo se suma aparte.
+ * Una caja cerrada usa el efectivo_esperado que guardó el backend.
  */
 export function calcularEfectivoEsperado(caja) {
   if (!caja) return 0;
+  if (caja.efectivo_esperado !== null && caja.efectivo_esperado !== undefined) {
+    return caja.efectivo_esperado;
+  }
   return (
-    caja.monto_inicial +
-    caja.ventas_efectivo +
-    caja.ingresos_total -
-    caja.retiros_total -
-    caja.gastos_total -
-    caja.devoluciones_monto -
-    (caja.cambio_total || 0)
+    (caja.monto_inicial || 0) +
+    (caja.ventas_efectivo || 0) +
+    (caja.redondeo_total || 0) +
+    (caja.ingresos_total || 0) -
+    (caja.retiros_total || 0) -
+    (caja.gastos_total || 0)
   );
 }
 